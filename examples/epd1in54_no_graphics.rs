@@ -58,42 +58,52 @@ fn main() -> Result<(), std::io::Error> {
 
     // Setup of the needed pins is finished here
     // Now the "real" usage of the eink-waveshare-rs crate begins
-    let mut epd = Epd1in54::new(&mut spi, cs_pin, busy, dc, rst, &mut delay)?;
+    let mut epd = Epd1in54::new(&mut spi, cs_pin, busy, dc, rst, &mut delay).map_err(io_error)?;
 
     // Clear the full screen
-    epd.clear_frame(&mut spi, &mut delay)?;
-    epd.display_frame(&mut spi, &mut delay)?;
+    epd.clear_frame(&mut spi, &mut delay).map_err(io_error)?;
+    epd.display_frame(&mut spi, &mut delay).map_err(io_error)?;
 
     // Speeddemo
-    epd.set_lut(&mut spi, Some(RefreshLut::Quick))?;
+    epd.set_lut(&mut spi, Some(RefreshLut::Quick))
+        .map_err(io_error)?;
     let small_buffer = [Color::Black.get_byte_value(); 32]; //16x16
     let number_of_runs = 1;
     for i in 0..number_of_runs {
         let offset = i * 8 % 150;
-        epd.update_partial_frame(&mut spi, &small_buffer, 25 + offset, 25 + offset, 16, 16)?;
-        epd.display_frame(&mut spi, &mut delay)?;
+        epd.update_partial_frame(&mut spi, &small_buffer, 25 + offset, 25 + offset, 16, 16)
+            .map_err(io_error)?;
+        epd.display_frame(&mut spi, &mut delay).map_err(io_error)?;
     }
 
     // Clear the full screen
-    epd.clear_frame(&mut spi, &mut delay)?;
-    epd.display_frame(&mut spi, &mut delay)?;
+    epd.clear_frame(&mut spi, &mut delay).map_err(io_error)?;
+    epd.display_frame(&mut spi, &mut delay).map_err(io_error)?;
 
     // Draw some squares
     let small_buffer = [Color::Black.get_byte_value(); 3200]; //160x160
-    epd.update_partial_frame(&mut spi, &small_buffer, 20, 20, 160, 160)?;
+    epd.update_partial_frame(&mut spi, &small_buffer, 20, 20, 160, 160)
+        .map_err(io_error)?;
 
     let small_buffer = [Color::White.get_byte_value(); 800]; //80x80
-    epd.update_partial_frame(&mut spi, &small_buffer, 60, 60, 80, 80)?;
+    epd.update_partial_frame(&mut spi, &small_buffer, 60, 60, 80, 80)
+        .map_err(io_error)?;
 
     let small_buffer = [Color::Black.get_byte_value(); 8]; //8x8
-    epd.update_partial_frame(&mut spi, &small_buffer, 96, 96, 8, 8)?;
+    epd.update_partial_frame(&mut spi, &small_buffer, 96, 96, 8, 8)
+        .map_err(io_error)?;
 
     // Display updated frame
-    epd.display_frame(&mut spi, &mut delay)?;
+    epd.display_frame(&mut spi, &mut delay).map_err(io_error)?;
     delay.delay_ms(5000u16);
 
     // Set the EPD to sleep
-    epd.sleep(&mut spi, &mut delay)?;
+    epd.sleep(&mut spi, &mut delay).map_err(io_error)?;
 
     Ok(())
 }
+
+/// Turns the errors of the driver into an io::Error for `main`
+fn io_error<E: std::fmt::Debug>(error: E) -> std::io::Error {
+    std::io::Error::other(format!("{:?}", error))
+}
@@ -80,7 +80,9 @@ fn main() -> Result<(), std::io::Error> {
     display.set_rotation(DisplayRotation::Rotate270);
     draw_text(&mut display, "Rotate 270!", 5, 50);
 
-    epd2in13.update_frame(&mut spi, display.buffer(), &mut delay)?;
+    epd2in13
+        .update_frame(&mut spi, display.buffer(), &mut delay)
+        .map_err(io_error)?;
     epd2in13
         .display_frame(&mut spi, &mut delay)
         .expect("display frame new graphics");
@@ -156,7 +158,7 @@ fn main() -> Result<(), std::io::Error> {
     }
 
     println!("Finished tests - going to sleep");
-    epd2in13.sleep(&mut spi, &mut delay)
+    epd2in13.sleep(&mut spi, &mut delay).map_err(io_error)
 }
 
 fn draw_text(display: &mut Display2in13, text: &str, x: i32, y: i32) {
@@ -170,3 +172,8 @@ fn draw_text(display: &mut Display2in13, text: &str, x: i32, y: i32) {
 
     let _ = Text::with_text_style(text, Point::new(x, y), style, text_style).draw(display);
 }
+
+/// Turns the errors of the driver into an io::Error for `main`
+fn io_error<E: std::fmt::Debug>(error: E) -> std::io::Error {
+    std::io::Error::other(format!("{:?}", error))
+}
@@ -137,7 +137,9 @@ fn main() -> Result<(), std::io::Error> {
 
     // we used three colors, so we need to update both bw-buffer and chromatic-buffer
 
-    epd2in13.update_color_frame(&mut spi, display.bw_buffer(), display.chromatic_buffer())?;
+    epd2in13
+        .update_color_frame(&mut spi, display.bw_buffer(), display.chromatic_buffer())
+        .map_err(io_error)?;
     epd2in13
         .display_frame(&mut spi, &mut delay)
         .expect("display frame new graphics");
@@ -147,11 +149,15 @@ fn main() -> Result<(), std::io::Error> {
 
     // clear both bw buffer and chromatic buffer
     display.clear_buffer(TriColor::White);
-    epd2in13.update_color_frame(&mut spi, display.bw_buffer(), display.chromatic_buffer())?;
-    epd2in13.display_frame(&mut spi, &mut delay)?;
+    epd2in13
+        .update_color_frame(&mut spi, display.bw_buffer(), display.chromatic_buffer())
+        .map_err(io_error)?;
+    epd2in13
+        .display_frame(&mut spi, &mut delay)
+        .map_err(io_error)?;
 
     println!("Finished tests - going to sleep");
-    epd2in13.sleep(&mut spi, &mut delay)
+    epd2in13.sleep(&mut spi, &mut delay).map_err(io_error)
 }
 
 fn draw_text(display: &mut Display2in13bc, text: &str, x: i32, y: i32) {
@@ -165,3 +171,8 @@ fn draw_text(display: &mut Display2in13bc, text: &str, x: i32, y: i32) {
 
     let _ = Text::with_text_style(text, Point::new(x, y), style, text_style).draw(display);
 }
+
+/// Turns the errors of the driver into an io::Error for `main`
+fn io_error<E: std::fmt::Debug>(error: E) -> std::io::Error {
+    std::io::Error::other(format!("{:?}", error))
+}
@@ -79,7 +79,9 @@ fn main() -> Result<(), std::io::Error> {
     display.set_rotation(DisplayRotation::Rotate270);
     draw_text(&mut display, "Rotate 270!", 5, 50);
 
-    epd4in2.update_frame(&mut spi, display.buffer(), &mut delay)?;
+    epd4in2
+        .update_frame(&mut spi, display.buffer(), &mut delay)
+        .map_err(io_error)?;
     epd4in2
         .display_frame(&mut spi, &mut delay)
         .expect("display frame new graphics");
@@ -145,7 +147,7 @@ fn main() -> Result<(), std::io::Error> {
     }
 
     println!("Finished tests - going to sleep");
-    epd4in2.sleep(&mut spi, &mut delay)
+    epd4in2.sleep(&mut spi, &mut delay).map_err(io_error)
 }
 
 fn draw_text(display: &mut Display4in2, text: &str, x: i32, y: i32) {
@@ -159,3 +161,8 @@ fn draw_text(display: &mut Display4in2, text: &str, x: i32, y: i32) {
 
     let _ = Text::with_text_style(text, Point::new(x, y), style, text_style).draw(display);
 }
+
+/// Turns the errors of the driver into an io::Error for `main`
+fn io_error<E: std::fmt::Debug>(error: E) -> std::io::Error {
+    std::io::Error::other(format!("{:?}", error))
+}
@@ -1,5 +1,4 @@
 #![deny(warnings)]
-#![deny(warnings)]
 
 use embedded_graphics::{
     mono_font::MonoTextStyleBuilder,
@@ -150,7 +149,7 @@ fn main() -> Result<(), std::io::Error> {
     }
 
     println!("Finished tests - going to sleep");
-    epd4in2.sleep(&mut spi, &mut delay)
+    epd4in2.sleep(&mut spi, &mut delay).map_err(io_error)
 }
 
 fn draw_text(display: &mut VarDisplay, text: &str, x: i32, y: i32) {
@@ -164,3 +163,8 @@ fn draw_text(display: &mut VarDisplay, text: &str, x: i32, y: i32) {
 
     let _ = Text::with_text_style(text, Point::new(x, y), style, text_style).draw(display);
 }
+
+/// Turns the errors of the driver into an io::Error for `main`
+fn io_error<E: std::fmt::Debug>(error: E) -> std::io::Error {
+    std::io::Error::other(format!("{:?}", error))
+}
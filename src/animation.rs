@@ -0,0 +1,246 @@
+//! Playing a sequence of frames with quick refreshes
+//!
+//! An [`Animation`] shows one frame after the other with the quick LUT, which takes a fraction
+//! of the time of a full refresh. The frames come from an iterator of buffers
+//! ([`play`](Animation::play)) or are rendered into a single buffer by a closure
+//! ([`play_with`](Animation::play_with)), e.g. a progress bar which grows with every frame.
+//!
+//! Between the frames the animation waits for the [frame interval](Animation::set_frame_interval).
+//! With a [clock](Animation::set_clock) the time of the upload and the refresh is subtracted,
+//! so the frames follow each other at a constant pace. The quick refreshes leave ghosts of the
+//! previous frames behind, so the last frame is shown with a full refresh again by default.
+//!
+//! The displays without a quick LUT do a full refresh for every frame.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!# use epd_waveshare::{animation::Animation, epd2in9::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!#
+//!let mut epd = Epd2in9::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// a bar which fills the display from the top in 8 frames
+//!let mut buffer = [0xFF; WIDTH as usize / 8 * HEIGHT as usize];
+//!let mut animation = Animation::new();
+//!animation.set_frame_interval(500);
+//!animation.play_with(&mut epd, &mut spi, &mut delay, &mut buffer, |frame, buffer| {
+//!    let rows = HEIGHT as usize * (frame as usize + 1) / 8;
+//!    buffer[..rows * WIDTH as usize / 8].fill(0x00);
+//!    frame < 7
+//!})?;
+//!# Ok(())
+//!# }
+//!```
+
+use crate::time::{Clock, Stopwatch};
+use crate::traits::{DisplayError, RefreshLut, WaveshareDisplay};
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+/// Plays frames with quick refreshes, see the [module docs](self)
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    frame_interval: u32,
+    clock: Option<Clock>,
+    final_full_refresh: bool,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Animation::new()
+    }
+}
+
+impl Animation {
+    /// Creates an animation without a pause between the frames and with a final full refresh
+    pub const fn new() -> Self {
+        Animation {
+            frame_interval: 0,
+            clock: None,
+            final_full_refresh: true,
+        }
+    }
+
+    /// Sets the time from the start of one frame to the start of the next one in milliseconds
+    ///
+    /// Without a clock this is the pause after every refresh.
+    pub fn set_frame_interval(&mut self, ms: u32) {
+        self.frame_interval = ms;
+    }
+
+    /// Sets the clock which measures the upload and refresh of the frames, `None` removes it
+    ///
+    /// The clock returns milliseconds, e.g. since the start of the MCU.
+    pub fn set_clock(&mut self, clock: Option<fn() -> u32>) {
+        self.clock = clock;
+    }
+
+    /// Sets whether the last frame is shown with a full refresh again after the animation
+    pub fn set_final_full_refresh(&mut self, enabled: bool) {
+        self.final_full_refresh = enabled;
+    }
+
+    /// Plays the buffers of `frames` one after the other and returns the number of frames shown
+    ///
+    /// The LUT is left at [`RefreshLut::Full`] after a final full refresh and at
+    /// [`RefreshLut::Quick`] otherwise.
+    pub fn play<'b, EPD, SPI, CS, BUSY, DC, RST, DELAY, I>(
+        &self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        frames: I,
+    ) -> Result<u32, DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        I: IntoIterator<Item = &'b [u8]>,
+    {
+        let mut shown = 0;
+        let mut last = None;
+        for frame in frames {
+            if shown == 0 {
+                epd.set_lut(spi, Some(RefreshLut::Quick))?;
+            } else {
+                self.pause(last.map(|(start, _)| start), delay);
+            }
+            let start = Stopwatch::start(self.clock);
+            epd.update_and_display_frame(spi, frame, delay)?;
+            last = Some((start, frame));
+            shown += 1;
+        }
+        if let Some((start, frame)) = last {
+            self.finish(epd, spi, delay, start, frame)?;
+        }
+        Ok(shown)
+    }
+
+    /// Renders every frame with `render` into `buffer` and plays it, returns the number of frames shown
+    ///
+    /// `render` gets the number of the frame, starting at `0`, and returns whether more frames
+    /// follow. See [`play`](Self::play) for the LUT after the animation.
+    pub fn play_with<EPD, SPI, CS, BUSY, DC, RST, DELAY, F>(
+        &self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &mut [u8],
+        mut render: F,
+    ) -> Result<u32, DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        F: FnMut(u32, &mut [u8]) -> bool,
+    {
+        epd.set_lut(spi, Some(RefreshLut::Quick))?;
+        let mut shown = 0;
+        loop {
+            let more = render(shown, buffer);
+            let start = Stopwatch::start(self.clock);
+            epd.update_and_display_frame(spi, buffer, delay)?;
+            shown += 1;
+            if !more {
+                self.finish(epd, spi, delay, start, buffer)?;
+                return Ok(shown);
+            }
+            self.pause(Some(start), delay);
+        }
+    }
+
+    /// Waits for the pause after the last frame and shows it with a full refresh, if enabled
+    fn finish<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        start: Stopwatch,
+        frame: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+    {
+        if !self.final_full_refresh {
+            return Ok(());
+        }
+        self.pause(Some(start), delay);
+        epd.set_lut(spi, Some(RefreshLut::Full))?;
+        epd.update_and_display_frame(spi, frame, delay)
+    }
+
+    /// Waits until the frame interval since `start` is over
+    fn pause<DELAY: DelayMs<u8>>(&self, start: Option<Stopwatch>, delay: &mut DELAY) {
+        let elapsed = start.and_then(|start| start.elapsed()).unwrap_or(0);
+        let mut remaining = self.frame_interval.saturating_sub(elapsed);
+        while remaining > 0 {
+            let ms = remaining.min(u32::from(u8::MAX)) as u8;
+            delay.delay_ms(ms);
+            remaining -= u32::from(ms);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::sim::sim_clock;
+
+    #[test]
+    fn pacing_subtracts_the_refresh() {
+        sim_clock!(TIME, clock, 0);
+        let mut delay = TIME.delay();
+
+        let mut animation = Animation::new();
+        animation.set_frame_interval(1000);
+        animation.set_clock(Some(clock));
+
+        // a refresh of 300 ms leaves a pause of 700 ms
+        let start = Stopwatch::start(animation.clock);
+        delay.delay_ms(200);
+        delay.delay_ms(100);
+        animation.pause(Some(start), &mut delay);
+        assert_eq!(TIME.now(), 1000);
+
+        // a refresh longer than the interval isn't paused at all
+        let start = Stopwatch::start(animation.clock);
+        for _ in 0..5 {
+            delay.delay_ms(250);
+        }
+        animation.pause(Some(start), &mut delay);
+        assert_eq!(TIME.now(), 2250);
+
+        // without a clock the whole interval is paused
+        animation.set_clock(None);
+        let start = Stopwatch::start(animation.clock);
+        animation.pause(Some(start), &mut delay);
+        assert_eq!(TIME.now(), 3250);
+    }
+}
@@ -0,0 +1,136 @@
+//! Baking pre-rendered frames into firmware images on the host
+//!
+//! An [`Asset`] is a frame or a plane in the layout the displays expect on the wire: line after
+//! line from the top, every line [`bytes_per_line`] long. A build script or a host tool renders
+//! the screens once and exports them as Rust or C source or as raw binary files, and the
+//! firmware sends them with
+//! [`RawPlaneUpload::write_raw_plane`](crate::prelude::RawPlaneUpload::write_raw_plane) or
+//! [`update_frame`](crate::prelude::WaveshareDisplay::update_frame) as they are.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::assets::Asset;
+//!
+//! // e.g. the buffer of a display drawn with embedded-graphics
+//! let frame = [0xFF; 16 / 8 * 2];
+//! let asset = Asset::new("SPLASH", 16, &frame);
+//!
+//! // written to a file in `OUT_DIR` by a build script and included by the firmware
+//! assert!(asset.to_rust().starts_with("pub const SPLASH: [u8; 4] = ["));
+//! assert!(asset.to_c().starts_with("const uint8_t SPLASH[4] = {"));
+//!
+//! // or as a raw file, e.g. for `include_bytes!` or a file system on the device
+//! let mut bin = Vec::new();
+//! asset.write_bin(&mut bin).unwrap();
+//! assert_eq!(bin, frame);
+//!```
+
+use crate::bytes_per_line;
+use std::fmt::Write;
+use std::io;
+use std::string::String;
+
+/// A frame or plane in the wire format of a display, see the [module docs](self)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Asset<'a> {
+    name: &'a str,
+    bytes_per_line: usize,
+    data: &'a [u8],
+}
+
+impl<'a> Asset<'a> {
+    /// A black/white or chromatic plane with `width` pixels per line, 8 pixels per byte
+    ///
+    /// `name` is the name of the constant in the generated source.
+    pub fn new(name: &'a str, width: u32, data: &'a [u8]) -> Self {
+        Asset {
+            name,
+            bytes_per_line: bytes_per_line(width as usize),
+            data,
+        }
+    }
+
+    /// A 7-color frame with `width` pixels per line, 2 pixels per byte
+    pub fn new_oct(name: &'a str, width: u32, data: &'a [u8]) -> Self {
+        Asset {
+            name,
+            bytes_per_line: width.div_ceil(2) as usize,
+            data,
+        }
+    }
+
+    /// Rust source with the data as `pub const <name>: [u8; N]`, one line of pixels per line
+    pub fn to_rust(&self) -> String {
+        let mut source = String::new();
+        let _ = writeln!(
+            source,
+            "pub const {}: [u8; {}] = [",
+            self.name,
+            self.data.len()
+        );
+        self.write_lines(&mut source);
+        source.push_str("];\n");
+        source
+    }
+
+    /// C source with the data as `const uint8_t <name>[N]`, one line of pixels per line
+    pub fn to_c(&self) -> String {
+        let mut source = String::new();
+        let _ = writeln!(
+            source,
+            "const uint8_t {}[{}] = {{",
+            self.name,
+            self.data.len()
+        );
+        self.write_lines(&mut source);
+        source.push_str("};\n");
+        source
+    }
+
+    /// Writes the data as it is, e.g. into a `.bin` file
+    pub fn write_bin<W: io::Write>(&self, out: &mut W) -> io::Result<()> {
+        out.write_all(self.data)
+    }
+
+    /// Writes the bytes as hex literals, one line of pixels per line
+    fn write_lines(&self, source: &mut String) {
+        for line in self.data.chunks(self.bytes_per_line.max(1)) {
+            source.push_str("   ");
+            for byte in line {
+                let _ = write!(source, " 0x{:02X},", byte);
+            }
+            source.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_with_padded_lines() {
+        // 12 pixels take 2 bytes per line
+        let data = [0xFF, 0xF0, 0x00, 0x0F];
+        let asset = Asset::new("ICON", 12, &data);
+        assert_eq!(
+            asset.to_rust(),
+            "pub const ICON: [u8; 4] = [\n    \
+             0xFF, 0xF0,\n    \
+             0x00, 0x0F,\n\
+             ];\n"
+        );
+        assert_eq!(
+            asset.to_c(),
+            "const uint8_t ICON[4] = {\n    \
+             0xFF, 0xF0,\n    \
+             0x00, 0x0F,\n\
+             };\n"
+        );
+
+        // 3 pixels of 7-color data take 2 bytes per line as well
+        let oct = Asset::new_oct("ICON", 3, &data);
+        assert_eq!(oct.to_rust(), asset.to_rust());
+    }
+}
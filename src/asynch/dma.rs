@@ -0,0 +1,62 @@
+//! Buffers for frame uploads with DMA
+//!
+//! [`ReadBuffer`] has the shape of the trait of the same name of the `embedded-dma` crate: a
+//! buffer which stays valid and in place until the transfer gave it back, even if the future
+//! of the upload is dropped. So the SPI of a HAL can hand it to its DMA and the executor runs
+//! other tasks while the frame is sent. A buffer type of a HAL which implements the
+//! `embedded-dma` trait only has to forward [`read_buffer`](ReadBuffer::read_buffer).
+//!
+//! The uploads take the buffer by value and only give it back with their result. A dropped
+//! upload drops the buffer with it, e.g. a `&'static mut` one, so the application can't write
+//! into the memory while the DMA may still read it.
+
+/// A buffer which the DMA may read from while the transfer runs
+///
+/// # Safety
+///
+/// The pointer and the length returned by [`read_buffer`](ReadBuffer::read_buffer) must stay
+/// valid and point to the same memory for as long as `self` lives, also if `self` is moved,
+/// and the memory must not be written meanwhile.
+pub unsafe trait ReadBuffer {
+    /// Word of the buffer, the drivers upload bytes
+    type Word;
+
+    /// Returns the start and the length in words of the buffer
+    ///
+    /// # Safety
+    ///
+    /// The memory must not be written through other references while the transfer runs.
+    unsafe fn read_buffer(&self) -> (*const Self::Word, usize);
+}
+
+unsafe impl ReadBuffer for &'static [u8] {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl ReadBuffer for &'static mut [u8] {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<const N: usize> ReadBuffer for &'static [u8; N] {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.as_ptr(), N)
+    }
+}
+
+unsafe impl<const N: usize> ReadBuffer for &'static mut [u8; N] {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.as_ptr(), N)
+    }
+}
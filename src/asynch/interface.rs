@@ -0,0 +1,170 @@
+use super::{dma::ReadBuffer, AsyncDisplayError};
+use crate::traits::{Command, Error, PinError};
+use core::marker::PhantomData;
+use embedded_hal_1::digital::OutputPin;
+use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::SpiDevice};
+
+/// Length of the chunks in which [`data_x_times`](DisplayInterfaceAsync::data_x_times) sends
+const FILL_CHUNK_LEN: usize = 64;
+
+/// The connection interface of the async drivers
+///
+/// Like [`DisplayInterface`](crate::interface::DisplayInterface), but the chip select belongs
+/// to the [`SpiDevice`] and the busy pin is awaited instead of polled.
+pub(crate) struct DisplayInterfaceAsync<SPI, BUSY, DC, RST, DELAY> {
+    /// SPI
+    _spi: PhantomData<SPI>,
+    /// DELAY
+    _delay: PhantomData<DELAY>,
+    /// Busy pin, awaited until the display is idle
+    busy: BUSY,
+    /// Data/Command Control Pin (High for data, Low for command)
+    dc: DC,
+    /// Pin for Resetting
+    rst: RST,
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> DisplayInterfaceAsync<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Creates a new `DisplayInterfaceAsync` struct
+    pub(crate) fn new(busy: BUSY, dc: DC, rst: RST) -> Self {
+        DisplayInterfaceAsync {
+            _spi: PhantomData,
+            _delay: PhantomData,
+            busy,
+            dc,
+            rst,
+        }
+    }
+
+    /// Sends a [Command]
+    pub(crate) async fn cmd<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        // low for commands
+        self.set_dc(false)?;
+        spi.write(&[command.address()]).await.map_err(Error::Spi)
+    }
+
+    /// Sends the data of the last command
+    pub(crate) async fn data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        // high for data
+        self.set_dc(true)?;
+        spi.write(data).await.map_err(Error::Spi)
+    }
+
+    /// Sends the data of the last command from a [ReadBuffer] in one write and gives the buffer
+    /// back once it's done
+    ///
+    /// The SPI of the HAL can read the buffer with its DMA. The buffer is moved into the future,
+    /// so if the future is dropped during the transfer, the buffer goes with it and nothing can
+    /// write into the memory the DMA may still read.
+    pub(crate) async fn data_dma<B: ReadBuffer<Word = u8>>(
+        &mut self,
+        spi: &mut SPI,
+        buffer: B,
+    ) -> Result<B, AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.set_dc(true)?;
+        // safe as `B` keeps the memory valid and unchanged while it's owned here
+        let data = unsafe {
+            let (ptr, len) = buffer.read_buffer();
+            core::slice::from_raw_parts(ptr, len)
+        };
+        spi.write(data).await.map_err(Error::Spi)?;
+        Ok(buffer)
+    }
+
+    /// Sends a [Command] and its data
+    pub(crate) async fn cmd_with_data<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+        data: &[u8],
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.cmd(spi, command).await?;
+        self.data(spi, data).await
+    }
+
+    /// Sends the byte `val` `repetitions` times as data
+    pub(crate) async fn data_x_times(
+        &mut self,
+        spi: &mut SPI,
+        val: u8,
+        repetitions: u32,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.set_dc(true)?;
+        let chunk = [val; FILL_CHUNK_LEN];
+        let mut left = repetitions as usize;
+        while left > 0 {
+            let len = left.min(FILL_CHUNK_LEN);
+            spi.write(&chunk[..len]).await.map_err(Error::Spi)?;
+            left -= len;
+        }
+        Ok(())
+    }
+
+    /// Waits until the busy pin reports the display as idle
+    ///
+    /// The executor can run other tasks meanwhile, e.g. during the seconds of a refresh.
+    pub(crate) async fn wait_until_idle(
+        &mut self,
+        is_busy_low: bool,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        if is_busy_low {
+            self.busy.wait_for_high().await
+        } else {
+            self.busy.wait_for_low().await
+        }
+        .map_err(|e| Error::Pin(PinError::Busy(e)))
+    }
+
+    /// Resets the device, keeping the reset pin low for `duration` ms
+    ///
+    /// See [`DisplayInterface::reset`](crate::interface::DisplayInterface::reset) for the timing.
+    pub(crate) async fn reset(
+        &mut self,
+        delay: &mut DELAY,
+        duration: u32,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.set_rst(true)?;
+        delay.delay_ms(10).await;
+
+        self.set_rst(false)?;
+        delay.delay_ms(duration).await;
+        self.set_rst(true)?;
+        delay.delay_ms(200).await;
+        Ok(())
+    }
+
+    /// Sets the data/command pin
+    fn set_dc(&mut self, high: bool) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        if high {
+            self.dc.set_high()
+        } else {
+            self.dc.set_low()
+        }
+        .map_err(|e| Error::Pin(PinError::Dc(e)))
+    }
+
+    /// Sets the reset pin
+    fn set_rst(&mut self, high: bool) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        if high {
+            self.rst.set_high()
+        } else {
+            self.rst.set_low()
+        }
+        .map_err(|e| Error::Pin(PinError::Rst(e)))
+    }
+}
@@ -0,0 +1,311 @@
+//! Async drivers on `embedded-hal-async`
+//!
+//! A refresh keeps the busy pin of the display busy for seconds. The blocking drivers poll
+//! the pin meanwhile, which stalls an async executor like Embassy. The drivers of this module
+//! await the pin with [`Wait`] instead and send over an async [`SpiDevice`], which owns the
+//! chip select, so other tasks keep running during the refreshes.
+//!
+//! Only the basic operations of [`WaveshareDisplay`](crate::prelude::WaveshareDisplay) are
+//! available, see [`WaveshareDisplayAsync`]. The drivers are:
+//!
+//! - [`Epd2in9Async`](crate::epd2in9_v2::Epd2in9Async) for the 2.9" V2 display
+//!
+//! With [`FrameUploadDma`] the frames are sent from [`ReadBuffer`]s, which the SPI of the HAL
+//! can read with its DMA while other tasks run.
+//!
+//! # Example
+//!
+//!```rust, ignore
+//!use epd_waveshare::{asynch::WaveshareDisplayAsync, epd2in9_v2::*, prelude::*};
+//!
+//!let mut epd = Epd2in9Async::new(&mut spi, busy, dc, rst, &mut delay).await?;
+//!let mut display = Display2in9::default();
+//!// draw something on the display
+//!epd.update_and_display_frame(&mut spi, display.buffer(), &mut delay).await?;
+//!epd.sleep(&mut spi, &mut delay).await?;
+//!```
+
+use core::convert::Infallible;
+
+use embedded_hal_1::digital::{self, OutputPin};
+use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::SpiDevice};
+
+use crate::traits::{Error, PinError};
+
+mod dma;
+pub(crate) mod interface;
+
+pub use self::dma::ReadBuffer;
+
+/// The [`Error`] of the async drivers, the chip select belongs to the
+/// [`SpiDevice`]
+pub type AsyncDisplayError<SPI, BUSY, DC, RST> = Error<
+    <SPI as embedded_hal_async::spi::ErrorType>::Error,
+    PinError<
+        Infallible,
+        <BUSY as digital::ErrorType>::Error,
+        <DC as digital::ErrorType>::Error,
+        <RST as digital::ErrorType>::Error,
+    >,
+>;
+
+/// The async counterpart of [`WaveshareDisplay`](crate::prelude::WaveshareDisplay)
+///
+/// The methods wait for the busy pin like the blocking ones, but by awaiting it.
+#[allow(async_fn_in_trait)]
+pub trait WaveshareDisplayAsync<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// The color type used by the display
+    type DisplayColor;
+
+    /// Creates a new driver and initializes the display
+    async fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, AsyncDisplayError<SPI, BUSY, DC, RST>>
+    where
+        Self: Sized;
+
+    /// Lets the display go into deep sleep, [`wake_up`](Self::wake_up) initializes it again
+    async fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>>;
+
+    /// Wakes the display up from deep sleep
+    async fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>>;
+
+    /// Writes the frame buffer into the display, without refreshing it
+    async fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>>;
+
+    /// Refreshes the display with the frame written before and waits until it's done
+    async fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>>;
+
+    /// Writes the frame buffer and refreshes the display with it
+    async fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay).await?;
+        self.display_frame(spi, delay).await
+    }
+
+    /// Clears the frame in the display with the background color, without refreshing it
+    async fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>>;
+
+    /// Sets the background color for [`clear_frame`](Self::clear_frame)
+    fn set_background_color(&mut self, color: Self::DisplayColor);
+
+    /// Returns the background color
+    fn background_color(&self) -> &Self::DisplayColor;
+
+    /// Width of the display in pixels
+    fn width(&self) -> u32;
+
+    /// Height of the display in pixels
+    fn height(&self) -> u32;
+}
+
+/// Frame uploads from buffers which the DMA of the HAL can read, see [`ReadBuffer`]
+///
+/// The driver sends the command before and waits for the display around the transfer like
+/// [`update_frame`](WaveshareDisplayAsync::update_frame), the frame itself is sent in one
+/// write. Example with a static frame:
+///
+///```rust, ignore
+///static FRAME: StaticCell<[u8; 4736]> = StaticCell::new();
+///let frame = FRAME.init([0xFF; 4736]);
+///// draw into the frame
+///let frame = epd.update_frame_dma(&mut spi, frame, &mut delay).await?;
+///epd.display_frame(&mut spi, &mut delay).await?;
+///```
+#[allow(async_fn_in_trait)]
+pub trait FrameUploadDma<SPI, BUSY, DC, RST, DELAY>:
+    WaveshareDisplayAsync<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Writes the frame from `buffer` into the display, without refreshing it
+    ///
+    /// Returns the buffer once the transfer is done, to draw the next frame into it.
+    async fn update_frame_dma<B>(
+        &mut self,
+        spi: &mut SPI,
+        buffer: B,
+        delay: &mut DELAY,
+    ) -> Result<B, AsyncDisplayError<SPI, BUSY, DC, RST>>
+    where
+        B: ReadBuffer<Word = u8>;
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::interface::DisplayInterfaceAsync;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+    use embedded_hal_1::digital::ErrorType;
+    use embedded_hal_async::spi::{self, Operation};
+    use std::{vec, vec::Vec};
+
+    /// Waker which does nothing, `Waker::noop` needs a newer compiler than the crate
+    const NOOP: RawWakerVTable = RawWakerVTable::new(|_| RAW, |_| {}, |_| {}, |_| {});
+    const RAW: RawWaker = RawWaker::new(core::ptr::null(), &NOOP);
+
+    /// Polls `future` until it's ready, the mocks below never make it wait
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        // Safety: the functions of the vtable ignore the null data pointer
+        let waker = unsafe { Waker::from_raw(RAW) };
+        let mut context = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    /// Records the writes
+    #[derive(Default)]
+    struct Bus {
+        writes: Vec<Vec<u8>>,
+    }
+    impl spi::ErrorType for Bus {
+        type Error = core::convert::Infallible;
+    }
+    impl spi::SpiDevice for Bus {
+        async fn transaction(
+            &mut self,
+            operations: &mut [Operation<'_, u8>],
+        ) -> Result<(), Self::Error> {
+            for operation in operations {
+                if let Operation::Write(words) = operation {
+                    self.writes.push(words.to_vec());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// A pin which is always idle
+    struct Pin;
+    impl ErrorType for Pin {
+        type Error = core::convert::Infallible;
+    }
+    impl embedded_hal_1::digital::OutputPin for Pin {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+    impl embedded_hal_async::digital::Wait for Pin {
+        async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    struct NoDelay;
+    impl embedded_hal_async::delay::DelayNs for NoDelay {
+        async fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    #[test]
+    fn commands_and_fills() {
+        let mut bus = Bus::default();
+        let mut interface: DisplayInterfaceAsync<Bus, Pin, Pin, Pin, NoDelay> =
+            DisplayInterfaceAsync::new(Pin, Pin, Pin);
+        block_on(async {
+            interface
+                .cmd_with_data(
+                    &mut bus,
+                    crate::type_a::command::Command::DeepSleepMode,
+                    &[0x01],
+                )
+                .await
+                .unwrap();
+            interface.data_x_times(&mut bus, 0xFF, 100).await.unwrap();
+            interface.wait_until_idle(false).await.unwrap();
+        });
+
+        assert_eq!(bus.writes[..2], [vec![0x10], vec![0x01]]);
+        // the fill is sent in chunks
+        assert_eq!(bus.writes[2], vec![0xFF; 64]);
+        assert_eq!(bus.writes[3], vec![0xFF; 36]);
+    }
+
+    #[test]
+    fn frames_from_dma_buffers() {
+        use super::{FrameUploadDma, WaveshareDisplayAsync};
+        use crate::epd2in9_v2::Epd2in9Async;
+
+        static FRAME: [u8; 4] = [1, 2, 3, 4];
+        let mut bus = Bus::default();
+        let mut interface: DisplayInterfaceAsync<Bus, Pin, Pin, Pin, NoDelay> =
+            DisplayInterfaceAsync::new(Pin, Pin, Pin);
+        let returned = block_on(interface.data_dma(&mut bus, &FRAME)).unwrap();
+        assert_eq!(returned, &FRAME);
+        assert_eq!(bus.writes, [vec![1, 2, 3, 4]]);
+
+        let mut bus = Bus::default();
+        let frame: &'static [u8] = &FRAME;
+        let returned = block_on(async {
+            let mut epd = Epd2in9Async::new(&mut bus, Pin, Pin, Pin, &mut NoDelay)
+                .await
+                .unwrap();
+            epd.update_frame_dma(&mut bus, frame, &mut NoDelay).await
+        })
+        .unwrap();
+        // the buffer is given back, the frame is sent in one write after the command
+        assert_eq!(returned, frame);
+        let writes = &bus.writes[bus.writes.len() - 2..];
+        assert_eq!(writes, [vec![0x24], vec![1, 2, 3, 4]]);
+    }
+}
@@ -126,10 +126,11 @@ impl From<embedded_graphics_core::pixelcolor::Rgb888> for OctColor {
 }
 
 #[cfg(feature = "graphics")]
+/// Raw values without a color are white
 impl From<embedded_graphics_core::pixelcolor::raw::RawU4> for OctColor {
     fn from(b: embedded_graphics_core::pixelcolor::raw::RawU4) -> Self {
         use embedded_graphics_core::prelude::RawData;
-        OctColor::from_nibble(b.into_inner()).unwrap()
+        OctColor::from_nibble(b.into_inner()).unwrap_or(OctColor::White)
     }
 }
 
@@ -182,6 +183,250 @@ impl OctColor {
         }
     }
 }
+
+/// For the 4 Color Displays of the G series
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum QuadColor {
+    /// Black Color
+    Black = 0x00,
+    /// White Color
+    White = 0x01,
+    /// Yellow Color
+    Yellow = 0x02,
+    /// Red Color
+    Red = 0x03,
+}
+
+impl From<()> for QuadColor {
+    fn from(_: ()) -> QuadColor {
+        QuadColor::White
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<BinaryColor> for QuadColor {
+    fn from(b: BinaryColor) -> QuadColor {
+        match b {
+            BinaryColor::On => QuadColor::Black,
+            BinaryColor::Off => QuadColor::White,
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<QuadColor> for embedded_graphics_core::pixelcolor::Rgb888 {
+    fn from(b: QuadColor) -> Self {
+        let (r, g, b) = b.rgb();
+        Self::new(r, g, b)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Rgb888> for QuadColor {
+    /// Picks the nearest of the four colors
+    fn from(p: embedded_graphics_core::pixelcolor::Rgb888) -> QuadColor {
+        use embedded_graphics_core::prelude::RgbColor;
+        let colors = [
+            QuadColor::Black,
+            QuadColor::White,
+            QuadColor::Yellow,
+            QuadColor::Red,
+        ];
+        *colors
+            .iter()
+            .min_by_key(|c| {
+                let (r, g, b) = c.rgb();
+                (i32::from(r) - i32::from(p.r())).pow(2)
+                    + (i32::from(g) - i32::from(p.g())).pow(2)
+                    + (i32::from(b) - i32::from(p.b())).pow(2)
+            })
+            .unwrap_or(&QuadColor::White)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::raw::RawU2> for QuadColor {
+    fn from(b: embedded_graphics_core::pixelcolor::raw::RawU2) -> Self {
+        use embedded_graphics_core::prelude::RawData;
+        QuadColor::from_bits(b.into_inner())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl PixelColor for QuadColor {
+    type Raw = embedded_graphics_core::pixelcolor::raw::RawU2;
+}
+
+impl QuadColor {
+    /// Gets the 2 bit representation of the Color as needed by the display
+    pub fn get_bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Converts four colors into a single byte for the Display, the first one in the upper bits
+    pub fn colors_byte(a: QuadColor, b: QuadColor, c: QuadColor, d: QuadColor) -> u8 {
+        a.get_bits() << 6 | b.get_bits() << 4 | c.get_bits() << 2 | d.get_bits()
+    }
+
+    /// Takes the lower 2 bits and converts them to a QuadColor
+    pub fn from_bits(bits: u8) -> QuadColor {
+        match bits & 0b11 {
+            0x00 => QuadColor::Black,
+            0x01 => QuadColor::White,
+            0x02 => QuadColor::Yellow,
+            _ => QuadColor::Red,
+        }
+    }
+
+    /// Splits a single byte into its four colors, the first one from the upper bits
+    pub fn split_byte(byte: u8) -> [QuadColor; 4] {
+        [
+            QuadColor::from_bits(byte >> 6),
+            QuadColor::from_bits(byte >> 4),
+            QuadColor::from_bits(byte >> 2),
+            QuadColor::from_bits(byte),
+        ]
+    }
+
+    /// Converts to limited range of RGB values.
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            QuadColor::White => (0xff, 0xff, 0xff),
+            QuadColor::Black => (0x00, 0x00, 0x00),
+            QuadColor::Yellow => (0xff, 0xff, 0x00),
+            QuadColor::Red => (0xff, 0x00, 0x00),
+        }
+    }
+}
+
+/// For the 6 Color Spectra Displays of the E series
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HexColor {
+    /// Black Color
+    Black = 0x00,
+    /// White Color
+    White = 0x01,
+    /// Yellow Color
+    Yellow = 0x02,
+    /// Red Color
+    Red = 0x03,
+    /// Blue Color
+    Blue = 0x05,
+    /// Green Color
+    Green = 0x06,
+}
+
+impl From<()> for HexColor {
+    fn from(_: ()) -> HexColor {
+        HexColor::White
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<BinaryColor> for HexColor {
+    fn from(b: BinaryColor) -> HexColor {
+        match b {
+            BinaryColor::On => HexColor::Black,
+            BinaryColor::Off => HexColor::White,
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<HexColor> for embedded_graphics_core::pixelcolor::Rgb888 {
+    fn from(b: HexColor) -> Self {
+        let (r, g, b) = b.rgb();
+        Self::new(r, g, b)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Rgb888> for HexColor {
+    /// Picks the nearest of the six colors
+    fn from(p: embedded_graphics_core::pixelcolor::Rgb888) -> HexColor {
+        use embedded_graphics_core::prelude::RgbColor;
+        *HexColor::ALL
+            .iter()
+            .min_by_key(|c| {
+                let (r, g, b) = c.rgb();
+                (i32::from(r) - i32::from(p.r())).pow(2)
+                    + (i32::from(g) - i32::from(p.g())).pow(2)
+                    + (i32::from(b) - i32::from(p.b())).pow(2)
+            })
+            .unwrap_or(&HexColor::White)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::raw::RawU4> for HexColor {
+    fn from(b: embedded_graphics_core::pixelcolor::raw::RawU4) -> Self {
+        use embedded_graphics_core::prelude::RawData;
+        HexColor::from_nibble(b.into_inner()).unwrap_or(HexColor::White)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl PixelColor for HexColor {
+    type Raw = embedded_graphics_core::pixelcolor::raw::RawU4;
+}
+
+impl HexColor {
+    /// All six colors, in the order of their nibbles
+    pub const ALL: [HexColor; 6] = [
+        HexColor::Black,
+        HexColor::White,
+        HexColor::Yellow,
+        HexColor::Red,
+        HexColor::Blue,
+        HexColor::Green,
+    ];
+
+    /// Gets the Nibble representation of the Color as needed by the display
+    pub fn get_nibble(self) -> u8 {
+        self as u8
+    }
+
+    /// Converts two colors into a single byte for the Display, the first one in the upper nibble
+    pub fn colors_byte(a: HexColor, b: HexColor) -> u8 {
+        a.get_nibble() << 4 | b.get_nibble()
+    }
+
+    /// Takes the nibble (lower 4 bits) and converts it to a HexColor if possible
+    ///
+    /// `0x04` isn't a color of the panels and is rejected like the values above `0x06`.
+    pub fn from_nibble(nibble: u8) -> Result<HexColor, OutOfColorRangeParseError> {
+        match nibble & 0xf {
+            0x00 => Ok(HexColor::Black),
+            0x01 => Ok(HexColor::White),
+            0x02 => Ok(HexColor::Yellow),
+            0x03 => Ok(HexColor::Red),
+            0x05 => Ok(HexColor::Blue),
+            0x06 => Ok(HexColor::Green),
+            e => Err(OutOfColorRangeParseError(e)),
+        }
+    }
+
+    /// Splits the nibbles of a single byte into both colors, the first one from the upper nibble
+    pub fn split_byte(byte: u8) -> Result<(HexColor, HexColor), OutOfColorRangeParseError> {
+        Ok((
+            HexColor::from_nibble(byte >> 4)?,
+            HexColor::from_nibble(byte)?,
+        ))
+    }
+
+    /// Converts to limited range of RGB values.
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            HexColor::White => (0xff, 0xff, 0xff),
+            HexColor::Black => (0x00, 0x00, 0x00),
+            HexColor::Yellow => (0xff, 0xff, 0x00),
+            HexColor::Red => (0xff, 0x00, 0x00),
+            HexColor::Blue => (0x00, 0x00, 0xff),
+            HexColor::Green => (0x00, 0xff, 0x00),
+        }
+    }
+}
+
 //TODO: Rename get_bit_value to bit() and get_byte_value to byte() ?
 
 impl Color {
@@ -202,17 +447,26 @@ impl Color {
     }
 
     /// Parses from u8 to Color
+    #[allow(clippy::panic)]
     fn from_u8(val: u8) -> Self {
-        match val {
-            0 => Color::Black,
-            1 => Color::White,
-            e => panic!(
+        match Color::from_bit(val) {
+            Ok(color) => color,
+            Err(e) => panic!(
                 "DisplayColor only parses 0 and 1 (Black and White) and not `{}`",
-                e
+                e.0
             ),
         }
     }
 
+    /// Parses the bit value of a color, `0` for black and `1` for white
+    pub fn from_bit(val: u8) -> Result<Color, OutOfColorRangeParseError> {
+        match val {
+            0 => Ok(Color::Black),
+            1 => Ok(Color::White),
+            e => Err(OutOfColorRangeParseError(e)),
+        }
+    }
+
     /// Returns the inverse of the given color.
     ///
     /// Black returns White and White returns Black
@@ -224,6 +478,11 @@ impl Color {
     }
 }
 
+/// Parses `0` as black and `1` as white
+///
+/// # Panics
+///
+/// For all other values, [`Color::from_bit`] returns an error for them instead.
 impl From<u8> for Color {
     fn from(value: u8) -> Self {
         Color::from_u8(value)
@@ -253,6 +512,16 @@ impl PixelColor for TriColor {
     type Raw = ();
 }
 
+#[cfg(feature = "graphics")]
+impl From<BinaryColor> for TriColor {
+    fn from(b: BinaryColor) -> TriColor {
+        match b {
+            BinaryColor::On => TriColor::Black,
+            BinaryColor::Off => TriColor::White,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -261,12 +530,14 @@ mod tests {
     fn from_u8() {
         assert_eq!(Color::Black, Color::from(0u8));
         assert_eq!(Color::White, Color::from(1u8));
+        assert_eq!(Color::from_bit(1), Ok(Color::White));
+        assert_eq!(Color::from_bit(2), Err(OutOfColorRangeParseError(2)));
     }
 
     // test all values aside from 0 and 1 which all should panic
     #[test]
     fn from_u8_panic() {
-        for val in 2..=u8::max_value() {
+        for val in 2..=u8::MAX {
             extern crate std;
             let result = std::panic::catch_unwind(|| Color::from(val));
             assert!(result.is_err());
@@ -294,4 +565,25 @@ mod tests {
             Ok((left, right))
         );
     }
+
+    #[test]
+    fn test_quad() {
+        use QuadColor::*;
+        let byte = QuadColor::colors_byte(Black, White, Yellow, Red);
+        assert_eq!(byte, 0b00_01_10_11);
+        assert_eq!(QuadColor::split_byte(byte), [Black, White, Yellow, Red]);
+    }
+
+    #[test]
+    fn test_hex() {
+        use HexColor::*;
+        let byte = HexColor::colors_byte(Blue, Green);
+        assert_eq!(byte, 0x56);
+        assert_eq!(HexColor::split_byte(byte), Ok((Blue, Green)));
+        // 0x4 is the orange of the 7-color panels, which the Spectra panels lack
+        assert_eq!(
+            HexColor::from_nibble(0x04),
+            Err(OutOfColorRangeParseError(0x04))
+        );
+    }
 }
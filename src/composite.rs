@@ -0,0 +1,266 @@
+//! Driving several displays as one large canvas
+//!
+//! For video walls and other setups with a row or a grid of panels: a [`TiledDisplay`]
+//! holds one [`Panel`] per display, made of the driver, its frame buffer and the position of
+//! the panel on the canvas. Drawing on the tiled display draws on every buffer which the
+//! drawing crosses, in the coordinates of that buffer, so shapes and text can span several
+//! panels. The displays are updated one after the other on a shared bus, each with its own
+//! chip select.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{
+//!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
+//!};
+//!use epd_waveshare::{composite::{Panel, TiledDisplay}, epd7in5_v2::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_left = pin::Mock::new(&expectations);
+//!# let cs_right = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// two 7.5" panels side by side, 1600 x 480 pixels in total
+//!let left = Epd7in5::new(&mut spi, cs_left, busy_in.clone(), dc.clone(), rst.clone(), &mut delay)?;
+//!let right = Epd7in5::new(&mut spi, cs_right, busy_in, dc, rst, &mut delay)?;
+//!let mut wall = TiledDisplay::new([
+//!    Panel::new(left, Display7in5::default(), Point::new(0, 0)),
+//!    Panel::new(right, Display7in5::default(), Point::new(800, 0)),
+//!]);
+//!
+//!// a line across both panels
+//!let _ = Line::new(Point::new(600, 240), Point::new(1000, 240))
+//!    .into_styled(PrimitiveStyle::with_stroke(Black, 4))
+//!    .draw(&mut wall);
+//!
+//!wall.update_and_display(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use crate::graphics::Display;
+use crate::traits::{DisplayError, WaveshareDisplay};
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+/// One display of a [`TiledDisplay`]
+pub struct Panel<E, D> {
+    /// The driver of the display
+    pub epd: E,
+    /// The frame buffer of the display
+    pub display: D,
+    /// Position of the top left corner of the display on the canvas
+    pub offset: Point,
+}
+
+impl<E, D> Panel<E, D> {
+    /// Places the display with the driver `epd` and the buffer `display` at `offset`
+    pub fn new(epd: E, display: D, offset: Point) -> Self {
+        Panel {
+            epd,
+            display,
+            offset,
+        }
+    }
+}
+
+impl<E, D: Dimensions> Panel<E, D> {
+    /// The area of the display on the canvas
+    pub fn area(&self) -> Rectangle {
+        let area = self.display.bounding_box();
+        Rectangle::new(area.top_left + self.offset, area.size)
+    }
+}
+
+/// `N` displays drawn as one canvas, see the [module](self)
+///
+/// The canvas spans from the top left corner of the panels to the bottom right one, the gaps
+/// between panels are not drawn. Where panels overlap, both get the pixels.
+pub struct TiledDisplay<E, D, const N: usize> {
+    panels: [Panel<E, D>; N],
+}
+
+impl<E, D, const N: usize> TiledDisplay<E, D, N> {
+    /// Combines the `panels` into one canvas
+    pub fn new(panels: [Panel<E, D>; N]) -> Self {
+        TiledDisplay { panels }
+    }
+
+    /// The panels, e.g. to change the settings of a driver
+    pub fn panels(&mut self) -> &mut [Panel<E, D>; N] {
+        &mut self.panels
+    }
+
+    /// Splits the canvas into its panels again
+    pub fn into_panels(self) -> [Panel<E, D>; N] {
+        self.panels
+    }
+}
+
+impl<E, D: Display, const N: usize> TiledDisplay<E, D, N> {
+    /// Writes the buffers of all panels into their displays, without refreshing them
+    pub fn update<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        E: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+    {
+        for panel in self.panels.iter_mut() {
+            panel.epd.update_frame(spi, panel.display.buffer(), delay)?;
+        }
+        Ok(())
+    }
+
+    /// Refreshes all panels with the frames written before, one after the other
+    pub fn display<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        E: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+    {
+        for panel in self.panels.iter_mut() {
+            panel.epd.display_frame(spi, delay)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffers of all panels and refreshes them
+    ///
+    /// All frames are written before the first refresh, so the panels change as close
+    /// together as the refreshes allow.
+    pub fn update_and_display<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        E: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+    {
+        self.update(spi, delay)?;
+        self.display(spi, delay)
+    }
+}
+
+impl<E, D: DrawTarget, const N: usize> DrawTarget for TiledDisplay<E, D, N> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            for panel in self.panels.iter_mut() {
+                if panel.area().contains(point) {
+                    let pixel = Pixel(point - panel.offset, color);
+                    panel.display.draw_iter(core::iter::once(pixel))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        for panel in self.panels.iter_mut() {
+            let part = area.intersection(&panel.area());
+            if !part.is_zero_sized() {
+                let part = Rectangle::new(part.top_left - panel.offset, part.size);
+                panel.display.fill_solid(&part, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        for panel in self.panels.iter_mut() {
+            panel.display.clear(color)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E, D: Dimensions, const N: usize> Dimensions for TiledDisplay<E, D, N> {
+    fn bounding_box(&self) -> Rectangle {
+        let mut areas = self.panels.iter().map(Panel::area);
+        let first = match areas.next() {
+            Some(area) => area,
+            None => return Rectangle::zero(),
+        };
+        let (top_left, bottom_right) = areas.fold(
+            (first.top_left, first.top_left + first.size),
+            |(top_left, bottom_right), area| {
+                (
+                    top_left.component_min(area.top_left),
+                    bottom_right.component_max(area.top_left + area.size),
+                )
+            },
+        );
+        let size = bottom_right - top_left;
+        Rectangle::new(top_left, Size::new(size.x as u32, size.y as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::VarDisplay;
+    use embedded_graphics_core::pixelcolor::BinaryColor;
+
+    #[test]
+    fn drawing_spans_the_panels() {
+        let (mut left, mut right) = ([0xFF; 16], [0xFF; 16]);
+        let mut wall = TiledDisplay::new([
+            Panel::new((), VarDisplay::new(16, 8, &mut left), Point::new(0, 0)),
+            Panel::new((), VarDisplay::new(16, 8, &mut right), Point::new(16, 0)),
+        ]);
+        assert_eq!(
+            wall.bounding_box(),
+            Rectangle::new(Point::zero(), Size::new(32, 8))
+        );
+
+        // one pixel in the second panel, and a line of 8 pixels across the border
+        let _ = Pixel(Point::new(20, 3), BinaryColor::On).draw(&mut wall);
+        let _ = wall.fill_solid(
+            &Rectangle::new(Point::new(12, 0), Size::new(8, 1)),
+            BinaryColor::On,
+        );
+
+        let [left, right] = wall.into_panels();
+        assert_eq!(left.display.buffer()[..2], [0xFF, 0xF0]);
+        assert_eq!(right.display.buffer()[..2], [0x0F, 0xFF]);
+        assert_eq!(right.display.buffer()[6], 0xF7);
+    }
+}
@@ -0,0 +1,470 @@
+//! The controller of the (G) 4-color panels
+//!
+//! The 1.64", 2.15", 2.36", 3.0", 4.37" and 7.3" (G) panels take the same
+//! [type G commands](crate::type_g::command::Command), the same frame layout with four pixels
+//! per byte and the same refresh. They only differ in their size, their init sequence and
+//! whether the panel stays powered on between the refreshes, which a [`GPanel`] describes.
+//! Their drivers are aliases of [`GPanelEpd`] for their panel.
+
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::{InputPin, OutputPin},
+};
+
+use crate::color::QuadColor;
+use crate::energy::RefreshEnergy;
+use crate::interface::{render_rows, DisplayInterface, InterfaceAccess, InterfaceState};
+use crate::settings::InitStep;
+use crate::traits::{
+    BusyState, Capabilities, DisplayError, DriverSnapshot, InitSequence, InternalWiAdditions,
+    LazyInit, LifecycleEvent, NonBlockingRefresh, Plane, RawPlaneError, RawPlaneUpload, RefreshLut,
+    RefreshState, RowRendering, Snapshot, WaveshareDisplay,
+};
+use crate::type_g::command::Command;
+
+/// The busy pin of the controller is low while it is busy
+const IS_BUSY_LOW: bool = true;
+
+/// What sets a (G) panel apart from the others
+pub trait GPanel {
+    /// Width of the display
+    const WIDTH: u32;
+    /// Height of the display
+    const HEIGHT: u32;
+    /// Energy of a refresh
+    const ENERGY: RefreshEnergy;
+    /// Init sequence, values taken from the Waveshare C driver
+    const INIT_SEQUENCE: &'static [InitStep];
+    /// Whether the panel is powered on for every refresh and off again afterwards, instead of
+    /// being powered on by the init sequence until [`sleep`](WaveshareDisplay::sleep)
+    const POWER_PER_REFRESH: bool;
+
+    /// A row of the frame, `WIDTH / 4` bytes
+    type Row: AsMut<[u8]>;
+    /// An empty row for [`RowRendering`]
+    const ROW: Self::Row;
+}
+
+/// Driver of the (G) panel `P`
+pub struct GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    /// Background Color
+    color: QuadColor,
+    /// Init sequence
+    init_sequence: &'static [InitStep],
+    /// Whether a refresh started without waiting still has to be followed by the power off
+    power_off_pending: bool,
+    /// Panel
+    _panel: PhantomData<P>,
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+    for GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // Values taken from the Waveshare C driver
+        self.interface.reset(delay, 2)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+
+        if !P::POWER_PER_REFRESH {
+            // ends with powering the panel on
+            self.interface.set_busy_reason(BusyState::PoweringUp);
+        }
+        self.interface
+            .run_init_sequence(spi, delay, self.init_sequence, IS_BUSY_LOW)
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type DisplayColor = QuadColor;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: false,
+        grayscale_levels: 2,
+        chromatic_colors: 2,
+        readback: false,
+        fast_mode: false,
+        ultrafast: false,
+        clear_refreshes: true,
+    };
+    const ENERGY: RefreshEnergy = P::ENERGY;
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        if !P::POWER_PER_REFRESH {
+            self.cmd_with_data(spi, Command::PowerOff, &[0x00])?;
+            self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        }
+        self.cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
+        Ok(())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission, buffer)?;
+        Ok(())
+    }
+
+    fn update_partial_frame(
+        &mut self,
+        _spi: &mut SPI,
+        _buffer: &[u8],
+        _x: u32,
+        _y: u32,
+        _width: u32,
+        _height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // not supported by the display, nothing is sent
+        Ok(())
+    }
+
+    /// Refreshes the panel, powering it on before and off after the refresh if the panel
+    /// isn't powered on by the init sequence
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+
+        if P::POWER_PER_REFRESH {
+            self.power_off_pending = false;
+            self.cmd_with_data(spi, Command::PowerOff, &[0x00])?;
+            self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        }
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        let c = self.color;
+        let bg = QuadColor::colors_byte(c, c, c, c);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.command(spi, Command::DataStartTransmission)?;
+        self.interface
+            .clear_data(spi, bg, P::WIDTH / 4 * P::HEIGHT)?;
+        if self.interface.clear_aborted() {
+            return Ok(());
+        }
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn set_background_color(&mut self, color: QuadColor) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &QuadColor {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        P::WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        P::HEIGHT
+    }
+
+    /// Four pixels per byte
+    fn bytes_per_line(&self) -> u32 {
+        P::WIDTH / 4
+    }
+
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        Ok(())
+    }
+
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        if P::POWER_PER_REFRESH {
+            self.interface.set_busy_reason(BusyState::PoweringUp);
+            self.command(spi, Command::PowerOn)?;
+            self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        }
+
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.cmd_with_data(spi, Command::DisplayRefresh, &[0x00])?;
+        self.power_off_pending = P::POWER_PER_REFRESH;
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let state = self.interface.poll_refresh(IS_BUSY_LOW)?;
+        if state == RefreshState::Complete && self.power_off_pending {
+            self.power_off_pending = false;
+            self.cmd_with_data(spi, Command::PowerOff, &[0x00])?;
+        }
+        Ok(state)
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> InitSequence for GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> RowRendering<SPI, CS, BUSY, DC, RST, DELAY>
+    for GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_frame_with<F>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        f: F,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut(u32, &mut [u8]),
+    {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface.cmd(spi, Command::DataStartTransmission)?;
+
+        // four pixels per byte
+        let mut row = P::ROW;
+        let c = self.color;
+        let background = QuadColor::colors_byte(c, c, c, c);
+        render_rows(P::HEIGHT, row.as_mut(), background, f, |row| {
+            self.interface.frame_data(spi, row)
+        })
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> Snapshot for GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = QuadColor;
+
+    fn snapshot(&self) -> DriverSnapshot<QuadColor> {
+        self.interface
+            .state()
+            .snapshot(self.color, RefreshLut::Full)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<QuadColor>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.color = snapshot.background_color;
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, P::WIDTH as usize / 4 * P::HEIGHT as usize)?;
+        match plane {
+            Plane::Bw => self
+                .update_frame(spi, data, delay)
+                .map_err(RawPlaneError::Interface),
+            Plane::Chromatic => Err(RawPlaneError::UnsupportedPlane(plane)),
+        }
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess
+    for GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+{
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        GPanelEpd {
+            interface: DisplayInterface::new(cs, busy, dc, rst),
+            color: QuadColor::White,
+            init_sequence: P::INIT_SEQUENCE,
+            power_off_pending: false,
+            _panel: PhantomData,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> GPanelEpd<P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn command(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.cmd(spi, command)
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.cmd_with_data(spi, command, data)
+    }
+}
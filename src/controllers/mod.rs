@@ -0,0 +1,8 @@
+//! Helpers shared by the drivers of the panels with the same controller family
+//!
+//! The drivers of a family only differ in their constants, like the size, the init values and
+//! the LUTs, and wrap these helpers for the commands which are identical on all of them.
+
+pub(crate) mod g_panel;
+pub(crate) mod ssd168x;
+pub(crate) mod uc8151;
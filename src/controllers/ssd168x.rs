@@ -0,0 +1,329 @@
+//! The SSD1680, SSD1681 and SSD1683 controllers
+//!
+//! Used by the 1.54" V2, 2.13" V4, 2.66", 2.7" V2 and 2.9" V2 panels and their tri-color
+//! variants with the [type A commands](crate::type_a::command::Command). All of them address the
+//! RAM in bytes of 8 pixels in x and in lines in y, take the border waveform from the same
+//! register and load a LUT of 153 bytes, optionally followed by the driving voltages.
+
+use core::convert::TryFrom;
+
+use embedded_hal::{
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
+    digital::v2::*,
+};
+
+use crate::interface::DisplayInterface;
+use crate::traits::DisplayError;
+use crate::type_a::command::Command;
+
+/// The busy pin of the controllers is high while they are busy
+const IS_BUSY_LOW: bool = false;
+
+/// Bit of the display update sequences which measures the temperature with the internal sensor
+const LOAD_TEMPERATURE: u8 = 0x20;
+
+/// Length of the waveform part of a LUT
+pub(crate) const LUT_LEN: usize = 153;
+/// Length of a LUT with the end option, the gate and source voltages and VCOM after the waveform
+pub(crate) const LUT_WITH_VOLTAGES_LEN: usize = LUT_LEN + 6;
+
+/// Direction in which the address counter moves after every byte
+///
+/// The counter moves in x first and then to the next line.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DataEntryMode {
+    /// x and y decrement
+    DecrementXDecrementY = 0x00,
+    /// x increments and y decrements
+    IncrementXDecrementY = 0x01,
+    /// x decrements and y increments
+    DecrementXIncrementY = 0x02,
+    /// x and y increment, the order of the frames of the crate
+    IncrementXIncrementY = 0x03,
+}
+
+/// Waveform of the border around the active area
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BorderWaveform {
+    /// The border keeps the VCOM level, used for partial refreshes
+    Vcom,
+    /// The border floats
+    HiZ,
+    /// The border takes the transition of LUT 0 to 3 (0 is black, 1 is white)
+    Lut(u8),
+    /// The border follows the transitions of LUT 0 to 3 of the frame
+    FollowLut(u8),
+}
+
+impl BorderWaveform {
+    /// The byte of the border waveform control register
+    fn byte(self) -> u8 {
+        match self {
+            BorderWaveform::Vcom => 0x80,
+            BorderWaveform::HiZ => 0xC0,
+            BorderWaveform::Lut(lut) => lut & 0x03,
+            BorderWaveform::FollowLut(lut) => 0x04 | (lut & 0x03),
+        }
+    }
+}
+
+/// Sets the direction of the address counter
+pub(crate) fn set_data_entry_mode<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    mode: DataEntryMode,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd_with_data(spi, Command::DataEntryModeSetting, &[mode as u8])
+}
+
+/// Sets the waveform of the border
+pub(crate) fn set_border_waveform<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    border: BorderWaveform,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd_with_data(spi, Command::BorderWaveformControl, &[border.byte()])
+}
+
+/// Sets the display update `sequence` for the following master activation
+///
+/// If the sequence measures the temperature and the interface has the temperature of an
+/// external sensor, that one is written to the temperature register instead, so the LUT of the
+/// OTP is loaded for it.
+pub(crate) fn set_update_sequence<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    sequence: u8,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    let sequence = match interface.temperature() {
+        Some(celsius) if sequence & LOAD_TEMPERATURE != 0 => {
+            // the integer part in the first byte, 1/16 °C in the upper nibble of the second
+            interface.cmd_with_data(
+                spi,
+                Command::TemperatureSensorControl,
+                &[celsius as u8, 0x00],
+            )?;
+            sequence & !LOAD_TEMPERATURE
+        }
+        _ => sequence,
+    };
+    interface.cmd_with_data(spi, Command::DisplayUpdateControl2, &[sequence])
+}
+
+/// Measures the temperature with the internal sensor and reads it back in °C
+///
+/// Only the clock runs for the measurement, the LUT and the RAM stay as they are. The
+/// fraction of 1/16 °C in the second byte is dropped. See
+/// [`DisplayInterface::read_data`] for the wiring which the read needs.
+pub(crate) fn read_temperature<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+) -> Result<i8, DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.wait_until_idle(IS_BUSY_LOW)?;
+    interface.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xA1])?;
+    interface.cmd(spi, Command::MasterActivation)?;
+    interface.wait_until_idle(IS_BUSY_LOW)?;
+
+    let mut temperature = [0; 2];
+    interface.read_data(spi, Command::TemperatureSensorRead, &mut temperature)?;
+    Ok(temperature[0] as i8)
+}
+
+/// Loads a LUT of [`LUT_LEN`] bytes, or of [`LUT_WITH_VOLTAGES_LEN`] with the voltages
+///
+/// The voltages after the waveform are the end option, the gate voltage, the three source
+/// voltages and VCOM, in the layout of the LUTs of the Waveshare drivers. Only the first
+/// [`LUT_LEN`] bytes of other LUTs are sent.
+pub(crate) fn load_lut<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    lut: &[u8],
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    let (waveform, voltages) = lut.split_at(lut.len().min(LUT_LEN));
+    if voltages.len() >= LUT_WITH_VOLTAGES_LEN - LUT_LEN {
+        interface.cmd_with_data(spi, Command::WriteVcomRegister, &voltages[5..6])?;
+        interface.cmd_with_data(spi, Command::EndOption, &voltages[..1])?;
+        interface.cmd_with_data(spi, Command::GateVoltage, &voltages[1..2])?;
+        interface.cmd_with_data(spi, Command::SourceVoltage, &voltages[2..5])?;
+    }
+    interface.cmd_with_data(spi, Command::WriteLutRegister, waveform)
+}
+
+/// Selects the RAM window of `width` x `height` pixels at `x`, `y` and moves the counter to
+/// its start, see [`set_ram_area`] for `x_offset`
+///
+/// Waits for the controller between the window and the counter.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn set_window<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    x_offset: u8,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    let (end_x, end_y) = interface.window_end(x, y, width, height)?;
+    set_ram_area(interface, spi, x_offset, x, y, end_x, end_y)?;
+    interface.wait_until_idle(IS_BUSY_LOW)?;
+    set_ram_counter(interface, spi, x_offset, x, y)
+}
+
+/// Selects the RAM window from `start_x`, `start_y` to `end_x`, `end_y` (inclusive)
+///
+/// x is positioned in bytes, `x_offset` is the number of bytes the first source line of the
+/// panel is shifted by in the RAM.
+pub(crate) fn set_ram_area<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    x_offset: u8,
+    start_x: u32,
+    start_y: u32,
+    end_x: u32,
+    end_y: u32,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    let (start_byte, end_byte) = match (ram_x(x_offset, start_x), ram_x(x_offset, end_x)) {
+        (Some(start_byte), Some(end_byte)) if start_x <= end_x && start_y <= end_y => {
+            (start_byte, end_byte)
+        }
+        _ => return interface.check_window(false),
+    };
+
+    interface.cmd_with_data(
+        spi,
+        Command::SetRamXAddressStartEndPosition,
+        &[start_byte, end_byte],
+    )?;
+
+    // 2 Databytes: A[7:0] & 0..A[8] for each - start and end
+    interface.cmd_with_data(
+        spi,
+        Command::SetRamYAddressStartEndPosition,
+        &[
+            start_y as u8,
+            (start_y >> 8) as u8,
+            end_y as u8,
+            (end_y >> 8) as u8,
+        ],
+    )
+}
+
+/// Moves the RAM address counter to `x`, `y`, with x shifted by `x_offset` bytes like in
+/// [`set_ram_area`]
+pub(crate) fn set_ram_counter<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    x_offset: u8,
+    x: u32,
+    y: u32,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    let byte = match ram_x(x_offset, x) {
+        Some(byte) => byte,
+        None => return interface.check_window(false),
+    };
+    interface.cmd_with_data(spi, Command::SetRamXAddressCounter, &[byte])?;
+
+    // 2 Databytes: A[7:0] & 0..A[8]
+    interface.cmd_with_data(
+        spi,
+        Command::SetRamYAddressCounter,
+        &[y as u8, (y >> 8) as u8],
+    )
+}
+
+/// Returns the RAM byte holding column `x`, shifted by `x_offset` bytes, if it's addressable
+///
+/// The last 3 bits of x show the position inside a byte in the ram and aren't relevant.
+fn ram_x(x_offset: u8, x: u32) -> Option<u8> {
+    u8::try_from(u32::from(x_offset) + (x >> 3)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn border_waveform_bytes() {
+        assert_eq!(BorderWaveform::Vcom.byte(), 0x80);
+        assert_eq!(BorderWaveform::Lut(0).byte(), 0x00);
+        assert_eq!(BorderWaveform::Lut(1).byte(), 0x01);
+        assert_eq!(BorderWaveform::FollowLut(1).byte(), 0x05);
+    }
+
+    #[test]
+    fn ram_x_is_checked() {
+        assert_eq!(ram_x(0, 121), Some(15));
+        assert_eq!(ram_x(1, 2039), Some(255));
+        assert_eq!(ram_x(1, 2040), None);
+        assert_eq!(ram_x(0, u32::MAX), None);
+    }
+}
@@ -0,0 +1,342 @@
+//! The UC8151 (IL0373) controller and its relatives
+//!
+//! Used by the 2.7", 2.9" (B/C), 4.2" and the flexible 2.13" (D) and 2.9" (D) panels with the
+//! [type D commands](crate::type_d::command::Command). All of them take the same power
+//! setting, booster soft start, PLL and VCOM and data interval registers, and get the old and
+//! the new frame with the two data start transmission commands. The LUTs and the resolution
+//! are what sets the panels apart.
+
+use embedded_hal::{
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
+    digital::v2::*,
+};
+
+use crate::interface::DisplayInterface;
+use crate::settings::{FrameRate, TconTiming, VcomAndDataInterval};
+use crate::traits::DisplayError;
+use crate::type_d::command::Command;
+
+/// The busy pin of the controllers is low while they are busy
+const IS_BUSY_LOW: bool = true;
+
+/// VCOM of the lowest register value, in mV
+const VCOM_MAX_MILLIVOLTS: i16 = -100;
+/// VCOM of the highest register value `0x3A`, in mV
+const VCOM_MIN_MILLIVOLTS: i16 = -3000;
+/// VCOM step of the register, in mV
+const VCOM_STEP_MILLIVOLTS: i16 = 50;
+
+/// The data of one of the two frames of a refresh
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FrameData<'a> {
+    /// A frame buffer, sent through the frame hooks
+    Buffer(&'a [u8]),
+    /// The same byte `len` times
+    Fill(u8, u32),
+}
+
+/// Sets the soft start of the boosters of the phases A, B and C
+pub(crate) fn set_booster_soft_start<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    phases: [u8; 3],
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd_with_data(spi, Command::BoosterSoftStart, &phases)
+}
+
+/// Sets the frame rate of the PLL
+pub(crate) fn set_frame_rate<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    frame_rate: FrameRate,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd_with_data(spi, Command::PllControl, &[frame_rate.to_byte()])
+}
+
+/// Sets the border, the data polarity and the VCOM and data interval in the single byte layout
+pub(crate) fn set_vcom_and_data_interval<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    setting: VcomAndDataInterval,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd_with_data(
+        spi,
+        Command::VcomAndDataIntervalSetting,
+        &[setting.to_byte()],
+    )
+}
+
+/// Sets the resolution with a width of up to 255 and a height of up to 511 pixels
+///
+/// The 4.2" panel is too wide for this layout and uses [`set_wide_resolution`].
+pub(crate) fn set_resolution<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    width: u32,
+    height: u32,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd_with_data(spi, Command::ResolutionSetting, &resolution(width, height))
+}
+
+/// Sets the resolution with two bytes for the width and two for the height
+pub(crate) fn set_wide_resolution<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    width: u32,
+    height: u32,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd_with_data(
+        spi,
+        Command::ResolutionSetting,
+        &wide_resolution(width, height),
+    )
+}
+
+/// Sets the non-overlap periods of the gates and the sources
+pub(crate) fn set_tcon<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    timing: TconTiming,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd_with_data(spi, Command::TconSetting, &[timing.to_byte()])
+}
+
+/// Sets the VCOM DC voltage to the register value `vcom`, see [`vcom_byte`]
+pub(crate) fn set_vcom_dc<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    vcom: u8,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd_with_data(spi, Command::VcmDcSetting, &[vcom])
+}
+
+/// Measures the VCOM of the panel and returns it as register value, see [`vcom_millivolts`]
+///
+/// The vendor procedure: with the power on, the controller switches the gates for 5 s and
+/// measures the kick-back of the panel on VCOM. The result is only read, the VCOM DC setting
+/// stays as it is. See [`DisplayInterface::read_data`] for the wiring which the read needs.
+pub(crate) fn measure_vcom<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+) -> Result<u8, DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd(spi, Command::PowerOn)?;
+    interface.wait_until_idle(IS_BUSY_LOW)?;
+
+    // 5 s of measurement (AMVT `01`), with the gates switching (AMVE)
+    interface.cmd_with_data(spi, Command::AutoMeasurementVcom, &[0x11])?;
+    interface.wait_until_idle(IS_BUSY_LOW)?;
+
+    let mut vcom = [0];
+    interface.read_data(spi, Command::ReadVcomValue, &mut vcom)?;
+    Ok(vcom[0] & 0x3F)
+}
+
+/// Register value of the VCOM DC setting for `millivolts`, from -100 mV down to -3000 mV in
+/// steps of 50 mV
+///
+/// Values outside of the range are clamped and the ones between the steps rounded.
+pub(crate) fn vcom_byte(millivolts: i16) -> u8 {
+    let millivolts = millivolts.clamp(VCOM_MIN_MILLIVOLTS, VCOM_MAX_MILLIVOLTS);
+    ((VCOM_MAX_MILLIVOLTS - millivolts + VCOM_STEP_MILLIVOLTS / 2) / VCOM_STEP_MILLIVOLTS) as u8
+}
+
+/// VCOM in mV of the register value of the VCOM DC setting
+pub(crate) fn vcom_millivolts(vcom: u8) -> i16 {
+    VCOM_MAX_MILLIVOLTS - VCOM_STEP_MILLIVOLTS * (vcom & 0x3F) as i16
+}
+
+/// Selects the LUTs of the OTP by the temperature of an external sensor, if the interface has one
+///
+/// Without one the controller measures the temperature again.
+pub(crate) fn apply_temperature<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    match interface.temperature() {
+        Some(celsius) => {
+            interface.cmd_with_data(spi, Command::ForceTemperature, &[celsius as u8])?;
+            interface.cmd_with_data(spi, Command::CascadeSetting, &[0x02])
+        }
+        None => interface.cmd_with_data(spi, Command::CascadeSetting, &[0x00]),
+    }
+}
+
+/// Sends the old frame with the first and the new frame with the second data start transmission
+///
+/// The black/white panels compare both frames in their LUTs, the tri-color panels take the
+/// black and the chromatic plane instead.
+pub(crate) fn write_frames<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    old: FrameData,
+    new: FrameData,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    for (command, frame) in [
+        (Command::DataStartTransmission1, old),
+        (Command::DataStartTransmission2, new),
+    ] {
+        interface.cmd(spi, command)?;
+        match frame {
+            FrameData::Buffer(buffer) => interface.frame_data(spi, buffer)?,
+            FrameData::Fill(byte, len) => interface.data_x_times(spi, byte, len)?,
+        }
+    }
+    Ok(())
+}
+
+/// Fills the old frame with `old` and the new frame with `new`, `len` bytes each
+///
+/// Like the other clears the progress is reported and the abort check is polled while filling.
+pub(crate) fn clear_frames<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    old: u8,
+    new: u8,
+    len: u32,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    interface.cmd(spi, Command::DataStartTransmission1)?;
+    interface.clear_data(spi, old, len)?;
+    if interface.clear_aborted() {
+        return Ok(());
+    }
+    interface.cmd(spi, Command::DataStartTransmission2)?;
+    interface.clear_data(spi, new, len)
+}
+
+/// Data of the resolution setting: the width and the height with the high byte first
+///
+/// The width needs to be a multiple of 8, the controller ignores its lowest 3 bits.
+fn resolution(width: u32, height: u32) -> [u8; 3] {
+    [width as u8, (height >> 8) as u8, height as u8]
+}
+
+/// Data of the wide resolution setting: two bytes for each, the high byte first
+fn wide_resolution(width: u32, height: u32) -> [u8; 4] {
+    [
+        (width >> 8) as u8,
+        width as u8,
+        (height >> 8) as u8,
+        height as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolution_bytes() {
+        assert_eq!(resolution(104, 212), [0x68, 0x00, 0xD4]);
+        assert_eq!(resolution(128, 296), [0x80, 0x01, 0x28]);
+        assert_eq!(wide_resolution(400, 300), [0x01, 0x90, 0x01, 0x2C]);
+    }
+
+    #[test]
+    fn vcom_conversion() {
+        assert_eq!(vcom_byte(-100), 0x00);
+        assert_eq!(vcom_byte(-1000), 0x12);
+        // the -1.15 V printed on a flex cable, and rounded to the closest step
+        assert_eq!(vcom_byte(-1150), 0x15);
+        assert_eq!(vcom_byte(-1170), 0x15);
+        // clamped to the range of the register
+        assert_eq!(vcom_byte(0), 0x00);
+        assert_eq!(vcom_byte(-5000), 0x3A);
+
+        assert_eq!(vcom_millivolts(0x0A), -600);
+        assert_eq!(vcom_millivolts(0x3A), -3000);
+        for vcom in 0..=0x3A {
+            assert_eq!(vcom_byte(vcom_millivolts(vcom)), vcom);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Debugging helpers which need the standard library
+//!
+//! [`write_diff_image`] renders the old frame, the new frame and an overlay of both next to
+//! each other into a [PPM](https://netpbm.sourceforge.net/doc/ppm.html) image, which most image
+//! viewers can open. The window which was sent with a partial update is outlined in blue on the
+//! new frame and the overlay, so e.g. a block which ended up shifted is easy to spot.
+//!
+//! In the overlay pixels which turned black are red, pixels which turned white are green and
+//! unchanged black pixels are gray.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//! use epd_waveshare::debug::{save_diff_image, Window};
+//!
+//! let old = [0xFF; 16 / 8 * 16];
+//! let mut new = old;
+//! new[10] = 0x00;
+//!
+//! let window = Window { x: 0, y: 4, width: 16, height: 4 };
+//! save_diff_image("partial.ppm", &old, &new, 16, 16, Some(window)).unwrap();
+//!```
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Pixels between the three parts of the image
+const GAP: u32 = 4;
+
+const WHITE: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const BLACK: [u8; 3] = [0x00, 0x00, 0x00];
+const GRAY: [u8; 3] = [0x60, 0x60, 0x60];
+const RED: [u8; 3] = [0xFF, 0x00, 0x00];
+const GREEN: [u8; 3] = [0x00, 0xC0, 0x00];
+const BLUE: [u8; 3] = [0x00, 0x00, 0xFF];
+const BACKGROUND: [u8; 3] = [0xC0, 0xC0, 0xC0];
+
+/// A rectangular part of a frame, like the one sent with [`update_partial_frame`](crate::prelude::WaveshareDisplay::update_partial_frame)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    /// Left edge in pixels
+    pub x: u32,
+    /// Top edge in pixels
+    pub y: u32,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+}
+
+impl Window {
+    /// Whether the pixel lies on the outline of the window
+    fn is_outline(&self, x: u32, y: u32) -> bool {
+        let inside =
+            x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height;
+        inside
+            && (x == self.x
+                || x == self.x + self.width - 1
+                || y == self.y
+                || y == self.y + self.height - 1)
+    }
+}
+
+/// Writes the old frame, the new frame and their overlay as a binary PPM image to `out`
+///
+/// Both frames are black/white buffers (or single planes of tri-color buffers) of
+/// `width` x `height` pixels. `window` is outlined if given.
+pub fn write_diff_image<W: Write>(
+    out: &mut W,
+    old: &[u8],
+    new: &[u8],
+    width: u32,
+    height: u32,
+    window: Option<Window>,
+) -> io::Result<()> {
+    let image_width = 3 * width + 2 * GAP;
+    write!(out, "P6\n{} {}\n255\n", image_width, height)?;
+
+    for y in 0..height {
+        for part in 0..3 {
+            if part > 0 {
+                for _ in 0..GAP {
+                    out.write_all(&BACKGROUND)?;
+                }
+            }
+            for x in 0..width {
+                let was_white = is_white(old, width, x, y);
+                let white = is_white(new, width, x, y);
+                let outlined = part > 0 && window.is_some_and(|w| w.is_outline(x, y));
+                let color = match (part, was_white, white) {
+                    _ if outlined => BLUE,
+                    (0, true, _) | (1, _, true) => WHITE,
+                    (0, false, _) | (1, _, false) => BLACK,
+                    (_, true, true) => WHITE,
+                    (_, false, false) => GRAY,
+                    (_, true, false) => RED,
+                    (_, false, true) => GREEN,
+                };
+                out.write_all(&color)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Saves the old frame, the new frame and their overlay as a PPM image file
+///
+/// See [`write_diff_image`].
+pub fn save_diff_image<P: AsRef<Path>>(
+    path: P,
+    old: &[u8],
+    new: &[u8],
+    width: u32,
+    height: u32,
+    window: Option<Window>,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_diff_image(&mut out, old, new, width, height, window)?;
+    out.flush()
+}
+
+/// Whether the pixel is set in a buffer with padded rows, missing bytes count as white
+fn is_white(buffer: &[u8], width: u32, x: u32, y: u32) -> bool {
+    let row_len = (width as usize).div_ceil(8);
+    let index = y as usize * row_len + x as usize / 8;
+    buffer
+        .get(index)
+        .map_or(true, |byte| byte & (0x80 >> (x % 8)) != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn diff_image() {
+        // 8x2 pixels, first row unchanged white, second row turns from black to white/black
+        let old = [0xFF, 0x00];
+        let new = [0xFF, 0xF0];
+        let window = Window {
+            x: 0,
+            y: 1,
+            width: 1,
+            height: 1,
+        };
+        let mut out = Vec::new();
+        write_diff_image(&mut out, &old, &new, 8, 2, Some(window)).unwrap();
+
+        let header = b"P6\n32 2\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels: Vec<&[u8]> = out[header.len()..].chunks(3).collect();
+        assert_eq!(pixels.len(), 32 * 2);
+
+        let row = &pixels[32..];
+        // old frame
+        assert_eq!(row[0], BLACK);
+        // new frame, the window is outlined
+        assert_eq!(row[8 + 4], BLUE);
+        assert_eq!(row[8 + 4 + 1], WHITE);
+        assert_eq!(row[8 + 4 + 4], BLACK);
+        // overlay
+        let overlay = &row[2 * (8 + 4)..];
+        assert_eq!(overlay[0], BLUE);
+        assert_eq!(overlay[1], GREEN);
+        assert_eq!(overlay[4], GRAY);
+    }
+}
@@ -0,0 +1,209 @@
+//! Drawing grayscale content on black/white displays
+//!
+//! Anti-aliased fonts and grayscale images use [`Gray8`] colors, which the black/white displays
+//! can't show. Thresholding them loses all smooth edges, while dithering the whole image needs a
+//! second buffer. [`DiffusionTarget`] sits in between: it accepts [`Gray8`] pixels, decides for
+//! black or white while they are drawn and spreads the error of every decision to the
+//! neighbouring pixels ([Floyd-Steinberg](https://en.wikipedia.org/wiki/Floyd%E2%80%93Steinberg_dithering)).
+//! Only the errors of the current and the next row are kept.
+//!
+//! The errors travel in the order in which the pixels are drawn, so this works best for content
+//! which is drawn row by row, like images and text. Every draw call starts without errors.
+//!
+//! # Example
+//!
+//!```rust
+//! use embedded_graphics::{
+//!     pixelcolor::Gray8,
+//!     prelude::*,
+//!     primitives::{PrimitiveStyle, Rectangle},
+//! };
+//! use epd_waveshare::{diffusion::DiffusionTarget, epd2in9::Display2in9};
+//!
+//! let mut display = Display2in9::default();
+//! let mut errors = [0i16; 2 * 128];
+//! let mut gray = DiffusionTarget::new(&mut display, &mut errors);
+//!
+//! // a 50% gray area ends up as a checkerboard like pattern
+//! Rectangle::new(Point::new(0, 0), Size::new(64, 32))
+//!     .into_styled(PrimitiveStyle::with_fill(Gray8::new(128)))
+//!     .draw(&mut gray)
+//!     .unwrap();
+//!```
+
+use crate::graphics::BufferTooSmall;
+use embedded_graphics_core::pixelcolor::{BinaryColor, Gray8, GrayColor};
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
+
+/// Draws [`Gray8`] pixels with error diffusion on a black/white target, see the [module docs](self)
+pub struct DiffusionTarget<'a, D> {
+    target: &'a mut D,
+    /// Errors of the current row, followed by the errors of the next row
+    errors: &'a mut [i16],
+    width: usize,
+    /// Row which the first half of `errors` belongs to
+    row: Option<i32>,
+}
+
+impl<'a, D> DiffusionTarget<'a, D>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    /// Wraps `target`, `errors` holds the errors of two rows of the target
+    ///
+    /// # Panics
+    ///
+    /// If `errors` is shorter than twice the width of the target, the fallible alternative is
+    /// [`try_new`](Self::try_new).
+    pub fn new(target: &'a mut D, errors: &'a mut [i16]) -> Self {
+        let width = target.bounding_box().size.width as usize;
+        assert!(errors.len() >= 2 * width);
+        DiffusionTarget {
+            target,
+            errors: &mut errors[..2 * width],
+            width,
+            row: None,
+        }
+    }
+
+    /// Wraps `target` like [`new`](Self::new), unless `errors` is too short
+    ///
+    /// The lengths in the error are numbers of errors instead of bytes.
+    pub fn try_new(target: &'a mut D, errors: &'a mut [i16]) -> Result<Self, BufferTooSmall> {
+        let width = target.bounding_box().size.width as usize;
+        let len = errors.len();
+        match errors.get_mut(..2 * width) {
+            Some(errors) => Ok(DiffusionTarget {
+                target,
+                errors,
+                width,
+                row: None,
+            }),
+            None => Err(BufferTooSmall {
+                needed: 2 * width,
+                len,
+            }),
+        }
+    }
+
+    /// Returns the wrapped target
+    pub fn target(&mut self) -> &mut D {
+        self.target
+    }
+
+    /// Moves the errors along when the pixels continue in another row
+    fn select_row(&mut self, y: i32) {
+        match self.row {
+            Some(row) if row == y => {}
+            Some(row) if row.checked_add(1) == Some(y) => {
+                self.errors.copy_within(self.width.., 0);
+                self.errors[self.width..].fill(0);
+            }
+            _ => self.errors.fill(0),
+        }
+        self.row = Some(y);
+    }
+
+    /// Decides the color of a pixel and spreads its error
+    fn diffuse(&mut self, x: usize, luma: u8) -> BinaryColor {
+        let value = i16::from(luma) + self.errors[x];
+        let (color, error) = if value >= 128 {
+            (BinaryColor::Off, value - 255)
+        } else {
+            (BinaryColor::On, value)
+        };
+
+        let (current, next) = self.errors.split_at_mut(self.width);
+        if x + 1 < self.width {
+            current[x + 1] += error * 7 / 16;
+            next[x + 1] += error / 16;
+        }
+        if x > 0 {
+            next[x - 1] += error * 3 / 16;
+        }
+        next[x] += error * 5 / 16;
+        color
+    }
+}
+
+impl<'a, D> DrawTarget for DiffusionTarget<'a, D>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    type Color = Gray8;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.row = None;
+        let offset = self.target.bounding_box().top_left.x;
+        for Pixel(point, color) in pixels {
+            let x = i64::from(point.x) - i64::from(offset);
+            if x < 0 || x as usize >= self.width {
+                continue;
+            }
+            self.select_row(point.y);
+            let color = self.diffuse(x as usize, color.luma());
+            self.target
+                .draw_iter(core::iter::once(Pixel(point, color)))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, D> Dimensions for DiffusionTarget<'a, D>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::graphics::{Display, VarDisplay};
+
+    fn draw_row(luma: u8) -> [u8; 2] {
+        let mut buffer = [0u8; 2];
+        let mut display = VarDisplay::new(16, 1, &mut buffer);
+        display.clear_buffer(Color::White);
+        let mut errors = [0i16; 2 * 16];
+        let mut gray = DiffusionTarget::new(&mut display, &mut errors);
+        gray.draw_iter((0..16).map(|x| Pixel(Point::new(x, 0), Gray8::new(luma))))
+            .unwrap();
+        buffer
+    }
+
+    #[test]
+    fn extremes_stay_solid() {
+        assert_eq!(draw_row(255), [0xFF, 0xFF]);
+        assert_eq!(draw_row(0), [0x00, 0x00]);
+    }
+
+    #[test]
+    fn too_few_errors() {
+        let mut buffer = [0u8; 2];
+        let mut display = VarDisplay::new(16, 1, &mut buffer);
+        let mut errors = [0i16; 2 * 16 - 1];
+        assert_eq!(
+            DiffusionTarget::try_new(&mut display, &mut errors).err(),
+            Some(BufferTooSmall {
+                needed: 32,
+                len: 31
+            })
+        );
+    }
+
+    #[test]
+    fn half_gray_is_mixed() {
+        let row = draw_row(128);
+        let white = row.iter().map(|byte| byte.count_ones()).sum::<u32>();
+        assert!((6..=10).contains(&white), "{} white pixels", white);
+    }
+}
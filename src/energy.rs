@@ -0,0 +1,167 @@
+//! Rough estimates of the energy used by the display
+//!
+//! Every driver has an estimate of the energy of its refreshes as
+//! [`WaveshareDisplay::ENERGY`](crate::traits::WaveshareDisplay::ENERGY). The values are the
+//! typical refresh power of the Waveshare specifications multiplied with the duration of the
+//! refresh, so they're only good for a rough budget, e.g. in the telemetry of battery powered
+//! devices. The [`EnergyMeter`] sums them up for the refreshes which were done.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::{energy::{EnergyMeter, RefreshKind}, epd2in9::Epd2in9, prelude::*};
+//! # type Epd = Epd2in9<
+//! #     embedded_hal_mock::spi::Mock,
+//! #     embedded_hal_mock::pin::Mock,
+//! #     embedded_hal_mock::pin::Mock,
+//! #     embedded_hal_mock::pin::Mock,
+//! #     embedded_hal_mock::pin::Mock,
+//! #     embedded_hal_mock::delay::MockNoop,
+//! # >;
+//!
+//! let mut meter = EnergyMeter::new();
+//! // after each refresh of the display
+//! meter.record(Epd::ENERGY, RefreshKind::Full);
+//! meter.record(Epd::ENERGY, RefreshKind::Partial);
+//!
+//! assert_eq!(meter.refreshes(RefreshKind::Full), 1);
+//! assert_eq!(meter.total_microjoules(), 52_800 + 7_920);
+//!```
+
+use crate::traits::RefreshLut;
+
+/// The kinds of refreshes with a different energy use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshKind {
+    /// Refresh of the whole display with the full waveform
+    Full,
+    /// Refresh of the whole display with a faster waveform, e.g. [`RefreshLut::Quick`]
+    Fast,
+    /// Refresh of a part of the display
+    Partial,
+}
+
+impl From<RefreshLut> for RefreshKind {
+    fn from(lut: RefreshLut) -> Self {
+        match lut {
+            RefreshLut::Full => RefreshKind::Full,
+            RefreshLut::Quick | RefreshLut::Ultrafast => RefreshKind::Fast,
+        }
+    }
+}
+
+/// Estimated energy of one refresh of each kind in microjoules
+///
+/// Displays without a faster or partial refresh use the energy of the full refresh for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshEnergy {
+    /// Energy of a full refresh
+    pub full: u32,
+    /// Energy of a fast refresh
+    pub fast: u32,
+    /// Energy of a partial refresh
+    pub partial: u32,
+}
+
+impl RefreshEnergy {
+    /// The same energy for all kinds of refreshes
+    pub const fn uniform(microjoules: u32) -> Self {
+        RefreshEnergy {
+            full: microjoules,
+            fast: microjoules,
+            partial: microjoules,
+        }
+    }
+
+    /// Energy of a refresh of the given kind in microjoules
+    pub const fn of(&self, kind: RefreshKind) -> u32 {
+        match kind {
+            RefreshKind::Full => self.full,
+            RefreshKind::Fast => self.fast,
+            RefreshKind::Partial => self.partial,
+        }
+    }
+}
+
+/// Sums up the estimated energy of refreshes, see the [module docs](self)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EnergyMeter {
+    total: u64,
+    full: u32,
+    fast: u32,
+    partial: u32,
+}
+
+impl EnergyMeter {
+    /// Creates a meter without any refreshes
+    pub const fn new() -> Self {
+        EnergyMeter {
+            total: 0,
+            full: 0,
+            fast: 0,
+            partial: 0,
+        }
+    }
+
+    /// Adds a refresh of the given kind of a display with the given energy estimate
+    pub fn record(&mut self, energy: RefreshEnergy, kind: RefreshKind) {
+        self.total += u64::from(energy.of(kind));
+        let count = match kind {
+            RefreshKind::Full => &mut self.full,
+            RefreshKind::Fast => &mut self.fast,
+            RefreshKind::Partial => &mut self.partial,
+        };
+        *count = count.saturating_add(1);
+    }
+
+    /// Estimated energy of all recorded refreshes in microjoules
+    pub fn total_microjoules(&self) -> u64 {
+        self.total
+    }
+
+    /// Estimated energy of all recorded refreshes in millijoules, rounded down
+    pub fn total_millijoules(&self) -> u64 {
+        self.total / 1000
+    }
+
+    /// Number of recorded refreshes of the given kind
+    pub fn refreshes(&self, kind: RefreshKind) -> u32 {
+        match kind {
+            RefreshKind::Full => self.full,
+            RefreshKind::Fast => self.fast,
+            RefreshKind::Partial => self.partial,
+        }
+    }
+
+    /// Starts again from zero, e.g. after the value was reported
+    pub fn reset(&mut self) {
+        *self = EnergyMeter::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meter_sums_up_refreshes() {
+        let energy = RefreshEnergy {
+            full: 1000,
+            fast: 300,
+            partial: 200,
+        };
+        let mut meter = EnergyMeter::new();
+        meter.record(energy, RefreshKind::Full);
+        meter.record(energy, RefreshLut::Quick.into());
+        meter.record(energy, RefreshKind::Partial);
+        meter.record(energy, RefreshKind::Partial);
+
+        assert_eq!(meter.total_microjoules(), 1700);
+        assert_eq!(meter.total_millijoules(), 1);
+        assert_eq!(meter.refreshes(RefreshKind::Fast), 1);
+        assert_eq!(meter.refreshes(RefreshKind::Partial), 2);
+
+        meter.reset();
+        assert_eq!(meter, EnergyMeter::default());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::buffer_len;
+use crate::color::Color;
+use crate::epd10in2::{BAND_HEIGHT, DEFAULT_BACKGROUND_COLOR, HEIGHT, WIDTH};
+use crate::graphics::{Display, DisplayRotation, Mirroring};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use embedded_graphics_core::prelude::*;
+
+/// Full size buffer for use with the 10in2 EPD
+///
+/// Can also be manually constructed:
+/// `buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value(); WIDTH / 8 * HEIGHT]`
+pub struct Display10in2 {
+    buffer: [u8; buffer_len(WIDTH as usize, HEIGHT as usize)],
+    rotation: DisplayRotation,
+    mirroring: Mirroring,
+}
+
+impl Default for Display10in2 {
+    fn default() -> Self {
+        Display10in2 {
+            buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value();
+                buffer_len(WIDTH as usize, HEIGHT as usize)],
+            rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
+        }
+    }
+}
+
+impl DrawTarget for Display10in2 {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.draw_helper(WIDTH, HEIGHT, pixel)?;
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Display10in2 {
+    fn size(&self) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+}
+
+impl Display for Display10in2 {
+    fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn get_mut_buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
+
+/// Buffer for a band of [`BAND_HEIGHT`] full lines of the 10in2 EPD
+///
+/// Takes the pixels of the whole frame and only keeps the ones between [`top`](Self::top) and
+/// the last line of the band, so the same scene can be drawn into every band of a frame and
+/// sent with [`update_band`](super::Epd10in2::update_band). Bands are always in the native
+/// orientation of the display.
+pub struct Band10in2 {
+    buffer: [u8; buffer_len(WIDTH as usize, BAND_HEIGHT as usize)],
+    top: u32,
+}
+
+impl Default for Band10in2 {
+    fn default() -> Self {
+        Band10in2 {
+            buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value();
+                buffer_len(WIDTH as usize, BAND_HEIGHT as usize)],
+            top: 0,
+        }
+    }
+}
+
+impl Band10in2 {
+    /// Moves the band to start at line `top` of the frame, the buffer is kept as it is
+    pub fn set_top(&mut self, top: u32) {
+        self.top = top;
+    }
+
+    /// First line of the frame in the band
+    pub fn top(&self) -> u32 {
+        self.top
+    }
+
+    /// Clears the buffer with the chosen color
+    pub fn clear_buffer(&mut self, color: Color) {
+        for elem in self.buffer.iter_mut() {
+            *elem = color.get_byte_value();
+        }
+    }
+
+    /// Returns the lines of the band which are inside of the frame
+    pub fn buffer(&self) -> &[u8] {
+        let lines = HEIGHT.saturating_sub(self.top).min(BAND_HEIGHT);
+        &self.buffer[..buffer_len(WIDTH as usize, lines as usize)]
+    }
+}
+
+impl DrawTarget for Band10in2 {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x < 0 || point.x as u32 >= WIDTH || point.y < self.top as i32 {
+                continue;
+            }
+            let x = point.x as u32;
+            let y = point.y as u32 - self.top;
+            if y >= BAND_HEIGHT {
+                continue;
+            }
+            let index = (y * WIDTH / 8 + x / 8) as usize;
+            let bit = 0x80 >> (x % 8);
+            if let Some(byte) = self.buffer.get_mut(index) {
+                // cleared for black and set for white
+                if color == BinaryColor::Off {
+                    *byte |= bit;
+                } else {
+                    *byte &= !bit;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Band10in2 {
+    fn size(&self) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test buffer length
+    #[test]
+    fn graphics_size() {
+        let display = Display10in2::default();
+        assert_eq!(display.buffer().len(), 76800);
+    }
+
+    // test default background color on all bytes
+    #[test]
+    fn graphics_default() {
+        let display = Display10in2::default();
+        for &byte in display.buffer() {
+            assert_eq!(byte, DEFAULT_BACKGROUND_COLOR.get_byte_value());
+        }
+    }
+
+    #[test]
+    fn band_keeps_its_lines() {
+        let mut band = Band10in2::default();
+        band.set_top(BAND_HEIGHT);
+        for y in [0, BAND_HEIGHT, 2 * BAND_HEIGHT - 1, 2 * BAND_HEIGHT] {
+            let _ = Pixel(Point::new(9, y as i32), BinaryColor::On).draw(&mut band);
+        }
+
+        let buffer = band.buffer();
+        assert_eq!(buffer.len(), 960 / 8 * 64);
+        assert_eq!(buffer[1], 0b1011_1111);
+        assert_eq!(buffer[(BAND_HEIGHT - 1) as usize * 120 + 1], 0b1011_1111);
+        let black = buffer.iter().filter(|&&byte| byte != 0xFF).count();
+        assert_eq!(black, 2);
+
+        // the last band ends with the frame
+        band.set_top(HEIGHT - 16);
+        assert_eq!(band.buffer().len(), 960 / 8 * 16);
+    }
+}
@@ -0,0 +1,347 @@
+//! Connection of the four controllers of the 12.48" display
+//!
+//! The panel is split into quadrants with a master and a slave controller in each half. The
+//! controllers of a half share their data/command and reset pins and have their own chip
+//! select and busy pins, all of them sit on the same SPI bus.
+
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::traits::{Command, DisplayError, Error, PinError};
+
+/// Bytes of a line of the whole display
+pub(crate) const LINE_LEN: usize = 163;
+/// Bytes of a line of the left quadrants, the right ones take the other 82
+const LEFT_LEN: usize = 81;
+/// Lines of a half
+const HALF_HEIGHT: usize = 492;
+
+/// The pins of the two controllers of one half of the display
+pub struct HalfPins<CS, BUSY, DC, RST> {
+    /// Chip select of the master, M1 at the top and M2 at the bottom
+    pub master_cs: CS,
+    /// Chip select of the slave, S1 at the top and S2 at the bottom
+    pub slave_cs: CS,
+    /// Busy pin of the master, low while it is busy
+    pub master_busy: BUSY,
+    /// Busy pin of the slave, low while it is busy
+    pub slave_busy: BUSY,
+    /// Data/Command pin of both controllers
+    pub dc: DC,
+    /// Reset pin of both controllers
+    pub rst: RST,
+}
+
+/// One of the four controllers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Controller {
+    /// Top left
+    M1,
+    /// Top right
+    S1,
+    /// Bottom right
+    M2,
+    /// Bottom left
+    S2,
+}
+
+impl Controller {
+    /// All controllers, in the order the Waveshare driver sends the quadrants
+    pub(crate) const ALL: [Controller; 4] = [
+        Controller::S2,
+        Controller::M2,
+        Controller::M1,
+        Controller::S1,
+    ];
+    /// The masters, which generate the driving voltages
+    pub(crate) const MASTERS: [Controller; 2] = [Controller::M1, Controller::M2];
+
+    /// Bytes of the lines of the whole display which belong to the quadrant
+    pub(crate) fn bytes(self) -> Range<usize> {
+        match self {
+            Controller::M1 | Controller::S2 => 0..LEFT_LEN,
+            Controller::S1 | Controller::M2 => LEFT_LEN..LINE_LEN,
+        }
+    }
+
+    /// Lines of the whole display which belong to the quadrant
+    pub(crate) fn lines(self) -> Range<usize> {
+        match self {
+            Controller::M1 | Controller::S1 => 0..HALF_HEIGHT,
+            Controller::M2 | Controller::S2 => HALF_HEIGHT..2 * HALF_HEIGHT,
+        }
+    }
+
+    /// Number of bytes of the quadrant
+    pub(crate) fn len(self) -> usize {
+        self.bytes().len() * self.lines().len()
+    }
+
+    /// Sources and gates of the quadrant for `TconResolution`
+    pub(crate) fn resolution(self) -> [u8; 4] {
+        let width = self.bytes().len() * 8;
+        let height = self.lines().len();
+        [
+            (width >> 8) as u8,
+            width as u8,
+            (height >> 8) as u8,
+            height as u8,
+        ]
+    }
+
+    /// The lines of the quadrant in a frame of the whole display
+    pub(crate) fn rows(self, frame: &[u8]) -> impl Iterator<Item = &[u8]> {
+        let bytes = self.bytes();
+        frame
+            .chunks(LINE_LEN)
+            .skip(self.lines().start)
+            .take(self.lines().len())
+            .filter_map(move |line| line.get(bytes.clone()))
+    }
+
+    fn is_top(self) -> bool {
+        matches!(self, Controller::M1 | Controller::S1)
+    }
+}
+
+/// The interface of the four controllers, which multiplexes the pins of both halves
+pub(crate) struct CascadeInterface<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// SPI
+    _spi: PhantomData<SPI>,
+    /// DELAY
+    _delay: PhantomData<DELAY>,
+    /// Pins of M1 and S1
+    top: HalfPins<CS, BUSY, DC, RST>,
+    /// Pins of M2 and S2
+    bottom: HalfPins<CS, BUSY, DC, RST>,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> CascadeInterface<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Creates a new `CascadeInterface` struct
+    pub(crate) fn new(
+        top: HalfPins<CS, BUSY, DC, RST>,
+        bottom: HalfPins<CS, BUSY, DC, RST>,
+    ) -> Self {
+        CascadeInterface {
+            _spi: PhantomData,
+            _delay: PhantomData,
+            top,
+            bottom,
+        }
+    }
+
+    /// Basic function for sending [Commands](Command) to the `controllers` at once
+    pub(crate) fn cmd<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        controllers: &[Controller],
+        command: T,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.write(spi, controllers, false, &[command.address()])
+    }
+
+    /// Basic function for sending an array of u8-values of data to the `controllers` at once
+    pub(crate) fn data(
+        &mut self,
+        spi: &mut SPI,
+        controllers: &[Controller],
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.write(spi, controllers, true, data)
+    }
+
+    /// Basic function for sending [Commands](Command) and the data belonging to it
+    pub(crate) fn cmd_with_data<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        controllers: &[Controller],
+        command: T,
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.cmd(spi, controllers, command)?;
+        self.data(spi, controllers, data)
+    }
+
+    /// Sends the same byte of data `repetitions` times to one controller
+    pub(crate) fn data_x_times(
+        &mut self,
+        spi: &mut SPI,
+        controller: Controller,
+        val: u8,
+        repetitions: usize,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let chunk = [val; crate::FRAME_CHUNK_LEN];
+        let mut sent = 0;
+        while sent < repetitions {
+            let len = (repetitions - sent).min(chunk.len());
+            self.data(spi, &[controller], &chunk[..len])?;
+            sent += len;
+        }
+        Ok(())
+    }
+
+    // spi write helper/abstraction function, the selected controllers listen at the same time
+    fn write(
+        &mut self,
+        spi: &mut SPI,
+        controllers: &[Controller],
+        is_data: bool,
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut selected = Ok(());
+        for &controller in controllers {
+            let half = self.half(controller);
+            let dc = if is_data {
+                half.dc.set_high()
+            } else {
+                half.dc.set_low()
+            };
+            let cs = if controller == Controller::M1 || controller == Controller::M2 {
+                half.master_cs.set_low()
+            } else {
+                half.slave_cs.set_low()
+            };
+            selected = selected
+                .and(dc.map_err(|e| Error::Pin(PinError::Dc(e))))
+                .and(cs.map_err(|e| Error::Pin(PinError::Cs(e))));
+        }
+
+        // Be careful!! Linux has a default limit of 4096 bytes per spi transfer
+        // see https://raspberrypi.stackexchange.com/questions/65595/spi-transfer-fails-with-buffer-size-greater-than-4096
+        let result = selected.and_then(|()| {
+            if cfg!(target_os = "linux") {
+                data.chunks(4096)
+                    .try_for_each(|data_chunk| spi.write(data_chunk))
+            } else {
+                spi.write(data)
+            }
+            .map_err(Error::Spi)
+        });
+
+        // deselect all of them, also after a failure
+        let mut deselected = Ok(());
+        for half in [&mut self.top, &mut self.bottom] {
+            deselected = deselected
+                .and(half.master_cs.set_high())
+                .and(half.slave_cs.set_high());
+        }
+        result.and(deselected.map_err(|e| Error::Pin(PinError::Cs(e))))
+    }
+
+    fn half(&mut self, controller: Controller) -> &mut HalfPins<CS, BUSY, DC, RST> {
+        if controller.is_top() {
+            &mut self.top
+        } else {
+            &mut self.bottom
+        }
+    }
+
+    /// Waits until none of the controllers is busy anymore
+    pub(crate) fn wait_until_idle(&mut self) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        while self.read_busy()? {}
+        Ok(())
+    }
+
+    /// Checks if any of the controllers is still busy, their busy pins are low while they are
+    ///
+    /// A failed read counts as idle, see [read_busy()](CascadeInterface::read_busy()).
+    pub(crate) fn is_busy(&self) -> bool {
+        self.read_busy().unwrap_or(false)
+    }
+
+    /// Checks like [is_busy()](CascadeInterface::is_busy()), but returns a failed read
+    pub(crate) fn read_busy(&self) -> Result<bool, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        for half in [&self.top, &self.bottom] {
+            for busy in [&half.master_busy, &half.slave_busy] {
+                if busy.is_low().map_err(|e| Error::Pin(PinError::Busy(e)))? {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Resets all controllers, both halves at the same time
+    pub(crate) fn reset(
+        &mut self,
+        delay: &mut DELAY,
+        duration: u8,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.set_rst(true)?;
+        delay.delay_ms(200);
+
+        self.set_rst(false)?;
+        delay.delay_ms(duration);
+        self.set_rst(true)?;
+        delay.delay_ms(200);
+        Ok(())
+    }
+
+    /// Sets the reset pins of both halves
+    fn set_rst(&mut self, high: bool) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        for half in [&mut self.top, &mut self.bottom] {
+            if high {
+                half.rst.set_high()
+            } else {
+                half.rst.set_low()
+            }
+            .map_err(|e| Error::Pin(PinError::Rst(e)))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quadrants_split_the_frame() {
+        assert_eq!(Controller::M1.resolution(), [0x02, 0x88, 0x01, 0xEC]);
+        assert_eq!(Controller::S1.resolution(), [0x02, 0x90, 0x01, 0xEC]);
+        assert_eq!(
+            Controller::ALL.iter().map(|c| c.len()).sum::<usize>(),
+            LINE_LEN * 2 * HALF_HEIGHT
+        );
+
+        // every byte of the frame holds the number of its quadrant
+        let mut frame = [0u8; LINE_LEN * 2 * HALF_HEIGHT];
+        for (y, line) in frame.chunks_mut(LINE_LEN).enumerate() {
+            for (x, byte) in line.iter_mut().enumerate() {
+                *byte = match (y < HALF_HEIGHT, x < LEFT_LEN) {
+                    (true, true) => 1,
+                    (true, false) => 2,
+                    (false, false) => 3,
+                    (false, true) => 4,
+                };
+            }
+        }
+        for (controller, quadrant) in [
+            (Controller::M1, 1),
+            (Controller::S1, 2),
+            (Controller::M2, 3),
+            (Controller::S2, 4),
+        ] {
+            let mut len = 0;
+            for row in controller.rows(&frame) {
+                assert_eq!(row.len(), controller.bytes().len());
+                assert!(row.iter().all(|&byte| byte == quadrant));
+                len += row.len();
+            }
+            assert_eq!(len, controller.len());
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! SPI Commands for the Waveshare 12.48" E-Ink Display
+
+use crate::traits;
+
+/// Epd12in48 commands
+///
+/// Each of the four UC8179 controllers understands the same commands, most of them are sent to
+/// all of them at once. Should rarely (never?) be needed directly.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// LUT selection, KW/KWR mode, gate scan and source shift direction, booster switch
+    PanelSetting = 0x00,
+    /// Internal or external power and the driving voltages, sent to the masters
+    PowerSetting = 0x01,
+    /// Switches off the charge pump and the drivers, the registers are kept
+    PowerOff = 0x02,
+    /// Switches on the charge pump and the drivers, sent to the masters
+    PowerOn = 0x04,
+    /// Soft start of the booster, sent to the masters
+    BoosterSoftStart = 0x06,
+    /// Deep sleep after 0xA5, only a reset wakes the controller up again
+    DeepSleep = 0x07,
+    /// Old frame, only used by a partial refresh
+    DataStartTransmission1 = 0x10,
+    /// Refreshes the display, every controller drives its quadrant
+    DisplayRefresh = 0x12,
+    /// New frame
+    DataStartTransmission2 = 0x13,
+    /// Dual SPI and the master/slave pin
+    DualSpi = 0x15,
+    /// Border output and the interval between VCOM and the data
+    VcomAndDataIntervalSetting = 0x50,
+    /// Non-overlap period of the gate and the source
+    TconSetting = 0x60,
+    /// Number of sources and gates of one controller
+    TconResolution = 0x61,
+    /// Clock cascading and whether the temperature of `ForceTemperature` is used
+    CascadeSetting = 0xE0,
+    /// Power saving of the source and the gate
+    PowerSaving = 0xE3,
+    /// Temperature in °C for the waveform, instead of the measured one
+    ForceTemperature = 0xE5,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
@@ -0,0 +1,392 @@
+//! A Driver for the Waveshare 12.48" E-Ink Display via SPI
+//!
+//! The 1304x984 panel is driven by four cascaded UC8179 controllers, a master and a slave for
+//! each half, see [`HalfPins`]. They share the SPI bus, so most commands are sent to all of them
+//! at once, while [`update_frame`](Epd12in48::update_frame) splits the frame into the quadrants
+//! and sends each one to its controller.
+//!
+//! The controllers can't be driven through the single set of pins of
+//! [`WaveshareDisplay`](crate::prelude::WaveshareDisplay), so [`Epd12in48`] offers the same
+//! methods without implementing the trait.
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/12.48inch_e-Paper_Module)
+//! - [Waveshare C driver](https://github.com/waveshare/12.48inch-e-paper/blob/master/rpi/c/lib/e-Paper/EPD_12in48.c)
+//!
+//! # Example for the 12.48" E-Ink Display
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{
+//!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
+//!};
+//!use epd_waveshare::{epd12in48::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let pin = || pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// Setup EPD with the pins of both halves
+//!let top = HalfPins {
+//!    master_cs: pin(),
+//!    slave_cs: pin(),
+//!    master_busy: pin(),
+//!    slave_busy: pin(),
+//!    dc: pin(),
+//!    rst: pin(),
+//!};
+//!let bottom = HalfPins {
+//!    master_cs: pin(),
+//!    slave_cs: pin(),
+//!    master_busy: pin(),
+//!    slave_busy: pin(),
+//!    dc: pin(),
+//!    rst: pin(),
+//!};
+//!let mut epd = Epd12in48::new(&mut spi, top, bottom, &mut delay)?;
+//!
+//!// Use display graphics from embedded-graphics
+//!let mut display = Box::new(Display12in48::default());
+//!
+//!// Use embedded graphics for drawing a line across all quadrants
+//!let _ = Line::new(Point::new(0, 0), Point::new(1303, 983))
+//!    .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+//!    .draw(display.as_mut());
+//!
+//!// Display updated frame
+//!epd.update_and_display_frame(&mut spi, display.buffer(), &mut delay)?;
+//!
+//!// Set the EPD to sleep
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::buffer_len;
+use crate::color::Color;
+use crate::traits::{DisplayError, Error};
+
+pub(crate) mod command;
+use self::command::Command;
+
+mod cascade;
+pub use self::cascade::HalfPins;
+use self::cascade::{CascadeInterface, Controller};
+
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(feature = "graphics")]
+pub use self::graphics::Display12in48;
+
+/// Width of the display
+pub const WIDTH: u32 = 1304;
+/// Height of the display
+pub const HEIGHT: u32 = 984;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// Temperature in °C which selects the waveform until [`Epd12in48::set_temperature`] is called
+///
+/// All controllers use the same one, each of them would otherwise pick the waveform of the
+/// temperature at its own sensor.
+pub const DEFAULT_TEMPERATURE: i8 = 25;
+
+/// Epd12in48 driver
+pub struct Epd12in48<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// Connection Interface of the four controllers
+    interface: CascadeInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    /// Background Color
+    color: Color,
+    /// Temperature for the waveform
+    temperature: i8,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd12in48<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Creates a new driver from the pins of the top and the bottom half and initializes the
+    /// controllers
+    pub fn new(
+        spi: &mut SPI,
+        top: HalfPins<CS, BUSY, DC, RST>,
+        bottom: HalfPins<CS, BUSY, DC, RST>,
+        delay: &mut DELAY,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Epd12in48 {
+            interface: CascadeInterface::new(top, bottom),
+            color: DEFAULT_BACKGROUND_COLOR,
+            temperature: DEFAULT_TEMPERATURE,
+        };
+        epd.init(spi, delay)?;
+        Ok(epd)
+    }
+
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // Values taken from the Waveshare C driver
+        self.interface.reset(delay, 10)?;
+
+        // KW mode with the LUT from the OTP, the bottom controllers are mounted upside down
+        // and scan the other way
+        for (controllers, panel) in [
+            ([Controller::M1, Controller::S1], 0x1F),
+            ([Controller::M2, Controller::S2], 0x13),
+        ] {
+            self.interface
+                .cmd_with_data(spi, &controllers, Command::PanelSetting, &[panel])?;
+        }
+
+        // the masters generate the voltages for their slaves
+        self.interface.cmd_with_data(
+            spi,
+            &Controller::MASTERS,
+            Command::PowerSetting,
+            &[0x07, 0x17, 0x3F, 0x3F, 0x0D],
+        )?;
+        self.interface.cmd_with_data(
+            spi,
+            &Controller::MASTERS,
+            Command::BoosterSoftStart,
+            &[0x17, 0x17, 0x39, 0x17],
+        )?;
+
+        for controller in Controller::ALL {
+            self.interface.cmd_with_data(
+                spi,
+                &[controller],
+                Command::TconResolution,
+                &controller.resolution(),
+            )?;
+        }
+
+        let all = &Controller::ALL;
+        // the master/slave pin decides about the role of the controller
+        self.interface
+            .cmd_with_data(spi, all, Command::DualSpi, &[0x20])?;
+        // white border, data 1 is white
+        self.interface.cmd_with_data(
+            spi,
+            all,
+            Command::VcomAndDataIntervalSetting,
+            &[0x21, 0x07],
+        )?;
+        self.interface
+            .cmd_with_data(spi, all, Command::TconSetting, &[0x22])?;
+        self.interface
+            .cmd_with_data(spi, all, Command::PowerSaving, &[0x00])?;
+        // cascaded clock and the same temperature for all controllers
+        self.interface
+            .cmd_with_data(spi, all, Command::CascadeSetting, &[0x03])?;
+        self.send_temperature(spi)
+    }
+
+    /// Puts all controllers into deep sleep, [`wake_up`](Epd12in48::wake_up) resets them again
+    pub fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle()?;
+        self.interface
+            .cmd(spi, &Controller::ALL, Command::PowerOff)?;
+        delay.delay_ms(200);
+        self.interface.wait_until_idle()?;
+        self.interface
+            .cmd_with_data(spi, &Controller::ALL, Command::DeepSleep, &[0xA5])
+    }
+
+    /// Wakes the controllers up from deep sleep
+    pub fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)
+    }
+
+    /// Sets the temperature in °C which selects the waveform of all controllers
+    pub fn set_temperature(
+        &mut self,
+        spi: &mut SPI,
+        celsius: i8,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.temperature = celsius;
+        self.send_temperature(spi)
+    }
+
+    /// Returns the temperature which selects the waveform
+    pub fn temperature(&self) -> i8 {
+        self.temperature
+    }
+
+    /// Sets the backgroundcolor for various commands like [clear_frame](Epd12in48::clear_frame)
+    pub fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    /// Get current background color
+    pub fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    /// Get the width of the display
+    pub fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    /// Get the height of the display
+    pub fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    /// Transmit a full frame to the SRAM of the controllers, each one gets its quadrant
+    pub fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if buffer.len() != buffer_len(WIDTH as usize, HEIGHT as usize) {
+            return Err(Error::InvalidWindow);
+        }
+        self.interface.wait_until_idle()?;
+        for controller in Controller::ALL {
+            self.interface
+                .cmd(spi, &[controller], Command::DataStartTransmission2)?;
+            for row in controller.rows(buffer) {
+                self.interface.data(spi, &[controller], row)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Displays the frame data from SRAM, all four quadrants at the same time
+    pub fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle()?;
+        self.interface
+            .cmd(spi, &Controller::MASTERS, Command::PowerOn)?;
+        delay.delay_ms(200);
+        self.interface
+            .cmd(spi, &Controller::ALL, Command::DisplayRefresh)?;
+        delay.delay_ms(100);
+        self.interface.wait_until_idle()?;
+        Ok(())
+    }
+
+    /// Provide a combined update&display and save some time (skipping a busy check in between)
+    pub fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer)?;
+        self.display_frame(spi, delay)
+    }
+
+    /// Clears the frame buffer of the controllers with the declared background color
+    ///
+    /// The background color can be changed with [`set_background_color`](Epd12in48::set_background_color)
+    pub fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle()?;
+        let color = self.color.get_byte_value();
+        for controller in Controller::ALL {
+            self.interface
+                .cmd(spi, &[controller], Command::DataStartTransmission2)?;
+            self.interface
+                .data_x_times(spi, controller, color, controller.len())?;
+        }
+        Ok(())
+    }
+
+    /// Checks if any of the controllers is busy
+    pub fn is_busy(&self) -> bool {
+        self.interface.is_busy()
+    }
+
+    fn send_temperature(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.cmd_with_data(
+            spi,
+            &Controller::ALL,
+            Command::ForceTemperature,
+            &[self.temperature as u8],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 1304);
+        assert_eq!(HEIGHT, 984);
+        assert_eq!(
+            buffer_len(WIDTH as usize, HEIGHT as usize),
+            cascade::LINE_LEN * HEIGHT as usize
+        );
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+    }
+
+    #[test]
+    fn init_and_frame_commands() {
+        extern crate std;
+        use crate::interface::mock::{CommandSpi, HighPin, NoPin};
+        use embedded_hal_mock::delay::MockNoop;
+        use std::vec;
+
+        let mut spi = CommandSpi::default();
+        let half = |spi: &CommandSpi| HalfPins {
+            master_cs: NoPin,
+            slave_cs: NoPin,
+            master_busy: HighPin,
+            slave_busy: HighPin,
+            dc: spi.dc(),
+            rst: NoPin,
+        };
+        let (top, bottom) = (half(&spi), half(&spi));
+        let mut epd: Epd12in48<_, _, _, _, _, MockNoop> =
+            Epd12in48::new(&mut spi, top, bottom, &mut MockNoop::new()).unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x00, 0x00, 0x01, 0x06, 0x61, 0x61, 0x61, 0x61, 0x15, 0x50, 0x60, 0xE3, 0xE0, 0xE5]
+        );
+        // the bottom controllers scan the other way
+        assert!(spi.sent(0x00, &[0x1F]));
+        assert!(spi.sent(0x00, &[0x13]));
+        assert!(spi.sent(0xE5, &[DEFAULT_TEMPERATURE as u8]));
+
+        // there are no partial windows, every controller gets its quadrant of the frame
+        spi.clear();
+        let frame = vec![0xAA; buffer_len(WIDTH as usize, HEIGHT as usize)];
+        epd.update_frame(&mut spi, &frame).unwrap();
+        assert_eq!(spi.commands(), [0x13; 4]);
+    }
+}
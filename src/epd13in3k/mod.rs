@@ -0,0 +1,820 @@
+//! A Driver for the Waveshare 13.3" (K) E-Ink Display via SPI
+//!
+//! The 960x680 panel uses an SSD1677 controller, which addresses the columns of its RAM windows
+//! in pixels with two bytes instead of in bytes like the smaller SSD controllers. The waveforms
+//! come from the OTP of the controller, so no LUT is sent. [`QuickRefresh`] refreshes only the
+//! pixels which differ from the base frame, in the whole frame or in a window of it.
+//!
+//! A frame is 81600 bytes, so the buffer of `Display13in3k` rarely fits into the RAM of a
+//! microcontroller; [`RowRendering`] renders the frame line by line instead.
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/13.3inch_e-Paper_HAT_(K)_Manual)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_13in3k.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd13in3k.py)
+//!
+//! # Example for the 13.3" (K) E-Ink Display
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{
+//!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
+//!};
+//!use epd_waveshare::{epd13in3k::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// Setup EPD
+//!let mut epd = Epd13in3k::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// Use display graphics from embedded-graphics
+//!let mut display = Box::new(Display13in3k::default());
+//!
+//!// Use embedded graphics for drawing a line
+//!let _ = Line::new(Point::new(0, 120), Point::new(0, 600))
+//!    .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+//!    .draw(display.as_mut());
+//!
+//!// Display updated frame
+//!epd.update_and_display_frame(&mut spi, display.buffer(), &mut delay)?;
+//!
+//!// Draw something new here
+//!
+//!// Partial refresh of the changed pixels
+//!epd.update_and_display_new_frame(&mut spi, display.buffer(), &mut delay)?;
+//!
+//!// Set the EPD to sleep
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::type_a::command::Command;
+
+use crate::color::Color;
+
+use crate::energy::RefreshEnergy;
+use crate::traits::*;
+
+use crate::buffer_len;
+use crate::interface::{render_rows, DisplayInterface, InterfaceAccess, InterfaceState};
+use crate::settings::InitStep;
+use crate::traits::{DisplayError, QuickRefresh};
+
+mod constants;
+pub use self::constants::INIT_SEQUENCE;
+
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(feature = "graphics")]
+pub use crate::epd13in3k::graphics::Display13in3k;
+
+/// Width of epd13in3k in pixels
+pub const WIDTH: u32 = 960;
+/// Height of epd13in3k in pixels
+pub const HEIGHT: u32 = 680;
+/// Default Background Color (white)
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+const IS_BUSY_LOW: bool = false;
+
+/// Epd13in3k driver
+///
+/// Every full refresh writes the frame to both RAMs of the controller, so the last frame
+/// is always the base of a partial refresh with [`QuickRefresh`].
+pub struct Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// SPI
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    /// Color
+    background_color: Color,
+    /// Init sequence
+    init_sequence: &'static [InitStep],
+    /// Waveform of `display_new_frame`
+    quick_lut: RefreshLut,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, 2)?;
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface
+            .run_init_sequence(spi, delay, self.init_sequence, IS_BUSY_LOW)?;
+
+        self.use_full_frame(spi)?;
+
+        // the border follows the LUT
+        self.interface
+            .cmd_with_data(spi, Command::BorderWaveformControl, &[0x01])?;
+
+        // the internal temperature sensor
+        self.interface
+            .cmd_with_data(spi, Command::TemperatureSensorSelection, &[0x80])?;
+
+        // Enable clock signal, Enable Analog, Load temperature value and the LUT, Disable Analog, Disable OSC
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xB1])?;
+        self.interface.cmd(spi, Command::MasterActivation)?;
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type DisplayColor = Color;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: true,
+        grayscale_levels: 2,
+        chromatic_colors: 0,
+        readback: false,
+        fast_mode: false,
+        ultrafast: false,
+        clear_refreshes: false,
+    };
+    const ENERGY: RefreshEnergy = RefreshEnergy {
+        full: 528_000,
+        fast: 528_000,
+        partial: 105_600,
+    };
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode 1
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
+        Ok(())
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)?;
+        Ok(())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.check_buffer(buffer, WIDTH, HEIGHT)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+
+        // Always keep the base buffer equal to current for the partial refreshes.
+        self.set_partial_base_buffer(spi, buffer)
+    }
+
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_partial_old_frame(spi, buffer, x, y, width, height)
+    }
+
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        let color = self.background_color.get_byte_value();
+
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.clear_data(
+            spi,
+            color,
+            buffer_len(WIDTH as usize, HEIGHT as usize) as u32,
+        )?;
+        if self.interface.clear_aborted() {
+            return Ok(());
+        }
+
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface.clear_data(
+            spi,
+            color,
+            buffer_len(WIDTH as usize, HEIGHT as usize) as u32,
+        )
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+
+    /// The waveforms are taken from the OTP of the controller, nothing is sent
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        Ok(())
+    }
+
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // the old content in the second RAM is ignored, the border follows the LUT
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl1, &[0x40, 0x00])?;
+        self.interface
+            .cmd_with_data(spi, Command::BorderWaveformControl, &[0x01])?;
+        // Enable clock signal, Enable Analog, Load temperature value, DISPLAY with DISPLAY Mode 1, Disable Analog, Disable OSC
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xF7])?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        _spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.poll_refresh(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RowRendering<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_frame_with<F>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        mut f: F,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut(u32, &mut [u8]),
+    {
+        let mut row = [0; buffer_len(WIDTH as usize, 1)];
+        let background = self.background_color.get_byte_value();
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        for command in [Command::WriteRam, Command::WriteRam2] {
+            self.use_full_frame(spi)?;
+            self.interface.cmd(spi, command)?;
+            render_rows(HEIGHT, &mut row, background, &mut f, |row| {
+                self.interface.frame_data(spi, row)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Snapshot for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = Color;
+
+    fn snapshot(&self) -> DriverSnapshot<Color> {
+        self.interface
+            .state()
+            .snapshot(self.background_color, RefreshLut::Full)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<Color>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.background_color = snapshot.background_color;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        match plane {
+            Plane::Bw => self
+                .update_frame(spi, data, delay)
+                .map_err(RawPlaneError::Interface),
+            Plane::Chromatic => Err(RawPlaneError::UnsupportedPlane(plane)),
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InitSequence for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY> {
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+
+        Epd13in3k {
+            interface,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            init_sequence: &INIT_SEQUENCE,
+            quick_lut: RefreshLut::Quick,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ConnectionCheck<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn ping(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // enable the clock, load the temperature and disable the clock again, the LUT and the
+        // RAM stay as they are
+        self.interface
+            .ping(spi, IS_BUSY_LOW, delay, |interface, spi| {
+                interface.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xA1])?;
+                interface.cmd(spi, Command::MasterActivation)
+            })
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Writes the base buffer, which the partial refresh compares the new frame with
+    ///
+    /// Only needed after a partial refresh with [`QuickRefresh`], the full refreshes
+    /// write it already.
+    pub fn set_partial_base_buffer(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.check_buffer(buffer, WIDTH, HEIGHT)?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam2, buffer)
+    }
+
+    fn wait_until_idle(&mut self) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW)
+    }
+
+    fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // choose full frame/ram
+        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
+
+        // start from the beginning
+        self.set_ram_counter(spi, 0, 0)
+    }
+
+    /// Selects the window of `width` x `height` pixels at `x`, `y` and moves the counter to its start
+    fn set_window(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let (end_x, end_y) = self.interface.window_end(x, y, width, height)?;
+        self.set_ram_area(spi, x, y, end_x, end_y)?;
+        self.set_ram_counter(spi, x, y)
+    }
+
+    fn set_ram_area(
+        &mut self,
+        spi: &mut SPI,
+        start_x: u32,
+        start_y: u32,
+        end_x: u32,
+        end_y: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_window(start_x <= end_x && start_y <= end_y)?;
+
+        // unlike the smaller controllers x is positioned in pixels, with 2 Databytes: A[7:0] & 0..A[9]
+        // for each - start and end
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[
+                start_x as u8,
+                (start_x >> 8) as u8,
+                end_x as u8,
+                (end_x >> 8) as u8,
+            ],
+        )?;
+
+        // 2 Databytes: A[7:0] & 0..A[8] for each - start and end
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[
+                start_y as u8,
+                (start_y >> 8) as u8,
+                end_y as u8,
+                (end_y >> 8) as u8,
+            ],
+        )
+    }
+
+    fn set_ram_counter(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
+        // 2 Databytes: A[7:0] & 0..A[9]
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamXAddressCounter,
+            &[x as u8, (x >> 8) as u8],
+        )?;
+
+        // 2 Databytes: A[7:0] & 0..A[8]
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamYAddressCounter,
+            &[y as u8, (y >> 8) as u8],
+        )?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd13in3k<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Writes the base image to both RAMs, to be shown with a full `display_frame`.
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+        self.set_partial_base_buffer(spi, buffer)
+    }
+
+    /// To be used after `update_old_frame`, the base image stays untouched.
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)
+    }
+
+    /// For a partial refresh of the new updated frame. To be used immediately after `update_new_frame`
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if self.quick_lut == RefreshLut::Full {
+            return self.display_frame(spi, delay);
+        }
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // compare with the base in the second RAM, the border keeps its level
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl1, &[0x00, 0x00])?;
+        self.interface
+            .cmd_with_data(spi, Command::BorderWaveformControl, &[0x80])?;
+        // DISPLAY Mode 2, which only drives the pixels differing from the base
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xFF])?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+
+    /// Updates and displays the new frame.
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_new_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam2, buffer)
+    }
+
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)
+    }
+
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_area(x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        let color = self.background_color.get_byte_value();
+
+        self.set_window(spi, x, y, width, height)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.clear_data(
+            spi,
+            color,
+            buffer_len(width as usize, height as usize) as u32,
+        )
+    }
+
+    fn set_quick_lut(
+        &mut self,
+        _spi: &mut SPI,
+        refresh: RefreshLut,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.quick_lut = refresh;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 960);
+        assert_eq!(HEIGHT, 680);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+    }
+
+    #[test]
+    fn init_and_partial_window_commands() {
+        use crate::interface::mock::{CommandSpi, NoPin};
+        use embedded_hal_mock::delay::MockNoop;
+
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let mut epd: Epd13in3k<_, _, _, _, _, MockNoop> =
+            Epd13in3k::new(&mut spi, NoPin, NoPin, dc, NoPin, &mut MockNoop::new()).unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x12, 0x0C, 0x01, 0x11, 0x44, 0x45, 0x4E, 0x4F, 0x3C, 0x18, 0x22, 0x20]
+        );
+        assert!(spi.sent(0x01, &[0xA7, 0x02, 0x00]));
+        assert!(spi.sent(0x44, &[0x00, 0x00, 0xBF, 0x03]));
+        assert!(spi.sent(0x45, &[0x00, 0x00, 0xA7, 0x02]));
+
+        spi.clear();
+        epd.update_partial_frame(&mut spi, &[0xAA, 0xBB, 0xCC, 0xDD], 8, 16, 16, 2)
+            .unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x44, 0x45, 0x4E, 0x4F, 0x24, 0x44, 0x45, 0x4E, 0x4F, 0x26]
+        );
+        // the controller addresses the columns in pixels
+        assert!(spi.sent(0x44, &[8, 0, 23, 0]));
+        assert!(spi.sent(0x45, &[16, 0, 17, 0]));
+        assert!(spi.sent(0x4E, &[8, 0]));
+        assert!(spi.sent(0x24, &[0xAA, 0xBB, 0xCC, 0xDD]));
+        // the window is also the base of the next partial refresh
+        assert!(spi.sent(0x26, &[0xAA, 0xBB, 0xCC, 0xDD]));
+    }
+}
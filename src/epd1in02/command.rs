@@ -0,0 +1,64 @@
+//! SPI Commands for the Waveshare 1.02" E-Ink Display
+
+use crate::traits;
+
+/// Epd1in02 commands
+///
+/// The controller is an UC8175. Should rarely (never?) be needed directly.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// LUT selection, resolution, scan directions and booster switch
+    PanelSetting = 0x00,
+    /// Internal or external power and the driving voltages
+    PowerSetting = 0x01,
+    /// Switches off the charge pump and the drivers, the registers are kept
+    PowerOff = 0x02,
+    /// Switches on the charge pump and the drivers, the display is busy until they are ready
+    PowerOn = 0x04,
+    /// Charge pump clock
+    ChargePumpSetting = 0x06,
+    /// Deep sleep after 0xA5, only a reset wakes the display up again
+    DeepSleep = 0x07,
+    /// Old frame, the base of a partial refresh
+    DataStartTransmission1 = 0x10,
+    /// Refreshes the display with the frames and the LUTs
+    DisplayRefresh = 0x12,
+    /// New frame
+    DataStartTransmission2 = 0x13,
+    /// LUT of the pixels which become white
+    LutWhite = 0x23,
+    /// LUT of the pixels which become black
+    LutBlack = 0x24,
+    /// XON and the options of the LUTs
+    LutOption = 0x2A,
+    /// Frame rate
+    PllControl = 0x30,
+    /// Senses the temperature with the internal sensor
+    TemperatureSensor = 0x40,
+    /// Border output and the interval between VCOM and the data
+    VcomAndDataIntervalSetting = 0x50,
+    /// Non-overlap period of the gate and the source
+    TconSetting = 0x60,
+    /// Number of sources and gates
+    ResolutionSetting = 0x61,
+    /// VCOM DC voltage
+    VcmDcSetting = 0x82,
+    /// Window of the next `DataStartTransmission1` and `DataStartTransmission2`
+    PartialWindow = 0x90,
+    /// Restricts the data transmissions to the partial window
+    PartialIn = 0x91,
+    /// Ends the restriction to the partial window
+    PartialOut = 0x92,
+    /// Not described in the datasheet, sent with 0x3F by the Waveshare driver
+    VendorSetting = 0xD2,
+    /// Power saving of the source and the gate
+    PowerSaving = 0xE3,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
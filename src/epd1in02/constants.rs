@@ -0,0 +1,72 @@
+//! Init sequence and LUTs of the Waveshare C driver
+
+use super::command::Command;
+use super::{HEIGHT, WIDTH};
+use crate::settings::InitStep;
+
+/// Panel and power setup of the Waveshare C driver, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 9] = [
+    InitStep::new(Command::VendorSetting as u8, &[0x3F]),
+    // LUT from the registers, 80x128, scanning up and right, booster on
+    InitStep::new(Command::PanelSetting as u8, &[0x6F]),
+    // internal power, VGH/VGL 20V/-20V, VDH/VDL 15V/-15V
+    InitStep::new(Command::PowerSetting as u8, &[0x03, 0x00, 0x2B, 0x2B]),
+    InitStep::new(Command::ChargePumpSetting as u8, &[0x3F]),
+    InitStep::new(Command::LutOption as u8, &[0x00, 0x00]),
+    InitStep::new(Command::TconSetting as u8, &[0x22]),
+    InitStep::new(
+        Command::ResolutionSetting as u8,
+        &[WIDTH as u8, HEIGHT as u8],
+    ),
+    // VCOM -1.0V
+    InitStep::new(Command::VcmDcSetting as u8, &[0x12]),
+    InitStep::new(Command::PowerSaving as u8, &[0x33]),
+];
+
+/// Full refresh, pixels which become white
+#[rustfmt::skip]
+pub(crate) const LUT_WHITE_FULL: [u8; 42] = [
+    0x60, 0x5A, 0x5A, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// Full refresh, pixels which become black
+#[rustfmt::skip]
+pub(crate) const LUT_BLACK_FULL: [u8; 42] = [
+    0x90, 0x5A, 0x5A, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// Partial refresh, pixels which become white
+#[rustfmt::skip]
+pub(crate) const LUT_WHITE_QUICK: [u8; 42] = [
+    0x60, 0x01, 0x01, 0x00, 0x00, 0x01,
+    0x80, 0x0F, 0x00, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// Partial refresh, pixels which become black
+#[rustfmt::skip]
+pub(crate) const LUT_BLACK_QUICK: [u8; 42] = [
+    0x90, 0x01, 0x01, 0x00, 0x00, 0x01,
+    0x40, 0x0F, 0x00, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
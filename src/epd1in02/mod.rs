@@ -0,0 +1,740 @@
+//! A Driver for the Waveshare 1.02" E-Ink Display via SPI
+//!
+//! The 80x128 panel is driven by an UC8175, which takes its waveforms from the LUTs sent by the
+//! driver. [`RefreshLut::Quick`] selects the LUTs of the partial refresh, which only drive the
+//! pixels differing from the last frame; [`update_frame`](WaveshareDisplay::update_frame) writes
+//! every frame to both RAMs, so the last full refresh is the base of the next partial one.
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/1.02inch_e-Paper_Module)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_1in02d.c)
+//!
+//! # Example for the 1.02" E-Ink Display
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{
+//!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
+//!};
+//!use epd_waveshare::{epd1in02::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// Setup EPD
+//!let mut epd = Epd1in02::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// Use display graphics from embedded-graphics
+//!let mut display = Display1in02::default();
+//!
+//!// Use embedded graphics for drawing a line
+//!let _ = Line::new(Point::new(0, 20), Point::new(0, 100))
+//!    .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+//!    .draw(&mut display);
+//!
+//!// Display updated frame with a full refresh, it is the base for the partial refreshes
+//!epd.update_and_display_frame(&mut spi, &display.buffer(), &mut delay)?;
+//!
+//!// Draw something new here
+//!
+//!// Partial refresh of the changed pixels
+//!epd.set_lut(&mut spi, Some(RefreshLut::Quick))?;
+//!epd.update_and_display_frame(&mut spi, &display.buffer(), &mut delay)?;
+//!
+//!// Set the EPD to sleep
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::buffer_len;
+use crate::color::Color;
+use crate::energy::RefreshEnergy;
+use crate::interface::{DisplayInterface, InterfaceAccess, InterfaceState};
+use crate::settings::InitStep;
+use crate::traits::{
+    BusyState, Capabilities, ConnectionCheck, DisplayError, DriverSnapshot, InitSequence,
+    InternalWiAdditions, LazyInit, LifecycleEvent, Link, NonBlockingRefresh, Plane, QuickRefresh,
+    RawPlaneError, RawPlaneUpload, RefreshLut, RefreshState, Snapshot, WaveshareDisplay,
+};
+
+pub(crate) mod command;
+use self::command::Command;
+mod constants;
+pub use self::constants::INIT_SEQUENCE;
+use self::constants::*;
+
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(feature = "graphics")]
+pub use self::graphics::Display1in02;
+
+/// Width of the display
+pub const WIDTH: u32 = 80;
+/// Height of the display
+pub const HEIGHT: u32 = 128;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+const IS_BUSY_LOW: bool = true;
+
+/// Epd1in02 driver
+pub struct Epd1in02<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    /// Background Color
+    color: Color,
+    /// Refresh LUT
+    refresh: RefreshLut,
+    /// Init sequence
+    init_sequence: &'static [InitStep],
+    /// Waveform of `display_new_frame`
+    quick_lut: RefreshLut,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // Values taken from the Waveshare C driver
+        self.interface.reset(delay, 2)?;
+
+        self.interface
+            .run_init_sequence(spi, delay, self.init_sequence, IS_BUSY_LOW)?;
+
+        // the frame rate, the border and the LUTs of the refresh
+        self.set_lut(spi, None)?;
+
+        self.interface.set_busy_reason(BusyState::PoweringUp);
+        self.command(spi, Command::PowerOn)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type DisplayColor = Color;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: true,
+        grayscale_levels: 2,
+        chromatic_colors: 0,
+        readback: false,
+        fast_mode: true,
+        ultrafast: false,
+        clear_refreshes: false,
+    };
+    const ENERGY: RefreshEnergy = RefreshEnergy {
+        full: 9_900,
+        fast: 1_650,
+        partial: 1_650,
+    };
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // floating border while the power is off
+        self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0xF7])?;
+        self.command(spi, Command::PowerOff)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
+        Ok(())
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.check_buffer(buffer, WIDTH, HEIGHT)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        if self.refresh == RefreshLut::Full {
+            // Always keep the base buffer equal to current if not doing partial refresh.
+            self.interface
+                .cmd_with_frame_data(spi, Command::DataStartTransmission1, buffer)?;
+        }
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission2, buffer)
+    }
+
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        self.set_window(spi, x, y, width, height)?;
+        if self.refresh == RefreshLut::Full {
+            self.interface
+                .cmd_with_frame_data(spi, Command::DataStartTransmission1, buffer)?;
+        }
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission2, buffer)?;
+        self.command(spi, Command::PartialOut)
+    }
+
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+
+        if self.refresh == RefreshLut::Quick {
+            self.set_partial_base_buffer(spi, buffer)?;
+        }
+        Ok(())
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        let color = self.color.get_byte_value();
+        let len = buffer_len(WIDTH as usize, HEIGHT as usize) as u32;
+
+        // Always keep the base buffer equal to current if not doing partial refresh.
+        if self.refresh == RefreshLut::Full {
+            self.command(spi, Command::DataStartTransmission1)?;
+            self.interface.clear_data(spi, color, len)?;
+            if self.interface.clear_aborted() {
+                return Ok(());
+            }
+        }
+        self.command(spi, Command::DataStartTransmission2)?;
+        self.interface.clear_data(spi, color, len)
+    }
+
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut.without_ultrafast();
+        }
+        match self.refresh {
+            RefreshLut::Full => {
+                // 50Hz, white border, default interval
+                self.cmd_with_data(spi, Command::PllControl, &[0x13])?;
+                self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x57])?;
+                self.set_lut_helper(spi, &LUT_WHITE_FULL, &LUT_BLACK_FULL)
+            }
+            RefreshLut::Quick | RefreshLut::Ultrafast => {
+                // faster frame rate, the border keeps its level
+                self.cmd_with_data(spi, Command::PllControl, &[0x05])?;
+                self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0xF2])?;
+                self.set_lut_helper(spi, &LUT_WHITE_QUICK, &LUT_BLACK_QUICK)
+            }
+        }
+    }
+
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.command(spi, Command::DisplayRefresh)?;
+        // the busy pin needs a moment to follow the refresh
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        _spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.poll_refresh(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Snapshot for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = Color;
+
+    fn snapshot(&self) -> DriverSnapshot<Color> {
+        self.interface.state().snapshot(self.color, self.refresh)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<Color>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.color = snapshot.background_color;
+        self.refresh = snapshot.refresh;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        match plane {
+            Plane::Bw => self
+                .update_frame(spi, data, delay)
+                .map_err(RawPlaneError::Interface),
+            Plane::Chromatic => Err(RawPlaneError::UnsupportedPlane(plane)),
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+
+        Epd1in02 {
+            interface,
+            color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            init_sequence: &INIT_SEQUENCE,
+            quick_lut: RefreshLut::Quick,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ConnectionCheck<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn ping(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // the busy pin is low while the temperature is sensed
+        self.interface
+            .ping(spi, IS_BUSY_LOW, delay, |interface, spi| {
+                interface.cmd(spi, Command::TemperatureSensor)
+            })
+    }
+}
+impl<SPI, CS, BUSY, DC, RST, DELAY> InitSequence for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY> {
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Writes the base buffer, which the partial refresh compares the new frame with
+    ///
+    /// Only needed with [`RefreshLut::Quick`], after a partial refresh
+    /// [`update_and_display_frame`](WaveshareDisplay::update_and_display_frame) does it already.
+    pub fn set_partial_base_buffer(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.check_buffer(buffer, WIDTH, HEIGHT)?;
+        self.wait_until_idle()?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission1, buffer)
+    }
+
+    fn command(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.cmd(spi, command)
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.cmd_with_data(spi, command, data)
+    }
+
+    fn wait_until_idle(&mut self) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW)
+    }
+
+    fn set_lut_helper(
+        &mut self,
+        spi: &mut SPI,
+        lut_white: &[u8],
+        lut_black: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.cmd_with_data(spi, Command::LutWhite, lut_white)?;
+        self.cmd_with_data(spi, Command::LutBlack, lut_black)
+    }
+
+    /// Restricts the following data transmissions to the window of `width` x `height` pixels
+    /// at `x`, `y`
+    ///
+    /// The horizontal bounds are rounded to whole bytes.
+    fn set_window(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let (end_x, end_y) = self.interface.window_end(x, y, width, height)?;
+        self.interface
+            .check_window(end_x < WIDTH && end_y < HEIGHT)?;
+
+        self.command(spi, Command::PartialIn)?;
+        self.cmd_with_data(
+            spi,
+            Command::PartialWindow,
+            &[
+                (x & 0xF8) as u8,
+                (end_x | 0x07) as u8,
+                y as u8,
+                end_y as u8,
+                // scan inside and outside of the window
+                0x01,
+            ],
+        )
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in02<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Writes the base image, which the partial refresh compares the new frame with
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.set_partial_base_buffer(spi, buffer)
+    }
+
+    /// To be used after `update_old_frame`, the base image stays untouched.
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission2, buffer)
+    }
+
+    /// For a partial refresh of the new updated frame. To be used immediately after `update_new_frame`
+    ///
+    /// Refreshes with the LUTs selected with [`set_quick_lut`](QuickRefresh::set_quick_lut),
+    /// the ones of the partial refresh by default. The ones selected with
+    /// [`set_lut`](WaveshareDisplay::set_lut) are sent again afterwards.
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let refresh = self.refresh;
+        self.set_lut(spi, Some(self.quick_lut))?;
+        self.display_frame(spi, delay)?;
+        self.set_lut(spi, Some(refresh))
+    }
+
+    /// Updates and displays the new frame.
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_new_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission1, buffer)?;
+        self.command(spi, Command::PartialOut)
+    }
+
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission2, buffer)?;
+        self.command(spi, Command::PartialOut)
+    }
+
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_area(x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        let color = self.color.get_byte_value();
+
+        self.set_window(spi, x, y, width, height)?;
+        self.command(spi, Command::DataStartTransmission2)?;
+        self.interface.clear_data(
+            spi,
+            color,
+            buffer_len(width as usize, height as usize) as u32,
+        )?;
+        self.command(spi, Command::PartialOut)
+    }
+
+    fn set_quick_lut(
+        &mut self,
+        _spi: &mut SPI,
+        refresh: RefreshLut,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.quick_lut = refresh;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 80);
+        assert_eq!(HEIGHT, 128);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+    }
+
+    #[test]
+    fn init_and_partial_window_commands() {
+        use crate::interface::mock::{CommandSpi, HighPin, NoPin};
+        use embedded_hal_mock::delay::MockNoop;
+
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let mut epd: Epd1in02<_, _, _, _, _, MockNoop> =
+            Epd1in02::new(&mut spi, NoPin, HighPin, dc, NoPin, &mut MockNoop::new()).unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0xD2, 0x00, 0x01, 0x06, 0x2A, 0x60, 0x61, 0x82, 0xE3, 0x30, 0x50, 0x23, 0x24, 0x04]
+        );
+        assert!(spi.sent(0x61, &[0x50, 0x80]));
+
+        spi.clear();
+        epd.update_partial_frame(&mut spi, &[0xAA, 0xBB, 0xCC, 0xDD], 8, 16, 16, 2)
+            .unwrap();
+        assert_eq!(spi.commands(), [0x91, 0x90, 0x10, 0x13, 0x92]);
+        assert!(spi.sent(0x90, &[8, 23, 16, 17, 0x01]));
+        // the full refresh keeps the old data equal
+        assert!(spi.sent(0x10, &[0xAA, 0xBB, 0xCC, 0xDD]));
+        assert!(spi.sent(0x13, &[0xAA, 0xBB, 0xCC, 0xDD]));
+    }
+}
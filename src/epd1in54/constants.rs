@@ -0,0 +1,33 @@
+//! Init sequence of the controller
+
+use super::HEIGHT;
+use crate::settings::InitStep;
+use crate::type_a::command::Command;
+
+/// Gate, booster, VCOM and data entry setup, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 6] = [
+    // 3 Databytes:
+    // A[7:0]
+    // 0.. A[8]
+    // 0.. B[2:0]
+    // Default Values: A = Height of Screen (0x127), B = 0x00 (GD, SM and TB=0?)
+    InitStep::new(
+        Command::DriverOutputControl as u8,
+        &[HEIGHT as u8, (HEIGHT >> 8) as u8, 0x00],
+    ),
+    // 3 Databytes: (and default values from datasheet and arduino)
+    // 1 .. A[6:0]  = 0xCF | 0xD7
+    // 1 .. B[6:0]  = 0xCE | 0xD6
+    // 1 .. C[6:0]  = 0x8D | 0x9D
+    //TODO: test
+    InitStep::new(Command::BoosterSoftStartControl as u8, &[0xD7, 0xD6, 0x9D]),
+    // One Databyte with value 0xA8 for 7V VCOM
+    InitStep::new(Command::WriteVcomRegister as u8, &[0xA8]),
+    // One Databyte with default value 0x1A for 4 dummy lines per gate
+    InitStep::new(Command::SetDummyLinePeriod as u8, &[0x1A]),
+    // One Databyte with default value 0x08 for 2us per line
+    InitStep::new(Command::SetGateLineWidth as u8, &[0x08]),
+    // One Databyte with default value 0x03
+    //  -> address: x increment, y increment, address counter is updated in x direction
+    InitStep::new(Command::DataEntryModeSetting as u8, &[0x03]),
+];
@@ -1,10 +1,13 @@
 //! A simple Driver for the Waveshare 1.54" E-Ink Display via SPI
 //!
+//! The V2 revision of the module with the SSD1681 controller is driven by
+//! [`epd1in54_v2`](crate::epd1in54_v2).
+//!
 //! # Example for the 1.54 in E-Ink Display
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
 //!use embedded_graphics::{
 //!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyleBuilder},
 //!};
@@ -60,14 +63,24 @@ use embedded_hal::{
 
 use crate::type_a::{
     command::Command,
-    constants::{LUT_FULL_UPDATE, LUT_PARTIAL_UPDATE},
+    constants::{LUT_FULL_UPDATE, LUT_PARTIAL_UPDATE, LUT_ULTRAFAST_UPDATE},
 };
 
 use crate::color::Color;
 
-use crate::traits::{RefreshLut, WaveshareDisplay};
+use crate::energy::RefreshEnergy;
+use crate::traits::{
+    BusyState, Capabilities, DisplayError, DriverSnapshot, InitSequence, InterruptedUpload,
+    LazyInit, LifecycleEvent, NonBlockingRefresh, Plane, RawPlaneError, RawPlaneUpload, RefreshLut,
+    RefreshState, ResumableUpload, RowRendering, Snapshot, UltrafastRefresh, WaveshareDisplay,
+};
+
+use crate::buffer_len;
+use crate::interface::{render_rows, DisplayInterface, InterfaceAccess, InterfaceState};
+use crate::settings::InitStep;
 
-use crate::interface::DisplayInterface;
+mod constants;
+pub use self::constants::INIT_SEQUENCE;
 
 #[cfg(feature = "graphics")]
 mod graphics;
@@ -82,6 +95,10 @@ pub struct Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> {
     background_color: Color,
     /// Refresh LUT
     refresh: RefreshLut,
+    /// Init sequence
+    init_sequence: &'static [InitStep],
+    /// Whether the full LUT of a forced full refresh is still loaded instead of the selected one
+    forced_lut: bool,
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
@@ -93,48 +110,19 @@ where
     RST: OutputPin,
     DELAY: DelayMs<u8>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.reset(delay, 10);
-
-        // 3 Databytes:
-        // A[7:0]
-        // 0.. A[8]
-        // 0.. B[2:0]
-        // Default Values: A = Height of Screen (0x127), B = 0x00 (GD, SM and TB=0?)
-        self.interface.cmd_with_data(
-            spi,
-            Command::DriverOutputControl,
-            &[HEIGHT as u8, (HEIGHT >> 8) as u8, 0x00],
-        )?;
-
-        // 3 Databytes: (and default values from datasheet and arduino)
-        // 1 .. A[6:0]  = 0xCF | 0xD7
-        // 1 .. B[6:0]  = 0xCE | 0xD6
-        // 1 .. C[6:0]  = 0x8D | 0x9D
-        //TODO: test
-        self.interface
-            .cmd_with_data(spi, Command::BoosterSoftStartControl, &[0xD7, 0xD6, 0x9D])?;
-
-        // One Databyte with value 0xA8 for 7V VCOM
-        self.interface
-            .cmd_with_data(spi, Command::WriteVcomRegister, &[0xA8])?;
-
-        // One Databyte with default value 0x1A for 4 dummy lines per gate
-        self.interface
-            .cmd_with_data(spi, Command::SetDummyLinePeriod, &[0x1A])?;
-
-        // One Databyte with default value 0x08 for 2us per line
-        self.interface
-            .cmd_with_data(spi, Command::SetGateLineWidth, &[0x08])?;
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, 10)?;
 
-        // One Databyte with default value 0x03
-        //  -> address: x increment, y increment, address counter is updated in x direction
         self.interface
-            .cmd_with_data(spi, Command::DataEntryModeSetting, &[0x03])?;
+            .run_init_sequence(spi, delay, self.init_sequence, IS_BUSY_LOW)?;
 
         self.set_lut(spi, None)?;
 
-        self.wait_until_idle();
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
         Ok(())
     }
 }
@@ -150,6 +138,20 @@ where
     DELAY: DelayMs<u8>,
 {
     type DisplayColor = Color;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: true,
+        grayscale_levels: 2,
+        chromatic_colors: 0,
+        readback: false,
+        fast_mode: true,
+        ultrafast: true,
+        clear_refreshes: false,
+    };
+    const ENERGY: RefreshEnergy = RefreshEnergy {
+        full: 52_800,
+        fast: 7_920,
+        partial: 7_920,
+    };
     fn width(&self) -> u32 {
         WIDTH
     }
@@ -165,26 +167,28 @@ where
         dc: DC,
         rst: RST,
         delay: &mut DELAY,
-    ) -> Result<Self, SPI::Error> {
-        let interface = DisplayInterface::new(cs, busy, dc, rst);
-
-        let mut epd = Epd1in54 {
-            interface,
-            background_color: DEFAULT_BACKGROUND_COLOR,
-            refresh: RefreshLut::Full,
-        };
-
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
         epd.init(spi, delay)?;
 
         Ok(epd)
     }
 
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.init(spi, delay)
     }
 
-    fn sleep(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
         // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode
         //TODO: is 0x00 needed here or would 0x01 be even more efficient?
         self.interface
@@ -196,12 +200,13 @@ where
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
-        _delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
         self.use_full_frame(spi)?;
         self.interface
-            .cmd_with_data(spi, Command::WriteRam, buffer)?;
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
         Ok(())
     }
 
@@ -214,28 +219,24 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
         self.set_ram_area(spi, x, y, x + width, y + height)?;
         self.set_ram_counter(spi, x, y)?;
 
         self.interface
-            .cmd_with_data(spi, Command::WriteRam, buffer)?;
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
         Ok(())
     }
 
-    fn display_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        // enable clock signal, enable cp, display pattern -> 0xC4 (tested with the arduino version)
-        //TODO: test control_1 or control_2 with default value 0xFF (from the datasheet)
-        self.interface
-            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xC4])?;
-
-        self.interface.cmd(spi, Command::MasterActivation)?;
-        // MASTER Activation should not be interupted to avoid currption of panel images
-        // therefore a terminate command is send
-        self.interface.cmd(spi, Command::Nop)?;
-        Ok(())
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)
     }
 
     fn update_and_display_frame(
@@ -243,22 +244,26 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
         self.use_full_frame(spi)?;
 
         // clear the ram with the background color
         let color = self.background_color.get_byte_value();
 
         self.interface.cmd(spi, Command::WriteRam)?;
-        self.interface
-            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
+        self.interface.clear_data(spi, color, WIDTH / 8 * HEIGHT)?;
         Ok(())
     }
 
@@ -274,18 +279,250 @@ where
         &mut self,
         spi: &mut SPI,
         refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         if let Some(refresh_lut) = refresh_rate {
             self.refresh = refresh_lut;
         }
+        self.forced_lut = false;
         match self.refresh {
             RefreshLut::Full => self.set_lut_helper(spi, &LUT_FULL_UPDATE),
             RefreshLut::Quick => self.set_lut_helper(spi, &LUT_PARTIAL_UPDATE),
+            RefreshLut::Ultrafast => self.set_lut_helper(spi, &LUT_ULTRAFAST_UPDATE),
+        }
+    }
+
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // the ghosts of too many ultrafast refreshes are cleared with a full refresh, the
+        // selected LUT is only sent again for the next refresh to not wait for this one
+        if self.interface.count_refresh(self.refresh) != self.refresh {
+            self.set_lut_helper(spi, &LUT_FULL_UPDATE)?;
+            self.forced_lut = true;
+        } else if self.forced_lut {
+            self.set_lut(spi, None)?;
+        }
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // enable clock signal, enable cp, display pattern -> 0xC4 (tested with the arduino version)
+        //TODO: test control_1 or control_2 with default value 0xFF (from the datasheet)
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xC4])?;
+
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        // MASTER Activation should not be interupted to avoid currption of panel images
+        // therefore a terminate command is send
+        self.interface.cmd(spi, Command::Nop)?;
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        _spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.poll_refresh(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RowRendering<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_frame_with<F>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        f: F,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut(u32, &mut [u8]),
+    {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        let mut row = [0; buffer_len(WIDTH as usize, 1)];
+        let background = self.background_color.get_byte_value();
+        render_rows(HEIGHT, &mut row, background, f, |row| {
+            self.interface.frame_data(spi, row)
+        })
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Snapshot for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = Color;
+
+    fn snapshot(&self) -> DriverSnapshot<Color> {
+        self.interface
+            .state()
+            .snapshot(self.background_color, self.refresh)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<Color>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.background_color = snapshot.background_color;
+        self.refresh = snapshot.refresh;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        match plane {
+            Plane::Bw => self
+                .update_frame(spi, data, delay)
+                .map_err(RawPlaneError::Interface),
+            Plane::Chromatic => Err(RawPlaneError::UnsupportedPlane(plane)),
         }
     }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ResumableUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_frame_from(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        offset: usize,
+        _delay: &mut DELAY,
+    ) -> Result<(), InterruptedUpload<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        self.start_frame_at(spi, offset)
+            .map_err(|error| InterruptedUpload {
+                error,
+                sent: offset,
+            })?;
+        self.interface.frame_data_from(spi, buffer, offset)
+    }
+}
 
-    fn is_busy(&self) -> bool {
-        self.interface.is_busy(IS_BUSY_LOW)
+impl<SPI, CS, BUSY, DC, RST, DELAY> InitSequence for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> {
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+
+        Epd1in54 {
+            interface,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            forced_lut: false,
+            init_sequence: &INIT_SEQUENCE,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> UltrafastRefresh for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_ultrafast_limit(&mut self, limit: u16) {
+        self.interface.set_ultrafast_limit(limit);
+    }
+
+    fn ultrafast_limit(&self) -> u16 {
+        self.interface.ultrafast_limit()
+    }
+
+    fn ghosting(&self) -> u16 {
+        self.interface.ghosting()
     }
 }
 
@@ -298,11 +535,14 @@ where
     RST: OutputPin,
     DELAY: DelayMs<u8>,
 {
-    fn wait_until_idle(&mut self) {
-        let _ = self.interface.wait_until_idle(IS_BUSY_LOW);
+    fn wait_until_idle(&mut self) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW)
     }
 
-    pub(crate) fn use_full_frame(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+    pub(crate) fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         // choose full frame/ram
         self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
 
@@ -310,6 +550,23 @@ where
         self.set_ram_counter(spi, 0, 0)
     }
 
+    /// Selects the full frame and starts writing the RAM at byte `offset` of the frame
+    fn start_frame_at(
+        &mut self,
+        spi: &mut SPI,
+        offset: usize,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let row_len = WIDTH as usize / 8;
+        self.wait_until_idle()?;
+        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
+        self.set_ram_counter(
+            spi,
+            (offset % row_len * 8) as u32,
+            (offset / row_len) as u32,
+        )?;
+        self.interface.cmd(spi, Command::WriteRam)
+    }
+
     pub(crate) fn set_ram_area(
         &mut self,
         spi: &mut SPI,
@@ -317,10 +574,10 @@ where
         start_y: u32,
         end_x: u32,
         end_y: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        assert!(start_x < end_x);
-        assert!(start_y < end_y);
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
+        self.interface
+            .check_window(start_x < end_x && start_y < end_y)?;
 
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
@@ -349,8 +606,8 @@ where
         spi: &mut SPI,
         x: u32,
         y: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
         self.interface
@@ -365,9 +622,13 @@ where
         Ok(())
     }
 
-    fn set_lut_helper(&mut self, spi: &mut SPI, buffer: &[u8]) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        assert!(buffer.len() == 30);
+    fn set_lut_helper(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
+        self.interface.check_window(buffer.len() == 30)?;
 
         self.interface
             .cmd_with_data(spi, Command::WriteLutRegister, buffer)?;
@@ -385,4 +646,69 @@ mod tests {
         assert_eq!(HEIGHT, 200);
         assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
     }
+
+    #[test]
+    fn forced_full_refresh_restores_the_lut_with_the_next_refresh() {
+        extern crate std;
+        use crate::interface::mock::{NoPin, RecordingSpi};
+        use crate::traits::Telemetry;
+        use embedded_hal_mock::delay::MockNoop;
+        use std::vec::Vec;
+
+        let mut spi = RecordingSpi::default();
+        let mut delay = MockNoop::new();
+        let mut epd: Epd1in54<_, _, _, _, _, MockNoop> =
+            Epd1in54::new_uninitialized(NoPin, NoPin, NoPin, NoPin);
+        epd.set_lut(&mut spi, Some(RefreshLut::Ultrafast)).unwrap();
+        epd.set_ultrafast_limit(1);
+        epd.start_display_frame(&mut spi, &mut delay).unwrap();
+        assert!(epd.driver_state().next_refresh_is_full());
+
+        spi.0.clear();
+        epd.start_display_frame(&mut spi, &mut delay).unwrap();
+        let full_lut: Vec<u8> = [0x32].iter().chain(&LUT_FULL_UPDATE).copied().collect();
+        assert!(spi.0.starts_with(&full_lut));
+        // nothing is sent after the activation, the refresh isn't waited for
+        assert!(spi.0.ends_with(&[0x20, 0xFF]));
+        assert!(!epd.driver_state().next_refresh_is_full());
+
+        spi.0.clear();
+        epd.start_display_frame(&mut spi, &mut delay).unwrap();
+        let ultrafast_lut: Vec<u8> = [0x32]
+            .iter()
+            .chain(&LUT_ULTRAFAST_UPDATE)
+            .copied()
+            .collect();
+        assert!(spi.0.starts_with(&ultrafast_lut));
+    }
+
+    #[test]
+    #[cfg(feature = "graphics")]
+    fn snapshot_keeps_the_ghosting_and_the_orientation() {
+        use crate::graphics::{DisplayRotation, Orientation};
+        use crate::interface::mock::{NoPin, RecordingSpi};
+        use crate::traits::{OrientationSetting, Telemetry};
+        use embedded_hal_mock::delay::MockNoop;
+
+        let mut spi = RecordingSpi::default();
+        let mut delay = MockNoop::new();
+        let mut epd: Epd1in54<_, _, _, _, _, MockNoop> =
+            Epd1in54::new_uninitialized(NoPin, NoPin, NoPin, NoPin);
+        epd.set_lut(&mut spi, Some(RefreshLut::Ultrafast)).unwrap();
+        epd.set_ultrafast_limit(2);
+        epd.start_display_frame(&mut spi, &mut delay).unwrap();
+        epd.start_display_frame(&mut spi, &mut delay).unwrap();
+        epd.set_orientation(Orientation::new(DisplayRotation::Rotate90));
+
+        // the driver is created again after a deep sleep
+        let snapshot = epd.snapshot();
+        let mut epd: Epd1in54<RecordingSpi, _, _, _, _, MockNoop> =
+            Epd1in54::new_uninitialized(NoPin, NoPin, NoPin, NoPin);
+        epd.set_ultrafast_limit(2);
+        epd.restore(snapshot);
+        assert_eq!(epd.snapshot(), snapshot);
+        assert_eq!(epd.ghosting(), 2);
+        assert_eq!(epd.orientation().rotation, DisplayRotation::Rotate90);
+        assert!(epd.driver_state().next_refresh_is_full());
+    }
 }
@@ -0,0 +1,860 @@
+//! A Driver for the Waveshare 1.54" E-Ink Display (V2) via SPI
+//!
+//! The V2 revision of the 1.54" module uses the SSD1681 controller instead of the IL3829 of the
+//! [V1 driver](crate::epd1in54), which sends its own LUTs. The waveforms of the full and the
+//! partial refresh come from the OTP of the controller, so no LUT is sent.
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/1.54inch_e-Paper_Module_Manual)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_1in54_V2.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd1in54_V2.py)
+//!
+//! # Example for the 1.54" E-Ink Display V2
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{
+//!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
+//!};
+//!use epd_waveshare::{epd1in54_v2::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// Setup EPD
+//!let mut epd = Epd1in54::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// Use display graphics from embedded-graphics
+//!let mut display = Display1in54::default();
+//!
+//!// Use embedded graphics for drawing a line
+//!let _ = Line::new(Point::new(0, 50), Point::new(0, 150))
+//!    .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+//!    .draw(&mut display);
+//!
+//!// Display updated frame with a full refresh, it is the base for the partial refreshes
+//!epd.update_and_display_frame(&mut spi, &display.buffer(), &mut delay)?;
+//!
+//!// Draw something new here
+//!
+//!// Partial refresh of the changed pixels
+//!epd.set_lut(&mut spi, Some(RefreshLut::Quick))?;
+//!epd.update_and_display_frame(&mut spi, &display.buffer(), &mut delay)?;
+//!
+//!// Set the EPD to sleep
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
+    digital::v2::*,
+};
+
+use crate::controllers::ssd168x::{self, BorderWaveform, DataEntryMode};
+use crate::type_a::command::Command;
+
+mod constants;
+pub use self::constants::INIT_SEQUENCE;
+
+use crate::color::Color;
+
+use crate::energy::RefreshEnergy;
+use crate::traits::*;
+
+use crate::buffer_len;
+use crate::interface::{render_rows, DisplayInterface, InterfaceAccess, InterfaceState};
+use crate::settings::InitStep;
+use crate::traits::{DisplayError, QuickRefresh};
+
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(feature = "graphics")]
+pub use crate::epd1in54_v2::graphics::Display1in54;
+
+/// Width of epd1in54 V2 in pixels
+pub const WIDTH: u32 = 200;
+/// Height of epd1in54 V2 in pixels
+pub const HEIGHT: u32 = 200;
+/// Default Background Color (white)
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+const IS_BUSY_LOW: bool = false;
+
+/// Epd1in54 (V2) driver
+///
+/// With [`RefreshLut::Full`] every frame is written to both RAMs of the controller, so the
+/// last frame is always the base of a partial refresh. With [`RefreshLut::Quick`]
+/// [`display_frame`](WaveshareDisplay::display_frame) only refreshes the pixels which differ
+/// from the base and the updates don't touch the base. Use
+/// [`set_partial_base_buffer`](Epd1in54::set_partial_base_buffer) or
+/// [`QuickRefresh`] to move the base along.
+pub struct Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// SPI
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    /// Color
+    background_color: Color,
+    /// Refresh LUT
+    refresh: RefreshLut,
+    /// Init sequence
+    init_sequence: &'static [InitStep],
+    /// Waveform of `display_new_frame`
+    quick_lut: RefreshLut,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, 2)?;
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface
+            .run_init_sequence(spi, delay, self.init_sequence, IS_BUSY_LOW)?;
+
+        self.use_full_frame(spi)?;
+
+        // the border follows LUT1 of the waveform
+        ssd168x::set_border_waveform(&mut self.interface, spi, BorderWaveform::Lut(1))?;
+
+        // the internal temperature sensor
+        self.interface
+            .cmd_with_data(spi, Command::TemperatureSensorSelection, &[0x80])?;
+
+        // Enable clock signal, Enable Analog, Load temperature value, Load LUT of DISPLAY Mode 1, Disable OSC
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xB1])?;
+        self.interface.cmd(spi, Command::MasterActivation)?;
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type DisplayColor = Color;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: true,
+        grayscale_levels: 2,
+        chromatic_colors: 0,
+        readback: false,
+        fast_mode: true,
+        ultrafast: false,
+        clear_refreshes: false,
+    };
+    const ENERGY: RefreshEnergy = RefreshEnergy {
+        full: 52_800,
+        fast: 7_920,
+        partial: 7_920,
+    };
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode 1
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
+        Ok(())
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)?;
+        Ok(())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.check_buffer(buffer, WIDTH, HEIGHT)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        if self.refresh == RefreshLut::Quick {
+            self.prepare_partial_refresh(spi, delay)?;
+        } else {
+            self.use_full_frame(spi)?;
+        }
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+
+        if self.refresh == RefreshLut::Full {
+            // Always keep the base buffer equal to current if not doing partial refresh.
+            self.set_partial_base_buffer(spi, buffer)?;
+        }
+        Ok(())
+    }
+
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+
+        if self.refresh == RefreshLut::Full {
+            // Always keep the base buffer equal to current if not doing partial refresh.
+            self.set_window(spi, x, y, width, height)?;
+            self.interface
+                .cmd_with_frame_data(spi, Command::WriteRam2, buffer)?;
+        }
+        Ok(())
+    }
+
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+
+        if self.refresh == RefreshLut::Quick {
+            self.set_partial_base_buffer(spi, buffer)?;
+        }
+        Ok(())
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        let color = self.background_color.get_byte_value();
+
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.clear_data(
+            spi,
+            color,
+            buffer_len(WIDTH as usize, HEIGHT as usize) as u32,
+        )?;
+        if self.interface.clear_aborted() {
+            return Ok(());
+        }
+
+        // Always keep the base buffer equal to current if not doing partial refresh.
+        if self.refresh == RefreshLut::Full {
+            self.use_full_frame(spi)?;
+            self.interface.cmd(spi, Command::WriteRam2)?;
+            self.interface.clear_data(
+                spi,
+                color,
+                buffer_len(WIDTH as usize, HEIGHT as usize) as u32,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+
+    /// Only selects the refresh, the waveforms are taken from the OTP of the controller
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut.without_ultrafast();
+        }
+        Ok(())
+    }
+
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RowRendering<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_frame_with<F>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        mut f: F,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut(u32, &mut [u8]),
+    {
+        let mut row = [0; buffer_len(WIDTH as usize, 1)];
+        let background = self.background_color.get_byte_value();
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        if self.refresh == RefreshLut::Quick {
+            self.prepare_partial_refresh(spi, delay)?;
+        } else {
+            self.use_full_frame(spi)?;
+        }
+        self.interface.cmd(spi, Command::WriteRam)?;
+        render_rows(HEIGHT, &mut row, background, &mut f, |row| {
+            self.interface.frame_data(spi, row)
+        })?;
+
+        if self.refresh == RefreshLut::Full {
+            // Always keep the base buffer equal to current if not doing partial refresh.
+            self.use_full_frame(spi)?;
+            self.interface.cmd(spi, Command::WriteRam2)?;
+            render_rows(HEIGHT, &mut row, background, &mut f, |row| {
+                self.interface.frame_data(spi, row)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Snapshot for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = Color;
+
+    fn snapshot(&self) -> DriverSnapshot<Color> {
+        self.interface
+            .state()
+            .snapshot(self.background_color, self.refresh)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<Color>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.background_color = snapshot.background_color;
+        self.refresh = snapshot.refresh;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        match plane {
+            Plane::Bw => self
+                .update_frame(spi, data, delay)
+                .map_err(RawPlaneError::Interface),
+            Plane::Chromatic => Err(RawPlaneError::UnsupportedPlane(plane)),
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InitSequence for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> {
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+
+        Epd1in54 {
+            interface,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            init_sequence: &INIT_SEQUENCE,
+            quick_lut: RefreshLut::Quick,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> TemperatureSetting for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_temperature(&mut self, celsius: i8) {
+        self.interface.set_temperature(Some(celsius));
+    }
+
+    fn use_internal_sensor(&mut self) {
+        self.interface.set_temperature(None);
+    }
+
+    fn temperature(&self) -> Option<i8> {
+        self.interface.temperature()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> TemperatureReading<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn read_temperature(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<i8, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        ssd168x::read_temperature(&mut self.interface, spi)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        let mode = match self.refresh {
+            // Enable clock signal, Enable Analog, Load temperature value, DISPLAY with DISPLAY Mode 1, Disable Analog, Disable OSC
+            RefreshLut::Full => 0xF7,
+            // the same with DISPLAY Mode 2, which only drives the pixels differing from the base
+            RefreshLut::Quick | RefreshLut::Ultrafast => 0xFF,
+        };
+        ssd168x::set_update_sequence(&mut self.interface, spi, mode)?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        _spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.poll_refresh(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ConnectionCheck<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn ping(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // enable the clock, load the temperature and disable the clock again, the LUT and the
+        // RAM stay as they are
+        self.interface
+            .ping(spi, IS_BUSY_LOW, delay, |interface, spi| {
+                interface.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xA1])?;
+                interface.cmd(spi, Command::MasterActivation)
+            })
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Writes the base buffer, which the partial refresh compares the new frame with
+    ///
+    /// Only needed with [`RefreshLut::Quick`], after a partial refresh
+    /// [`update_and_display_frame`](WaveshareDisplay::update_and_display_frame) does it already.
+    pub fn set_partial_base_buffer(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.check_buffer(buffer, WIDTH, HEIGHT)?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam2, buffer)
+    }
+
+    fn wait_until_idle(&mut self) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW)
+    }
+
+    /// Resets the controller without losing the RAM and sets it up for a partial refresh
+    fn prepare_partial_refresh(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, 1)?;
+
+        // the border keeps its level instead of following the LUT
+        ssd168x::set_border_waveform(&mut self.interface, spi, BorderWaveform::Vcom)?;
+        self.interface
+            .cmd_with_data(spi, Command::DriverOutputControl, &[0xC7, 0x00, 0x00])?;
+        ssd168x::set_data_entry_mode(
+            &mut self.interface,
+            spi,
+            DataEntryMode::IncrementXIncrementY,
+        )?;
+
+        self.use_full_frame(spi)
+    }
+
+    fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.set_window(spi, 0, 0, WIDTH, HEIGHT)
+    }
+
+    /// Selects the window of `width` x `height` pixels at `x`, `y` and moves the counter to its start
+    fn set_window(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        ssd168x::set_window(&mut self.interface, spi, 0, x, y, width, height)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Writes the base image to both RAMs, to be shown with a full `display_frame`.
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+        self.set_partial_base_buffer(spi, buffer)
+    }
+
+    /// To be used after `update_old_frame`, the base image stays untouched.
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.prepare_partial_refresh(spi, delay)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)
+    }
+
+    /// For a partial refresh of the new updated frame. To be used immediately after `update_new_frame`
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if self.quick_lut == RefreshLut::Full {
+            // the full waveform of `display_frame`, the LUT selected with `set_lut` stays
+            let refresh = core::mem::replace(&mut self.refresh, RefreshLut::Full);
+            let result = self.display_frame(spi, delay);
+            self.refresh = refresh;
+            return result;
+        }
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        ssd168x::set_update_sequence(&mut self.interface, spi, 0xFF)?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+
+    /// Updates and displays the new frame.
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_new_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam2, buffer)
+    }
+
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)
+    }
+
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_area(x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        let color = self.background_color.get_byte_value();
+
+        self.set_window(spi, x, y, width, height)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.clear_data(
+            spi,
+            color,
+            buffer_len(width as usize, height as usize) as u32,
+        )
+    }
+
+    fn set_quick_lut(
+        &mut self,
+        _spi: &mut SPI,
+        refresh: RefreshLut,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.quick_lut = refresh;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 200);
+        assert_eq!(HEIGHT, 200);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+    }
+
+    #[test]
+    fn init_and_partial_window_commands() {
+        use crate::interface::mock::{CommandSpi, NoPin};
+        use embedded_hal_mock::delay::MockNoop;
+
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let mut epd: Epd1in54<_, _, _, _, _, MockNoop> =
+            Epd1in54::new(&mut spi, NoPin, NoPin, dc, NoPin, &mut MockNoop::new()).unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x12, 0x01, 0x11, 0x44, 0x45, 0x4E, 0x4F, 0x3C, 0x18, 0x22, 0x20]
+        );
+        assert!(spi.sent(0x01, &[0xC7, 0x00, 0x00]));
+        assert!(spi.sent(0x44, &[0x00, 0x18]));
+        assert!(spi.sent(0x45, &[0x00, 0x00, 0xC7, 0x00]));
+        assert!(spi.sent(0x22, &[0xB1]));
+
+        spi.clear();
+        epd.update_partial_frame(&mut spi, &[0xAA, 0xBB, 0xCC, 0xDD], 8, 16, 16, 2)
+            .unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x44, 0x45, 0x4E, 0x4F, 0x24, 0x44, 0x45, 0x4E, 0x4F, 0x26]
+        );
+        assert!(spi.sent(0x44, &[1, 2]));
+        assert!(spi.sent(0x45, &[16, 0, 17, 0]));
+        assert!(spi.sent(0x24, &[0xAA, 0xBB, 0xCC, 0xDD]));
+        // the full refresh keeps the base buffer equal
+        assert!(spi.sent(0x26, &[0xAA, 0xBB, 0xCC, 0xDD]));
+    }
+}
@@ -1,3 +1,18 @@
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Power, booster and panel setup, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 4] = [
+    // set the power settings
+    InitStep::new(Command::PowerSetting as u8, &[0x07, 0x00, 0x08, 0x00]),
+    // start the booster
+    InitStep::new(Command::BoosterSoftStart as u8, &[0x07, 0x07, 0x07]),
+    // power on
+    InitStep::new(Command::PowerOn as u8, &[]).delay(5).wait(),
+    // set the panel settings
+    InitStep::new(Command::PanelSetting as u8, &[0xCF]),
+];
+
 pub(crate) const LUT_VCOM0: &[u8] = &[
     0x0E, 0x14, 0x01, 0x0A, 0x06, 0x04, 0x0A, 0x0A, 0x0F, 0x03, 0x03, 0x0C, 0x06, 0x0A, 0x00,
 ];
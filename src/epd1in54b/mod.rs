@@ -5,13 +5,23 @@ use embedded_hal::{
     digital::v2::*,
 };
 
-use crate::interface::DisplayInterface;
+use crate::buffer_len;
+use crate::energy::RefreshEnergy;
+use crate::interface::{
+    for_each_frame_chunk, render_rows, DisplayInterface, InterfaceAccess, InterfaceState,
+};
+use crate::settings::{FrameRate, InitStep, VcomAndDataInterval};
 use crate::traits::{
-    InternalWiAdditions, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+    BusyState, Capabilities, ChangedPlanes, ColorSpec, ConnectionCheck, DisplayError,
+    DriverSnapshot, FastWake, FrameRateSetting, InitSequence, InternalWiAdditions, LazyInit,
+    LifecycleEvent, Link, NonBlockingRefresh, Plane, PlaneTracking, RawPlaneError, RawPlaneUpload,
+    RefreshLut, RefreshState, RowRendering, Snapshot, VcomAndDataIntervalSetting, WaveshareDisplay,
+    WaveshareThreeColorDisplay,
 };
 
 //The Lookup Tables for the Display
 mod constants;
+pub use self::constants::INIT_SEQUENCE;
 use crate::epd1in54b::constants::*;
 
 /// Width of epd1in54 in pixels
@@ -22,6 +32,10 @@ pub const HEIGHT: u32 = 200;
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
 const IS_BUSY_LOW: bool = true;
 
+const DEFAULT_FRAME_RATE: FrameRate = FrameRate::Hz200;
+
+const DEFAULT_VCOM_AND_DATA_INTERVAL: VcomAndDataInterval = VcomAndDataInterval::from_byte(0x37);
+
 use crate::color::Color;
 
 pub(crate) mod command;
@@ -36,6 +50,10 @@ pub use self::graphics::Display1in54b;
 pub struct Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY> {
     interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
     color: Color,
+    chromatic_background: bool,
+    vcom_interval: VcomAndDataInterval,
+    frame_rate: FrameRate,
+    init_sequence: &'static [InitStep],
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
@@ -48,29 +66,24 @@ where
     RST: OutputPin,
     DELAY: DelayMs<u8>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.reset(delay, 10);
-
-        // set the power settings
-        self.interface
-            .cmd_with_data(spi, Command::PowerSetting, &[0x07, 0x00, 0x08, 0x00])?;
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, 10)?;
 
-        // start the booster
         self.interface
-            .cmd_with_data(spi, Command::BoosterSoftStart, &[0x07, 0x07, 0x07])?;
-
-        // power on
-        self.command(spi, Command::PowerOn)?;
-        delay.delay_ms(5);
-        self.wait_until_idle();
+            .run_init_sequence(spi, delay, self.init_sequence, IS_BUSY_LOW)?;
 
-        // set the panel settings
-        self.cmd_with_data(spi, Command::PanelSetting, &[0xCF])?;
-
-        self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x37])?;
+        self.cmd_with_data(
+            spi,
+            Command::VcomAndDataIntervalSetting,
+            &[self.vcom_interval.to_byte()],
+        )?;
 
         // PLL
-        self.cmd_with_data(spi, Command::PllControl, &[0x39])?;
+        self.cmd_with_data(spi, Command::PllControl, &[self.frame_rate.to_byte()])?;
 
         // set resolution
         self.send_resolution(spi)?;
@@ -79,7 +92,7 @@ where
 
         self.set_lut(spi, None)?;
 
-        self.wait_until_idle();
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
 
         Ok(())
     }
@@ -100,33 +113,46 @@ where
         spi: &mut SPI,
         black: &[u8],
         chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.update_achromatic_frame(spi, black)?;
         self.update_chromatic_frame(spi, chromatic)
     }
 
-    fn update_achromatic_frame(&mut self, spi: &mut SPI, black: &[u8]) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn update_achromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
         self.send_resolution(spi)?;
 
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
 
-        for b in black {
-            let expanded = expand_bits(*b);
-            self.interface.data(spi, &expanded)?;
-        }
-        Ok(())
+        self.send_expanded_frame_data(spi, black)
     }
 
     fn update_chromatic_frame(
         &mut self,
         spi: &mut SPI,
         chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
         self.interface.cmd(spi, Command::DataStartTransmission2)?;
-        self.interface.data(spi, chromatic)?;
+        self.interface.frame_data(spi, chromatic)?;
         Ok(())
     }
+
+    fn set_background(&mut self, background: ColorSpec) {
+        self.color = background.achromatic;
+        self.chromatic_background = background.chromatic;
+    }
+
+    fn background(&self) -> ColorSpec {
+        ColorSpec {
+            achromatic: self.color,
+            chromatic: self.chromatic_background,
+        }
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
@@ -140,6 +166,16 @@ where
     DELAY: DelayMs<u8>,
 {
     type DisplayColor = Color;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: false,
+        grayscale_levels: 2,
+        chromatic_colors: 1,
+        readback: false,
+        fast_mode: false,
+        ultrafast: false,
+        clear_refreshes: false,
+    };
+    const ENERGY: RefreshEnergy = RefreshEnergy::uniform(211_200);
     fn new(
         spi: &mut SPI,
         cs: CS,
@@ -147,19 +183,21 @@ where
         dc: DC,
         rst: RST,
         delay: &mut DELAY,
-    ) -> Result<Self, SPI::Error> {
-        let interface = DisplayInterface::new(cs, busy, dc, rst);
-        let color = DEFAULT_BACKGROUND_COLOR;
-
-        let mut epd = Epd1in54b { interface, color };
-
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
         epd.init(spi, delay)?;
 
         Ok(epd)
     }
 
-    fn sleep(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.set_retained(false);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
         self.interface
             .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x17])?; //border floating
 
@@ -169,7 +207,7 @@ where
         self.interface
             .cmd_with_data(spi, Command::PowerSetting, &[0x02, 0x00, 0x00, 0x00])?; //VG&VS to 0V fast
 
-        self.wait_until_idle();
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
 
         //NOTE: The example code has a 1s delay here
 
@@ -178,7 +216,11 @@ where
         Ok(())
     }
 
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.init(spi, delay)
     }
 
@@ -202,18 +244,15 @@ where
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
-        _delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
         self.send_resolution(spi)?;
 
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
 
-        for b in buffer {
-            // Two bits per pixel
-            let expanded = expand_bits(*b);
-            self.interface.data(spi, &expanded)?;
-        }
+        self.send_expanded_frame_data(spi, buffer)?;
 
         //NOTE: Example code has a delay here
 
@@ -237,14 +276,17 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        unimplemented!()
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // not supported by the display, nothing is sent
+        Ok(())
     }
 
-    fn display_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        self.command(spi, Command::DisplayRefresh)?;
-        Ok(())
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)
     }
 
     fn update_and_display_frame(
@@ -252,29 +294,40 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
         self.send_resolution(spi)?;
 
-        let color = DEFAULT_BACKGROUND_COLOR.get_byte_value();
+        let background = self.background();
 
         // Clear the black
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
 
         // Uses 2 bits per pixel
-        self.interface
-            .data_x_times(spi, color, 2 * (WIDTH * HEIGHT / 8))?;
+        self.interface.clear_data(
+            spi,
+            background.achromatic.get_byte_value(),
+            2 * (WIDTH * HEIGHT / 8),
+        )?;
+        if self.interface.clear_aborted() {
+            return Ok(());
+        }
 
         // Clear the red
         self.interface.cmd(spi, Command::DataStartTransmission2)?;
         self.interface
-            .data_x_times(spi, color, WIDTH * HEIGHT / 8)?;
+            .clear_data(spi, background.chromatic_byte(), WIDTH * HEIGHT / 8)?;
         Ok(())
     }
 
@@ -282,7 +335,7 @@ where
         &mut self,
         spi: &mut SPI,
         _refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.interface
             .cmd_with_data(spi, Command::LutForVcom, LUT_VCOM0)?;
         self.interface
@@ -301,8 +354,330 @@ where
         Ok(())
     }
 
-    fn is_busy(&self) -> bool {
-        self.interface.is_busy(IS_BUSY_LOW)
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.command(spi, Command::DisplayRefresh)?;
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        _spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.poll_refresh(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RowRendering<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_frame_with<F>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        f: F,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut(u32, &mut [u8]),
+    {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.send_resolution(spi)?;
+
+        self.interface.cmd(spi, Command::DataStartTransmission1)?;
+        let mut row = [0; buffer_len(WIDTH as usize, 1)];
+        let background = self.color.get_byte_value();
+        render_rows(HEIGHT, &mut row, background, f, |row| {
+            self.send_expanded_frame_data(spi, row)
+        })?;
+
+        // Clear the read layer
+        let color = self.color.get_byte_value();
+        let nbits = WIDTH * (HEIGHT / 8);
+
+        self.interface.cmd(spi, Command::DataStartTransmission2)?;
+        self.interface.data_x_times(spi, color, nbits)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> VcomAndDataIntervalSetting<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_vcom_and_data_interval(
+        &mut self,
+        spi: &mut SPI,
+        setting: VcomAndDataInterval,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.vcom_interval = setting;
+        self.interface.cmd_with_data(
+            spi,
+            Command::VcomAndDataIntervalSetting,
+            &[self.vcom_interval.to_byte()],
+        )
+    }
+
+    fn vcom_and_data_interval(&self) -> VcomAndDataInterval {
+        self.vcom_interval
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> FrameRateSetting<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_frame_rate(
+        &mut self,
+        spi: &mut SPI,
+        rate: FrameRate,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.frame_rate = rate;
+        self.interface
+            .cmd_with_data(spi, Command::PllControl, &[self.frame_rate.to_byte()])
+    }
+
+    fn frame_rate(&self) -> FrameRate {
+        self.frame_rate
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Snapshot for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = Color;
+
+    fn snapshot(&self) -> DriverSnapshot<Color> {
+        self.interface
+            .state()
+            .snapshot(self.color, RefreshLut::Full)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<Color>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.color = snapshot.background_color;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        _delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        match plane {
+            Plane::Bw => self.update_achromatic_frame(spi, data),
+            Plane::Chromatic => self.update_chromatic_frame(spi, data),
+        }
+        .map_err(RawPlaneError::Interface)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> FastWake<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn power_off(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface.cmd(spi, Command::PowerOff)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface.set_retained(true);
+        Ok(())
+    }
+
+    fn wake_up_fast(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if !self.interface.retained() {
+            return self.wake_up(spi, delay);
+        }
+        self.interface.set_busy_reason(BusyState::PoweringUp);
+        self.interface.cmd(spi, Command::PowerOn)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface.set_retained(false);
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InitSequence for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY> {
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        Epd1in54b {
+            interface,
+            color,
+            chromatic_background: false,
+            vcom_interval: DEFAULT_VCOM_AND_DATA_INTERVAL,
+            frame_rate: DEFAULT_FRAME_RATE,
+            init_sequence: &INIT_SEQUENCE,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> PlaneTracking<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_changed_planes_and_display(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+        chromatic: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<ChangedPlanes, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        let changed = self.interface.changed_planes(black, chromatic);
+        if changed.achromatic {
+            self.update_achromatic_frame(spi, black)?;
+        }
+        if changed.chromatic {
+            self.update_chromatic_frame(spi, chromatic)?;
+        }
+        self.display_frame(spi, delay)?;
+        self.interface.planes_sent(black, chromatic);
+        Ok(changed)
+    }
+
+    fn forget_planes(&mut self) {
+        self.interface.forget_planes();
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ConnectionCheck<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn ping(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // the busy pin is low while the temperature is sensed
+        self.interface
+            .ping(spi, IS_BUSY_LOW, delay, |interface, spi| {
+                interface.cmd(spi, Command::TemperatureSensor)
+            })
     }
 }
 
@@ -315,28 +690,55 @@ where
     RST: OutputPin,
     DELAY: DelayMs<u8>,
 {
-    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
+    fn command(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.interface.cmd(spi, command)
     }
 
-    fn send_data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+    fn send_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.interface.data(spi, data)
     }
 
+    /// Sends frame data with two bits per pixel, as the black/white layer needs it
+    fn send_expanded_frame_data(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let hooks = self.interface.frame_hooks();
+        for_each_frame_chunk(hooks, buffer, |chunk| {
+            for b in chunk {
+                let expanded = expand_bits(*b);
+                self.interface.data(spi, &expanded)?;
+            }
+            Ok(())
+        })
+    }
+
     fn cmd_with_data(
         &mut self,
         spi: &mut SPI,
         command: Command,
         data: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.interface.cmd_with_data(spi, command, data)
     }
 
-    fn wait_until_idle(&mut self) {
-        let _ = self.interface.wait_until_idle(IS_BUSY_LOW);
+    fn wait_until_idle(&mut self) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW)
     }
 
-    fn send_resolution(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+    fn send_resolution(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         let w = self.width();
         let h = self.height();
 
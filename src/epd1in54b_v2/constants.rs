@@ -0,0 +1,18 @@
+//! Init sequence of the controller
+
+use crate::controllers::ssd168x::DataEntryMode;
+use crate::settings::InitStep;
+use crate::type_a::command::Command;
+
+/// Software reset, gate lines and data entry mode, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 3] = [
+    InitStep::new(Command::SwReset as u8, &[]).wait(),
+    // 200 gate lines (0xC7 + 1), default scanning order; the Waveshare driver reverses both
+    // the scanning order and the y direction of the counter, which gives the same image
+    InitStep::new(Command::DriverOutputControl as u8, &[0xC7, 0x00, 0x00]),
+    // x increment, y increment, address counter is updated in x direction
+    InitStep::new(
+        Command::DataEntryModeSetting as u8,
+        &[DataEntryMode::IncrementXIncrementY as u8],
+    ),
+];
@@ -0,0 +1,129 @@
+use crate::color::TriColor;
+use crate::epd1in54b_v2::{DEFAULT_BACKGROUND_COLOR, HEIGHT, NUM_DISPLAY_BITS, WIDTH};
+use crate::graphics::{DisplayColorRendering, DisplayRotation, Mirroring, TriDisplay};
+use embedded_graphics_core::prelude::*;
+
+/// Full size buffer for use with the 1in54b V2 EPD
+///
+/// The chromatic plane is inverted, set bits are red in it.
+pub struct Display1in54b {
+    buffer: [u8; 2 * NUM_DISPLAY_BITS as usize],
+    rotation: DisplayRotation,
+    mirroring: Mirroring,
+}
+
+impl Default for Display1in54b {
+    fn default() -> Self {
+        let mut display = Display1in54b {
+            buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value(); 2 * NUM_DISPLAY_BITS as usize],
+            rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
+        };
+        // the chromatic plane starts without any red
+        let offset = display.chromatic_offset();
+        display.buffer[offset..].fill(0x00);
+        display
+    }
+}
+
+impl DrawTarget for Display1in54b {
+    type Color = TriColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.draw_helper_tri(WIDTH, HEIGHT, pixel, DisplayColorRendering::Negative)?;
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Display1in54b {
+    fn size(&self) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+}
+
+impl TriDisplay for Display1in54b {
+    fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn get_mut_buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn chromatic_offset(&self) -> usize {
+        NUM_DISPLAY_BITS as usize
+    }
+
+    fn bw_buffer(&self) -> &[u8] {
+        &self.buffer[0..self.chromatic_offset()]
+    }
+
+    fn chromatic_buffer(&self) -> &[u8] {
+        &self.buffer[self.chromatic_offset()..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use embedded_graphics::{
+        prelude::*,
+        primitives::{Line, PrimitiveStyle},
+    };
+
+    // test buffer length
+    #[test]
+    fn graphics_size() {
+        let display = Display1in54b::default();
+        assert_eq!(display.bw_buffer().len(), 5000);
+        assert_eq!(display.chromatic_buffer().len(), 5000);
+    }
+
+    // test default background color on all bytes
+    #[test]
+    fn graphics_default() {
+        let display = Display1in54b::default();
+        for &byte in display.bw_buffer() {
+            assert_eq!(byte, DEFAULT_BACKGROUND_COLOR.get_byte_value());
+        }
+        for &byte in display.chromatic_buffer() {
+            assert_eq!(byte, 0x00);
+        }
+    }
+
+    // a chromatic pixel sets its bit in the chromatic plane and leaves the b/w plane white
+    #[test]
+    fn graphics_chromatic_pixel() {
+        let mut display = Display1in54b::default();
+        let _ = Line::new(Point::new(0, 0), Point::new(7, 0))
+            .into_styled(PrimitiveStyle::with_stroke(TriColor::Chromatic, 1))
+            .draw(&mut display);
+
+        assert_eq!(display.bw_buffer()[0], Color::White.get_byte_value());
+        assert_eq!(display.chromatic_buffer()[0], 0xFF);
+        assert_eq!(display.chromatic_buffer()[1], 0x00);
+    }
+}
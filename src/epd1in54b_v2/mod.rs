@@ -0,0 +1,728 @@
+//! A simple Driver for the Waveshare 1.54" (B) V2 E-Ink Display via SPI
+//!
+//! The V2 revision of the 200x200 black/white/red panel has a SSD1681 instead of the controller
+//! of the [original panel](crate::epd1in54b). It takes the waveform from its OTP, so unlike the
+//! original no LUTs are sent, and it signals busy with a high level. The black plane
+//! goes into the black/white RAM and the red plane into the red RAM, where a set bit shows red.
+//! A [`Display1in54b`] holds both planes in one buffer, already in this format.
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/1.54inch_e-Paper_Module_(B)_Manual)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_1in54b_V2.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd1in54b_V2.py)
+//!
+//! # Example for the 1.54" (B) V2 E-Ink Display
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{prelude::*, primitives::{Line, PrimitiveStyle}};
+//!use epd_waveshare::{epd1in54b_v2::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// Setup EPD
+//!let mut epd = Epd1in54b::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// Use display graphics from embedded-graphics
+//!let mut display = Display1in54b::default();
+//!
+//!// Use embedded graphics for drawing a black and a red line
+//!let _ = Line::new(Point::new(0, 120), Point::new(0, 199))
+//!    .into_styled(PrimitiveStyle::with_stroke(TriColor::Black, 1))
+//!    .draw(&mut display);
+//!let _ = Line::new(Point::new(15, 120), Point::new(15, 199))
+//!    .into_styled(PrimitiveStyle::with_stroke(TriColor::Chromatic, 1))
+//!    .draw(&mut display);
+//!
+//!// Display updated frame
+//!epd.update_color_frame(&mut spi, display.bw_buffer(), display.chromatic_buffer())?;
+//!epd.display_frame(&mut spi, &mut delay)?;
+//!
+//!// Set the EPD to sleep
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
+    digital::v2::*,
+};
+
+use crate::buffer_len;
+use crate::color::{Color, TriColor};
+use crate::controllers::ssd168x::{self, BorderWaveform};
+use crate::energy::RefreshEnergy;
+use crate::interface::{render_rows, DisplayInterface, InterfaceAccess, InterfaceState};
+use crate::settings::InitStep;
+use crate::traits::{
+    BusyState, Capabilities, ChangedPlanes, ColorSpec, ConnectionCheck, DisplayError,
+    DriverSnapshot, InitSequence, InternalWiAdditions, LazyInit, LifecycleEvent, Link,
+    NonBlockingRefresh, Plane, PlaneTracking, RawPlaneError, RawPlaneUpload, RefreshLut,
+    RefreshState, RowRendering, Snapshot, TemperatureReading, TemperatureSetting, WaveshareDisplay,
+    WaveshareThreeColorDisplay,
+};
+use crate::type_a::command::Command;
+
+mod constants;
+pub use self::constants::INIT_SEQUENCE;
+
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(feature = "graphics")]
+pub use self::graphics::Display1in54b;
+
+/// Width of epd1in54b_v2 in pixels
+pub const WIDTH: u32 = 200;
+/// Height of epd1in54b_v2 in pixels
+pub const HEIGHT: u32 = 200;
+/// Default background color (white) of epd1in54b_v2 display
+pub const DEFAULT_BACKGROUND_COLOR: TriColor = TriColor::White;
+
+/// Number of bytes for b/w buffer and same for chromatic buffer
+const NUM_DISPLAY_BITS: u32 = buffer_len(WIDTH as usize, HEIGHT as usize) as u32;
+
+const IS_BUSY_LOW: bool = false;
+
+/// Epd1in54b driver
+pub struct Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY> {
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    color: TriColor,
+    chromatic_background: bool,
+    init_sequence: &'static [InitStep],
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, 2)?;
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface
+            .run_init_sequence(spi, delay, self.init_sequence, IS_BUSY_LOW)?;
+
+        self.use_full_frame(spi)?;
+
+        // the border follows the white waveform
+        ssd168x::set_border_waveform(&mut self.interface, spi, BorderWaveform::FollowLut(1))?;
+
+        // the internal temperature sensor
+        self.interface
+            .cmd_with_data(spi, Command::TemperatureSensorSelection, &[0x80])?;
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_color_frame(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+        chromatic: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_achromatic_frame(spi, black)?;
+        self.update_chromatic_frame(spi, chromatic)
+    }
+
+    /// Update only the black/white data of the display.
+    fn update_achromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, black)
+    }
+
+    /// Update only chromatic data of the display.
+    ///
+    /// Set bits are red, this data takes precedence over the black/white data.
+    fn update_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        chromatic: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam2, chromatic)
+    }
+
+    fn set_background(&mut self, background: ColorSpec) {
+        self.color = match background.achromatic {
+            Color::Black => TriColor::Black,
+            Color::White => TriColor::White,
+        };
+        self.chromatic_background = background.chromatic;
+    }
+
+    fn background(&self) -> ColorSpec {
+        let achromatic = match self.color {
+            TriColor::Black => Color::Black,
+            _ => Color::White,
+        };
+        ColorSpec {
+            achromatic,
+            chromatic: self.chromatic_background,
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type DisplayColor = TriColor;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: false,
+        grayscale_levels: 2,
+        chromatic_colors: 1,
+        readback: false,
+        fast_mode: false,
+        ultrafast: false,
+        clear_refreshes: false,
+    };
+    const ENERGY: RefreshEnergy = RefreshEnergy::uniform(120_000);
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode 1
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x01])
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)
+    }
+
+    fn set_background_color(&mut self, color: TriColor) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &TriColor {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.update_achromatic_frame(spi, buffer)?;
+
+        // Clear the chromatic layer, cleared bits aren't red
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface.data_x_times(spi, 0x00, NUM_DISPLAY_BITS)
+    }
+
+    /// Writes the black/white window and clears the red one, only shown with the next
+    /// full [`display_frame`](WaveshareDisplay::display_frame)
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        self.set_window(spi, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+
+        self.set_window(spi, x, y, width, height)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface.data_x_times(
+            spi,
+            0x00,
+            buffer_len(width as usize, height as usize) as u32,
+        )
+    }
+
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+
+        // The chromatic plane is inverted, set bits are red
+        let background = self.background();
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.clear_data(
+            spi,
+            background.achromatic.get_byte_value(),
+            NUM_DISPLAY_BITS,
+        )?;
+        if self.interface.clear_aborted() {
+            return Ok(());
+        }
+
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface
+            .clear_data(spi, !background.chromatic_byte(), NUM_DISPLAY_BITS)
+    }
+
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        Ok(())
+    }
+
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RowRendering<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_frame_with<F>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        f: F,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut(u32, &mut [u8]),
+    {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        let mut row = [0; buffer_len(WIDTH as usize, 1)];
+        let background = self.color.get_byte_value();
+        render_rows(HEIGHT, &mut row, background, f, |row| {
+            self.interface.frame_data(spi, row)
+        })?;
+
+        // Clear the chromatic layer, cleared bits aren't red
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface.data_x_times(spi, 0x00, NUM_DISPLAY_BITS)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Snapshot for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = TriColor;
+
+    fn snapshot(&self) -> DriverSnapshot<TriColor> {
+        self.interface
+            .state()
+            .snapshot(self.color, RefreshLut::Full)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<TriColor>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.color = snapshot.background_color;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        _delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        match plane {
+            Plane::Bw => self.update_achromatic_frame(spi, data),
+            Plane::Chromatic => self.update_chromatic_frame(spi, data),
+        }
+        .map_err(RawPlaneError::Interface)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InitSequence for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY> {
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        Epd1in54b {
+            interface,
+            color,
+            chromatic_background: false,
+            init_sequence: &INIT_SEQUENCE,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> PlaneTracking<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_changed_planes_and_display(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+        chromatic: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<ChangedPlanes, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        let changed = self.interface.changed_planes(black, chromatic);
+        if changed.achromatic {
+            self.update_achromatic_frame(spi, black)?;
+        }
+        if changed.chromatic {
+            self.update_chromatic_frame(spi, chromatic)?;
+        }
+        self.display_frame(spi, delay)?;
+        self.interface.planes_sent(black, chromatic);
+        Ok(changed)
+    }
+
+    fn forget_planes(&mut self) {
+        self.interface.forget_planes();
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> TemperatureSetting for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_temperature(&mut self, celsius: i8) {
+        self.interface.set_temperature(Some(celsius));
+    }
+
+    fn use_internal_sensor(&mut self) {
+        self.interface.set_temperature(None);
+    }
+
+    fn temperature(&self) -> Option<i8> {
+        self.interface.temperature()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> TemperatureReading<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn read_temperature(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<i8, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        ssd168x::read_temperature(&mut self.interface, spi)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // Enable clock signal, Enable Analog, Load temperature value, DISPLAY with DISPLAY Mode 1, Disable Analog, Disable OSC
+        ssd168x::set_update_sequence(&mut self.interface, spi, 0xF7)?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        _spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.poll_refresh(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ConnectionCheck<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn ping(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // enable the clock, load the temperature and disable the clock again, the RAM stays as
+        // it is
+        self.interface
+            .ping(spi, IS_BUSY_LOW, delay, |interface, spi| {
+                interface.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xA1])?;
+                interface.cmd(spi, Command::MasterActivation)
+            })
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn wait_until_idle(&mut self) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW)
+    }
+
+    fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.set_window(spi, 0, 0, WIDTH, HEIGHT)
+    }
+
+    /// Selects the window of `width` x `height` pixels at `x`, `y` and moves the counter to its start
+    fn set_window(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        ssd168x::set_window(&mut self.interface, spi, 0, x, y, width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 200);
+        assert_eq!(HEIGHT, 200);
+        assert_eq!(NUM_DISPLAY_BITS, 5000);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, TriColor::White);
+    }
+
+    #[test]
+    fn init_and_partial_window_commands() {
+        use crate::interface::mock::{CommandSpi, NoPin};
+        use embedded_hal_mock::delay::MockNoop;
+
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let mut epd: Epd1in54b<_, _, _, _, _, MockNoop> =
+            Epd1in54b::new(&mut spi, NoPin, NoPin, dc, NoPin, &mut MockNoop::new()).unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x12, 0x01, 0x11, 0x44, 0x45, 0x4E, 0x4F, 0x3C, 0x18]
+        );
+        assert!(spi.sent(0x01, &[0xC7, 0x00, 0x00]));
+        assert!(spi.sent(0x44, &[0, 24]));
+        assert!(spi.sent(0x45, &[0, 0, 199, 0]));
+
+        spi.clear();
+        epd.update_partial_frame(&mut spi, &[0xAA, 0xBB, 0xCC, 0xDD], 8, 16, 16, 2)
+            .unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x44, 0x45, 0x4E, 0x4F, 0x24, 0x44, 0x45, 0x4E, 0x4F, 0x26]
+        );
+        assert!(spi.sent(0x44, &[1, 2]));
+        assert!(spi.sent(0x45, &[16, 0, 17, 0]));
+        assert!(spi.sent(0x24, &[0xAA, 0xBB, 0xCC, 0xDD]));
+        // the red window is cleared
+        assert!(spi.sent(0x26, &[0x00; 4]));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::epd1in54c::{DEFAULT_BACKGROUND_COLOR, HEIGHT, NUM_DISPLAY_BITS, WIDTH};
-use crate::graphics::{Display, DisplayRotation};
+use crate::graphics::{Display, DisplayRotation, Mirroring};
 use embedded_graphics_core::pixelcolor::BinaryColor;
 use embedded_graphics_core::prelude::*;
 
@@ -9,6 +9,7 @@ use embedded_graphics_core::prelude::*;
 pub struct Display1in54c {
     buffer: [u8; NUM_DISPLAY_BITS as usize],
     rotation: DisplayRotation,
+    mirroring: Mirroring,
 }
 
 impl Default for Display1in54c {
@@ -16,6 +17,7 @@ impl Default for Display1in54c {
         Display1in54c {
             buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value(); NUM_DISPLAY_BITS as usize],
             rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
         }
     }
 }
@@ -57,4 +59,12 @@ impl Display for Display1in54c {
     fn rotation(&self) -> DisplayRotation {
         self.rotation
     }
+
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
 }
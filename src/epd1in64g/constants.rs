@@ -0,0 +1,38 @@
+//! Init sequence of the 1.64" (G) display
+
+use crate::controllers::g_panel::GPanel;
+use crate::energy::RefreshEnergy;
+use crate::settings::InitStep;
+use crate::type_g::command::Command;
+
+use super::{HEIGHT, WIDTH};
+
+/// Unlock, power, booster, panel and resolution settings
+///
+/// Values taken from the Waveshare C driver. The panel is only powered on for the refreshes.
+pub const INIT_SEQUENCE: [InitStep; 10] = [
+    InitStep::new(Command::Unlock as u8, &[0x49, 0x55, 0x13, 0x5D, 0x05, 0x10]),
+    InitStep::new(Command::VendorSettingB0 as u8, &[0x00]),
+    InitStep::new(Command::PowerSetting as u8, &[0x0F, 0x00]),
+    InitStep::new(Command::PanelSetting as u8, &[0x4F, 0x6B]),
+    InitStep::new(Command::BoosterSoftStart2 as u8, &[0xD7, 0xDE, 0x12]),
+    InitStep::new(Command::TconResolution as u8, &[0x00, 0xA8, 0x00, 0xA8]),
+    InitStep::new(Command::VcomAndDataIntervalSetting as u8, &[0x37]),
+    InitStep::new(Command::TconSetting as u8, &[0x0C, 0x05]),
+    InitStep::new(Command::PowerSaving as u8, &[0xFF]),
+    InitStep::new(Command::VendorSetting as u8, &[0x00]),
+];
+
+/// The 1.64" (G) panel
+pub struct Panel1in64g;
+
+impl GPanel for Panel1in64g {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
+    const ENERGY: RefreshEnergy = RefreshEnergy::uniform(70_000);
+    const INIT_SEQUENCE: &'static [InitStep] = &INIT_SEQUENCE;
+    const POWER_PER_REFRESH: bool = true;
+
+    type Row = [u8; WIDTH as usize / 4];
+    const ROW: Self::Row = [0; WIDTH as usize / 4];
+}
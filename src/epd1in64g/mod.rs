@@ -0,0 +1,134 @@
+//! A simple Driver for the Waveshare 1.64 inch (G) 4-color E-Ink Display via SPI
+//!
+//! The display shows the four colors of [`QuadColor`]. A frame packs four pixels into every
+//! byte, the left one in the upper bits, so it is `WIDTH / 4 * HEIGHT` bytes long, and
+//! [`Display1in64g`] draws into a full frame with embedded-graphics.
+//!
+//! The square panel is powered on for every refresh and off again afterwards, unlike the
+//! larger (G) panels. A refresh takes about 15 seconds,
+//! [`busy_state`](crate::traits::WaveshareDisplay::busy_state) reports [`BusyState::Refreshing`](crate::traits::BusyState::Refreshing) in the
+//! meantime.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/1.64inch_e-Paper_Module_(G)_Manual)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_1in64g.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd1in64g.py)
+//!
+//! # Example for the 1.64 in 4-color E-Ink Display
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{prelude::*, primitives::{Circle, PrimitiveStyle}};
+//!use epd_waveshare::{color::QuadColor, epd1in64g::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!let mut epd = Epd1in64g::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// draw a yellow circle onto a white frame
+//!let mut display = Display1in64g::default();
+//!let _ = Circle::new(Point::new(34, 34), 100)
+//!    .into_styled(PrimitiveStyle::with_fill(QuadColor::Yellow))
+//!    .draw(&mut display);
+//!epd.update_and_display_frame(&mut spi, display.buffer(), &mut delay)?;
+//!
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use crate::color::QuadColor;
+use crate::controllers::g_panel::GPanelEpd;
+
+mod constants;
+pub use self::constants::INIT_SEQUENCE;
+
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(feature = "graphics")]
+pub use self::graphics::Display1in64g;
+
+/// Width of the display
+pub const WIDTH: u32 = 168;
+/// Height of the display
+pub const HEIGHT: u32 = 168;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: QuadColor = QuadColor::White;
+
+/// Epd1in64g driver
+///
+/// The shared driver of the (G) panels, for the 1.64" panel.
+pub type Epd1in64g<SPI, CS, BUSY, DC, RST, DELAY> =
+    GPanelEpd<constants::Panel1in64g, SPI, CS, BUSY, DC, RST, DELAY>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_g::command::Command;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 168);
+        assert_eq!(HEIGHT, 168);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, QuadColor::White);
+    }
+
+    #[test]
+    fn init_sequence_sends_resolution() {
+        let step = INIT_SEQUENCE
+            .iter()
+            .find(|step| step.command == Command::TconResolution as u8);
+        let data = step.map(|step| step.data);
+        assert_eq!(
+            data,
+            Some(
+                &[
+                    (WIDTH >> 8) as u8,
+                    WIDTH as u8,
+                    (HEIGHT >> 8) as u8,
+                    HEIGHT as u8
+                ][..]
+            )
+        );
+    }
+
+    #[test]
+    fn init_and_frame_commands() {
+        extern crate std;
+        use crate::interface::mock::{CommandSpi, HighPin, NoPin};
+        use crate::traits::WaveshareDisplay;
+        use embedded_hal_mock::delay::MockNoop;
+        use std::vec;
+
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let mut delay = MockNoop::new();
+        let mut epd: Epd1in64g<_, _, _, _, _, MockNoop> =
+            Epd1in64g::new(&mut spi, NoPin, HighPin, dc, NoPin, &mut delay).unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x66, 0xB0, 0x01, 0x00, 0x06, 0x61, 0x50, 0x60, 0xE3, 0x84]
+        );
+        assert!(spi.sent(0x61, &[0x00, 0xA8, 0x00, 0xA8]));
+
+        // there are no partial windows
+        spi.clear();
+        epd.update_partial_frame(&mut spi, &[0xAA, 0xBB, 0xCC, 0xDD], 8, 16, 16, 2)
+            .unwrap();
+        assert!(spi.commands().is_empty());
+
+        let frame = vec![0x1B; (WIDTH * HEIGHT / 4) as usize];
+        epd.update_frame(&mut spi, &frame, &mut delay).unwrap();
+        assert_eq!(spi.commands(), [0x10]);
+        assert!(spi.sent(0x10, &frame));
+    }
+}
@@ -0,0 +1,241 @@
+//! A Driver for the Waveshare 1.9" Segment E-Ink Display via I2C
+//!
+//! The display isn't a matrix of pixels but a fixed set of segments: a temperature with up to
+//! 199.9 degrees and a unit, a humidity with up to 99.9 percent and a bluetooth and a low
+//! battery symbol. The frame has one bit per segment and is built with [`Segments`].
+//!
+//! The controller is connected via I2C and has no DC pin, commands are written to the address
+//! `0x3C` and data to `0x3D`. Its waveforms depend on the temperature, which is set with
+//! [`set_ambient_temperature`](Epd1in9Segment::set_ambient_temperature).
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/1.9inch_Segment_e-Paper_Module)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/Arduino/epd1in9/epd1in9.cpp)
+//!
+//! # Example for the 1.9" Segment E-Ink Display
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::I2cError<i2c::Mock, pin::Mock, pin::Mock>> {
+//!use epd_waveshare::epd1in9_segment::*;
+//!#
+//!# let expectations = [];
+//!# let mut i2c = i2c::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// Setup EPD
+//!let mut epd = Epd1in9Segment::new(&mut i2c, busy_in, rst, &mut delay)?;
+//!
+//!// Show 23.5 °C and 45.6 %
+//!let mut segments = Segments::new();
+//!segments.set_temperature(235, TemperatureUnit::Celsius);
+//!segments.set_humidity(456);
+//!epd.update_and_display_frame(&mut i2c, segments.buffer())?;
+//!
+//!// Set the EPD to sleep
+//!epd.sleep(&mut i2c)?;
+//!# Ok(())
+//!# }
+//!```
+use embedded_hal::{
+    blocking::{delay::*, i2c::Write},
+    digital::v2::*,
+};
+
+pub use crate::i2c_interface::I2cError;
+use crate::i2c_interface::I2cInterface;
+
+mod segments;
+pub use self::segments::{Digit, Segments, TemperatureUnit};
+
+/// Number of bytes of a frame, one bit per segment
+pub const FRAME_LEN: usize = 15;
+
+/// I2C address of the commands
+const COMMAND_ADDRESS: u8 = 0x3C;
+/// I2C address of the data
+const DATA_ADDRESS: u8 = 0x3D;
+
+const IS_BUSY_LOW: bool = true;
+
+/// Ambient temperature the driver starts with, in °C
+const DEFAULT_TEMPERATURE: i8 = 20;
+
+/// Epd1in9Segment driver
+pub struct Epd1in9Segment<I2C, BUSY, RST, DELAY> {
+    /// Connection Interface
+    interface: I2cInterface<I2C, BUSY, RST, DELAY>,
+}
+
+impl<I2C, BUSY, RST, DELAY> Epd1in9Segment<I2C, BUSY, RST, DELAY>
+where
+    I2C: Write,
+    BUSY: InputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Creates a new driver and initializes the display for a temperature of 20 °C
+    pub fn new(
+        i2c: &mut I2C,
+        busy: BUSY,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, I2cError<I2C, BUSY, RST>> {
+        let interface = I2cInterface::new(busy, rst, COMMAND_ADDRESS, DATA_ADDRESS);
+        let mut epd = Epd1in9Segment { interface };
+        epd.wake_up(i2c, delay)?;
+        Ok(epd)
+    }
+
+    /// Wakes the display up from deep sleep, the ambient temperature is reset to 20 °C
+    pub fn wake_up(
+        &mut self,
+        i2c: &mut I2C,
+        delay: &mut DELAY,
+    ) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        self.interface.reset(delay, 20)?;
+        delay.delay_ms(100);
+
+        // power on
+        self.interface.cmd(i2c, 0x2B)?;
+        delay.delay_ms(10);
+        // boost and TSON
+        self.interface.cmds(i2c, &[0xA7, 0xE0])?;
+        delay.delay_ms(10);
+
+        self.set_ambient_temperature(i2c, DEFAULT_TEMPERATURE)
+    }
+
+    /// Selects the waveform and the frame time for the ambient temperature in °C
+    pub fn set_ambient_temperature(
+        &mut self,
+        i2c: &mut I2C,
+        celsius: i8,
+    ) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        let waveform = if celsius < 10 { 0x7E } else { 0x7B };
+        self.interface.cmds(i2c, &[waveform, 0x81, 0xB4])?;
+        self.interface.wait_until_idle(IS_BUSY_LOW)?;
+
+        // the colder the slower
+        let frame_time = match celsius {
+            i8::MIN..=4 => 0x31,
+            5..=9 => 0x22,
+            10..=14 => 0x18,
+            15..=19 => 0x13,
+            _ => 0x0E,
+        };
+        self.interface.cmds(i2c, &[0xE7, frame_time])
+    }
+
+    /// Writes the frame and refreshes the display, e.g. the [buffer](Segments::buffer) of [`Segments`]
+    ///
+    /// The high voltages are switched off again after the refresh.
+    pub fn update_and_display_frame(
+        &mut self,
+        i2c: &mut I2C,
+        frame: &[u8; FRAME_LEN],
+    ) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        // out of sleep, power on, write from RAM address 0 into both RAMs
+        self.interface.cmds(i2c, &[0xAC, 0x2B, 0x40, 0xA9, 0xA8])?;
+        self.interface.data(i2c, frame)?;
+        self.interface.data(i2c, &[0x00])?;
+        self.interface.cmds(i2c, &[0xAB, 0xAA, 0xAF])?;
+        self.interface.wait_until_idle(IS_BUSY_LOW)?;
+        // display off, power off, sleep
+        self.interface.cmds(i2c, &[0xAE, 0x28, 0xAD])
+    }
+
+    /// Switches all segments off
+    pub fn clear_frame(&mut self, i2c: &mut I2C) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        self.update_and_display_frame(i2c, &[0x00; FRAME_LEN])
+    }
+
+    /// Lets the display enter deep sleep, [`wake_up`](Epd1in9Segment::wake_up) resets it again
+    pub fn sleep(&mut self, i2c: &mut I2C) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        self.interface.cmd(i2c, 0x28)?;
+        self.interface.wait_until_idle(IS_BUSY_LOW)?;
+        self.interface.cmd(i2c, 0xAC)
+    }
+
+    /// Checks if the display is still busy
+    pub fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_mock::{delay::MockNoop, i2c, pin};
+
+    #[test]
+    fn frame_is_written_to_the_data_address() {
+        extern crate std;
+        use std::{vec, vec::Vec};
+
+        let mut segments = Segments::new();
+        segments.set_humidity(456);
+        let commands = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .map(|&byte| i2c::Transaction::write(COMMAND_ADDRESS, vec![byte]))
+                .collect::<Vec<_>>()
+        };
+        let mut expectations = commands(&[0xAC, 0x2B, 0x40, 0xA9, 0xA8]);
+        expectations.push(i2c::Transaction::write(
+            DATA_ADDRESS,
+            segments.buffer().to_vec(),
+        ));
+        expectations.push(i2c::Transaction::write(DATA_ADDRESS, vec![0x00]));
+        expectations.extend(commands(&[0xAB, 0xAA, 0xAF, 0xAE, 0x28, 0xAD]));
+        let mut i2c = i2c::Mock::new(&expectations);
+
+        let busy = pin::Mock::new(&[pin::Transaction::get(pin::State::High)]);
+        let mut epd = Epd1in9Segment {
+            interface: I2cInterface::<_, _, _, MockNoop>::new(
+                busy,
+                pin::Mock::new(&[]),
+                COMMAND_ADDRESS,
+                DATA_ADDRESS,
+            ),
+        };
+        epd.update_and_display_frame(&mut i2c, segments.buffer())
+            .unwrap();
+        i2c.done();
+    }
+
+    #[test]
+    fn init_commands_follow_the_temperature() {
+        extern crate std;
+        use std::{vec, vec::Vec};
+
+        let commands = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .map(|&byte| i2c::Transaction::write(COMMAND_ADDRESS, vec![byte]))
+                .collect::<Vec<_>>()
+        };
+        // power on, boost and TSON, then the waveform and the frame time of 20 °C, then the
+        // ones of 5 °C
+        let mut expectations = commands(&[0x2B, 0xA7, 0xE0, 0x7B, 0x81, 0xB4, 0xE7, 0x0E]);
+        expectations.extend(commands(&[0x7E, 0x81, 0xB4, 0xE7, 0x22]));
+        let mut i2c = i2c::Mock::new(&expectations);
+
+        let busy = pin::Mock::new(&[
+            pin::Transaction::get(pin::State::High),
+            pin::Transaction::get(pin::State::High),
+        ]);
+        let rst = pin::Mock::new(&[
+            pin::Transaction::set(pin::State::High),
+            pin::Transaction::set(pin::State::Low),
+            pin::Transaction::set(pin::State::High),
+        ]);
+        let mut epd = Epd1in9Segment::new(&mut i2c, busy, rst, &mut MockNoop::new()).unwrap();
+        epd.set_ambient_temperature(&mut i2c, 5).unwrap();
+        i2c.done();
+    }
+}
@@ -0,0 +1,220 @@
+use crate::epd1in9_segment::FRAME_LEN;
+
+/// Segment codes of the digits 0 to 9, each digit takes two bytes of the frame
+const DIGITS: [[u8; 2]; 10] = [
+    [0xBF, 0x1F],
+    [0x00, 0x1F],
+    [0xFD, 0x17],
+    [0xF5, 0x1F],
+    [0x47, 0x1F],
+    [0xF7, 0x1D],
+    [0xFF, 0x1D],
+    [0x21, 0x1F],
+    [0xFF, 0x1F],
+    [0xF7, 0x1F],
+];
+
+/// Segments of the leading 1 of temperatures of 100 degrees and above
+const LEADING_ONE: u8 = 0x1F;
+/// Bit of the decimal point of the temperature in byte 4
+const TEMPERATURE_POINT: u8 = 0x20;
+/// Bit of the decimal point of the humidity in byte 8
+const HUMIDITY_POINT: u8 = 0x20;
+/// Bit of the percent sign in byte 10
+const PERCENT: u8 = 0x20;
+/// Index of the byte with the symbols
+const SYMBOLS: usize = 13;
+/// Bits of the °C symbol in the symbol byte
+const CELSIUS: u8 = 0x05;
+/// Bits of the °F symbol in the symbol byte
+const FAHRENHEIT: u8 = 0x06;
+/// Bit of the bluetooth symbol in the symbol byte
+const BLUETOOTH: u8 = 0x08;
+/// Bit of the low battery symbol in the symbol byte
+const LOW_BATTERY: u8 = 0x10;
+
+/// The positions of the digits on the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Digit {
+    /// Tens of the temperature in the upper line
+    TemperatureTens,
+    /// Units of the temperature
+    TemperatureUnits,
+    /// Tenths of the temperature, right of the decimal point
+    TemperatureTenths,
+    /// Tens of the humidity in the lower line
+    HumidityTens,
+    /// Units of the humidity
+    HumidityUnits,
+    /// Tenths of the humidity, right of the decimal point
+    HumidityTenths,
+}
+
+impl Digit {
+    /// Index of the first of the two bytes of the digit in the frame
+    fn index(self) -> usize {
+        match self {
+            Digit::TemperatureTens => 1,
+            Digit::TemperatureUnits => 3,
+            Digit::HumidityTens => 5,
+            Digit::HumidityUnits => 7,
+            Digit::HumidityTenths => 9,
+            Digit::TemperatureTenths => 11,
+        }
+    }
+}
+
+/// The unit symbol shown next to the temperature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    /// °C
+    Celsius,
+    /// °F
+    Fahrenheit,
+}
+
+/// The frame of the segment display
+///
+/// Every bit of the frame switches one segment, a set bit is a black segment. The digits can
+/// be set one by one with [`set_digit`](Segments::set_digit) or as a whole reading with
+/// [`set_temperature`](Segments::set_temperature) and [`set_humidity`](Segments::set_humidity).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Segments {
+    buffer: [u8; FRAME_LEN],
+}
+
+impl Segments {
+    /// A frame with all segments off
+    pub fn new() -> Self {
+        Segments::default()
+    }
+
+    /// Returns the frame for [`update_and_display_frame`](super::Epd1in9Segment::update_and_display_frame)
+    pub fn buffer(&self) -> &[u8; FRAME_LEN] {
+        &self.buffer
+    }
+
+    /// Returns the frame as mutable, to switch single segments
+    pub fn get_mut_buffer(&mut self) -> &mut [u8; FRAME_LEN] {
+        &mut self.buffer
+    }
+
+    /// Switches all segments off
+    pub fn clear(&mut self) {
+        self.buffer = [0; FRAME_LEN];
+    }
+
+    /// Shows `value` at the position, `None` or values above 9 blank the digit
+    ///
+    /// The decimal points and the percent sign share their bytes with digits and are kept.
+    pub fn set_digit(&mut self, digit: Digit, value: Option<u8>) {
+        let [first, second] = value
+            .and_then(|value| DIGITS.get(usize::from(value)).copied())
+            .unwrap_or([0, 0]);
+        let index = digit.index();
+        self.buffer[index] = first;
+        // bit 5 of the second byte is the decimal point or the percent sign
+        self.buffer[index + 1] = (self.buffer[index + 1] & 0x20) | second;
+    }
+
+    /// Shows a temperature in tenths of a degree, e.g. `235` for 23.5, with its unit
+    ///
+    /// The display shows up to 199.9, larger values are shown as 199.9. Leading zeros are
+    /// blanked.
+    pub fn set_temperature(&mut self, tenths: u16, unit: TemperatureUnit) {
+        let tenths = tenths.min(1999);
+        self.buffer[0] = if tenths >= 1000 { LEADING_ONE } else { 0 };
+        let tens = (tenths / 100 % 10) as u8;
+        let leading = tenths >= 1000 || tens != 0;
+        self.set_digit(Digit::TemperatureTens, Some(tens).filter(|_| leading));
+        self.set_digit(Digit::TemperatureUnits, Some((tenths / 10 % 10) as u8));
+        self.set_digit(Digit::TemperatureTenths, Some((tenths % 10) as u8));
+        self.set_temperature_point(true);
+        self.set_temperature_unit(Some(unit));
+    }
+
+    /// Shows a relative humidity in tenths of a percent, e.g. `456` for 45.6 %
+    ///
+    /// The display shows up to 99.9, larger values are shown as 99.9. A leading zero is
+    /// blanked.
+    pub fn set_humidity(&mut self, tenths: u16) {
+        let tenths = tenths.min(999);
+        let tens = (tenths / 100) as u8;
+        self.set_digit(Digit::HumidityTens, Some(tens).filter(|&tens| tens != 0));
+        self.set_digit(Digit::HumidityUnits, Some((tenths / 10 % 10) as u8));
+        self.set_digit(Digit::HumidityTenths, Some((tenths % 10) as u8));
+        self.set_humidity_point(true);
+        self.set_percent(true);
+    }
+
+    /// Switches the decimal point of the temperature
+    pub fn set_temperature_point(&mut self, on: bool) {
+        set_bits(&mut self.buffer[4], TEMPERATURE_POINT, on);
+    }
+
+    /// Switches the decimal point of the humidity
+    pub fn set_humidity_point(&mut self, on: bool) {
+        set_bits(&mut self.buffer[8], HUMIDITY_POINT, on);
+    }
+
+    /// Switches the percent sign of the humidity
+    pub fn set_percent(&mut self, on: bool) {
+        set_bits(&mut self.buffer[10], PERCENT, on);
+    }
+
+    /// Shows the unit symbol of the temperature, `None` hides it
+    pub fn set_temperature_unit(&mut self, unit: Option<TemperatureUnit>) {
+        let symbols = &mut self.buffer[SYMBOLS];
+        *symbols &= !(CELSIUS | FAHRENHEIT);
+        *symbols |= match unit {
+            Some(TemperatureUnit::Celsius) => CELSIUS,
+            Some(TemperatureUnit::Fahrenheit) => FAHRENHEIT,
+            None => 0,
+        };
+    }
+
+    /// Switches the bluetooth symbol
+    pub fn set_bluetooth(&mut self, on: bool) {
+        set_bits(&mut self.buffer[SYMBOLS], BLUETOOTH, on);
+    }
+
+    /// Switches the low battery symbol
+    pub fn set_low_battery(&mut self, on: bool) {
+        set_bits(&mut self.buffer[SYMBOLS], LOW_BATTERY, on);
+    }
+}
+
+/// Sets or clears the `bits` of `byte`
+fn set_bits(byte: &mut u8, bits: u8, on: bool) {
+    if on {
+        *byte |= bits;
+    } else {
+        *byte &= !bits;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readings_to_segments() {
+        let mut segments = Segments::new();
+        segments.set_temperature(235, TemperatureUnit::Celsius);
+        segments.set_humidity(56);
+        assert_eq!(
+            segments.buffer(),
+            &[
+                0x00, 0xFD, 0x17, 0xF5, 0x3F, 0x00, 0x00, 0xF7, 0x3D, 0xFF, 0x3D, 0xF7, 0x1D, 0x05,
+                0x00
+            ]
+        );
+
+        // the leading one and the points are kept when digits change
+        segments.set_temperature(1001, TemperatureUnit::Fahrenheit);
+        segments.set_digit(Digit::HumidityUnits, None);
+        assert_eq!(&segments.buffer()[..5], &[0x1F, 0xBF, 0x1F, 0xBF, 0x3F]);
+        assert_eq!(&segments.buffer()[7..9], &[0x00, 0x20]);
+        assert_eq!(segments.buffer()[SYMBOLS], FAHRENHEIT);
+    }
+}
@@ -89,7 +89,6 @@ impl DriverOutput {
 ///  | | `------------- load temp
 ///  | `--------------- enable clock
 ///  `----------------- enable analog
-
 pub(crate) struct DisplayUpdateControl2(pub u8);
 #[allow(dead_code)]
 impl DisplayUpdateControl2 {
@@ -204,6 +203,8 @@ pub(crate) struct GateDrivingVoltage(pub u8);
 pub(crate) struct SourceDrivingVoltage(pub u8);
 pub(crate) struct Vcom(pub u8);
 
+/// Conversions of the voltages of the init sequence, values outside of the ranges of the
+/// controller are clamped to them
 pub(crate) trait I32Ext {
     fn vcom(self) -> Vcom;
     fn gate_driving_decivolt(self) -> GateDrivingVoltage;
@@ -214,8 +215,7 @@ impl I32Ext for i32 {
     // This is really not very nice. Until I find something better, this will be
     // a placeholder.
     fn vcom(self) -> Vcom {
-        assert!((-30..=-2).contains(&self));
-        let u = match -self {
+        let u = match -self.clamp(-30, -2) {
             2 => 0x08,
             3 => 0x0B,
             4 => 0x10,
@@ -251,19 +251,16 @@ impl I32Ext for i32 {
     }
 
     fn gate_driving_decivolt(self) -> GateDrivingVoltage {
-        assert!((100..=210).contains(&self) && self % 5 == 0);
-        GateDrivingVoltage(((self - 100) / 5 + 0x03) as u8)
+        GateDrivingVoltage(((self.clamp(100, 210) - 100) / 5 + 0x03) as u8)
     }
 
     fn source_driving_decivolt(self) -> SourceDrivingVoltage {
-        assert!((24..=88).contains(&self) || (self % 5 == 0 && (90..=180).contains(&self.abs())));
-
         if (24..=88).contains(&self) {
             SourceDrivingVoltage(((self - 24) + 0x8E) as u8)
-        } else if (90..=180).contains(&self) {
-            SourceDrivingVoltage(((self - 90) / 2 + 0x23) as u8)
+        } else if self >= 89 {
+            SourceDrivingVoltage(((self.min(180) - 90).max(0) / 2 + 0x23) as u8)
         } else {
-            SourceDrivingVoltage((((-self - 90) / 5) * 2 + 0x1A) as u8)
+            SourceDrivingVoltage((((-self.clamp(-180, -90) - 90) / 5) * 2 + 0x1A) as u8)
         }
     }
 }
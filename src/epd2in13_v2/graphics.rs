@@ -1,6 +1,6 @@
 use crate::buffer_len;
 use crate::epd2in13_v2::{DEFAULT_BACKGROUND_COLOR, HEIGHT, WIDTH};
-use crate::graphics::{Display, DisplayRotation};
+use crate::graphics::{Display, DisplayRotation, Mirroring};
 use embedded_graphics_core::pixelcolor::BinaryColor;
 use embedded_graphics_core::prelude::*;
 
@@ -11,6 +11,7 @@ use embedded_graphics_core::prelude::*;
 pub struct Display2in13 {
     buffer: [u8; buffer_len(WIDTH as usize, HEIGHT as usize)],
     rotation: DisplayRotation,
+    mirroring: Mirroring,
 }
 
 impl Default for Display2in13 {
@@ -19,6 +20,7 @@ impl Default for Display2in13 {
             buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value();
                 buffer_len(WIDTH as usize, HEIGHT as usize)],
             rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
         }
     }
 }
@@ -60,6 +62,14 @@ impl Display for Display2in13 {
     fn rotation(&self) -> DisplayRotation {
         self.rotation
     }
+
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
 }
 
 #[cfg(test)]
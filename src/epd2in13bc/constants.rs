@@ -0,0 +1,14 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Booster soft start, power on and panel settings
+///
+/// The VCOM and data interval, resolution, TCON and VCOM DC settings are sent after this
+/// sequence.
+pub const INIT_SEQUENCE: [InitStep; 3] = [
+    InitStep::new(Command::BoosterSoftStart as u8, &[0x17, 0x17, 0x17]),
+    InitStep::new(Command::PowerOn as u8, &[]).delay(5).wait(),
+    InitStep::new(Command::PanelSetting as u8, &[0x8F]),
+];
@@ -1,6 +1,6 @@
 use crate::color::TriColor;
 use crate::epd2in13bc::{DEFAULT_BACKGROUND_COLOR, HEIGHT, NUM_DISPLAY_BITS, WIDTH};
-use crate::graphics::{DisplayColorRendering, DisplayRotation, TriDisplay};
+use crate::graphics::{DisplayColorRendering, DisplayRotation, Mirroring, TriDisplay};
 use embedded_graphics_core::prelude::*;
 
 /// Full size buffer for use with the 2.13" b/c EPD
@@ -12,6 +12,7 @@ pub struct Display2in13bc {
     // * &buffer[NUM_DISPLAY_BITS..2*NUM_DISPLAY_BITS] for chromatic buffer
     buffer: [u8; 2 * NUM_DISPLAY_BITS as usize],
     rotation: DisplayRotation,
+    mirroring: Mirroring,
 }
 
 impl Default for Display2in13bc {
@@ -19,6 +20,7 @@ impl Default for Display2in13bc {
         Display2in13bc {
             buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value(); 2 * NUM_DISPLAY_BITS as usize],
             rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
         }
     }
 }
@@ -60,6 +62,14 @@ impl TriDisplay for Display2in13bc {
         self.rotation
     }
 
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
     fn chromatic_offset(&self) -> usize {
         NUM_DISPLAY_BITS as usize
     }
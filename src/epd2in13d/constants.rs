@@ -0,0 +1,13 @@
+//! Init sequence of the controller
+
+use crate::settings::InitStep;
+use crate::type_d::command::Command;
+
+/// Power and panel setup of the Waveshare C driver, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 4] = [
+    InitStep::new(Command::PowerSetting as u8, &[0x03, 0x00, 0x2B, 0x2B, 0x03]),
+    InitStep::new(Command::BoosterSoftStart as u8, &[0x17, 0x17, 0x17]),
+    InitStep::new(Command::PowerOn as u8, &[]).wait(),
+    // LUTs from the registers, black/white
+    InitStep::new(Command::PanelSetting as u8, &[0xBF, 0x0D]),
+];
@@ -0,0 +1,46 @@
+//! Init sequence of the 2.15" (G) display
+
+use crate::controllers::g_panel::GPanel;
+use crate::energy::RefreshEnergy;
+use crate::settings::InitStep;
+use crate::type_g::command::Command;
+
+use super::{HEIGHT, WIDTH};
+
+/// Unlock, power, booster, panel and resolution settings, ending with powering the panel on
+///
+/// Values taken from the Waveshare C driver.
+pub const INIT_SEQUENCE: [InitStep; 15] = [
+    InitStep::new(Command::VendorSetting4D as u8, &[0x78]),
+    InitStep::new(Command::PanelSetting as u8, &[0x0F, 0x29]),
+    InitStep::new(Command::PowerSetting as u8, &[0x07, 0x00]),
+    InitStep::new(Command::PowerOffSequenceSetting as u8, &[0x10, 0x54, 0x44]),
+    InitStep::new(
+        Command::BoosterSoftStart2 as u8,
+        &[0x05, 0x00, 0x3F, 0x0A, 0x25, 0x12, 0x1A],
+    ),
+    InitStep::new(Command::VcomAndDataIntervalSetting as u8, &[0x37]),
+    InitStep::new(Command::TconSetting as u8, &[0x02, 0x02]),
+    InitStep::new(Command::TconResolution as u8, &[0x00, 0xA0, 0x01, 0x28]),
+    InitStep::new(Command::VendorSettingE7 as u8, &[0x1C]),
+    InitStep::new(Command::PowerSaving as u8, &[0x22]),
+    InitStep::new(Command::VendorSettingB4 as u8, &[0xD0]),
+    InitStep::new(Command::VendorSettingB5 as u8, &[0x03]),
+    InitStep::new(Command::VendorSettingE9 as u8, &[0x01]),
+    InitStep::new(Command::PllControl as u8, &[0x08]),
+    InitStep::new(Command::PowerOn as u8, &[]).wait(),
+];
+
+/// The 2.15" (G) panel
+pub struct Panel2in15g;
+
+impl GPanel for Panel2in15g {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
+    const ENERGY: RefreshEnergy = RefreshEnergy::uniform(100_000);
+    const INIT_SEQUENCE: &'static [InitStep] = &INIT_SEQUENCE;
+    const POWER_PER_REFRESH: bool = false;
+
+    type Row = [u8; WIDTH as usize / 4];
+    const ROW: Self::Row = [0; WIDTH as usize / 4];
+}
@@ -0,0 +1,144 @@
+use crate::color::QuadColor;
+use crate::epd2in36g::{DEFAULT_BACKGROUND_COLOR, HEIGHT, WIDTH};
+use crate::graphics::{DisplayRotation, Mirroring, QuadDisplay};
+use embedded_graphics_core::prelude::*;
+
+/// Full size buffer for use with the 2in36g EPD
+///
+/// Can also be manually constructed:
+/// `buffer: [QuadColor::colors_byte(c, c, c, c); WIDTH / 4 * HEIGHT]`
+pub struct Display2in36g {
+    buffer: [u8; WIDTH as usize / 4 * HEIGHT as usize],
+    rotation: DisplayRotation,
+    mirroring: Mirroring,
+}
+
+impl Default for Display2in36g {
+    fn default() -> Self {
+        let c = DEFAULT_BACKGROUND_COLOR;
+        Display2in36g {
+            buffer: [QuadColor::colors_byte(c, c, c, c); WIDTH as usize / 4 * HEIGHT as usize],
+            rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
+        }
+    }
+}
+
+impl DrawTarget for Display2in36g {
+    type Color = QuadColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.draw_helper(WIDTH, HEIGHT, pixel)?;
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Display2in36g {
+    fn size(&self) -> Size {
+        match self.rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => Size::new(WIDTH, HEIGHT),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => Size::new(HEIGHT, WIDTH),
+        }
+    }
+}
+
+impl QuadDisplay for Display2in36g {
+    fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn get_mut_buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{
+        prelude::*,
+        primitives::{Line, PrimitiveStyle},
+    };
+
+    const WHITE: [QuadColor; 4] = [QuadColor::White; 4];
+
+    // test buffer length
+    #[test]
+    fn graphics_size() {
+        let display = Display2in36g::default();
+        assert_eq!(display.buffer().len(), 168 / 4 * 296);
+    }
+
+    // test default background color on all bytes
+    #[test]
+    fn graphics_default() {
+        let display = Display2in36g::default();
+        for &byte in display.buffer() {
+            assert_eq!(QuadColor::split_byte(byte), WHITE);
+        }
+    }
+
+    #[test]
+    fn graphics_colors() {
+        let mut display = Display2in36g::default();
+        for (x, color) in [
+            QuadColor::Black,
+            QuadColor::White,
+            QuadColor::Yellow,
+            QuadColor::Red,
+        ]
+        .iter()
+        .enumerate()
+        {
+            let _ = Pixel(Point::new(x as i32, 0), *color).draw(&mut display);
+        }
+
+        let buffer = display.buffer();
+        assert_eq!(buffer[0], 0b00_01_10_11);
+        assert!(buffer[1..]
+            .iter()
+            .all(|&byte| QuadColor::split_byte(byte) == WHITE));
+    }
+
+    #[test]
+    fn graphics_rotation_90() {
+        let mut display = Display2in36g::default();
+        display.set_rotation(DisplayRotation::Rotate90);
+        assert_eq!(display.size(), Size::new(HEIGHT, WIDTH));
+
+        let _ = Line::new(
+            Point::new(0, WIDTH as i32 - 4),
+            Point::new(0, WIDTH as i32 - 1),
+        )
+        .into_styled(PrimitiveStyle::with_stroke(QuadColor::Red, 1))
+        .draw(&mut display);
+
+        let buffer = display.buffer();
+        assert_eq!(QuadColor::split_byte(buffer[0]), [QuadColor::Red; 4]);
+        assert!(buffer[1..]
+            .iter()
+            .all(|&byte| QuadColor::split_byte(byte) == WHITE));
+    }
+}
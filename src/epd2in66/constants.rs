@@ -0,0 +1,41 @@
+//! Waveforms of the 2.66" display
+
+use crate::controllers::ssd168x::DataEntryMode;
+use crate::settings::InitStep;
+use crate::type_a::command::Command;
+
+/// Software reset and data entry mode, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 2] = [
+    InitStep::new(Command::SwReset as u8, &[]).wait(),
+    // x increment, y increment, address counter is updated in x direction
+    InitStep::new(
+        Command::DataEntryModeSetting as u8,
+        &[DataEntryMode::IncrementXIncrementY as u8],
+    ),
+];
+
+#[rustfmt::skip]
+// Partial waveform from Waveshare, the full refresh uses the OTP of the controller
+pub(crate) const LUT_PARTIAL_UPDATE: [u8; 153] = [
+    // VS L0 - L4
+    0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x80, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x40, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // TP/RP G0 - G11
+    0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // FR, XON
+    0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x00, 0x00, 0x00,
+];
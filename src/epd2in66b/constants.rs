@@ -0,0 +1,15 @@
+//! Init sequence of the controller
+
+use crate::controllers::ssd168x::DataEntryMode;
+use crate::settings::InitStep;
+use crate::type_a::command::Command;
+
+/// Software reset and data entry mode, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 2] = [
+    InitStep::new(Command::SwReset as u8, &[]).wait(),
+    // x increment, y increment, address counter is updated in x direction
+    InitStep::new(
+        Command::DataEntryModeSetting as u8,
+        &[DataEntryMode::IncrementXIncrementY as u8],
+    ),
+];
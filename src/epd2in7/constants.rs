@@ -1,3 +1,29 @@
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Power, booster and panel setup, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 12] = [
+    // set the power settings
+    InitStep::new(Command::PowerSetting as u8, &[0x03, 0x00, 0x2b, 0x2b, 0x09]),
+    // start the booster
+    InitStep::new(Command::BoosterSoftStart as u8, &[0x07, 0x07, 0x17]),
+    // power optimization
+    InitStep::new(Command::PowerOptimization as u8, &[0x60, 0xa5]),
+    InitStep::new(Command::PowerOptimization as u8, &[0x89, 0xa5]),
+    InitStep::new(Command::PowerOptimization as u8, &[0x90, 0x00]),
+    InitStep::new(Command::PowerOptimization as u8, &[0x93, 0x2a]),
+    InitStep::new(Command::PowerOptimization as u8, &[0xA0, 0xA5]),
+    InitStep::new(Command::PowerOptimization as u8, &[0xA1, 0x00]),
+    InitStep::new(Command::PowerOptimization as u8, &[0x73, 0x41]),
+    InitStep::new(Command::PartialDisplayRefresh as u8, &[0x00]),
+    // power on
+    InitStep::new(Command::PowerOn as u8, &[]).wait(),
+    // set panel settings, 0xbf is bw, 0xaf is multi-color
+    InitStep::new(Command::PanelSetting as u8, &[0xaf]),
+];
+
+// Waveforms of the current Waveshare sample code
+#[cfg(not(feature = "epd2in7_lut_v1"))]
 #[rustfmt::skip]
 pub(crate) const LUT_VCOM_DC: [u8; 44] = [
   0x00	,0x00,
@@ -10,6 +36,7 @@ pub(crate) const LUT_VCOM_DC: [u8; 44] = [
   0x00	,0x00	,0x00	,0x00	,0x00	,0x00
 ];
 
+#[cfg(not(feature = "epd2in7_lut_v1"))]
 #[rustfmt::skip]
 pub(crate) const LUT_WW: [u8; 42] =[
   0x40	,0x08	,0x00	,0x00	,0x00	,0x02,
@@ -21,6 +48,7 @@ pub(crate) const LUT_WW: [u8; 42] =[
   0x00	,0x00	,0x00	,0x00	,0x00	,0x00,
 ];
 
+#[cfg(not(feature = "epd2in7_lut_v1"))]
 #[rustfmt::skip]
 pub(crate) const LUT_BW: [u8; 42] =[
   0x40	,0x08	,0x00	,0x00	,0x00	,0x02,
@@ -32,6 +60,7 @@ pub(crate) const LUT_BW: [u8; 42] =[
   0x00	,0x00	,0x00	,0x00	,0x00	,0x00,
 ];
 
+#[cfg(not(feature = "epd2in7_lut_v1"))]
 #[rustfmt::skip]
 pub(crate) const LUT_BB: [u8; 42] =[
   0x80	,0x08	,0x00	,0x00	,0x00	,0x02,
@@ -43,6 +72,7 @@ pub(crate) const LUT_BB: [u8; 42] =[
   0x00	,0x00	,0x00	,0x00	,0x00	,0x00,
 ];
 
+#[cfg(not(feature = "epd2in7_lut_v1"))]
 #[rustfmt::skip]
 pub(crate) const LUT_WB: [u8; 42] =[
   0x80	,0x08	,0x00	,0x00	,0x00	,0x02,
@@ -52,4 +82,66 @@ pub(crate) const LUT_WB: [u8; 42] =[
   0x00	,0x00	,0x00	,0x00	,0x00	,0x00,
   0x00	,0x00	,0x00	,0x00	,0x00	,0x00,
   0x00	,0x00	,0x00	,0x00	,0x00	,0x00,
-];
\ No newline at end of file
+];
+
+// Waveforms of the original Waveshare sample code, for older panels
+#[cfg(feature = "epd2in7_lut_v1")]
+#[rustfmt::skip]
+pub(crate) const LUT_VCOM_DC: [u8; 44] = [
+0x00, 0x00,
+0x00, 0x1A, 0x1A, 0x00, 0x00, 0x01,
+0x00, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0x00, 0x0E, 0x01, 0x0E, 0x01, 0x10,
+0x00, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0x00, 0x04, 0x10, 0x00, 0x00, 0x05,
+0x00, 0x03, 0x0E, 0x00, 0x00, 0x0A,
+0x00, 0x23, 0x00, 0x00, 0x00, 0x01,
+];
+
+#[cfg(feature = "epd2in7_lut_v1")]
+#[rustfmt::skip]
+pub(crate) const LUT_WW: [u8; 42] =[
+0x90, 0x1A, 0x1A, 0x00, 0x00, 0x01,
+0x40, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0x84, 0x0E, 0x01, 0x0E, 0x01, 0x10,
+0x80, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0x00, 0x04, 0x10, 0x00, 0x00, 0x05,
+0x00, 0x03, 0x0E, 0x00, 0x00, 0x0A,
+0x00, 0x23, 0x00, 0x00, 0x00, 0x01,
+];
+
+#[cfg(feature = "epd2in7_lut_v1")]
+#[rustfmt::skip]
+pub(crate) const LUT_BW: [u8; 42] =[
+0xA0, 0x1A, 0x1A, 0x00, 0x00, 0x01,
+0x00, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0x84, 0x0E, 0x01, 0x0E, 0x01, 0x10,
+0x90, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0xB0, 0x04, 0x10, 0x00, 0x00, 0x05,
+0xB0, 0x03, 0x0E, 0x00, 0x00, 0x0A,
+0xC0, 0x23, 0x00, 0x00, 0x00, 0x01,
+];
+
+#[cfg(feature = "epd2in7_lut_v1")]
+#[rustfmt::skip]
+pub(crate) const LUT_BB: [u8; 42] =[
+0x90, 0x1A, 0x1A, 0x00, 0x00, 0x01,
+0x40, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0x84, 0x0E, 0x01, 0x0E, 0x01, 0x10,
+0x80, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0x00, 0x04, 0x10, 0x00, 0x00, 0x05,
+0x00, 0x03, 0x0E, 0x00, 0x00, 0x0A,
+0x00, 0x23, 0x00, 0x00, 0x00, 0x01,
+];
+
+#[cfg(feature = "epd2in7_lut_v1")]
+#[rustfmt::skip]
+pub(crate) const LUT_WB: [u8; 42] =[
+0x90, 0x1A, 0x1A, 0x00, 0x00, 0x01,
+0x20, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0x84, 0x0E, 0x01, 0x0E, 0x01, 0x10,
+0x10, 0x0A, 0x0A, 0x00, 0x00, 0x08,
+0x00, 0x04, 0x10, 0x00, 0x00, 0x05,
+0x00, 0x03, 0x0E, 0x00, 0x00, 0x0A,
+0x00, 0x23, 0x00, 0x00, 0x00, 0x01,
+];
@@ -1,5 +1,5 @@
 use crate::epd2in7::{DEFAULT_BACKGROUND_COLOR, HEIGHT, WIDTH};
-use crate::graphics::{Display, DisplayRotation};
+use crate::graphics::{Display, DisplayRotation, Mirroring};
 use embedded_graphics_core::pixelcolor::BinaryColor;
 use embedded_graphics_core::prelude::*;
 
@@ -10,6 +10,7 @@ use embedded_graphics_core::prelude::*;
 pub struct Display2in7 {
     buffer: [u8; WIDTH as usize * HEIGHT as usize / 8],
     rotation: DisplayRotation,
+    mirroring: Mirroring,
 }
 
 impl Default for Display2in7 {
@@ -18,6 +19,7 @@ impl Default for Display2in7 {
             buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value();
                 WIDTH as usize * HEIGHT as usize / 8],
             rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
         }
     }
 }
@@ -59,6 +61,14 @@ impl Display for Display2in7 {
     fn rotation(&self) -> DisplayRotation {
         self.rotation
     }
+
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
 }
 
 #[cfg(test)]
@@ -1,19 +1,33 @@
 //! A simple Driver for the Waveshare 2.7" B Tri-Color E-Ink Display via SPI
 //!
 //! [Documentation](https://www.waveshare.com/wiki/2.7inch_e-Paper_HAT_(B))
+//!
+//! The `epd2in7_lut_v1` feature selects the waveforms of the original sample code,
+//! if the default ones don't give a clean refresh on an older panel.
 
 use embedded_hal::{
-  blocking::{delay::*, spi::Write},
-  digital::v2::*,
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
+    digital::v2::*,
 };
 
-use crate::interface::DisplayInterface;
+use crate::buffer_len;
+use crate::controllers::uc8151;
+use crate::energy::RefreshEnergy;
+use crate::interface::{render_rows, DisplayInterface, InterfaceAccess, InterfaceState};
+use crate::settings::{FrameRate, InitStep};
 use crate::traits::{
-  InternalWiAdditions, RefreshLut, WaveshareDisplay,
+    BusyState, Capabilities, ConnectionCheck, DisplayError, DriverSnapshot, FastWake,
+    FrameRateSetting, InitSequence, InternalWiAdditions, LazyInit, LifecycleEvent, Link,
+    NonBlockingRefresh, Plane, QuickRefresh, RawPlaneError, RawPlaneUpload, RefreshLut,
+    RefreshState, RowRendering, Snapshot, VcomMeasurement, VcomSetting, WaveshareDisplay,
 };
 
 // The Lookup Tables for the Display
 mod constants;
+pub use self::constants::INIT_SEQUENCE;
 use crate::epd2in7::constants::*;
 
 /// Width of the display
@@ -23,6 +37,10 @@ pub const HEIGHT: u32 = 264;
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
 const IS_BUSY_LOW: bool = true;
+/// VCOM DC of -1.0 V
+const DEFAULT_VCOM: u8 = 0x12;
+
+const DEFAULT_FRAME_RATE: FrameRate = FrameRate::Hz100;
 
 use crate::color::Color;
 
@@ -36,276 +54,750 @@ pub use self::graphics::Display2in7;
 
 /// Epd2in7 driver
 pub struct Epd2in7<SPI, CS, BUSY, DC, RST, DELAY> {
-  /// Connection Interface
-  interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
-  /// Background Color
-  color: Color,
+    /// Connection Interface
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    /// Background Color
+    color: Color,
+    /// Frame rate
+    frame_rate: FrameRate,
+    /// VCOM DC setting
+    vcom: u8,
+    /// Window of the last partial new frame, refreshed by the next `display_new_frame`
+    partial_window: Option<(u32, u32, u32, u32)>,
+    /// Init sequence
+    init_sequence: &'static [InitStep],
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
-  for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
 where
-  SPI: Write<u8>,
-  CS: OutputPin,
-  BUSY: InputPin,
-  DC: OutputPin,
-  RST: OutputPin,
-  DELAY: DelayMs<u8>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
 {
-  fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-      // reset the device
-      self.interface.reset(delay, 2);
-
-      // set the power settings
-      self.interface.cmd_with_data(
-          spi,
-          Command::PowerSetting,
-          &[0x03, 0x00, 0x2b, 0x2b, 0x09],
-      )?;
-
-      // start the booster
-      self.interface
-          .cmd_with_data(spi, Command::BoosterSoftStart, &[0x07, 0x07, 0x17])?;
-
-      // power optimization
-      self.interface
-          .cmd_with_data(spi, Command::PowerOptimization, &[0x60, 0xa5])?;
-      self.interface
-          .cmd_with_data(spi, Command::PowerOptimization, &[0x89, 0xa5])?;
-      self.interface
-          .cmd_with_data(spi, Command::PowerOptimization, &[0x90, 0x00])?;
-      self.interface
-          .cmd_with_data(spi, Command::PowerOptimization, &[0x93, 0x2a])?;
-      self.interface
-          .cmd_with_data(spi, Command::PowerOptimization, &[0xA0, 0xA5])?;
-      self.interface
-          .cmd_with_data(spi, Command::PowerOptimization, &[0xA1, 0x00])?;
-      self.interface
-          .cmd_with_data(spi, Command::PowerOptimization, &[0x73, 0x41])?;
-
-      self.interface
-          .cmd_with_data(spi, Command::PartialDisplayRefresh, &[0x00])?;
-
-      // power on
-      self.interface.cmd(spi, Command::PowerOn)?;
-      self.wait_until_idle(spi)?;
-
-      // set panel settings, 0xbf is bw, 0xaf is multi-color
-      self.interface
-          .cmd_with_data(spi, Command::PanelSetting, &[0xaf])?;
-
-      // pll control
-      self.interface
-          .cmd_with_data(spi, Command::PllControl, &[0x3a])?;
-
-      self.interface
-          .cmd_with_data(spi, Command::VcmDcSetting, &[0x12])?;
-
-      // self.interface
-      //     .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x87])?;
-
-      self.set_lut(spi, None)?;
-      Ok(())
-  }
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // reset the device
+        self.interface.reset(delay, 2)?;
+
+        self.interface
+            .run_init_sequence_with(spi, delay, self.init_sequence, wait_for_status)?;
+
+        // pll control
+        uc8151::set_frame_rate(&mut self.interface, spi, self.frame_rate)?;
+
+        uc8151::set_vcom_dc(&mut self.interface, spi, self.vcom)?;
+
+        // self.interface
+        //     .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x87])?;
+
+        self.set_lut(spi, None)?;
+        Ok(())
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
-  for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type DisplayColor = Color;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: true,
+        grayscale_levels: 2,
+        chromatic_colors: 0,
+        readback: false,
+        fast_mode: false,
+        ultrafast: false,
+        clear_refreshes: false,
+    };
+    const ENERGY: RefreshEnergy = RefreshEnergy::uniform(158_400);
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.set_retained(false);
+        self.wait_until_idle_ticked(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0xf7])?;
+
+        self.interface.cmd(spi, Command::PowerOff)?;
+        self.wait_until_idle_ticked(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
+        Ok(())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission2, buffer)?;
+        Ok(())
+    }
+
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.interface
+            .cmd(spi, Command::PartialDataStartTransmission1)?;
+        self.send_partial_window(spi, x, y, width, height)?;
+
+        self.interface.frame_data(spi, buffer)?;
+
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::DisplayRefresh)?;
+
+        self.wait_until_idle(spi)?;
+        Ok(())
+    }
+
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)?;
+        self.wait_until_idle_ticked(spi, delay)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        let color_value = self.color.get_byte_value();
+        uc8151::clear_frames(
+            &mut self.interface,
+            spi,
+            color_value,
+            color_value,
+            WIDTH * HEIGHT / 8,
+        )
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .cmd_with_data(spi, Command::LutForVcom, &LUT_VCOM_DC)?;
+        self.interface
+            .cmd_with_data(spi, Command::LutWhiteToWhite, &LUT_WW)?;
+        self.interface
+            .cmd_with_data(spi, Command::LutBlackToWhite, &LUT_BW)?;
+        self.interface
+            .cmd_with_data(spi, Command::LutWhiteToBlack, &LUT_BB)?;
+        self.interface
+            .cmd_with_data(spi, Command::LutBlackToBlack, &LUT_WB)?;
+
+        Ok(())
+    }
+
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle_ticked(spi, delay)?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::DisplayRefresh)?;
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        _spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.poll_refresh(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RowRendering<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_frame_with<F>(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+        f: F,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut(u32, &mut [u8]),
+    {
+        self.interface.cmd(spi, Command::DataStartTransmission2)?;
+        let mut row = [0; buffer_len(WIDTH as usize, 1)];
+        let background = self.color.get_byte_value();
+        render_rows(HEIGHT, &mut row, background, f, |row| {
+            self.interface.frame_data(spi, row)
+        })
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> FrameRateSetting<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_frame_rate(
+        &mut self,
+        spi: &mut SPI,
+        rate: FrameRate,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.frame_rate = rate;
+        uc8151::set_frame_rate(&mut self.interface, spi, self.frame_rate)
+    }
+
+    fn frame_rate(&self) -> FrameRate {
+        self.frame_rate
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> VcomSetting<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_vcom(
+        &mut self,
+        spi: &mut SPI,
+        millivolts: i16,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.vcom = uc8151::vcom_byte(millivolts);
+        uc8151::set_vcom_dc(&mut self.interface, spi, self.vcom)
+    }
+
+    fn vcom(&self) -> i16 {
+        uc8151::vcom_millivolts(self.vcom)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> VcomMeasurement<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn measure_vcom(&mut self, spi: &mut SPI) -> Result<i16, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        uc8151::measure_vcom(&mut self.interface, spi).map(uc8151::vcom_millivolts)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Snapshot for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = Color;
+
+    fn snapshot(&self) -> DriverSnapshot<Color> {
+        self.interface
+            .state()
+            .snapshot(self.color, RefreshLut::Full)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<Color>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.color = snapshot.background_color;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        match plane {
+            Plane::Bw => self
+                .update_frame(spi, data, delay)
+                .map_err(RawPlaneError::Interface),
+            Plane::Chromatic => Err(RawPlaneError::UnsupportedPlane(plane)),
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> FastWake<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn power_off(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle_ticked(spi, delay)?;
+        self.interface.cmd(spi, Command::PowerOff)?;
+        self.wait_until_idle_ticked(spi, delay)?;
+        self.interface.set_retained(true);
+        Ok(())
+    }
+
+    fn wake_up_fast(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if !self.interface.retained() {
+            return self.wake_up(spi, delay);
+        }
+        self.interface.set_busy_reason(BusyState::PoweringUp);
+        self.interface.cmd(spi, Command::PowerOn)?;
+        self.wait_until_idle_ticked(spi, delay)?;
+        self.interface.set_retained(false);
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InitSequence for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY> {
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        Epd2in7 {
+            interface,
+            color,
+            frame_rate: DEFAULT_FRAME_RATE,
+            vcom: DEFAULT_VCOM,
+            partial_window: None,
+            init_sequence: &INIT_SEQUENCE,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ConnectionCheck<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn ping(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // the busy pin is low while the temperature is sensed
+        self.interface
+            .ping(spi, IS_BUSY_LOW, delay, |interface, spi| {
+                interface.cmd(spi, Command::TemperatureSensor)
+            })
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
 where
-  SPI: Write<u8>,
-  CS: OutputPin,
-  BUSY: InputPin,
-  DC: OutputPin,
-  RST: OutputPin,
-  DELAY: DelayMs<u8>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
 {
-  type DisplayColor = Color;
-  fn new(
-      spi: &mut SPI,
-      cs: CS,
-      busy: BUSY,
-      dc: DC,
-      rst: RST,
-      delay: &mut DELAY,
-  ) -> Result<Self, SPI::Error> {
-      let interface = DisplayInterface::new(cs, busy, dc, rst);
-      let color = DEFAULT_BACKGROUND_COLOR;
-
-      let mut epd = Epd2in7 { interface, color };
-
-      epd.init(spi, delay)?;
-
-      Ok(epd)
-  }
-
-  fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-      self.init(spi, delay)
-  }
-
-  fn sleep(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-      self.wait_until_idle(spi)?;
-      self.interface
-          .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0xf7])?;
-
-      self.interface.cmd(spi, Command::PowerOff)?;
-      self.wait_until_idle(spi)?;
-      self.interface
-          .cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
-      Ok(())
-  }
-
-  fn update_frame(
-      &mut self,
-      spi: &mut SPI,
-      buffer: &[u8],
-      _delay: &mut DELAY,
-  ) -> Result<(), SPI::Error> {
-      self.interface.cmd_with_data(spi, Command::DataStartTransmission2, buffer)?;
-      Ok(())
-  }
-
-  fn update_partial_frame(
-      &mut self,
-      spi: &mut SPI,
-      buffer: &[u8],
-      x: u32,
-      y: u32,
-      width: u32,
-      height: u32,
-  ) -> Result<(), SPI::Error> {
-      self.interface
-          .cmd(spi, Command::PartialDataStartTransmission1)?;
-
-      self.interface.data(spi, &[(x >> 8) as u8])?;
-      self.interface.data(spi, &[(x & 0xf8) as u8])?;
-      self.interface.data(spi, &[(y >> 8) as u8])?;
-      self.interface.data(spi, &[(y & 0xff) as u8])?;
-      self.interface.data(spi, &[(width >> 8) as u8])?;
-      self.interface.data(spi, &[(width & 0xf8) as u8])?;
-      self.interface.data(spi, &[(height >> 8) as u8])?;
-      self.interface.data(spi, &[(height & 0xff) as u8])?;
-
-      self.interface.data(spi, buffer)?;
-
-      self.interface.cmd(spi, Command::DisplayRefresh)?;
-
-      self.wait_until_idle(spi)?;
-      Ok(())
-  }
-
-  fn display_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-      self.interface.cmd(spi, Command::DisplayRefresh)?;
-      self.wait_until_idle(spi)?;
-      Ok(())
-  }
-
-  fn update_and_display_frame(
-      &mut self,
-      spi: &mut SPI,
-      buffer: &[u8],
-      delay: &mut DELAY,
-  ) -> Result<(), SPI::Error> {
-      self.update_frame(spi, buffer, delay)?;
-      self.display_frame(spi, delay)?;
-      Ok(())
-  }
-
-  fn clear_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-      let color_value = self.color.get_byte_value();
-      self.interface.cmd(spi, Command::DataStartTransmission1)?;
-      self.interface
-          .data_x_times(spi, color_value, WIDTH * HEIGHT / 8)?;
-
-      // self.interface.cmd(spi, Command::DataStop)?;
-
-      self.interface.cmd(spi, Command::DataStartTransmission2)?;
-      self.interface
-          .data_x_times(spi, color_value, WIDTH * HEIGHT / 8)?;
-      // self.interface.cmd(spi, Command::DataStop)?;
-      Ok(())
-  }
-
-  fn set_background_color(&mut self, color: Color) {
-      self.color = color;
-  }
-
-  fn background_color(&self) -> &Color {
-      &self.color
-  }
-
-  fn width(&self) -> u32 {
-      WIDTH
-  }
-
-  fn height(&self) -> u32 {
-      HEIGHT
-  }
-
-  fn set_lut(
-      &mut self,
-      spi: &mut SPI,
-      _refresh_rate: Option<RefreshLut>,
-  ) -> Result<(), SPI::Error> {
-      self.interface.cmd_with_data(spi, Command::LutForVcom, &LUT_VCOM_DC)?;
-      self.interface.cmd_with_data(spi, Command::LutWhiteToWhite, &LUT_WW)?;
-      self.interface.cmd_with_data(spi, Command::LutBlackToWhite, &LUT_BW)?;
-      self.interface.cmd_with_data(spi, Command::LutWhiteToBlack, &LUT_BB)?;
-      self.interface.cmd_with_data(spi, Command::LutBlackToBlack, &LUT_WB)?;
-
-      Ok(())
-  }
-
-  fn is_busy(&self) -> bool {
-      self.interface.is_busy(IS_BUSY_LOW)
-  }
+    /// Writes the frame shown on the display, which the new frame is compared with
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission1, buffer)
+    }
+
+    /// Writes the new frame, to be shown with `display_new_frame`
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.partial_window = None;
+        self.interface
+            .cmd_with_frame_data(spi, Command::DataStartTransmission2, buffer)
+    }
+
+    /// Refreshes the display, only the window after `update_partial_new_frame`
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        match self.partial_window.take() {
+            Some((x, y, width, height)) => {
+                self.interface.cmd(spi, Command::PartialDisplayRefresh)?;
+                self.send_partial_window(spi, x, y, width, height)?;
+            }
+            None => self.interface.cmd(spi, Command::DisplayRefresh)?,
+        }
+        self.wait_until_idle_ticked(spi, delay)
+    }
+
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_new_frame(spi, delay)
+    }
+
+    /// Writes the old frame of a window, `x` and `width` are rounded down to multiples of 8
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.interface
+            .cmd(spi, Command::PartialDataStartTransmission1)?;
+        self.send_partial_window(spi, x, y, width, height)?;
+        self.interface.frame_data(spi, buffer)
+    }
+
+    /// Writes the new frame of a window, the next `display_new_frame` only refreshes the window
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.interface
+            .cmd(spi, Command::PartialDataStartTransmission2)?;
+        self.send_partial_window(spi, x, y, width, height)?;
+        self.interface.frame_data(spi, buffer)?;
+        self.partial_window = Some((x, y, width, height));
+        Ok(())
+    }
+
+    /// Clears the new frame of a window with the background color, see `update_partial_new_frame`
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_area(x, y, width, height, (WIDTH, HEIGHT))?;
+        let color = self.color.get_byte_value();
+        self.interface
+            .cmd(spi, Command::PartialDataStartTransmission2)?;
+        self.send_partial_window(spi, x, y, width, height)?;
+        self.interface.clear_data(
+            spi,
+            color,
+            buffer_len(width as usize, height as usize) as u32,
+        )?;
+        self.partial_window = Some((x, y, width, height));
+        Ok(())
+    }
+
+    /// The controller only has the full waveform, which compares the old and the new frame as
+    /// well, so the LUT is ignored
+    fn set_quick_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _refresh: RefreshLut,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        Ok(())
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
 where
-  SPI: Write<u8>,
-  CS: OutputPin,
-  BUSY: InputPin,
-  DC: OutputPin,
-  RST: OutputPin,
-  DELAY: DelayMs<u8>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn wait_until_idle(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.cmd(spi, Command::GetStatus)?;
+        self.interface.wait_until_idle(IS_BUSY_LOW)
+    }
+
+    /// Waits like [wait_until_idle()](Epd2in7::wait_until_idle()), counting the busy ticks
+    fn wait_until_idle_ticked(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        wait_for_status(&mut self.interface, spi, delay)
+    }
+
+    /// Refresh display for partial frame
+    #[deprecated(
+        since = "0.6.0",
+        note = "use `update_partial_new_frame` and `display_new_frame` of `QuickRefresh`"
+    )]
+    pub fn display_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_area(x, y, width, height, (WIDTH, HEIGHT))?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::PartialDisplayRefresh)?;
+        self.send_partial_window(spi, x, y, width, height)?;
+        self.interface.cmd(spi, Command::GetStatus)?;
+        self.interface.wait_until_idle(IS_BUSY_LOW)
+    }
+
+    /// Sends the window of a partial command, `x` and `width` are rounded down to multiples of 8
+    fn send_partial_window(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.data(
+            spi,
+            &[
+                (x >> 8) as u8,
+                (x & 0xf8) as u8,
+                (y >> 8) as u8,
+                (y & 0xff) as u8,
+                (width >> 8) as u8,
+                (width & 0xf8) as u8,
+                (height >> 8) as u8,
+                (height & 0xff) as u8,
+            ],
+        )
+    }
+}
+
+/// Reads the status and waits until the controller is idle
+fn wait_for_status<SPI, CS, BUSY, DC, RST, DELAY>(
+    interface: &mut DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    spi: &mut SPI,
+    delay: &mut DELAY,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
 {
-  fn wait_until_idle(&mut self, spi: &mut SPI,) -> Result<(), SPI::Error> {
-      self.interface.cmd(spi, Command::GetStatus)?;
-      let _ = self.interface.wait_until_idle(IS_BUSY_LOW);
-      Ok(())
-  }
-
-  /// Refresh display for partial frame
-  pub fn display_partial_frame(
-      &mut self,
-      spi: &mut SPI,
-      x: u32,
-      y: u32,
-      width: u32,
-      height: u32,
-  ) -> Result<(), SPI::Error> {
-      self.interface.cmd(spi, Command::PartialDisplayRefresh)?;
-      self.interface.data(spi, &[(x >> 8) as u8])?;
-      self.interface.data(spi, &[(x & 0xf8) as u8])?;
-      self.interface.data(spi, &[(y >> 8) as u8])?;
-      self.interface.data(spi, &[(y & 0xff) as u8])?;
-      self.interface.data(spi, &[(width >> 8) as u8])?;
-      self.interface.data(spi, &[(width & 0xf8) as u8])?;
-      self.interface.data(spi, &[(height >> 8) as u8])?;
-      self.interface.data(spi, &[(height & 0xff) as u8])?;
-      self.wait_until_idle(spi)?;
-      Ok(())
-  }
+    interface.cmd(spi, Command::GetStatus)?;
+    interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)
 }
 
 #[cfg(test)]
 mod tests {
-  use super::*;
-
-  #[test]
-  fn epd_size() {
-      assert_eq!(WIDTH, 176);
-      assert_eq!(HEIGHT, 264);
-      assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
-  }
-}
\ No newline at end of file
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 176);
+        assert_eq!(HEIGHT, 264);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+    }
+}
@@ -0,0 +1,44 @@
+//! Waveforms of the Waveshare C driver
+
+use crate::controllers::ssd168x::DataEntryMode;
+use crate::settings::InitStep;
+use crate::type_a::command::Command;
+
+/// Software reset, gate lines and data entry mode, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 3] = [
+    InitStep::new(Command::SwReset as u8, &[]).wait(),
+    // 264 gate lines (0x107 + 1), default scanning order
+    InitStep::new(Command::DriverOutputControl as u8, &[0x07, 0x01, 0x00]),
+    // x increment, y increment, address counter is updated in x direction
+    InitStep::new(
+        Command::DataEntryModeSetting as u8,
+        &[DataEntryMode::IncrementXIncrementY as u8],
+    ),
+];
+
+/// 4 gray levels, the level of a pixel is selected by its bits in both RAMs
+///
+/// The 153 bytes of the waveform are followed by the end option, the gate voltage, the three
+/// source voltages and VCOM.
+#[rustfmt::skip]
+pub(crate) const LUT_4GRAY: [u8; 159] = [
+    0x40, 0x48, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x08, 0x48, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x02, 0x48, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x20, 0x48, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x0A, 0x19, 0x00, 0x03, 0x08, 0x00, 0x00,
+    0x14, 0x01, 0x00, 0x14, 0x01, 0x00, 0x03,
+    0x0A, 0x03, 0x00, 0x08, 0x19, 0x00, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x00, 0x00, 0x00,
+    0x22, 0x17, 0x41, 0x00, 0x32, 0x1C,
+];
@@ -0,0 +1,913 @@
+//! A Driver for the Waveshare 2.7" V2 E-Ink Display via SPI
+//!
+//! The V2 revision of the 176x264 panel has a SSD1680 instead of the controller of the
+//! [original board](crate::epd2in7), the drivers aren't interchangeable.
+//!
+//! The panel shows black and white with 1 bit per pixel or 4 gray levels with 2 bits per pixel,
+//! selected by the [`Mode`] of the driver or through [`GrayscaleDisplay`]. The frames of both modes
+//! are drawn with a [`Display2in7`] in the same mode. In the grayscale mode the driver splits the
+//! frame into the two RAMs of the controller while sending it, the high bits of the levels go to
+//! the black/white RAM and the low bits to the red RAM. Black and white frames are shown with the
+//! full waveform of the controller or, with [`RefreshLut::Quick`], with its fast one.
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/2.7inch_e-Paper_HAT_Manual)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_2in7_V2.c)
+//!
+//! # Example for the 2.7" E-Ink Display
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{
+//!    pixelcolor::Gray2, prelude::*, primitives::{Line, PrimitiveStyle},
+//!};
+//!use epd_waveshare::{epd2in7_v2::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// Setup EPD
+//!let mut epd = Epd2in7::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!epd.set_mode(&mut spi, Mode::Gray4, &mut delay)?;
+//!
+//!// Use display graphics from embedded-graphics
+//!let mut display = Display2in7::new(Mode::Gray4);
+//!
+//!// Use embedded graphics for drawing a line in light gray
+//!let _ = Line::new(Point::new(0, 120), Point::new(0, 263))
+//!    .into_styled(PrimitiveStyle::with_stroke(Gray2::new(2), 1))
+//!    .draw(&mut display);
+//!
+//!// Display updated frame
+//!epd.update_and_display_frame(&mut spi, &display.buffer(), &mut delay)?;
+//!
+//!// Set the EPD to sleep
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
+    digital::v2::*,
+};
+
+use crate::buffer_len;
+use crate::color::Color;
+use crate::controllers::ssd168x::{self, BorderWaveform};
+use crate::energy::RefreshEnergy;
+use crate::interface::{DisplayInterface, InterfaceAccess, InterfaceState};
+use crate::settings::InitStep;
+use crate::traits::DisplayError;
+use crate::traits::*;
+use crate::type_a::command::Command;
+
+mod constants;
+pub use self::constants::INIT_SEQUENCE;
+use self::constants::LUT_4GRAY;
+
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(feature = "graphics")]
+pub use self::graphics::Display2in7;
+
+/// Width of epd2in7_v2 in pixels
+pub const WIDTH: u32 = 176;
+/// Height of epd2in7_v2 in pixels
+pub const HEIGHT: u32 = 264;
+/// Default Background Color (white)
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+const IS_BUSY_LOW: bool = false;
+
+/// Bytes of one line of a frame in [`Mode::Gray4`]
+const GRAY_LINE_LEN: usize = (WIDTH as usize * 2).div_ceil(8);
+/// Bytes of each of the two RAM planes a frame in [`Mode::Gray4`] is split into
+const PLANE_LEN: usize = buffer_len(WIDTH as usize, HEIGHT as usize);
+
+/// Pixel format of the frames and the waveform for them, see [`Epd2in7::set_mode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// 1 bit per pixel, set for white, with the waveforms of the controller
+    #[default]
+    BlackWhite,
+    /// 2 bits per pixel from `0b00` for black to `0b11` for white, with the waveform of the
+    /// Waveshare driver
+    Gray4,
+}
+
+impl Mode {
+    /// Length of a frame in this mode in bytes
+    pub const fn frame_len(self) -> usize {
+        match self {
+            Mode::BlackWhite => buffer_len(WIDTH as usize, HEIGHT as usize),
+            Mode::Gray4 => GRAY_LINE_LEN * HEIGHT as usize,
+        }
+    }
+}
+
+/// Epd2in7 driver
+pub struct Epd2in7<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// SPI
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    /// Color
+    background_color: Color,
+    /// Pixel format of the frames
+    mode: Mode,
+    /// Refresh of black and white frames
+    refresh: RefreshLut,
+    /// Init sequence
+    init_sequence: &'static [InitStep],
+    /// Waveform of `display_new_frame`
+    quick_lut: RefreshLut,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, 2)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+
+        self.interface
+            .run_init_sequence(spi, delay, self.init_sequence, IS_BUSY_LOW)?;
+
+        self.send_mode(spi)?;
+        self.use_full_frame(spi)?;
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type DisplayColor = Color;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: true,
+        grayscale_levels: 4,
+        chromatic_colors: 0,
+        readback: false,
+        fast_mode: true,
+        ultrafast: false,
+        clear_refreshes: false,
+    };
+    const ENERGY: RefreshEnergy = RefreshEnergy {
+        full: 52_800,
+        fast: 13_200,
+        partial: 13_200,
+    };
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
+        Ok(())
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    /// Sends a frame in the format of the [`Mode`] of the driver
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface
+            .check_window(buffer.len() == self.mode.frame_len())?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        match self.mode {
+            Mode::BlackWhite => self
+                .interface
+                .cmd_with_frame_data(spi, Command::WriteRam, buffer),
+            Mode::Gray4 => {
+                self.interface.cmd_with_gray_plane(
+                    spi,
+                    Command::WriteRam,
+                    buffer,
+                    PLANE_LEN,
+                    true,
+                )?;
+                self.use_full_frame(spi)?;
+                self.interface.cmd_with_gray_plane(
+                    spi,
+                    Command::WriteRam2,
+                    buffer,
+                    PLANE_LEN,
+                    false,
+                )
+            }
+        }
+    }
+
+    #[allow(unused)]
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // not supported by the driver yet, nothing is sent
+        Ok(())
+    }
+
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // both bits of a gray level are set for white and cleared for black
+        let color = self.background_color.get_byte_value();
+        let len = buffer_len(WIDTH as usize, HEIGHT as usize) as u32;
+
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.clear_data(spi, color, len)?;
+        if self.interface.clear_aborted() {
+            return Ok(());
+        }
+
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface.clear_data(spi, color, len)
+    }
+
+    /// Selects the refresh of black and white frames, frames in [`Mode::Gray4`] always use the
+    /// gray waveform
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut.without_ultrafast();
+        }
+        if self.mode == Mode::BlackWhite {
+            self.wait_until_idle()?;
+            self.send_mode(spi)?;
+        }
+        Ok(())
+    }
+
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Snapshot for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = Color;
+
+    fn snapshot(&self) -> DriverSnapshot<Color> {
+        self.interface
+            .state()
+            .snapshot(self.background_color, self.refresh)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<Color>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.background_color = snapshot.background_color;
+        self.refresh = snapshot.refresh;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Writes a plane of 1 bit per pixel as it is, the black/white plane is the RAM of the
+    /// high bits of the gray levels and the chromatic plane the one of the low bits
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        let command = match plane {
+            Plane::Bw => Command::WriteRam,
+            Plane::Chromatic if self.mode == Mode::Gray4 => Command::WriteRam2,
+            Plane::Chromatic => return Err(RawPlaneError::UnsupportedPlane(plane)),
+        };
+        self.ensure_initialized(spi, delay)
+            .map_err(RawPlaneError::Interface)?;
+        self.interface
+            .wait_until_idle_ticked(IS_BUSY_LOW, delay)
+            .map_err(RawPlaneError::Interface)?;
+        self.use_full_frame(spi).map_err(RawPlaneError::Interface)?;
+        self.interface
+            .cmd_with_frame_data(spi, command, data)
+            .map_err(RawPlaneError::Interface)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+
+        Epd2in7 {
+            interface,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            mode: Mode::default(),
+            refresh: RefreshLut::Full,
+            init_sequence: &INIT_SEQUENCE,
+            quick_lut: RefreshLut::Quick,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InitSequence for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY> {
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> TemperatureSetting for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_temperature(&mut self, celsius: i8) {
+        self.interface.set_temperature(Some(celsius));
+    }
+
+    fn use_internal_sensor(&mut self) {
+        self.interface.set_temperature(None);
+    }
+
+    fn temperature(&self) -> Option<i8> {
+        self.interface.temperature()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> TemperatureReading<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn read_temperature(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<i8, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        ssd168x::read_temperature(&mut self.interface, spi)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // the full refresh loads the waveform for the temperature from the OTP, the fast and the
+        // gray refresh use the one loaded by `send_mode`
+        let sequence = match (self.mode, self.refresh) {
+            (Mode::BlackWhite, RefreshLut::Full) => 0xF7,
+            _ => 0xC7,
+        };
+        ssd168x::set_update_sequence(&mut self.interface, spi, sequence)?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        _spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.poll_refresh(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ConnectionCheck<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn ping(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // enable the clock, load the temperature and disable the clock again, the LUT and the
+        // RAM stay as they are
+        self.interface
+            .ping(spi, IS_BUSY_LOW, delay, |interface, spi| {
+                interface.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xA1])?;
+                interface.cmd(spi, Command::MasterActivation)
+            })
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Writes the base image to both RAMs, to be shown with a full `display_frame`
+    ///
+    /// Like all quick refreshes only for frames in [`Mode::BlackWhite`].
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam2, buffer)
+    }
+
+    /// Writes the new frame, to be shown with `display_new_frame`
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)
+    }
+
+    /// Refreshes the pixels which differ from the base image with the partial waveform of the OTP
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if self.quick_lut == RefreshLut::Full {
+            // the full waveform of `display_frame`, the LUT selected with `set_lut` stays
+            let refresh = core::mem::replace(&mut self.refresh, RefreshLut::Full);
+            let result = self.display_frame(spi, delay);
+            self.refresh = refresh;
+            return result;
+        }
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        ssd168x::set_border_waveform(&mut self.interface, spi, BorderWaveform::Vcom)?;
+        ssd168x::set_update_sequence(&mut self.interface, spi, 0xFF)?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_new_frame(spi, delay)
+    }
+
+    /// Writes the base image of a window to both RAMs
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        ssd168x::set_window(&mut self.interface, spi, 0, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)?;
+        ssd168x::set_window(&mut self.interface, spi, 0, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam2, buffer)
+    }
+
+    /// Writes the new content of a window, to be shown with `display_new_frame`
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_partial(buffer, x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        ssd168x::set_window(&mut self.interface, spi, 0, x, y, width, height)?;
+        self.interface
+            .cmd_with_frame_data(spi, Command::WriteRam, buffer)
+    }
+
+    /// Clears the new content of a window with the background color
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .check_area(x, y, width, height, (WIDTH, HEIGHT))?;
+        self.wait_until_idle()?;
+        ssd168x::set_window(&mut self.interface, spi, 0, x, y, width, height)?;
+        let color = self.background_color.get_byte_value();
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.clear_data(
+            spi,
+            color,
+            buffer_len(width as usize, height as usize) as u32,
+        )
+    }
+
+    fn set_quick_lut(
+        &mut self,
+        _spi: &mut SPI,
+        refresh: RefreshLut,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.quick_lut = refresh;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> GrayscaleDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Selects [`Mode::Gray4`], see [`set_mode`](Epd2in7::set_mode)
+    fn enter_gray_mode(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.set_mode(spi, Mode::Gray4, delay)
+    }
+
+    /// Selects [`Mode::BlackWhite`], see [`set_mode`](Epd2in7::set_mode)
+    fn leave_gray_mode(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.set_mode(spi, Mode::BlackWhite, delay)
+    }
+
+    fn is_gray_mode(&self) -> bool {
+        self.mode == Mode::Gray4
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd2in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Selects the pixel format of the frames and the waveform for it
+    ///
+    /// The frames sent afterwards need to be in the format of `mode`, e.g. drawn with a
+    /// [`Display2in7`] in the same mode. The image on the display stays until the next refresh.
+    /// The controller is reset for the switch, as the waveforms of the OTP only come back with
+    /// the reset after the gray waveform was loaded.
+    pub fn set_mode(
+        &mut self,
+        spi: &mut SPI,
+        mode: Mode,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.mode = mode;
+        self.init(spi, delay)
+    }
+
+    /// Returns the pixel format of the frames
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn wait_until_idle(&mut self) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW)
+    }
+
+    /// Loads the waveform of the mode and, for black and white, of the refresh
+    fn send_mode(&mut self, spi: &mut SPI) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        match (self.mode, self.refresh) {
+            (Mode::BlackWhite, RefreshLut::Full) => {
+                // the waveform is loaded for the measured temperature on every refresh
+                self.interface
+                    .cmd_with_data(spi, Command::TemperatureSensorSelection, &[0x80])
+            }
+            (Mode::BlackWhite, _) => {
+                // the fast waveform of the OTP is the one of 100 °C, it is loaded once here
+                self.interface
+                    .cmd_with_data(spi, Command::TemperatureSensorSelection, &[0x80])?;
+                self.interface.cmd_with_data(
+                    spi,
+                    Command::TemperatureSensorControl,
+                    &[0x64, 0x00],
+                )?;
+                self.interface
+                    .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0x91])?;
+                self.interface.cmd(spi, Command::MasterActivation)?;
+                self.wait_until_idle()?;
+                Ok(())
+            }
+            (Mode::Gray4, _) => {
+                ssd168x::set_border_waveform(&mut self.interface, spi, BorderWaveform::Lut(0))?;
+                ssd168x::load_lut(&mut self.interface, spi, &LUT_4GRAY)
+            }
+        }
+    }
+
+    fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        ssd168x::set_window(&mut self.interface, spi, 0, 0, 0, WIDTH, HEIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 176);
+        assert_eq!(HEIGHT, 264);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+        assert_eq!(Mode::BlackWhite.frame_len(), 5808);
+        assert_eq!(Mode::Gray4.frame_len(), 11616);
+    }
+
+    #[test]
+    fn gray_frames_are_sent_as_two_planes() {
+        extern crate std;
+        use crate::interface::mock::{NoPin, RecordingSpi};
+        use embedded_hal_mock::delay::MockNoop;
+        use std::vec;
+
+        let mut spi = RecordingSpi::default();
+        let mut delay = MockNoop::new();
+        let mut epd: Epd2in7<_, _, _, _, _, MockNoop> =
+            Epd2in7::new_uninitialized(NoPin, NoPin, NoPin, NoPin);
+        epd.set_mode(&mut spi, Mode::Gray4, &mut delay).unwrap();
+
+        // black, dark gray, light gray and white in every byte
+        let frame = vec![0b00_01_10_11; Mode::Gray4.frame_len()];
+        spi.0.clear();
+        epd.update_frame(&mut spi, &frame, &mut delay).unwrap();
+        // the high bits with the first RAM command, the low bits with the second one
+        let plane_len = frame.len() / 2;
+        assert!(spi.sent(0x24, &vec![0x33; plane_len]));
+        assert!(spi.sent(0x26, &vec![0x55; plane_len]));
+    }
+
+    #[test]
+    fn full_quick_lut_refreshes_all_pixels() {
+        use crate::interface::mock::{NoPin, RecordingSpi};
+        use embedded_hal_mock::delay::MockNoop;
+
+        let mut spi = RecordingSpi::default();
+        let mut delay = MockNoop::new();
+        let mut epd: Epd2in7<_, _, _, _, _, MockNoop> =
+            Epd2in7::new_uninitialized(NoPin, NoPin, NoPin, NoPin);
+
+        // DISPLAY Mode 2, which only drives the pixels differing from the old frame
+        epd.display_new_frame(&mut spi, &mut delay).unwrap();
+        assert!(spi.sent(0x22, &[0xFF]));
+
+        // DISPLAY Mode 1 with the waveform of the full refresh
+        spi.0.clear();
+        epd.set_quick_lut(&mut spi, RefreshLut::Full).unwrap();
+        epd.display_new_frame(&mut spi, &mut delay).unwrap();
+        assert!(spi.sent(0x22, &[0xF7]));
+        assert!(!spi.sent(0x22, &[0xFF]));
+    }
+
+    #[test]
+    fn init_and_frame_commands() {
+        extern crate std;
+        use crate::interface::mock::{CommandSpi, NoPin};
+        use embedded_hal_mock::delay::MockNoop;
+        use std::vec;
+
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let mut delay = MockNoop::new();
+        let mut epd: Epd2in7<_, _, _, _, _, MockNoop> =
+            Epd2in7::new(&mut spi, NoPin, NoPin, dc, NoPin, &mut delay).unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x12, 0x01, 0x11, 0x18, 0x44, 0x45, 0x4E, 0x4F]
+        );
+        assert!(spi.sent(0x01, &[0x07, 0x01, 0x00]));
+        assert!(spi.sent(0x44, &[0x00, 0x15]));
+        assert!(spi.sent(0x45, &[0x00, 0x00, 0x07, 0x01]));
+
+        // there are no partial windows
+        spi.clear();
+        epd.update_partial_frame(&mut spi, &[0xAA, 0xBB, 0xCC, 0xDD], 8, 16, 16, 2)
+            .unwrap();
+        assert!(spi.commands().is_empty());
+
+        let frame = vec![0xAA; Mode::BlackWhite.frame_len()];
+        epd.update_frame(&mut spi, &frame, &mut delay).unwrap();
+        assert_eq!(spi.commands(), [0x44, 0x45, 0x4E, 0x4F, 0x24]);
+        assert!(spi.sent(0x24, &frame));
+    }
+}
@@ -1,3 +1,14 @@
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Power on and panel setup, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 2] = [
+    // power on
+    InitStep::new(Command::PowerOn as u8, &[]).delay(5).wait(),
+    // set panel settings, 0xbf is bw, 0xaf is multi-color
+    InitStep::new(Command::PanelSetting as u8, &[0xaf]),
+];
+
 #[rustfmt::skip]
 pub(crate) const LUT_VCOM_DC: [u8; 44] = [
 0x00, 0x00,
@@ -0,0 +1,16 @@
+#[rustfmt::skip]
+// Waveform of the GDEY029T94 panels, from the Good Display sample code
+pub(crate) const LUT_FULL_UPDATE_GDEY029T94: [u8; 30] = [
+    0x50, 0xAA, 0x55, 0xAA, 0x11, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x1F, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+];
+
+#[rustfmt::skip]
+pub(crate) const LUT_PARTIAL_UPDATE_GDEY029T94: [u8; 30] = [
+    0x10, 0x18, 0x18, 0x08, 0x18, 0x18, 0x08, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x13, 0x11, 0x22, 0x63,
+    0x11, 0x00, 0x00, 0x00, 0x00, 0x00
+];
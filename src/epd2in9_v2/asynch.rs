@@ -0,0 +1,212 @@
+use embedded_hal_1::digital::OutputPin;
+use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::SpiDevice};
+
+use super::{DEFAULT_BACKGROUND_COLOR, HEIGHT, IS_BUSY_LOW, WIDTH};
+use crate::asynch::{
+    interface::DisplayInterfaceAsync, AsyncDisplayError, FrameUploadDma, ReadBuffer,
+    WaveshareDisplayAsync,
+};
+use crate::color::Color;
+use crate::type_a::command::Command;
+
+/// Async Epd2in9 driver, see [`asynch`](crate::asynch)
+///
+/// Only the full refreshes of [`Epd2in9`](super::Epd2in9) are available.
+pub struct Epd2in9Async<SPI, BUSY, DC, RST, DELAY> {
+    /// SPI
+    interface: DisplayInterfaceAsync<SPI, BUSY, DC, RST, DELAY>,
+    /// Color
+    background_color: Color,
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> Epd2in9Async<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// The init of [`Epd2in9`](super::Epd2in9) with the async interface
+    async fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.interface.reset(delay, 2).await?;
+
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+        self.interface.cmd(spi, Command::SwReset).await?;
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+
+        self.interface
+            .cmd_with_data(spi, Command::DriverOutputControl, &[0x27, 0x01, 0x00])
+            .await?;
+
+        // x and y increment
+        self.interface
+            .cmd_with_data(spi, Command::DataEntryModeSetting, &[0x03])
+            .await?;
+
+        self.interface
+            .cmd_with_data(
+                spi,
+                Command::SetRamXAddressStartEndPosition,
+                &[0x00, ((WIDTH - 1) >> 3) as u8],
+            )
+            .await?;
+        self.interface
+            .cmd_with_data(
+                spi,
+                Command::SetRamYAddressStartEndPosition,
+                &[0x00, 0x00, (HEIGHT - 1) as u8, ((HEIGHT - 1) >> 8) as u8],
+            )
+            .await?;
+
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl1, &[0x00, 0x80])
+            .await?;
+
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+        self.interface
+            .cmd_with_data(spi, Command::SetRamXAddressCounter, &[0x00])
+            .await?;
+        self.interface
+            .cmd_with_data(spi, Command::SetRamYAddressCounter, &[0x00, 0x00])
+            .await?;
+
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+        Ok(())
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> WaveshareDisplayAsync<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in9Async<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = Color;
+
+    async fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        let mut epd = Epd2in9Async {
+            interface: DisplayInterfaceAsync::new(busy, dc, rst),
+            background_color: DEFAULT_BACKGROUND_COLOR,
+        };
+        epd.init(spi, delay).await?;
+
+        Ok(epd)
+    }
+
+    async fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+        // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x01])
+            .await
+    }
+
+    async fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.init(spi, delay).await
+    }
+
+    async fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        _delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteRam, buffer)
+            .await
+    }
+
+    async fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+        // Enable clock signal, Enable Analog, Load temperature value, DISPLAY with DISPLAY Mode 1, Disable Analog, Disable OSC
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xF7])
+            .await?;
+        self.interface.cmd(spi, Command::MasterActivation).await?;
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+        Ok(())
+    }
+
+    async fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), AsyncDisplayError<SPI, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+
+        // clear the ram with the background color
+        let color = self.background_color.get_byte_value();
+
+        self.interface.cmd(spi, Command::WriteRam).await?;
+        self.interface
+            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)
+            .await
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> FrameUploadDma<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in9Async<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    async fn update_frame_dma<B>(
+        &mut self,
+        spi: &mut SPI,
+        buffer: B,
+        _delay: &mut DELAY,
+    ) -> Result<B, AsyncDisplayError<SPI, BUSY, DC, RST>>
+    where
+        B: ReadBuffer<Word = u8>,
+    {
+        self.interface.wait_until_idle(IS_BUSY_LOW).await?;
+        self.interface.cmd(spi, Command::WriteRam).await?;
+        self.interface.data_dma(spi, buffer).await
+    }
+}
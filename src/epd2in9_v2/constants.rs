@@ -0,0 +1,22 @@
+//! Init sequence of the controller
+
+use crate::controllers::ssd168x::DataEntryMode;
+use crate::settings::InitStep;
+use crate::type_a::command::Command;
+
+/// Software reset, gate lines and data entry mode, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 3] = [
+    InitStep::new(Command::SwReset as u8, &[]).wait(),
+    // 3 Databytes:
+    // A[7:0]
+    // 0.. A[8]
+    // 0.. B[2:0]
+    // Default Values: A = Height of Screen (0x127), B = 0x00 (GD, SM and TB=0?)
+    InitStep::new(Command::DriverOutputControl as u8, &[0x27, 0x01, 0x00]),
+    // One Databyte with default value 0x03
+    //  -> address: x increment, y increment, address counter is updated in x direction
+    InitStep::new(
+        Command::DataEntryModeSetting as u8,
+        &[DataEntryMode::IncrementXIncrementY as u8],
+    ),
+];
@@ -25,6 +25,7 @@ pub(crate) enum Command {
     TemperatureSensor = 0x40,
     TemperatureSensorSelection = 0x41,
     VcomAndDataIntervalSetting = 0x50,
+    TconSetting = 0x60,
     ResolutionSetting = 0x61,
     VcmDcSetting = 0x82,
     PowerSaving = 0xE3,
@@ -0,0 +1,14 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Power on and panel settings
+///
+/// The booster runs with its default settings. The panel takes the LUT from the OTP in the
+/// black/white/chromatic mode. The resolution and the VCOM and data interval are sent after
+/// this sequence.
+pub const INIT_SEQUENCE: [InitStep; 2] = [
+    InitStep::new(Command::PowerOn as u8, &[]).wait(),
+    InitStep::new(Command::PanelSetting as u8, &[0x0F, 0x89]),
+];
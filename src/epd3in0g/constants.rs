@@ -0,0 +1,54 @@
+//! Init sequence of the 3.0" (G) display
+
+use crate::controllers::g_panel::GPanel;
+use crate::energy::RefreshEnergy;
+use crate::settings::InitStep;
+use crate::type_g::command::Command;
+
+use super::{HEIGHT, WIDTH};
+
+/// Unlock, power, booster, panel and resolution settings, ending with powering the panel on
+///
+/// Values taken from the Waveshare C driver.
+pub const INIT_SEQUENCE: [InitStep; 23] = [
+    InitStep::new(Command::Unlock as u8, &[0x49, 0x55, 0x13, 0x5D, 0x05, 0x10]),
+    InitStep::new(Command::VendorSettingB0 as u8, &[0x00]),
+    InitStep::new(Command::PowerSetting as u8, &[0x0F, 0x00]),
+    InitStep::new(Command::PanelSetting as u8, &[0x6F, 0x69]),
+    InitStep::new(
+        Command::PowerOffSequenceSetting as u8,
+        &[0x00, 0x54, 0x00, 0x44],
+    ),
+    InitStep::new(Command::BoosterSoftStart1 as u8, &[0x40, 0x1F, 0x1F, 0x2C]),
+    InitStep::new(Command::BoosterSoftStart2 as u8, &[0x6F, 0x1F, 0x16, 0x25]),
+    InitStep::new(Command::BoosterSoftStart3 as u8, &[0x6F, 0x1F, 0x1F, 0x22]),
+    InitStep::new(Command::InternalPowerControl as u8, &[0x00, 0x04]),
+    InitStep::new(Command::PllControl as u8, &[0x02]),
+    InitStep::new(Command::TemperatureSensorEnable as u8, &[0x00]),
+    InitStep::new(Command::VcomAndDataIntervalSetting as u8, &[0x3F]),
+    InitStep::new(Command::TconSetting as u8, &[0x02, 0x00]),
+    InitStep::new(Command::TconResolution as u8, &[0x00, 0xA8, 0x01, 0x90]),
+    InitStep::new(Command::GateSourceStart as u8, &[0x00, 0x00, 0x00, 0x00]),
+    InitStep::new(Command::VendorSettingE7 as u8, &[0x1C]),
+    InitStep::new(Command::PowerSaving as u8, &[0x22]),
+    InitStep::new(Command::CascadeSetting as u8, &[0x00]),
+    InitStep::new(Command::VendorSettingB4 as u8, &[0xD0]),
+    InitStep::new(Command::VendorSettingB5 as u8, &[0x03]),
+    InitStep::new(Command::VendorSettingE9 as u8, &[0x01]),
+    InitStep::new(Command::PllControl as u8, &[0x08]),
+    InitStep::new(Command::PowerOn as u8, &[]).wait(),
+];
+
+/// The 3.0" (G) panel
+pub struct Panel3in0g;
+
+impl GPanel for Panel3in0g {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
+    const ENERGY: RefreshEnergy = RefreshEnergy::uniform(130_000);
+    const INIT_SEQUENCE: &'static [InitStep] = &INIT_SEQUENCE;
+    const POWER_PER_REFRESH: bool = false;
+
+    type Row = [u8; WIDTH as usize / 4];
+    const ROW: Self::Row = [0; WIDTH as usize / 4];
+}
@@ -0,0 +1,156 @@
+//! Init sequence and waveforms of the Waveshare C driver
+//!
+//! Every LUT has 8 groups of 7 bytes, the waveforms only use the first group. GC is the full
+//! refresh, which drives every pixel, DU the fast one, which only drives the changing pixels.
+
+use super::command::Command;
+use super::{HEIGHT, WIDTH};
+use crate::settings::InitStep;
+
+/// Panel, power and booster setup of the Waveshare C driver, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 9] = [
+    // LUTs from the registers, black/white
+    InitStep::new(Command::PanelSetting as u8, &[0xFF, 0x01]),
+    // VGH 20 V, VGL -20 V, VSH 15 V, VSL -15 V, VSHR 6.4 V
+    InitStep::new(Command::PowerSetting as u8, &[0x03, 0x10, 0x3F, 0x3F, 0x03]),
+    InitStep::new(Command::BoosterSoftStart as u8, &[0x37, 0x3D, 0x3D]),
+    InitStep::new(Command::TconSetting as u8, &[0x22]),
+    InitStep::new(Command::VcmDcSetting as u8, &[0x07]),
+    InitStep::new(Command::PllControl as u8, &[0x09]),
+    InitStep::new(Command::PowerSaving as u8, &[0x88]),
+    InitStep::new(
+        Command::ResolutionSetting as u8,
+        &[WIDTH as u8, (HEIGHT >> 8) as u8, HEIGHT as u8],
+    ),
+    InitStep::new(Command::VcomAndDataIntervalSetting as u8, &[0xB7]),
+];
+
+/// VCOM in the full refresh
+#[rustfmt::skip]
+pub(crate) const LUT_VCOM_GC: [u8; 56] = [
+    0x01, 0x0F, 0x0F, 0x0F, 0x01, 0x01, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The pixels which stay white in the full refresh
+#[rustfmt::skip]
+pub(crate) const LUT_WW_GC: [u8; 56] = [
+    0x01, 0x4F, 0x8F, 0x0F, 0x01, 0x01, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The pixels which change from black to white in the full refresh
+#[rustfmt::skip]
+pub(crate) const LUT_BW_GC: [u8; 56] = [
+    0x01, 0x0F, 0x8F, 0x0F, 0x01, 0x01, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The pixels which change from white to black in the full refresh
+#[rustfmt::skip]
+pub(crate) const LUT_WB_GC: [u8; 56] = [
+    0x01, 0x4F, 0x8F, 0x4F, 0x01, 0x01, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The pixels which stay black in the full refresh
+#[rustfmt::skip]
+pub(crate) const LUT_BB_GC: [u8; 56] = [
+    0x01, 0x0F, 0x8F, 0x4F, 0x01, 0x01, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// VCOM in the fast refresh
+#[rustfmt::skip]
+pub(crate) const LUT_VCOM_DU: [u8; 56] = [
+    0x01, 0x0F, 0x01, 0x00, 0x00, 0x01, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The pixels which stay white in the fast refresh
+#[rustfmt::skip]
+pub(crate) const LUT_WW_DU: [u8; 56] = [
+    0x01, 0x0F, 0x01, 0x00, 0x00, 0x01, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The pixels which change from black to white in the fast refresh
+#[rustfmt::skip]
+pub(crate) const LUT_BW_DU: [u8; 56] = [
+    0x01, 0x8F, 0x01, 0x00, 0x00, 0x01, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The pixels which change from white to black in the fast refresh
+#[rustfmt::skip]
+pub(crate) const LUT_WB_DU: [u8; 56] = [
+    0x01, 0x4F, 0x01, 0x00, 0x00, 0x01, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The pixels which stay black in the fast refresh
+#[rustfmt::skip]
+pub(crate) const LUT_BB_DU: [u8; 56] = [
+    0x01, 0x0F, 0x01, 0x00, 0x00, 0x01, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
@@ -0,0 +1,63 @@
+//! SPI Commands for the Waveshare 3.7" E-Ink Display
+
+use crate::traits;
+
+/// Epd3in7 commands
+///
+/// The controller is a SSD1677, the commands are the ones of the [type A displays](crate::type_a)
+/// with a few additional voltage and RAM fill commands.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Number of gate lines and the scanning order
+    DriverOutputControl = 0x01,
+    /// Gate driving voltage
+    GateVoltage = 0x03,
+    /// Source driving voltages VSH1, VSH2 and VSL
+    SourceVoltage = 0x04,
+    /// Booster soft start strength and timing
+    BoosterSoftStartControl = 0x0C,
+    /// 0x00 for normal mode, 0x03 for deep sleep mode 2, which loses the RAM
+    DeepSleepMode = 0x10,
+    /// Direction in which the address counters are incremented
+    DataEntryModeSetting = 0x11,
+    /// Resets the commands and parameters to their defaults, the RAM is kept
+    SwReset = 0x12,
+    /// 0x80 for the internal temperature sensor
+    TemperatureSensorSelection = 0x18,
+    /// Runs the sequence selected with `DisplayUpdateControl2`
+    MasterActivation = 0x20,
+    /// Selects the steps of the next `MasterActivation`
+    DisplayUpdateControl2 = 0x22,
+    /// The black/white RAM, the low bits of the gray levels
+    WriteRam = 0x24,
+    /// The red RAM, the high bits of the gray levels
+    WriteRam2 = 0x26,
+    /// VCOM voltage
+    WriteVcomRegister = 0x2C,
+    /// 105 bytes of waveform
+    WriteLutRegister = 0x32,
+    /// Display options, e.g. how the RAMs are used in the display mode 2
+    WriteDisplayOption = 0x37,
+    /// Waveform of the border
+    BorderWaveformControl = 0x3C,
+    /// Start and end of the RAM window in x direction, in pixels
+    SetRamXAddressStartEndPosition = 0x44,
+    /// Start and end of the RAM window in y direction
+    SetRamYAddressStartEndPosition = 0x45,
+    /// Fills the red RAM with a pattern
+    AutoWriteRedRam = 0x46,
+    /// Fills the black/white RAM with a pattern
+    AutoWriteBwRam = 0x47,
+    /// Address counter in x direction, in pixels
+    SetRamXAddressCounter = 0x4E,
+    /// Address counter in y direction
+    SetRamYAddressCounter = 0x4F,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
@@ -0,0 +1,58 @@
+//! Waveforms of the Waveshare C driver
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Software reset, RAM fill and panel setup, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 11] = [
+    InitStep::new(Command::SwReset as u8, &[]).delay(10).wait(),
+    // fill both RAMs with white
+    InitStep::new(Command::AutoWriteRedRam as u8, &[0xF7]).wait(),
+    InitStep::new(Command::AutoWriteBwRam as u8, &[0xF7]).wait(),
+    // 480 gate lines (0x1DF + 1), default scanning order
+    InitStep::new(Command::DriverOutputControl as u8, &[0xDF, 0x01, 0x00]),
+    InitStep::new(Command::GateVoltage as u8, &[0x00]),
+    InitStep::new(Command::SourceVoltage as u8, &[0x41, 0xA8, 0x32]),
+    // x increment, y increment, address counter is updated in x direction
+    InitStep::new(Command::DataEntryModeSetting as u8, &[0x03]),
+    InitStep::new(Command::BorderWaveformControl as u8, &[0x00]),
+    InitStep::new(
+        Command::BoosterSoftStartControl as u8,
+        &[0xAE, 0xC7, 0xC3, 0xC0, 0xC0],
+    ),
+    // the internal temperature sensor
+    InitStep::new(Command::TemperatureSensorSelection as u8, &[0x80]),
+    InitStep::new(Command::WriteVcomRegister as u8, &[0x44]),
+];
+
+/// 4 gray levels, the level of a pixel is selected by its bits in both RAMs
+#[rustfmt::skip]
+pub(crate) const LUT_4GRAY: [u8; 105] = [
+    0x2A, 0x06, 0x15, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x28, 0x06, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x20, 0x06, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x14, 0x06, 0x28, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x02, 0x02, 0x0A, 0x00, 0x00, 0x00, 0x08, 0x08, 0x02,
+    0x00, 0x02, 0x02, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x22, 0x22, 0x22, 0x22, 0x22,
+];
+
+/// Full refresh in black and white
+#[rustfmt::skip]
+pub(crate) const LUT_1GRAY: [u8; 105] = [
+    0x2A, 0x05, 0x15, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x05, 0x2A, 0x15, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x2A, 0x15, 0x15, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x05, 0x0A, 0x15, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x02, 0x03, 0x0A, 0x00, 0x02, 0x06, 0x0A, 0x05, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x22, 0x22, 0x22, 0x22, 0x22,
+];
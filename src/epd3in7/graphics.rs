@@ -0,0 +1,165 @@
+use crate::epd3in7::{Mode, HEIGHT, WIDTH};
+use crate::graphics::{packed_position, DisplayRotation, Mirroring, Orientation};
+use embedded_graphics_core::pixelcolor::{Gray2, GrayColor};
+use embedded_graphics_core::prelude::*;
+
+/// Full size buffer for use with the 3in7 EPD
+///
+/// Holds a frame in the [`Mode`] of the display: 1 bit per pixel for [`Mode::BlackWhite`],
+/// where the light levels of [`Gray2`] are white and the dark ones black, and 2 bits per pixel
+/// with the luma of [`Gray2`] for [`Mode::Gray4`].
+pub struct Display3in7 {
+    buffer: [u8; Mode::Gray4.frame_len()],
+    mode: Mode,
+    rotation: DisplayRotation,
+    mirroring: Mirroring,
+}
+
+impl Default for Display3in7 {
+    fn default() -> Self {
+        Display3in7 {
+            buffer: [0xFF; Mode::Gray4.frame_len()],
+            mode: Mode::default(),
+            rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
+        }
+    }
+}
+
+impl Display3in7 {
+    /// An empty white buffer for frames in `mode`
+    pub fn new(mode: Mode) -> Self {
+        Display3in7 {
+            mode,
+            ..Display3in7::default()
+        }
+    }
+
+    /// Switches the pixel format and clears the buffer to white
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+        self.clear_buffer(Gray2::WHITE);
+    }
+
+    /// Returns the pixel format of the buffer
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the frame in the format of the mode
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer[..self.mode.frame_len()]
+    }
+
+    /// Returns the frame in the format of the mode as mutable
+    pub fn get_mut_buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer[..self.mode.frame_len()]
+    }
+
+    /// Clears the buffer with the chosen color
+    pub fn clear_buffer(&mut self, color: Gray2) {
+        let byte = match self.mode {
+            Mode::BlackWhite if color.luma() >= 2 => 0xFF,
+            Mode::BlackWhite => 0x00,
+            Mode::Gray4 => color.luma() * 0x55,
+        };
+        for elem in self.get_mut_buffer().iter_mut() {
+            *elem = byte;
+        }
+    }
+
+    /// Sets the rotation of the display
+    pub fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    /// Get the current rotation of the display
+    pub fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    /// Sets the mirroring of the display
+    pub fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    /// Get the current mirroring of the display
+    pub fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
+
+impl DrawTarget for Display3in7 {
+    type Color = Gray2;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let orientation = Orientation {
+            rotation: self.rotation,
+            mirroring: self.mirroring,
+        };
+        let (bits, mask) = match self.mode {
+            Mode::BlackWhite => (1, 0x01),
+            Mode::Gray4 => (2, 0x03),
+        };
+        for Pixel(point, color) in pixels {
+            let value = match self.mode {
+                Mode::BlackWhite => color.luma() >> 1,
+                Mode::Gray4 => color.luma(),
+            };
+            if let Some((index, shift)) = packed_position(point, WIDTH, HEIGHT, orientation, bits) {
+                if let Some(byte) = self.get_mut_buffer().get_mut(index) {
+                    *byte = (*byte & !(mask << shift)) | (value << shift);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Display3in7 {
+    fn size(&self) -> Size {
+        match self.rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => Size::new(WIDTH, HEIGHT),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => Size::new(HEIGHT, WIDTH),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test buffer length
+    #[test]
+    fn graphics_size() {
+        let mut display = Display3in7::default();
+        assert_eq!(display.buffer().len(), 16800);
+        display.set_mode(Mode::Gray4);
+        assert_eq!(display.buffer().len(), 33600);
+    }
+
+    #[test]
+    fn graphics_gray_levels() {
+        let mut display = Display3in7::new(Mode::Gray4);
+        for (x, luma) in (0..4).enumerate() {
+            Pixel(Point::new(x as i32, 0), Gray2::new(luma))
+                .draw(&mut display)
+                .unwrap();
+        }
+        assert_eq!(display.buffer()[0], 0b00_01_10_11);
+        assert_eq!(display.buffer()[1], 0xFF);
+
+        // the dark levels are black in black/white
+        display.set_mode(Mode::BlackWhite);
+        for (x, luma) in (0..4).enumerate() {
+            Pixel(Point::new(x as i32, 0), Gray2::new(luma))
+                .draw(&mut display)
+                .unwrap();
+        }
+        assert_eq!(display.buffer()[0], 0b0011_1111);
+    }
+}
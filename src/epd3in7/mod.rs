@@ -0,0 +1,671 @@
+//! A Driver for the Waveshare 3.7" E-Ink Display via SPI
+//!
+//! The 280x480 panel shows black and white with 1 bit per pixel or 4 gray levels with 2 bits per
+//! pixel, selected by the [`Mode`] of the driver or through [`GrayscaleDisplay`]. The frames of
+//! both modes are drawn with a [`Display3in7`] in the same mode. In the grayscale mode the driver
+//! splits the frame into the two RAMs of the controller while sending it, the low bits of the
+//! levels go to the black/white RAM and the high bits to the red RAM.
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/3.7inch_e-Paper_HAT)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_3in7.c)
+//!
+//! # Example for the 3.7" E-Ink Display
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{
+//!    pixelcolor::Gray2, prelude::*, primitives::{Line, PrimitiveStyle},
+//!};
+//!use epd_waveshare::{epd3in7::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// Setup EPD
+//!let mut epd = Epd3in7::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!epd.set_mode(&mut spi, Mode::Gray4, &mut delay)?;
+//!
+//!// Use display graphics from embedded-graphics
+//!let mut display = Display3in7::new(Mode::Gray4);
+//!
+//!// Use embedded graphics for drawing a line in light gray
+//!let _ = Line::new(Point::new(0, 120), Point::new(0, 295))
+//!    .into_styled(PrimitiveStyle::with_stroke(Gray2::new(2), 1))
+//!    .draw(&mut display);
+//!
+//!// Display updated frame
+//!epd.update_and_display_frame(&mut spi, &display.buffer(), &mut delay)?;
+//!
+//!// Set the EPD to sleep
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::buffer_len;
+use crate::color::Color;
+use crate::energy::RefreshEnergy;
+use crate::interface::{DisplayInterface, InterfaceAccess, InterfaceState};
+use crate::settings::InitStep;
+use crate::traits::DisplayError;
+use crate::traits::*;
+
+pub(crate) mod command;
+use self::command::Command;
+
+mod constants;
+pub use self::constants::INIT_SEQUENCE;
+use self::constants::{LUT_1GRAY, LUT_4GRAY};
+
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(feature = "graphics")]
+pub use self::graphics::Display3in7;
+
+/// Width of epd3in7 in pixels
+pub const WIDTH: u32 = 280;
+/// Height of epd3in7 in pixels
+pub const HEIGHT: u32 = 480;
+/// Default Background Color (white)
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+const IS_BUSY_LOW: bool = false;
+
+/// Bytes of one line of a frame in [`Mode::Gray4`]
+const GRAY_LINE_LEN: usize = (WIDTH as usize * 2).div_ceil(8);
+/// Bytes of each of the two RAM planes a frame in [`Mode::Gray4`] is split into
+const PLANE_LEN: usize = buffer_len(WIDTH as usize, HEIGHT as usize);
+
+/// Pixel format of the frames, see [`Epd3in7::set_mode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// 1 bit per pixel, set for white
+    #[default]
+    BlackWhite,
+    /// 2 bits per pixel from `0b00` for black to `0b11` for white
+    Gray4,
+}
+
+impl Mode {
+    /// Length of a frame in this mode in bytes
+    pub const fn frame_len(self) -> usize {
+        match self {
+            Mode::BlackWhite => buffer_len(WIDTH as usize, HEIGHT as usize),
+            Mode::Gray4 => GRAY_LINE_LEN * HEIGHT as usize,
+        }
+    }
+}
+
+/// Epd3in7 driver
+pub struct Epd3in7<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// SPI
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    /// Color
+    background_color: Color,
+    /// Pixel format of the frames
+    mode: Mode,
+    /// Init sequence
+    init_sequence: &'static [InitStep],
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, 3)?;
+
+        self.interface
+            .run_init_sequence(spi, delay, self.init_sequence, IS_BUSY_LOW)?;
+
+        self.send_mode(spi)?;
+        self.use_full_frame(spi)?;
+
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type DisplayColor = Color;
+    const CAPABILITIES: Capabilities = Capabilities {
+        partial_refresh: false,
+        grayscale_levels: 4,
+        chromatic_colors: 0,
+        readback: false,
+        fast_mode: false,
+        ultrafast: false,
+        clear_refreshes: false,
+    };
+    const ENERGY: RefreshEnergy = RefreshEnergy::uniform(150_000);
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst);
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.emit(LifecycleEvent::Sleep);
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x03])?;
+        Ok(())
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    /// Sends a frame in the format of the [`Mode`] of the driver
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface
+            .check_window(buffer.len() == self.mode.frame_len())?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.use_full_frame(spi)?;
+        match self.mode {
+            Mode::BlackWhite => self
+                .interface
+                .cmd_with_frame_data(spi, Command::WriteRam, buffer),
+            Mode::Gray4 => {
+                self.interface.cmd_with_gray_plane(
+                    spi,
+                    Command::WriteRam,
+                    buffer,
+                    PLANE_LEN,
+                    false,
+                )?;
+                self.use_full_frame(spi)?;
+                self.interface
+                    .cmd_with_gray_plane(spi, Command::WriteRam2, buffer, PLANE_LEN, true)
+            }
+        }
+    }
+
+    #[allow(unused)]
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // not supported by the driver yet, nothing is sent
+        Ok(())
+    }
+
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.start_display_frame(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // both bits of a gray level are set for white and cleared for black
+        let color = self.background_color.get_byte_value();
+        let len = buffer_len(WIDTH as usize, HEIGHT as usize) as u32;
+
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.clear_data(spi, color, len)?;
+        if self.interface.clear_aborted() {
+            return Ok(());
+        }
+
+        self.use_full_frame(spi)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface.clear_data(spi, color, len)
+    }
+
+    /// The waveform follows from the [`Mode`], see [`Epd3in7::set_mode`]
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        Ok(())
+    }
+
+    fn busy_state(&self) -> BusyState {
+        self.interface.busy_state(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        // Enable clock signal, Enable Analog, DISPLAY with DISPLAY Mode 2, Disable Analog, Disable OSC
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xCF])?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.interface.wait_for_refresh_start(IS_BUSY_LOW, delay)
+    }
+
+    fn poll_display_complete(
+        &mut self,
+        _spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.poll_refresh(IS_BUSY_LOW)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Snapshot for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    type Color = Color;
+
+    fn snapshot(&self) -> DriverSnapshot<Color> {
+        self.interface
+            .state()
+            .snapshot(self.background_color, RefreshLut::Full)
+    }
+
+    fn restore(&mut self, snapshot: DriverSnapshot<Color>) {
+        self.interface.state_mut().restore(&snapshot);
+        self.background_color = snapshot.background_color;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Writes a plane of 1 bit per pixel as it is, the black/white plane is the RAM of the
+    /// low bits of the gray levels and the chromatic plane the one of the high bits
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RawPlaneError::check_len(data, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        let command = match plane {
+            Plane::Bw => Command::WriteRam,
+            Plane::Chromatic if self.mode == Mode::Gray4 => Command::WriteRam2,
+            Plane::Chromatic => return Err(RawPlaneError::UnsupportedPlane(plane)),
+        };
+        self.ensure_initialized(spi, delay)
+            .map_err(RawPlaneError::Interface)?;
+        self.interface
+            .wait_until_idle_ticked(IS_BUSY_LOW, delay)
+            .map_err(RawPlaneError::Interface)?;
+        self.use_full_frame(spi).map_err(RawPlaneError::Interface)?;
+        self.interface
+            .cmd_with_frame_data(spi, command, data)
+            .map_err(RawPlaneError::Interface)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> LazyInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+
+        Epd3in7 {
+            interface,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            mode: Mode::default(),
+            init_sequence: &INIT_SEQUENCE,
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.interface.initialized()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InitSequence for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn init_sequence(&self) -> &'static [InitStep] {
+        self.init_sequence
+    }
+
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]) {
+        self.init_sequence = sequence;
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> InterfaceAccess for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY> {
+    fn interface_state(&self) -> &InterfaceState {
+        self.interface.state()
+    }
+
+    fn interface_state_mut(&mut self) -> &mut InterfaceState {
+        self.interface.state_mut()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ConnectionCheck<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn ping(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // enable the clock, load the temperature and disable the clock again, the LUT and the
+        // RAM stay as they are
+        self.interface
+            .ping(spi, IS_BUSY_LOW, delay, |interface, spi| {
+                interface.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xA1])?;
+                interface.cmd(spi, Command::MasterActivation)
+            })
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> GrayscaleDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Selects [`Mode::Gray4`], see [`set_mode`](Epd3in7::set_mode)
+    fn enter_gray_mode(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.set_mode(spi, Mode::Gray4, delay)
+    }
+
+    /// Selects [`Mode::BlackWhite`], see [`set_mode`](Epd3in7::set_mode)
+    fn leave_gray_mode(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.ensure_initialized(spi, delay)?;
+        self.set_mode(spi, Mode::BlackWhite, delay)
+    }
+
+    fn is_gray_mode(&self) -> bool {
+        self.mode == Mode::Gray4
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Selects the pixel format of the frames and the waveform for it
+    ///
+    /// The frames sent afterwards need to be in the format of `mode`, e.g. drawn with a
+    /// [`Display3in7`] in the same mode. The image on the display stays until the next refresh.
+    pub fn set_mode(
+        &mut self,
+        spi: &mut SPI,
+        mode: Mode,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.mode = mode;
+        self.interface.wait_until_idle_ticked(IS_BUSY_LOW, delay)?;
+        self.send_mode(spi)
+    }
+
+    /// Returns the pixel format of the frames
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Sends the display options and the waveform of the mode
+    fn send_mode(&mut self, spi: &mut SPI) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let (options, lut) = match self.mode {
+            Mode::BlackWhite => (
+                [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x4F, 0xFF, 0xFF, 0xFF, 0xFF],
+                &LUT_1GRAY,
+            ),
+            Mode::Gray4 => ([0x00; 10], &LUT_4GRAY),
+        };
+        self.interface
+            .cmd_with_data(spi, Command::WriteDisplayOption, &options)?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteLutRegister, lut)
+    }
+
+    fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // x is positioned in pixels here, with 2 bytes each for the start and the end
+        let (end_x, end_y) = (WIDTH - 1, HEIGHT - 1);
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[0x00, 0x00, end_x as u8, (end_x >> 8) as u8],
+        )?;
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[0x00, 0x00, end_y as u8, (end_y >> 8) as u8],
+        )?;
+
+        // start from the beginning
+        self.interface
+            .cmd_with_data(spi, Command::SetRamXAddressCounter, &[0x00, 0x00])?;
+        self.interface
+            .cmd_with_data(spi, Command::SetRamYAddressCounter, &[0x00, 0x00])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 280);
+        assert_eq!(HEIGHT, 480);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+        assert_eq!(Mode::BlackWhite.frame_len(), 16800);
+        assert_eq!(Mode::Gray4.frame_len(), 33600);
+    }
+
+    #[test]
+    fn gray_frames_are_sent_as_two_planes() {
+        extern crate std;
+        use crate::interface::mock::{NoPin, RecordingSpi};
+        use embedded_hal_mock::delay::MockNoop;
+        use std::vec;
+
+        let mut spi = RecordingSpi::default();
+        let mut delay = MockNoop::new();
+        let mut epd: Epd3in7<_, _, _, _, _, MockNoop> =
+            Epd3in7::new_uninitialized(NoPin, NoPin, NoPin, NoPin);
+        epd.set_mode(&mut spi, Mode::Gray4, &mut delay).unwrap();
+
+        // black, dark gray, light gray and white in every byte
+        let frame = vec![0b00_01_10_11; Mode::Gray4.frame_len()];
+        spi.0.clear();
+        epd.update_frame(&mut spi, &frame, &mut delay).unwrap();
+        // the low bits with the first RAM command, the high bits with the second one
+        let plane_len = frame.len() / 2;
+        assert!(spi.sent(0x24, &vec![0x55; plane_len]));
+        assert!(spi.sent(0x26, &vec![0x33; plane_len]));
+    }
+
+    #[test]
+    fn init_and_frame_commands() {
+        extern crate std;
+        use crate::interface::mock::{CommandSpi, NoPin};
+        use embedded_hal_mock::delay::MockNoop;
+        use std::vec;
+
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let mut delay = MockNoop::new();
+        let mut epd: Epd3in7<_, _, _, _, _, MockNoop> =
+            Epd3in7::new(&mut spi, NoPin, NoPin, dc, NoPin, &mut delay).unwrap();
+        assert_eq!(
+            spi.commands(),
+            [
+                0x12, 0x46, 0x47, 0x01, 0x03, 0x04, 0x11, 0x3C, 0x0C, 0x18, 0x2C, 0x37, 0x32, 0x44,
+                0x45, 0x4E, 0x4F
+            ]
+        );
+        assert!(spi.sent(0x32, &LUT_1GRAY));
+        assert!(spi.sent(0x44, &[0x00, 0x00, 0x17, 0x01]));
+        assert!(spi.sent(0x45, &[0x00, 0x00, 0xDF, 0x01]));
+
+        // there are no partial windows
+        spi.clear();
+        epd.update_partial_frame(&mut spi, &[0xAA, 0xBB, 0xCC, 0xDD], 8, 16, 16, 2)
+            .unwrap();
+        assert!(spi.commands().is_empty());
+
+        let frame = vec![0xAA; Mode::BlackWhite.frame_len()];
+        epd.update_frame(&mut spi, &frame, &mut delay).unwrap();
+        assert_eq!(spi.commands(), [0x44, 0x45, 0x4E, 0x4F, 0x24]);
+        assert!(spi.sent(0x24, &frame));
+    }
+}
@@ -0,0 +1,13 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Panel, power and booster setup, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 5] = [
+    InitStep::new(Command::PanelSetting as u8, &[0x2F, 0x00]),
+    InitStep::new(Command::PowerSetting as u8, &[0x37, 0x00, 0x05, 0x05]),
+    InitStep::new(Command::PowerOffSequenceSetting as u8, &[0x00]),
+    InitStep::new(Command::BoosterSoftStart as u8, &[0xC7, 0xC7, 0x1D]),
+    InitStep::new(Command::TemperatureCalibration as u8, &[0x00]),
+];
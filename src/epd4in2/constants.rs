@@ -1,6 +1,19 @@
 //! This file contains look-up-tables used to set voltages used during
 //! various categories of pixel refreshes.
 
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Power settings, booster soft start, power on and panel settings
+///
+/// The frame rate, resolution, VCOM settings and LUTs are sent after this sequence.
+pub const INIT_SEQUENCE: [InitStep; 4] = [
+    InitStep::new(Command::PowerSetting as u8, &[0x03, 0x00, 0x2b, 0x2b, 0xff]),
+    InitStep::new(Command::BoosterSoftStart as u8, &[0x17, 0x17, 0x17]),
+    InitStep::new(Command::PowerOn as u8, &[]).delay(5).wait(),
+    InitStep::new(Command::PanelSetting as u8, &[0x3F]),
+];
+
 #[rustfmt::skip]
 pub(crate) const LUT_VCOM0: [u8; 44] = [
 // The commented-out line below was used in a Ben Krasnow video explaining
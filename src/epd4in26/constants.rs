@@ -0,0 +1,21 @@
+//! Init sequence of the controller
+
+use super::HEIGHT;
+use crate::settings::InitStep;
+use crate::type_a::command::Command;
+
+/// Software reset, booster and gate setup of the Waveshare C driver, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 4] = [
+    InitStep::new(Command::SwReset as u8, &[]).wait(),
+    InitStep::new(
+        Command::BoosterSoftStartControl as u8,
+        &[0xAE, 0xC7, 0xC3, 0xC0, 0x80],
+    ),
+    // 480 gate lines, the scanning order of the Waveshare driver
+    InitStep::new(
+        Command::DriverOutputControl as u8,
+        &[(HEIGHT - 1) as u8, ((HEIGHT - 1) >> 8) as u8, 0x02],
+    ),
+    // x increment, y increment, address counter is updated in x direction
+    InitStep::new(Command::DataEntryModeSetting as u8, &[0x03]),
+];
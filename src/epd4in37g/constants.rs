@@ -0,0 +1,44 @@
+//! Init sequence of the 4.37" (G) display
+
+use crate::controllers::g_panel::GPanel;
+use crate::energy::RefreshEnergy;
+use crate::settings::InitStep;
+use crate::type_g::command::Command;
+
+use super::{HEIGHT, WIDTH};
+
+/// Unlock, power, booster, panel and resolution settings, ending with powering the panel on
+///
+/// Values taken from the Waveshare C driver.
+pub const INIT_SEQUENCE: [InitStep; 13] = [
+    InitStep::new(Command::PanelSetting as u8, &[0x0F, 0x29]),
+    InitStep::new(Command::BoosterSoftStart2 as u8, &[0x0F, 0x8B, 0x93, 0xA1]),
+    InitStep::new(Command::TemperatureSensorEnable as u8, &[0x00]),
+    InitStep::new(Command::VcomAndDataIntervalSetting as u8, &[0x37]),
+    InitStep::new(Command::TconSetting as u8, &[0x02, 0x02]),
+    InitStep::new(Command::TconResolution as u8, &[0x02, 0x00, 0x01, 0x70]),
+    InitStep::new(
+        Command::SourceVoltage as u8,
+        &[0x98, 0x98, 0x98, 0x75, 0xCA, 0xB2, 0x98, 0x7E],
+    ),
+    InitStep::new(Command::GateSourceStart as u8, &[0x00, 0x00, 0x00, 0x00]),
+    InitStep::new(Command::VendorSettingE7 as u8, &[0x1C]),
+    InitStep::new(Command::PowerSaving as u8, &[0x00]),
+    InitStep::new(Command::VendorSettingE9 as u8, &[0x01]),
+    InitStep::new(Command::PllControl as u8, &[0x08]),
+    InitStep::new(Command::PowerOn as u8, &[]).wait(),
+];
+
+/// The 4.37" (G) panel
+pub struct Panel4in37g;
+
+impl GPanel for Panel4in37g {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
+    const ENERGY: RefreshEnergy = RefreshEnergy::uniform(300_000);
+    const INIT_SEQUENCE: &'static [InitStep] = &INIT_SEQUENCE;
+    const POWER_PER_REFRESH: bool = false;
+
+    type Row = [u8; WIDTH as usize / 4];
+    const ROW: Self::Row = [0; WIDTH as usize / 4];
+}
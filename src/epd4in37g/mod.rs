@@ -0,0 +1,132 @@
+//! A simple Driver for the Waveshare 4.37 inch (G) 4-color E-Ink Display via SPI
+//!
+//! The display shows the four colors of [`QuadColor`]. A frame packs four pixels into every
+//! byte, the left one in the upper bits, so it is `WIDTH / 4 * HEIGHT` bytes long, and
+//! [`Display4in37g`] draws into a full frame with embedded-graphics.
+//!
+//! The panel is powered on by the init sequence and stays on until [`sleep`](crate::traits::WaveshareDisplay::sleep).
+//! A refresh takes about 25 seconds, [`busy_state`](crate::traits::WaveshareDisplay::busy_state) reports
+//! [`BusyState::Refreshing`](crate::traits::BusyState::Refreshing) in the meantime.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/4.37inch_e-Paper_Module_(G)_Manual)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_4in37g.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd4in37g.py)
+//!
+//! # Example for the 4.37 in 4-color E-Ink Display
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use embedded_graphics::{prelude::*, primitives::{Circle, PrimitiveStyle}};
+//!use epd_waveshare::{color::QuadColor, epd4in37g::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!let mut epd = Epd4in37g::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// draw a yellow circle onto a white frame
+//!let mut display = Display4in37g::default();
+//!let _ = Circle::new(Point::new(156, 84), 200)
+//!    .into_styled(PrimitiveStyle::with_fill(QuadColor::Yellow))
+//!    .draw(&mut display);
+//!epd.update_and_display_frame(&mut spi, display.buffer(), &mut delay)?;
+//!
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use crate::color::QuadColor;
+use crate::controllers::g_panel::GPanelEpd;
+
+mod constants;
+pub use self::constants::INIT_SEQUENCE;
+
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(feature = "graphics")]
+pub use self::graphics::Display4in37g;
+
+/// Width of the display
+pub const WIDTH: u32 = 512;
+/// Height of the display
+pub const HEIGHT: u32 = 368;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: QuadColor = QuadColor::White;
+
+/// Epd4in37g driver
+///
+/// The shared driver of the (G) panels, for the 4.37" panel.
+pub type Epd4in37g<SPI, CS, BUSY, DC, RST, DELAY> =
+    GPanelEpd<constants::Panel4in37g, SPI, CS, BUSY, DC, RST, DELAY>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_g::command::Command;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 512);
+        assert_eq!(HEIGHT, 368);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, QuadColor::White);
+    }
+
+    #[test]
+    fn init_sequence_sends_resolution() {
+        let step = INIT_SEQUENCE
+            .iter()
+            .find(|step| step.command == Command::TconResolution as u8);
+        let data = step.map(|step| step.data);
+        assert_eq!(
+            data,
+            Some(
+                &[
+                    (WIDTH >> 8) as u8,
+                    WIDTH as u8,
+                    (HEIGHT >> 8) as u8,
+                    HEIGHT as u8
+                ][..]
+            )
+        );
+    }
+
+    #[test]
+    fn init_and_frame_commands() {
+        extern crate std;
+        use crate::interface::mock::{CommandSpi, HighPin, NoPin};
+        use crate::traits::WaveshareDisplay;
+        use embedded_hal_mock::delay::MockNoop;
+        use std::vec;
+
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let mut delay = MockNoop::new();
+        let mut epd: Epd4in37g<_, _, _, _, _, MockNoop> =
+            Epd4in37g::new(&mut spi, NoPin, HighPin, dc, NoPin, &mut delay).unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x00, 0x06, 0x41, 0x50, 0x60, 0x61, 0x62, 0x65, 0xE7, 0xE3, 0xE9, 0x30, 0x04]
+        );
+
+        // there are no partial windows
+        spi.clear();
+        epd.update_partial_frame(&mut spi, &[0xAA, 0xBB, 0xCC, 0xDD], 8, 16, 16, 2)
+            .unwrap();
+        assert!(spi.commands().is_empty());
+
+        let frame = vec![0x1B; (WIDTH * HEIGHT / 4) as usize];
+        epd.update_frame(&mut spi, &frame, &mut delay).unwrap();
+        assert_eq!(spi.commands(), [0x10]);
+        assert!(spi.sent(0x10, &frame));
+    }
+}
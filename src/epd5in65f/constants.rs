@@ -0,0 +1,12 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Panel, power and booster setup, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 4] = [
+    InitStep::new(Command::PanelSetting as u8, &[0xEF, 0x08]),
+    InitStep::new(Command::PowerSetting as u8, &[0x37, 0x00, 0x23, 0x23]),
+    InitStep::new(Command::PowerOffSequenceSetting as u8, &[0x00]),
+    InitStep::new(Command::BoosterSoftStart as u8, &[0xC7, 0xC7, 0x1D]),
+];
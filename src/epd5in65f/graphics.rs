@@ -1,6 +1,6 @@
 use crate::color::OctColor;
 use crate::epd5in65f::{DEFAULT_BACKGROUND_COLOR, HEIGHT, WIDTH};
-use crate::graphics::{DisplayRotation, OctDisplay};
+use crate::graphics::{DisplayRotation, Mirroring, OctDisplay};
 use embedded_graphics_core::prelude::*;
 
 /// Full size buffer for use with the 5in65f EPD
@@ -10,6 +10,7 @@ use embedded_graphics_core::prelude::*;
 pub struct Display5in65f {
     buffer: [u8; WIDTH as usize * HEIGHT as usize / 2],
     rotation: DisplayRotation,
+    mirroring: Mirroring,
 }
 
 impl Default for Display5in65f {
@@ -18,6 +19,7 @@ impl Default for Display5in65f {
             buffer: [OctColor::colors_byte(DEFAULT_BACKGROUND_COLOR, DEFAULT_BACKGROUND_COLOR);
                 WIDTH as usize * HEIGHT as usize / 2],
             rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
         }
     }
 }
@@ -59,6 +61,14 @@ impl OctDisplay for Display5in65f {
     fn rotation(&self) -> DisplayRotation {
         self.rotation
     }
+
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
 }
 
 #[cfg(test)]
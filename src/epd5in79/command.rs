@@ -0,0 +1,61 @@
+//! SPI Commands for the Waveshare 5.79" E-Ink Display
+
+use crate::traits;
+
+/// Epd5in79 commands
+///
+/// Should rarely (never?) be needed directly.
+///
+/// The panel is driven by two controllers, the commands with the high bit set address the
+/// second one, which drives the right half of the panel.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Gate lines and the scanning direction
+    DriverOutputControl = 0x01,
+    /// Deep sleep, only a reset wakes the display up again
+    DeepSleepMode = 0x10,
+    /// Address counter direction of the first controller
+    DataEntryModeSetting = 0x11,
+    /// Resets the commands and parameters of both controllers to their defaults
+    SwReset = 0x12,
+    /// Selects the internal or an external temperature sensor
+    TemperatureSensorSelection = 0x18,
+    /// Starts the sequence selected with `DisplayUpdateControl2`
+    MasterActivation = 0x20,
+    /// RAM content options for the display update
+    DisplayUpdateControl1 = 0x21,
+    /// Sequence of the display update, e.g. loading the LUT and refreshing
+    DisplayUpdateControl2 = 0x22,
+    /// Black/white RAM of the first controller
+    WriteRam = 0x24,
+    /// Border waveform
+    BorderWaveformControl = 0x3C,
+    /// RAM x window of the first controller, in bytes
+    SetRamXAddressStartEndPosition = 0x44,
+    /// RAM y window of the first controller
+    SetRamYAddressStartEndPosition = 0x45,
+    /// RAM x counter of the first controller
+    SetRamXAddressCounter = 0x4E,
+    /// RAM y counter of the first controller
+    SetRamYAddressCounter = 0x4F,
+    /// Address counter direction of the second controller
+    SecondaryDataEntryModeSetting = 0x91,
+    /// Black/white RAM of the second controller
+    SecondaryWriteRam = 0xA4,
+    /// RAM x window of the second controller, in bytes
+    SecondarySetRamXAddressStartEndPosition = 0xC4,
+    /// RAM y window of the second controller
+    SecondarySetRamYAddressStartEndPosition = 0xC5,
+    /// RAM x counter of the second controller
+    SecondarySetRamXAddressCounter = 0xCE,
+    /// RAM y counter of the second controller
+    SecondarySetRamYAddressCounter = 0xCF,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
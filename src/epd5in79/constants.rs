@@ -0,0 +1,14 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Software reset and data entry modes of both controllers, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 3] = [
+    InitStep::new(Command::SwReset as u8, &[]).wait(),
+    // Values taken from the Waveshare C driver, the gates are scanned from the bottom
+    // x increment, y decrement in the first controller
+    InitStep::new(Command::DataEntryModeSetting as u8, &[0x01]),
+    // the second controller is mirrored, so x decrements as well
+    InitStep::new(Command::SecondaryDataEntryModeSetting as u8, &[0x00]),
+];
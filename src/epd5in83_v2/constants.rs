@@ -0,0 +1,16 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Power and panel setup of the UC8179, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 5] = [
+    // VGH=20V, VGL=-20V, VDH=15V, VDL=-15V
+    InitStep::new(Command::PowerSetting as u8, &[0x07, 0x07, 0x3F, 0x3F]),
+    InitStep::new(Command::PowerOn as u8, &[]).delay(100).wait(),
+    // KW mode, the resolution of the TCON resolution command
+    InitStep::new(Command::PanelSetting as u8, &[0x1F]),
+    // 648x480
+    InitStep::new(Command::TconResolution as u8, &[0x02, 0x88, 0x01, 0xE0]),
+    InitStep::new(Command::DualSpi as u8, &[0x00]),
+];
@@ -0,0 +1,16 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Booster soft start, power settings, power on and panel settings
+///
+/// The power settings are VGH=20V, VGL=-20V, VDH=15V and VDL=-15V, the panel settings BWROTP.
+/// The resolution, dual SPI, VCOM and data interval and TCON settings are sent after this
+/// sequence.
+pub const INIT_SEQUENCE: [InitStep; 4] = [
+    InitStep::new(Command::BoosterSoftStart as u8, &[0x17, 0x17, 0x1e, 0x17]),
+    InitStep::new(Command::PowerSetting as u8, &[0x07, 0x07, 0x3F, 0x3F]),
+    InitStep::new(Command::PowerOn as u8, &[]).delay(5).wait(),
+    InitStep::new(Command::PanelSetting as u8, &[0x0F]),
+];
@@ -1,6 +1,6 @@
 use crate::color::TriColor;
 use crate::epd5in83b_v2::{DEFAULT_BACKGROUND_COLOR, HEIGHT, NUM_DISPLAY_BITS, WIDTH};
-use crate::graphics::{DisplayColorRendering, DisplayRotation};
+use crate::graphics::{DisplayColorRendering, DisplayRotation, Mirroring};
 use crate::prelude::TriDisplay;
 use embedded_graphics_core::prelude::*;
 
@@ -11,6 +11,7 @@ use embedded_graphics_core::prelude::*;
 pub struct Display5in83 {
     buffer: [u8; 2 * NUM_DISPLAY_BITS as usize],
     rotation: DisplayRotation,
+    mirroring: Mirroring,
 }
 
 impl Default for Display5in83 {
@@ -18,6 +19,7 @@ impl Default for Display5in83 {
         let mut display = Display5in83 {
             buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value(); 2 * NUM_DISPLAY_BITS as usize],
             rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
         };
         // We need to invert chromatic part to black so it will be render white
         let offset = display.chromatic_offset();
@@ -64,6 +66,14 @@ impl TriDisplay for Display5in83 {
         self.rotation
     }
 
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
     fn chromatic_offset(&self) -> usize {
         NUM_DISPLAY_BITS as usize
     }
@@ -0,0 +1,67 @@
+//! SPI Commands for the Waveshare 7.3" (F) E-Ink Display
+
+use crate::traits;
+
+/// Epd7in3f commands
+///
+/// Should rarely (never?) be needed directly. Most of the registers are only described by the
+/// values of the Waveshare C driver.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Resolution, scan directions and the booster switch
+    PanelSetting = 0x00,
+    /// Internal or external power and the driving voltages
+    PowerSetting = 0x01,
+    /// Switches off the charge pump and the drivers, the busy pin is low until they are off
+    PowerOff = 0x02,
+    /// Power off sequence
+    PowerOffSequenceSetting = 0x03,
+    /// Switches on the charge pump and the drivers, the busy pin is low until they are ready
+    PowerOn = 0x04,
+    /// Soft start of booster 1
+    BoosterSoftStart1 = 0x05,
+    /// Soft start of booster 2
+    BoosterSoftStart2 = 0x06,
+    /// Deep sleep after 0xA5, only a reset wakes the display up again
+    DeepSleep = 0x07,
+    /// Soft start of booster 3
+    BoosterSoftStart3 = 0x08,
+    /// The frame, two pixels per byte
+    DataStartTransmission = 0x10,
+    /// Refreshes the display, the busy pin is low for more than 30 seconds
+    DisplayRefresh = 0x12,
+    /// Internal power control
+    InternalPowerControl = 0x13,
+    /// Frame rate
+    PllControl = 0x30,
+    /// Selects the internal temperature sensor
+    TemperatureSensorEnable = 0x41,
+    /// Border color and the interval between VCOM and the data
+    VcomAndDataIntervalSetting = 0x50,
+    /// Non-overlap period of the gate and the source
+    TconSetting = 0x60,
+    /// Number of sources and gates
+    TconResolution = 0x61,
+    /// VCOM DC voltage
+    VcmDcSetting = 0x82,
+    /// Not described, sent with 0x00 by the Waveshare driver
+    VendorSetting1 = 0x84,
+    /// Not described, sent with 0x00 by the Waveshare driver
+    VendorSetting2 = 0x86,
+    /// Unlocks the command set with a magic sequence
+    Unlock = 0xAA,
+    /// Clock cascading and the temperature setting
+    CascadeSetting = 0xE0,
+    /// Power saving of the source and the gate
+    PowerSaving = 0xE3,
+    /// Temperature of the waveform
+    TemperatureSetting = 0xE6,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
@@ -0,0 +1,24 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Panel, power and booster setup of the Waveshare C driver, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 10] = [
+    InitStep::new(Command::Unlock as u8, &[0x49, 0x55, 0x20, 0x08, 0x09, 0x18]),
+    InitStep::new(
+        Command::PowerSetting as u8,
+        &[0x3F, 0x00, 0x32, 0x2A, 0x0E, 0x2A],
+    ),
+    InitStep::new(Command::PanelSetting as u8, &[0x5F, 0x69]),
+    InitStep::new(
+        Command::PowerOffSequenceSetting as u8,
+        &[0x00, 0x54, 0x00, 0x44],
+    ),
+    InitStep::new(Command::BoosterSoftStart1 as u8, &[0x40, 0x1F, 0x1F, 0x2C]),
+    InitStep::new(Command::BoosterSoftStart2 as u8, &[0x6F, 0x1F, 0x1F, 0x22]),
+    InitStep::new(Command::BoosterSoftStart3 as u8, &[0x6F, 0x1F, 0x1F, 0x22]),
+    InitStep::new(Command::InternalPowerControl as u8, &[0x00, 0x04]),
+    InitStep::new(Command::PllControl as u8, &[0x3C]),
+    InitStep::new(Command::TemperatureSensorEnable as u8, &[0x00]),
+];
@@ -0,0 +1,48 @@
+//! Init sequence of the 7.3" (G) display
+
+use crate::controllers::g_panel::GPanel;
+use crate::energy::RefreshEnergy;
+use crate::settings::InitStep;
+use crate::type_g::command::Command;
+
+use super::{HEIGHT, WIDTH};
+
+/// Unlock, power, booster, panel and resolution settings, ending with powering the panel on
+///
+/// Values taken from the Waveshare C driver.
+pub const INIT_SEQUENCE: [InitStep; 14] = [
+    InitStep::new(
+        Command::UnlockExtended as u8,
+        &[0x49, 0x55, 0x20, 0x08, 0x09, 0x18],
+    ),
+    InitStep::new(Command::PowerSetting as u8, &[0x3F]),
+    InitStep::new(Command::PanelSetting as u8, &[0x4F, 0x69]),
+    InitStep::new(Command::BoosterSoftStart1 as u8, &[0x40, 0x1F, 0x1F, 0x2C]),
+    InitStep::new(Command::BoosterSoftStart3 as u8, &[0x6F, 0x1F, 0x1F, 0x22]),
+    InitStep::new(Command::BoosterSoftStart2 as u8, &[0x6F, 0x1F, 0x17, 0x17]),
+    InitStep::new(
+        Command::PowerOffSequenceSetting as u8,
+        &[0x00, 0x54, 0x00, 0x44],
+    ),
+    InitStep::new(Command::TconSetting as u8, &[0x02, 0x00]),
+    InitStep::new(Command::PllControl as u8, &[0x08]),
+    InitStep::new(Command::VcomAndDataIntervalSetting as u8, &[0x3F]),
+    InitStep::new(Command::TconResolution as u8, &[0x03, 0x20, 0x01, 0xE0]),
+    InitStep::new(Command::PowerSaving as u8, &[0x2F]),
+    InitStep::new(Command::VendorSetting as u8, &[0x01]),
+    InitStep::new(Command::PowerOn as u8, &[]).wait(),
+];
+
+/// The 7.3" (G) panel
+pub struct Panel7in3g;
+
+impl GPanel for Panel7in3g {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
+    const ENERGY: RefreshEnergy = RefreshEnergy::uniform(700_000);
+    const INIT_SEQUENCE: &'static [InitStep] = &INIT_SEQUENCE;
+    const POWER_PER_REFRESH: bool = false;
+
+    type Row = [u8; WIDTH as usize / 4];
+    const ROW: Self::Row = [0; WIDTH as usize / 4];
+}
@@ -0,0 +1,18 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Power, panel and booster setup, run after the reset
+pub const INIT_SEQUENCE: [InitStep; 4] = [
+    // Set the power settings
+    InitStep::new(Command::PowerSetting as u8, &[0x37, 0x00]),
+    // Set the panel settings:
+    // - 600 x 448
+    // - Using LUT from external flash
+    InitStep::new(Command::PanelSetting as u8, &[0xCF, 0x08]),
+    // Start the booster
+    InitStep::new(Command::BoosterSoftStart as u8, &[0xC7, 0xCC, 0x28]),
+    // Power on
+    InitStep::new(Command::PowerOn as u8, &[]).delay(5).wait(),
+];
@@ -0,0 +1,22 @@
+//! Init sequence of the controller
+
+use super::command::Command;
+use crate::settings::InitStep;
+
+/// Software reset, RAM and panel setup and the first loading of the temperature and LUT
+pub const INIT_SEQUENCE: [InitStep; 14] = [
+    InitStep::new(Command::SwReset as u8, &[]).wait(),
+    InitStep::new(Command::AutoWriteRed as u8, &[0xF7]).wait(),
+    InitStep::new(Command::AutoWriteBw as u8, &[0xF7]).wait(),
+    InitStep::new(Command::SoftStart as u8, &[0xAE, 0xC7, 0xC3, 0xC0, 0x40]),
+    InitStep::new(Command::DriverOutputControl as u8, &[0xAF, 0x02, 0x01]),
+    InitStep::new(Command::DataEntry as u8, &[0x01]),
+    InitStep::new(Command::SetRamXStartEnd as u8, &[0x00, 0x00, 0x6F, 0x03]),
+    InitStep::new(Command::SetRamYStartEnd as u8, &[0xAF, 0x02, 0x00, 0x00]),
+    InitStep::new(Command::VbdControl as u8, &[0x05]),
+    InitStep::new(Command::TemperatureSensorControl as u8, &[0x80]),
+    InitStep::new(Command::DisplayUpdateControl2 as u8, &[0xB1]),
+    InitStep::new(Command::MasterActivation as u8, &[]).wait(),
+    InitStep::new(Command::SetRamXAc as u8, &[0x00, 0x00]),
+    InitStep::new(Command::SetRamYAc as u8, &[0x00, 0x00]),
+];
@@ -0,0 +1,621 @@
+//! A driver for panels which aren't listed, but use one of the known controllers
+//!
+//! Many panels of Good Display and other makers use the same controllers as the Waveshare
+//! displays and only differ in their size and colors. With the waveforms from the OTP of the
+//! panel, a [`PanelConfig`] for the size and a [`Controller`] is all [`GenericEpd`] needs to
+//! bring them up.
+//!
+//! Unlike the drivers of the listed displays, the size of a [`GenericEpd`] is only known at
+//! runtime, so it doesn't implement [`WaveshareDisplay`](crate::traits::WaveshareDisplay)
+//! and has inherent methods with the same names instead.
+//!
+//! # Example for a 2.9" panel with an SSD1680
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!use epd_waveshare::generic::*;
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!let config = PanelConfig {
+//!    color_mode: ColorMode::TriColor,
+//!    ..Ssd1680::panel(128, 296)
+//!};
+//!let mut epd = GenericEpd::<Ssd1680, _, _, _, _, _, _>::new(
+//!    &mut spi, cs_pin, busy_in, dc, rst, &mut delay, config,
+//!)?;
+//!
+//!let black = [0xFF; 128 / 8 * 296];
+//!let chromatic = [0x00; 128 / 8 * 296];
+//!epd.update_color_frame(&mut spi, &black, &chromatic)?;
+//!epd.display_frame(&mut spi, &mut delay)?;
+//!
+//!epd.sleep(&mut spi)?;
+//!# Ok(())
+//!# }
+//!```
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
+    digital::v2::*,
+};
+
+use crate::buffer_len;
+use crate::color::Color;
+use crate::controllers::{
+    ssd168x::{self, BorderWaveform, DataEntryMode},
+    uc8151::{self, FrameData},
+};
+use crate::interface::DisplayInterface;
+use crate::settings::VcomAndDataInterval;
+use crate::traits::{BusyState, DisplayError, TemperatureReading, TemperatureSetting};
+use crate::type_a::command::Command as SsdCommand;
+use crate::type_d::command::Command as UcCommand;
+
+/// The colors of a panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Black and white, with a single plane
+    BlackWhite,
+    /// Black, white and a chromatic color, with a black and a chromatic plane
+    TriColor,
+}
+
+/// What sets a panel apart from the other panels with the same controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanelConfig {
+    /// Width in pixels, the size of the source lines
+    pub width: u32,
+    /// Height in pixels, the size of the gate lines
+    pub height: u32,
+    /// Whether the busy pin is low while the panel is busy
+    pub busy_is_low: bool,
+    /// The colors of the panel
+    pub color_mode: ColorMode,
+    /// How long the reset pin is kept low in ms
+    pub reset_duration: u8,
+}
+
+impl PanelConfig {
+    /// Length of one plane of a frame in bytes, each line starts with a new byte
+    pub const fn frame_len(&self) -> u32 {
+        buffer_len(self.width as usize, self.height as usize) as u32
+    }
+}
+
+mod sealed {
+    /// The command sets [`GenericEpd`](super::GenericEpd) knows
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Family {
+        Ssd1680,
+        Uc8151,
+    }
+
+    pub trait Sealed {
+        const FAMILY: Family;
+    }
+}
+
+use self::sealed::Family;
+
+/// A display controller [`GenericEpd`] can drive
+///
+/// Only implemented by the controllers of this crate.
+pub trait Controller: sealed::Sealed {}
+
+/// The SSD1680 and the compatible SSD1675B and SSD1681, as on the 2.13" V4 and the 2.9" V2
+///
+/// Set bits of the chromatic plane are chromatic, like in buffers drawn with
+/// [`DisplayColorRendering::Negative`](crate::graphics::DisplayColorRendering::Negative).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ssd1680;
+
+impl Ssd1680 {
+    /// A black/white panel of `width` x `height` pixels with the defaults of the controller
+    pub const fn panel(width: u32, height: u32) -> PanelConfig {
+        PanelConfig {
+            width,
+            height,
+            busy_is_low: false,
+            color_mode: ColorMode::BlackWhite,
+            reset_duration: 2,
+        }
+    }
+}
+
+impl sealed::Sealed for Ssd1680 {
+    const FAMILY: Family = Family::Ssd1680;
+}
+
+impl Controller for Ssd1680 {}
+
+/// The UC8151 and the compatible IL0373, as on the 2.9" (B/C) and the flexible panels
+///
+/// Cleared bits of the chromatic plane are chromatic, like in buffers drawn with
+/// [`DisplayColorRendering::Positive`](crate::graphics::DisplayColorRendering::Positive).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Uc8151;
+
+impl Uc8151 {
+    /// A black/white panel of `width` x `height` pixels with the defaults of the controller
+    pub const fn panel(width: u32, height: u32) -> PanelConfig {
+        PanelConfig {
+            width,
+            height,
+            busy_is_low: true,
+            color_mode: ColorMode::BlackWhite,
+            reset_duration: 10,
+        }
+    }
+}
+
+impl sealed::Sealed for Uc8151 {
+    const FAMILY: Family = Family::Uc8151;
+}
+
+impl Controller for Uc8151 {}
+
+/// Driver for a panel described by a [`PanelConfig`] with the controller `C`
+pub struct GenericEpd<C, SPI, CS, BUSY, DC, RST, DELAY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    /// The panel
+    config: PanelConfig,
+    /// Background Color
+    color: Color,
+    _controller: PhantomData<C>,
+}
+
+impl<C, SPI, CS, BUSY, DC, RST, DELAY> GenericEpd<C, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    C: Controller,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Creates a new driver for the panel and initializes it
+    pub fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        config: PanelConfig,
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = GenericEpd {
+            interface: DisplayInterface::new(cs, busy, dc, rst),
+            config,
+            color: Color::White,
+            _controller: PhantomData,
+        };
+        epd.wake_up(spi, delay)?;
+        Ok(epd)
+    }
+
+    /// Resets and initializes the panel, also to wake it up from deep sleep
+    pub fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, self.config.reset_duration)?;
+        let tricolor = self.config.color_mode == ColorMode::TriColor;
+        match C::FAMILY {
+            Family::Ssd1680 => {
+                self.interface
+                    .wait_until_idle_ticked(self.config.busy_is_low, delay)?;
+                self.interface.cmd(spi, SsdCommand::SwReset)?;
+                self.interface
+                    .wait_until_idle_ticked(self.config.busy_is_low, delay)?;
+
+                let gates = self.config.height.saturating_sub(1);
+                self.interface.cmd_with_data(
+                    spi,
+                    SsdCommand::DriverOutputControl,
+                    &[gates as u8, (gates >> 8) as u8, 0x00],
+                )?;
+                ssd168x::set_data_entry_mode(
+                    &mut self.interface,
+                    spi,
+                    DataEntryMode::IncrementXIncrementY,
+                )?;
+                ssd168x::set_window(
+                    &mut self.interface,
+                    spi,
+                    0,
+                    0,
+                    0,
+                    self.config.width,
+                    self.config.height,
+                )?;
+                ssd168x::set_border_waveform(
+                    &mut self.interface,
+                    spi,
+                    BorderWaveform::FollowLut(1),
+                )?;
+                // the internal temperature sensor
+                self.interface.cmd_with_data(
+                    spi,
+                    SsdCommand::TemperatureSensorSelection,
+                    &[0x80],
+                )?;
+                // neither RAM is inverted
+                self.interface.cmd_with_data(
+                    spi,
+                    SsdCommand::DisplayUpdateControl1,
+                    &[0x00, 0x80],
+                )?;
+            }
+            Family::Uc8151 => {
+                uc8151::set_booster_soft_start(&mut self.interface, spi, [0x17, 0x17, 0x17])?;
+                self.interface.cmd(spi, UcCommand::PowerOn)?;
+                self.interface
+                    .wait_until_idle_ticked(self.config.busy_is_low, delay)?;
+
+                // LUTs from the OTP, black/white or black/white/chromatic
+                let panel = if tricolor { 0x0F } else { 0x1F };
+                self.interface
+                    .cmd_with_data(spi, UcCommand::PanelSetting, &[panel])?;
+                if self.config.width > 0xFF {
+                    uc8151::set_wide_resolution(
+                        &mut self.interface,
+                        spi,
+                        self.config.width,
+                        self.config.height,
+                    )?;
+                } else {
+                    uc8151::set_resolution(
+                        &mut self.interface,
+                        spi,
+                        self.config.width,
+                        self.config.height,
+                    )?;
+                }
+                let interval = if tricolor { 0x77 } else { 0x97 };
+                uc8151::set_vcom_and_data_interval(
+                    &mut self.interface,
+                    spi,
+                    VcomAndDataInterval::from_byte(interval),
+                )?;
+            }
+        }
+        self.interface
+            .wait_until_idle_ticked(self.config.busy_is_low, delay)?;
+        Ok(())
+    }
+
+    /// Lets the panel enter deep sleep, only [`wake_up`](GenericEpd::wake_up) wakes it up again
+    pub fn sleep(&mut self, spi: &mut SPI) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
+        match C::FAMILY {
+            Family::Ssd1680 => {
+                self.interface
+                    .cmd_with_data(spi, SsdCommand::DeepSleepMode, &[0x01])
+            }
+            Family::Uc8151 => {
+                self.interface.cmd(spi, UcCommand::PowerOff)?;
+                self.wait_until_idle()?;
+                self.interface
+                    .cmd_with_data(spi, UcCommand::DeepSleep, &[0xA5])
+            }
+        }
+    }
+
+    /// Writes a black/white frame of [`frame_len`](PanelConfig::frame_len) bytes
+    ///
+    /// On tri-color panels the chromatic plane is cleared.
+    pub fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle()?;
+        let len = self.config.frame_len();
+        match C::FAMILY {
+            Family::Ssd1680 => {
+                ssd168x::set_ram_counter(&mut self.interface, spi, 0, 0, 0)?;
+                self.interface
+                    .cmd_with_frame_data(spi, SsdCommand::WriteRam, buffer)?;
+                if self.config.color_mode == ColorMode::TriColor {
+                    self.interface.cmd(spi, SsdCommand::WriteRam2)?;
+                    self.interface.data_x_times(spi, 0x00, len)?;
+                }
+                Ok(())
+            }
+            Family::Uc8151 => {
+                let (old, new) = match self.config.color_mode {
+                    ColorMode::BlackWhite => (
+                        FrameData::Fill(self.color.get_byte_value(), len),
+                        FrameData::Buffer(buffer),
+                    ),
+                    ColorMode::TriColor => (FrameData::Buffer(buffer), FrameData::Fill(0xFF, len)),
+                };
+                uc8151::write_frames(&mut self.interface, spi, old, new)
+            }
+        }
+    }
+
+    /// Writes the black and the chromatic plane of a tri-color frame
+    ///
+    /// Which bits of the chromatic plane are chromatic depends on the controller, see
+    /// [`Ssd1680`] and [`Uc8151`]. Black/white panels only take the black plane.
+    pub fn update_color_frame(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+        chromatic: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if self.config.color_mode == ColorMode::BlackWhite {
+            return self.update_frame(spi, black);
+        }
+        self.wait_until_idle()?;
+        match C::FAMILY {
+            Family::Ssd1680 => {
+                ssd168x::set_ram_counter(&mut self.interface, spi, 0, 0, 0)?;
+                self.interface
+                    .cmd_with_frame_data(spi, SsdCommand::WriteRam, black)?;
+                ssd168x::set_ram_counter(&mut self.interface, spi, 0, 0, 0)?;
+                self.interface
+                    .cmd_with_frame_data(spi, SsdCommand::WriteRam2, chromatic)
+            }
+            Family::Uc8151 => uc8151::write_frames(
+                &mut self.interface,
+                spi,
+                FrameData::Buffer(black),
+                FrameData::Buffer(chromatic),
+            ),
+        }
+    }
+
+    /// Refreshes the panel with the frame written before and waits until it's done
+    pub fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .wait_until_idle_ticked(self.config.busy_is_low, delay)?;
+        self.interface.set_busy_reason(BusyState::Refreshing);
+        match C::FAMILY {
+            Family::Ssd1680 => {
+                // clock, analog and the full waveform from the OTP
+                ssd168x::set_update_sequence(&mut self.interface, spi, 0xF7)?;
+                self.interface.cmd(spi, SsdCommand::MasterActivation)?;
+            }
+            Family::Uc8151 => {
+                uc8151::apply_temperature(&mut self.interface, spi)?;
+                self.interface.cmd(spi, UcCommand::DisplayRefresh)?;
+            }
+        }
+        // the busy pin needs some time to change
+        delay.delay_ms(10);
+        self.interface
+            .wait_until_idle_ticked(self.config.busy_is_low, delay)?;
+        Ok(())
+    }
+
+    /// Writes the frame and refreshes the panel with it
+    pub fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer)?;
+        self.display_frame(spi, delay)
+    }
+
+    /// Clears the panel with the background color
+    pub fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .wait_until_idle_ticked(self.config.busy_is_low, delay)?;
+        let len = self.config.frame_len();
+        let color = self.color.get_byte_value();
+        let tricolor = self.config.color_mode == ColorMode::TriColor;
+        match C::FAMILY {
+            Family::Ssd1680 => {
+                ssd168x::set_ram_counter(&mut self.interface, spi, 0, 0, 0)?;
+                self.interface.cmd(spi, SsdCommand::WriteRam)?;
+                self.interface.clear_data(spi, color, len)?;
+                if self.interface.clear_aborted() {
+                    return Ok(());
+                }
+                if tricolor {
+                    ssd168x::set_ram_counter(&mut self.interface, spi, 0, 0, 0)?;
+                    self.interface.cmd(spi, SsdCommand::WriteRam2)?;
+                    self.interface.clear_data(spi, 0x00, len)?;
+                }
+            }
+            Family::Uc8151 if tricolor => {
+                uc8151::clear_frames(&mut self.interface, spi, color, 0xFF, len)?
+            }
+            Family::Uc8151 => uc8151::clear_frames(&mut self.interface, spi, color, color, len)?,
+        }
+        if self.interface.clear_aborted() {
+            return Ok(());
+        }
+        self.display_frame(spi, delay)
+    }
+
+    /// Sets the background color for [`clear_frame`](GenericEpd::clear_frame)
+    pub fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    /// Returns the background color
+    pub fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    /// Returns the description of the panel
+    pub fn config(&self) -> &PanelConfig {
+        &self.config
+    }
+
+    /// Width of the panel in pixels
+    pub fn width(&self) -> u32 {
+        self.config.width
+    }
+
+    /// Height of the panel in pixels
+    pub fn height(&self) -> u32 {
+        self.config.height
+    }
+
+    /// Checks if the panel is still busy
+    pub fn is_busy(&self) -> bool {
+        self.interface.is_busy(self.config.busy_is_low)
+    }
+
+    fn wait_until_idle(&mut self) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(self.config.busy_is_low)
+    }
+}
+
+impl<C, SPI, CS, BUSY, DC, RST, DELAY> TemperatureSetting
+    for GenericEpd<C, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    C: Controller,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_temperature(&mut self, celsius: i8) {
+        self.interface.set_temperature(Some(celsius));
+    }
+
+    fn use_internal_sensor(&mut self) {
+        self.interface.set_temperature(None);
+    }
+
+    fn temperature(&self) -> Option<i8> {
+        self.interface.temperature()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> TemperatureReading<SPI, CS, BUSY, DC, RST, DELAY>
+    for GenericEpd<Ssd1680, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn read_temperature(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<i8, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        ssd168x::read_temperature(&mut self.interface, spi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panel_defaults() {
+        let panel = Ssd1680::panel(122, 250);
+        assert!(!panel.busy_is_low);
+        assert_eq!(panel.color_mode, ColorMode::BlackWhite);
+        // the lines are padded to whole bytes
+        assert_eq!(panel.frame_len(), 16 * 250);
+
+        let panel = Uc8151::panel(104, 212);
+        assert!(panel.busy_is_low);
+        assert_eq!(panel.frame_len(), 13 * 212);
+    }
+    #[test]
+    fn init_and_frame_commands() {
+        extern crate std;
+        use crate::interface::mock::{CommandSpi, HighPin, NoPin};
+        use embedded_hal_mock::delay::MockNoop;
+        use std::vec;
+
+        let black = vec![0xAA; 16 * 296];
+        let chromatic = vec![0x55; 16 * 296];
+
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let config = PanelConfig {
+            color_mode: ColorMode::TriColor,
+            ..Ssd1680::panel(128, 296)
+        };
+        let mut epd: GenericEpd<Ssd1680, _, _, _, _, _, MockNoop> = GenericEpd::new(
+            &mut spi,
+            NoPin,
+            NoPin,
+            dc,
+            NoPin,
+            &mut MockNoop::new(),
+            config,
+        )
+        .unwrap();
+        assert_eq!(
+            spi.commands(),
+            [0x12, 0x01, 0x11, 0x44, 0x45, 0x4E, 0x4F, 0x3C, 0x18, 0x21]
+        );
+        assert!(spi.sent(0x01, &[0x27, 0x01, 0x00]));
+        assert!(spi.sent(0x44, &[0x00, 0x0F]));
+        assert!(spi.sent(0x45, &[0x00, 0x00, 0x27, 0x01]));
+
+        spi.clear();
+        epd.update_color_frame(&mut spi, &black, &chromatic)
+            .unwrap();
+        assert_eq!(spi.commands(), [0x4E, 0x4F, 0x24, 0x4E, 0x4F, 0x26]);
+        assert!(spi.sent(0x24, &black));
+        assert!(spi.sent(0x26, &chromatic));
+
+        // the UC8151 family is busy low and gets its resolution in one command
+        let mut spi = CommandSpi::default();
+        let dc = spi.dc();
+        let config = PanelConfig {
+            color_mode: ColorMode::TriColor,
+            ..Uc8151::panel(128, 296)
+        };
+        let mut epd: GenericEpd<Uc8151, _, _, _, _, _, MockNoop> = GenericEpd::new(
+            &mut spi,
+            NoPin,
+            HighPin,
+            dc,
+            NoPin,
+            &mut MockNoop::new(),
+            config,
+        )
+        .unwrap();
+        assert_eq!(spi.commands(), [0x06, 0x04, 0x00, 0x61, 0x50]);
+        assert!(spi.sent(0x61, &[0x80, 0x01, 0x28]));
+
+        spi.clear();
+        epd.update_color_frame(&mut spi, &black, &chromatic)
+            .unwrap();
+        assert_eq!(spi.commands(), [0x10, 0x13]);
+        assert!(spi.sent(0x10, &black));
+        assert!(spi.sent(0x13, &chromatic));
+    }
+}
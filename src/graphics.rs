@@ -1,14 +1,16 @@
 //! Graphics Support for EPDs
 
-use crate::buffer_len;
-use crate::color::{Color, OctColor, TriColor};
+use crate::color::{Color, HexColor, OctColor, QuadColor, TriColor};
+use crate::{buffer_len, bytes_per_line};
 use embedded_graphics_core::pixelcolor::BinaryColor;
 use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
 
 /// Displayrotation
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
 pub enum DisplayRotation {
     /// No rotation
+    #[default]
     Rotate0,
     /// Rotate by 90 degrees clockwise
     Rotate90,
@@ -18,9 +20,113 @@ pub enum DisplayRotation {
     Rotate270,
 }
 
-impl Default for DisplayRotation {
-    fn default() -> Self {
-        DisplayRotation::Rotate0
+/// Mirroring of the display, independent of the [rotation](DisplayRotation)
+///
+/// E.g. for panels which are mounted mirrored behind a window. The axes are the ones of the
+/// rotated display, so a horizontally mirrored display is always mirrored left to right as
+/// it's seen by the application.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Mirroring {
+    /// Mirrors left and right
+    pub horizontal: bool,
+    /// Mirrors top and bottom
+    pub vertical: bool,
+}
+
+impl Mirroring {
+    /// No mirroring
+    pub const NONE: Mirroring = Mirroring {
+        horizontal: false,
+        vertical: false,
+    };
+    /// Mirrors left and right
+    pub const HORIZONTAL: Mirroring = Mirroring {
+        horizontal: true,
+        vertical: false,
+    };
+    /// Mirrors top and bottom
+    pub const VERTICAL: Mirroring = Mirroring {
+        horizontal: false,
+        vertical: true,
+    };
+
+    /// Mirrors a point of a rotated display with the native size `width` x `height`
+    fn apply(self, point: Point, width: u32, height: u32, rotation: DisplayRotation) -> Point {
+        let (width, height) = match rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (width, height),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (height, width),
+        };
+        let x = if self.horizontal {
+            width as i32 - 1 - point.x
+        } else {
+            point.x
+        };
+        let y = if self.vertical {
+            height as i32 - 1 - point.y
+        } else {
+            point.y
+        };
+        Point::new(x, y)
+    }
+}
+
+/// Rotation and mirroring of a display, which change together e.g. when the device is turned
+///
+/// Set it on the [`Display`] with [`Display::set_orientation`] and on the driver with
+/// [`OrientationSetting::set_orientation`](crate::traits::OrientationSetting::set_orientation),
+/// so both map the coordinates of the application in the same way.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Orientation {
+    /// Rotation of the display
+    pub rotation: DisplayRotation,
+    /// Mirroring in the rotated coordinates
+    pub mirroring: Mirroring,
+}
+
+impl Orientation {
+    /// Orientation with `rotation` and without mirroring
+    pub const fn new(rotation: DisplayRotation) -> Self {
+        Orientation {
+            rotation,
+            mirroring: Mirroring::NONE,
+        }
+    }
+
+    /// Maps an area in the coordinates of the application to the native coordinates of a display
+    ///
+    /// `width` and `height` are the native size of the display. The area is clamped to the
+    /// display, `None` is returned if nothing of it is on the display.
+    pub fn native_area(self, area: Rectangle, width: u32, height: u32) -> Option<Rectangle> {
+        let size = match self.rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => Size::new(width, height),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => Size::new(height, width),
+        };
+        let area = area.intersection(&Rectangle::new(Point::zero(), size));
+        let bottom_right = area.bottom_right()?;
+        let native = |point: Point| {
+            let point = self.mirroring.apply(point, width, height, self.rotation);
+            let (x, y) =
+                find_rotation(point.x as u32, point.y as u32, width, height, self.rotation);
+            Point::new(x as i32, y as i32)
+        };
+        let (a, b) = (native(area.top_left), native(bottom_right));
+        Some(Rectangle::with_corners(
+            Point::new(a.x.min(b.x), a.y.min(b.y)),
+            Point::new(a.x.max(b.x), a.y.max(b.y)),
+        ))
+    }
+
+    /// Like [`native_area`](Self::native_area), but aligned to whole bytes like [`partial_window`]
+    ///
+    /// This is the window which a partial update of `area` sends.
+    pub fn partial_window(self, area: Rectangle, width: u32, height: u32) -> Option<Rectangle> {
+        partial_window(self.native_area(area, width, height)?, width, height)
+    }
+}
+
+impl From<DisplayRotation> for Orientation {
+    fn from(rotation: DisplayRotation) -> Self {
+        Orientation::new(rotation)
     }
 }
 
@@ -33,7 +139,7 @@ impl Default for DisplayRotation {
 /// Other displays, like [crate::epd5in83b_v2] in opposite, will draw color pixel if bit is
 /// cleared for that pixel, which is a [DisplayColorRendering::Negative] mode.
 ///
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum DisplayColorRendering {
     /// Positive: chromatic doesn't override white, white bit cleared for black, white bit set for white, both bits set for chromatic
     Positive,
@@ -67,6 +173,34 @@ pub trait Display: DrawTarget<Color = BinaryColor> {
     /// Get the current rotation of the display
     fn rotation(&self) -> DisplayRotation;
 
+    /// Sets the mirroring of the display
+    fn set_mirroring(&mut self, mirroring: Mirroring);
+
+    /// Get the current mirroring of the display
+    fn mirroring(&self) -> Mirroring;
+
+    /// Sets the rotation and the mirroring of the display at once
+    fn set_orientation(&mut self, orientation: Orientation) {
+        self.set_rotation(orientation.rotation);
+        self.set_mirroring(orientation.mirroring);
+    }
+
+    /// Get the current rotation and mirroring of the display
+    fn orientation(&self) -> Orientation {
+        Orientation {
+            rotation: self.rotation(),
+            mirroring: self.mirroring(),
+        }
+    }
+
+    /// Returns a view of the display which only draws inside of `area`, see [`RegionGuard`]
+    fn region_guard(&mut self, area: Rectangle) -> RegionGuard<'_, Self>
+    where
+        Self: Sized,
+    {
+        RegionGuard::new(self, area)
+    }
+
     /// Helperfunction for the Embedded Graphics draw trait
     ///
     /// Becomes uneccesary when const_generics become stablised
@@ -77,9 +211,11 @@ pub trait Display: DrawTarget<Color = BinaryColor> {
         pixel: Pixel<BinaryColor>,
     ) -> Result<(), Self::Error> {
         let rotation = self.rotation();
+        let mirroring = self.mirroring();
         let buffer = self.get_mut_buffer();
 
         let Pixel(point, color) = pixel;
+        let point = mirroring.apply(point, width, height, rotation);
         if outside_display(point, width, height, rotation) {
             return Ok(());
         }
@@ -88,17 +224,8 @@ pub trait Display: DrawTarget<Color = BinaryColor> {
         let (index, bit) = find_position(point.x as u32, point.y as u32, width, height, rotation);
         let index = index as usize;
 
-        // "Draw" the Pixel on that bit
-        match color {
-            // Black
-            BinaryColor::On => {
-                buffer[index] &= !bit;
-            }
-            // White
-            BinaryColor::Off => {
-                buffer[index] |= bit;
-            }
-        }
+        // "Draw" the Pixel on that bit, cleared for black and set for white
+        write_bit(buffer, index, bit, color == BinaryColor::Off);
         Ok(())
     }
 }
@@ -129,6 +256,34 @@ pub trait TriDisplay: DrawTarget<Color = TriColor> {
     /// Get the current rotation of the display
     fn rotation(&self) -> DisplayRotation;
 
+    /// Sets the mirroring of the display
+    fn set_mirroring(&mut self, mirroring: Mirroring);
+
+    /// Get the current mirroring of the display
+    fn mirroring(&self) -> Mirroring;
+
+    /// Sets the rotation and the mirroring of the display at once
+    fn set_orientation(&mut self, orientation: Orientation) {
+        self.set_rotation(orientation.rotation);
+        self.set_mirroring(orientation.mirroring);
+    }
+
+    /// Get the current rotation and mirroring of the display
+    fn orientation(&self) -> Orientation {
+        Orientation {
+            rotation: self.rotation(),
+            mirroring: self.mirroring(),
+        }
+    }
+
+    /// Returns a view of the display which only draws inside of `area`, see [`RegionGuard`]
+    fn region_guard(&mut self, area: Rectangle) -> RegionGuard<'_, Self>
+    where
+        Self: Sized,
+    {
+        RegionGuard::new(self, area)
+    }
+
     /// Get the offset into buffer where chromatic data starts
     fn chromatic_offset(&self) -> usize;
 
@@ -148,8 +303,8 @@ pub trait TriDisplay: DrawTarget<Color = TriColor> {
     /// * `height` - Screen height in pixels
     /// * `pixel` - Pixel to draw
     /// * `rendering` - Chooses rendering mode for the color plane,
-    ///  whether it is positive or negative. Check [DisplayColorRendering] for details.
-    ///  This is a hardware defined setting, that needs to be checked from the datasheet.
+    ///   whether it is positive or negative. Check [DisplayColorRendering] for details.
+    ///   This is a hardware defined setting, that needs to be checked from the datasheet.
     fn draw_helper_tri(
         &mut self,
         width: u32,
@@ -158,8 +313,10 @@ pub trait TriDisplay: DrawTarget<Color = TriColor> {
         rendering: DisplayColorRendering,
     ) -> Result<(), Self::Error> {
         let rotation = self.rotation();
+        let mirroring = self.mirroring();
 
         let Pixel(point, color) = pixel;
+        let point = mirroring.apply(point, width, height, rotation);
         if outside_display(point, width, height, rotation) {
             return Ok(());
         }
@@ -171,53 +328,13 @@ pub trait TriDisplay: DrawTarget<Color = TriColor> {
 
         let buffer = self.get_mut_buffer();
 
-        // "Draw" the Pixel on that bit
-        match color {
-            TriColor::Black => {
-                // clear bit in bw-buffer -> black
-                buffer[index] &= !bit;
-                match rendering {
-                    DisplayColorRendering::Positive => {
-                        // set bit in chromatic-buffer -> white
-                        buffer[index + offset] |= bit;
-                    }
-                    DisplayColorRendering::Negative => {
-                        // clear bit in chromatic-buffer -> white
-                        buffer[index + offset] &= !bit;
-                    }
-                }
-            }
-            TriColor::White => {
-                // set bit in bw-buffer -> white
-                buffer[index] |= bit;
-                match rendering {
-                    DisplayColorRendering::Positive => {
-                        // set bit in chromatic-buffer -> white
-                        buffer[index + offset] |= bit;
-                    }
-                    DisplayColorRendering::Negative => {
-                        // clear bit in chromatic-buffer -> white
-                        buffer[index + offset] &= !bit;
-                    }
-                }
-            }
-            TriColor::Chromatic => {
-                match rendering {
-                    DisplayColorRendering::Positive => {
-                        // set bit in b/w buffer (white)
-                        buffer[index] |= bit;
-                        // clear bit in chromatic buffer -> chromatic
-                        buffer[index + offset] &= !bit;
-                    }
-                    DisplayColorRendering::Negative => {
-                        // set bit in b/w buffer (white)
-                        buffer[index] |= bit;
-                        // set bit in chromatic-buffer -> chromatic
-                        buffer[index + offset] |= bit;
-                    }
-                }
-            }
-        }
+        // "Draw" the Pixel on that bit, the b/w bit is only cleared for black
+        write_bit(buffer, index, bit, color != TriColor::Black);
+        // the chromatic bit is cleared for chromatic pixels with positive rendering and set
+        // for them with negative rendering
+        let chromatic = color == TriColor::Chromatic;
+        let positive = rendering == DisplayColorRendering::Positive;
+        write_bit(buffer, index + offset, bit, chromatic != positive);
         Ok(())
     }
 }
@@ -248,6 +365,34 @@ pub trait OctDisplay: DrawTarget<Color = OctColor> {
     /// Get the current rotation of the display
     fn rotation(&self) -> DisplayRotation;
 
+    /// Sets the mirroring of the display
+    fn set_mirroring(&mut self, mirroring: Mirroring);
+
+    /// Get the current mirroring of the display
+    fn mirroring(&self) -> Mirroring;
+
+    /// Sets the rotation and the mirroring of the display at once
+    fn set_orientation(&mut self, orientation: Orientation) {
+        self.set_rotation(orientation.rotation);
+        self.set_mirroring(orientation.mirroring);
+    }
+
+    /// Get the current rotation and mirroring of the display
+    fn orientation(&self) -> Orientation {
+        Orientation {
+            rotation: self.rotation(),
+            mirroring: self.mirroring(),
+        }
+    }
+
+    /// Returns a view of the display which only draws inside of `area`, see [`RegionGuard`]
+    fn region_guard(&mut self, area: Rectangle) -> RegionGuard<'_, Self>
+    where
+        Self: Sized,
+    {
+        RegionGuard::new(self, area)
+    }
+
     /// Helperfunction for the Embedded Graphics draw trait
     ///
     /// Becomes uneccesary when const_generics become stablised
@@ -258,9 +403,11 @@ pub trait OctDisplay: DrawTarget<Color = OctColor> {
         pixel: Pixel<OctColor>,
     ) -> Result<(), Self::Error> {
         let rotation = self.rotation();
+        let mirroring = self.mirroring();
         let buffer = self.get_mut_buffer();
 
         let Pixel(point, color) = pixel;
+        let point = mirroring.apply(point, width, height, rotation);
         if outside_display(point, width, height, rotation) {
             return Ok(());
         }
@@ -276,7 +423,150 @@ pub trait OctDisplay: DrawTarget<Color = OctColor> {
         } else {
             (0xf0, color.get_nibble())
         };
-        buffer[index] = (buffer[index] & mask) | color_nibble;
+        if let Some(byte) = buffer.get_mut(index) {
+            *byte = (*byte & mask) | color_nibble;
+        }
+        Ok(())
+    }
+}
+
+/// Necessary traits for the 4 color displays to implement for drawing
+///
+/// The frames pack four pixels into every byte, the left one in the upper bits, and every
+/// line starts with a new byte.
+pub trait QuadDisplay: DrawTarget<Color = QuadColor> {
+    /// Clears the buffer of the display with the chosen background color
+    fn clear_buffer(&mut self, background_color: QuadColor) {
+        let c = background_color;
+        for elem in self.get_mut_buffer().iter_mut() {
+            *elem = QuadColor::colors_byte(c, c, c, c);
+        }
+    }
+
+    /// Returns the buffer
+    fn buffer(&self) -> &[u8];
+
+    /// Returns a mutable buffer
+    fn get_mut_buffer(&mut self) -> &mut [u8];
+
+    /// Sets the rotation of the display
+    fn set_rotation(&mut self, rotation: DisplayRotation);
+
+    /// Get the current rotation of the display
+    fn rotation(&self) -> DisplayRotation;
+
+    /// Sets the mirroring of the display
+    fn set_mirroring(&mut self, mirroring: Mirroring);
+
+    /// Get the current mirroring of the display
+    fn mirroring(&self) -> Mirroring;
+
+    /// Sets the rotation and the mirroring of the display at once
+    fn set_orientation(&mut self, orientation: Orientation) {
+        self.set_rotation(orientation.rotation);
+        self.set_mirroring(orientation.mirroring);
+    }
+
+    /// Get the current rotation and mirroring of the display
+    fn orientation(&self) -> Orientation {
+        Orientation {
+            rotation: self.rotation(),
+            mirroring: self.mirroring(),
+        }
+    }
+
+    /// Returns a view of the display which only draws inside of `area`, see [`RegionGuard`]
+    fn region_guard(&mut self, area: Rectangle) -> RegionGuard<'_, Self>
+    where
+        Self: Sized,
+    {
+        RegionGuard::new(self, area)
+    }
+
+    /// Helperfunction for the Embedded Graphics draw trait
+    fn draw_helper(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixel: Pixel<QuadColor>,
+    ) -> Result<(), Self::Error> {
+        let orientation = self.orientation();
+        let Pixel(point, color) = pixel;
+        if let Some((index, shift)) = packed_position(point, width, height, orientation, 2) {
+            if let Some(byte) = self.get_mut_buffer().get_mut(index) {
+                *byte = (*byte & !(0b11 << shift)) | (color.get_bits() << shift);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Necessary traits for the 6 color Spectra displays to implement for drawing
+///
+/// The frames pack two pixels into every byte, the left one in the upper nibble, and every
+/// line starts with a new byte.
+pub trait HexDisplay: DrawTarget<Color = HexColor> {
+    /// Clears the buffer of the display with the chosen background color
+    fn clear_buffer(&mut self, background_color: HexColor) {
+        for elem in self.get_mut_buffer().iter_mut() {
+            *elem = HexColor::colors_byte(background_color, background_color);
+        }
+    }
+
+    /// Returns the buffer
+    fn buffer(&self) -> &[u8];
+
+    /// Returns a mutable buffer
+    fn get_mut_buffer(&mut self) -> &mut [u8];
+
+    /// Sets the rotation of the display
+    fn set_rotation(&mut self, rotation: DisplayRotation);
+
+    /// Get the current rotation of the display
+    fn rotation(&self) -> DisplayRotation;
+
+    /// Sets the mirroring of the display
+    fn set_mirroring(&mut self, mirroring: Mirroring);
+
+    /// Get the current mirroring of the display
+    fn mirroring(&self) -> Mirroring;
+
+    /// Sets the rotation and the mirroring of the display at once
+    fn set_orientation(&mut self, orientation: Orientation) {
+        self.set_rotation(orientation.rotation);
+        self.set_mirroring(orientation.mirroring);
+    }
+
+    /// Get the current rotation and mirroring of the display
+    fn orientation(&self) -> Orientation {
+        Orientation {
+            rotation: self.rotation(),
+            mirroring: self.mirroring(),
+        }
+    }
+
+    /// Returns a view of the display which only draws inside of `area`, see [`RegionGuard`]
+    fn region_guard(&mut self, area: Rectangle) -> RegionGuard<'_, Self>
+    where
+        Self: Sized,
+    {
+        RegionGuard::new(self, area)
+    }
+
+    /// Helperfunction for the Embedded Graphics draw trait
+    fn draw_helper(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixel: Pixel<HexColor>,
+    ) -> Result<(), Self::Error> {
+        let orientation = self.orientation();
+        let Pixel(point, color) = pixel;
+        if let Some((index, shift)) = packed_position(point, width, height, orientation, 4) {
+            if let Some(byte) = self.get_mut_buffer().get_mut(index) {
+                *byte = (*byte & !(0x0f << shift)) | (color.get_nibble() << shift);
+            }
+        }
         Ok(())
     }
 }
@@ -311,23 +601,42 @@ pub struct VarDisplay<'a> {
     width: u32,
     height: u32,
     rotation: DisplayRotation,
+    mirroring: Mirroring,
     buffer: &'a mut [u8], //buffer: Box<u8>//[u8; 15000]
 }
 
 impl<'a> VarDisplay<'a> {
     /// Create a new variable sized display.
     ///
-    /// Buffersize must be at least (width + 7) / 8 * height bytes.
+    /// Buffersize must be at least (width + 7) / 8 * height bytes. Pixels which don't fit into
+    /// a shorter buffer are not drawn, use [`try_new`](Self::try_new) to check the size.
     pub fn new(width: u32, height: u32, buffer: &'a mut [u8]) -> VarDisplay<'a> {
-        let len = buffer.len() as u32;
-        assert!(buffer_len(width as usize, height as usize) >= len as usize);
         VarDisplay {
             width,
             height,
             rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
             buffer,
         }
     }
+
+    /// Create a new variable sized display, if the buffer is large enough
+    ///
+    /// The display only uses the first (width + 7) / 8 * height bytes of the buffer.
+    pub fn try_new(
+        width: u32,
+        height: u32,
+        buffer: &'a mut [u8],
+    ) -> Result<VarDisplay<'a>, BufferTooSmall> {
+        let needed = buffer_len(width as usize, height as usize);
+        let buffer = BufferTooSmall::check(buffer, needed)?;
+        Ok(VarDisplay::new(width, height, buffer))
+    }
+
+    /// Length of one line of the buffer in bytes, see [`bytes_per_line`]
+    pub fn bytes_per_line(&self) -> u32 {
+        bytes_per_line(self.width as usize) as u32
+    }
 }
 
 impl<'a> DrawTarget for VarDisplay<'a> {
@@ -367,6 +676,249 @@ impl<'a> Display for VarDisplay<'a> {
     fn rotation(&self) -> DisplayRotation {
         self.rotation
     }
+
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
+
+/// Error of the fallible buffer constructors like [`VarDisplay::try_new`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    /// Bytes the buffer needs to hold
+    pub needed: usize,
+    /// Bytes the buffer holds
+    pub len: usize,
+}
+
+impl BufferTooSmall {
+    /// Returns the first `needed` bytes of `buffer`, if it holds as many
+    pub(crate) fn check(buffer: &mut [u8], needed: usize) -> Result<&mut [u8], BufferTooSmall> {
+        let len = buffer.len();
+        buffer
+            .get_mut(..needed)
+            .ok_or(BufferTooSmall { needed, len })
+    }
+}
+
+/// A variable 7-color display without a predefined buffer
+///
+/// Like [`VarDisplay`] with 4 bits per pixel. The buffer can be allocated anywhere, e.g. in
+/// the external PSRAM of an ESP32-S3 for the large 7-color displays, see
+/// [`BounceBuffering`](crate::traits::BounceBuffering) for sending it from there.
+///
+/// Example:
+/// ```rust
+/// # use epd_waveshare::epd5in65f::{HEIGHT, WIDTH};
+/// # use epd_waveshare::prelude::*;
+/// # use epd_waveshare::graphics::VarOctDisplay;
+/// # use epd_waveshare::color::OctColor;
+/// # use embedded_graphics::prelude::*;
+/// // e.g. a `&'static mut [u8]` allocated from PSRAM
+/// let mut buffer = vec![0u8; WIDTH as usize / 2 * HEIGHT as usize];
+/// let mut display = VarOctDisplay::new(WIDTH, HEIGHT, &mut buffer);
+///
+/// display.clear_buffer(OctColor::White);
+/// let _ = Pixel(Point::new(0, 0), OctColor::Red).draw(&mut display);
+/// ```
+pub struct VarOctDisplay<'a> {
+    width: u32,
+    height: u32,
+    rotation: DisplayRotation,
+    mirroring: Mirroring,
+    buffer: &'a mut [u8],
+}
+
+impl<'a> VarOctDisplay<'a> {
+    /// Create a new variable sized 7-color display.
+    ///
+    /// Buffersize must be at least (width + 1) / 2 * height bytes, see [`VarDisplay::new`] for
+    /// shorter buffers.
+    pub fn new(width: u32, height: u32, buffer: &'a mut [u8]) -> VarOctDisplay<'a> {
+        let len = buffer.len().min((width.div_ceil(2) * height) as usize);
+        VarOctDisplay {
+            width,
+            height,
+            rotation: DisplayRotation::default(),
+            mirroring: Mirroring::default(),
+            buffer: &mut buffer[..len],
+        }
+    }
+
+    /// Create a new variable sized 7-color display, if the buffer is large enough
+    pub fn try_new(
+        width: u32,
+        height: u32,
+        buffer: &'a mut [u8],
+    ) -> Result<VarOctDisplay<'a>, BufferTooSmall> {
+        let buffer = BufferTooSmall::check(buffer, (width.div_ceil(2) * height) as usize)?;
+        Ok(VarOctDisplay::new(width, height, buffer))
+    }
+
+    /// Length of one line of the buffer in bytes, two pixels per byte and a padded last byte
+    /// for an odd width
+    pub fn bytes_per_line(&self) -> u32 {
+        self.width.div_ceil(2)
+    }
+}
+
+impl<'a> DrawTarget for VarOctDisplay<'a> {
+    type Color = OctColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.draw_helper(self.width, self.height, pixel)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> OriginDimensions for VarOctDisplay<'a> {
+    fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+impl<'a> OctDisplay for VarOctDisplay<'a> {
+    fn buffer(&self) -> &[u8] {
+        self.buffer
+    }
+
+    fn get_mut_buffer(&mut self) -> &mut [u8] {
+        self.buffer
+    }
+
+    fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    fn set_mirroring(&mut self, mirroring: Mirroring) {
+        self.mirroring = mirroring;
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
+
+/// A view of a display which only draws inside of its area
+///
+/// Meant for UIs which are split into widgets: every widget gets a guard of its own area and
+/// can't draw over its neighbours, even if its content is too big. The coordinates stay the
+/// ones of the display, and the [bounding box](Dimensions::bounding_box) is the area, so
+/// widgets can lay out their content in it. The area can be sent with a partial update
+/// afterwards, e.g. with [`partial_window`] and [`copy_window`].
+///
+/// Example:
+///```rust
+///# use embedded_graphics::{prelude::*, primitives::{PrimitiveStyle, Rectangle}};
+///# use epd_waveshare::{color::Black, epd2in9::Display2in9, graphics::Display};
+///let mut display = Display2in9::default();
+///
+///let clock = Rectangle::new(Point::new(0, 0), Size::new(128, 32));
+///let mut widget = display.region_guard(clock);
+///// only the part inside of the clock area is drawn
+///let _ = Rectangle::new(Point::new(0, 0), Size::new(128, 64))
+///    .into_styled(PrimitiveStyle::with_fill(Black))
+///    .draw(&mut widget);
+///```
+pub struct RegionGuard<'a, D> {
+    target: &'a mut D,
+    area: Rectangle,
+}
+
+impl<'a, D: DrawTarget> RegionGuard<'a, D> {
+    /// Restricts the drawing on `target` to `area`
+    pub fn new(target: &'a mut D, area: Rectangle) -> Self {
+        RegionGuard { target, area }
+    }
+
+    /// The area which can be drawn
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl<'a, D: DrawTarget> DrawTarget for RegionGuard<'a, D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.area;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .filter(|Pixel(point, _)| area.contains(*point)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.target
+            .fill_solid(&area.intersection(&self.area), color)
+    }
+}
+
+impl<'a, D: DrawTarget> Dimensions for RegionGuard<'a, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.area
+    }
+}
+
+/// Clamps `area` to a display of `width` x `height` pixels and aligns it to whole bytes
+///
+/// Partial updates work on whole bytes of the buffer, so the window is widened to
+/// multiples of 8 pixels horizontally. Returns `None` if nothing of `area` is on the display.
+/// The coordinates are in the native orientation of the display, i.e. without rotation.
+pub fn partial_window(area: Rectangle, width: u32, height: u32) -> Option<Rectangle> {
+    let clamped = area.intersection(&Rectangle::new(Point::zero(), Size::new(width, height)));
+    if clamped.is_zero_sized() {
+        return None;
+    }
+    let left = clamped.top_left.x as u32 / 8 * 8;
+    let right = (clamped.top_left.x as u32 + clamped.size.width).div_ceil(8) * 8;
+    Some(Rectangle::new(
+        Point::new(left as i32, clamped.top_left.y),
+        Size::new(right - left, clamped.size.height),
+    ))
+}
+
+/// Copies the part of a black/white frame buffer inside `window` to `out`
+///
+/// `frame` is a full buffer with `width` pixels per row, like the one of a [`Display`].
+/// `window` has to be byte aligned, e.g. by [`partial_window`], and `out` is filled
+/// with its rows so it can be passed to a partial update.
+///
+/// # Panics
+///
+/// If `out` is shorter than the window or the window isn't completely inside of `frame`.
+pub fn copy_window(frame: &[u8], width: u32, window: Rectangle, out: &mut [u8]) {
+    let row_len = width.div_ceil(8) as usize;
+    let start = window.top_left.x as usize / 8;
+    let window_row_len = buffer_len(window.size.width as usize, 1);
+    for (i, out_row) in out
+        .chunks_mut(window_row_len)
+        .take(window.size.height as usize)
+        .enumerate()
+    {
+        let y = window.top_left.y as usize + i;
+        let offset = y * row_len + start;
+        out_row.copy_from_slice(&frame[offset..offset + window_row_len]);
+    }
 }
 
 // Checks if a pos is outside the defined display
@@ -420,30 +972,68 @@ fn find_oct_position(x: u32, y: u32, width: u32, height: u32, rotation: DisplayR
     let (nx, ny) = find_rotation(x, y, width, height, rotation);
     (
         /* what byte address is this? */
-        nx / 2 + (width / 2) * ny,
+        nx / 2 + width.div_ceil(2) * ny,
         /* is this the lower nibble (within byte)? */
         (nx & 0x1) == 0,
     )
 }
 
+/// Sets or clears `bit` of the byte at `index`, buffers which are too short are left as they are
+fn write_bit(buffer: &mut [u8], index: usize, bit: u8, set: bool) {
+    if let Some(byte) = buffer.get_mut(index) {
+        if set {
+            *byte |= bit;
+        } else {
+            *byte &= !bit;
+        }
+    }
+}
+
+/// Returns the index of the byte and the shift of the bits of a pixel with `bits` bits per pixel
+///
+/// The pixels are packed from the most significant bits on and every line starts with a new
+/// byte. `None` for pixels outside of the display. Used by [`QuadDisplay`], [`HexDisplay`] and by
+/// the displays which don't fit one of the display traits, like the grayscale mode of the
+/// [3.7" display](crate::epd3in7).
+pub(crate) fn packed_position(
+    point: Point,
+    width: u32,
+    height: u32,
+    orientation: Orientation,
+    bits: u32,
+) -> Option<(usize, u32)> {
+    let rotation = orientation.rotation;
+    let point = orientation.mirroring.apply(point, width, height, rotation);
+    if outside_display(point, width, height, rotation) {
+        return None;
+    }
+    let (nx, ny) = find_rotation(point.x as u32, point.y as u32, width, height, rotation);
+    let per_byte = 8 / bits;
+    let index = nx / per_byte + (width * bits).div_ceil(8) * ny;
+    Some((index as usize, 8 - bits * (nx % per_byte + 1)))
+}
+
 #[rustfmt::skip]
 //returns index position in the u8-slice and the bit-position inside that u8
 fn find_position(x: u32, y: u32, width: u32, height: u32, rotation: DisplayRotation) -> (u32, u8) {
     let (nx, ny) = find_rotation(x, y, width, height, rotation);
     (
-        nx / 8 + ((width + 7) / 8) * ny,
+        nx / 8 + bytes_per_line(width as usize) as u32 * ny,
         0x80 >> (nx % 8),
     )
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{buffer_len, find_position, outside_display, Display, DisplayRotation, VarDisplay};
+    use super::{
+        buffer_len, copy_window, find_position, outside_display, partial_window, Display,
+        DisplayRotation, Mirroring, Orientation, VarDisplay,
+    };
     use crate::color::Black;
     use crate::color::Color;
     use embedded_graphics::{
         prelude::*,
-        primitives::{Line, PrimitiveStyle},
+        primitives::{Line, PrimitiveStyle, Rectangle},
     };
 
     #[test]
@@ -468,8 +1058,8 @@ mod tests {
     #[test]
     fn rotation_overflow() {
         use crate::epd4in2::{HEIGHT, WIDTH};
-        let width = WIDTH as u32;
-        let height = HEIGHT as u32;
+        let width = WIDTH;
+        let height = HEIGHT;
         test_rotation_overflow(width, height, DisplayRotation::Rotate0);
         test_rotation_overflow(width, height, DisplayRotation::Rotate90);
         test_rotation_overflow(width, height, DisplayRotation::Rotate180);
@@ -480,7 +1070,7 @@ mod tests {
         let max_value = width / 8 * height;
         for x in 0..(width + height) {
             //limit x because it runs too long
-            for y in 0..(u32::max_value()) {
+            for y in 0..(u32::MAX) {
                 if outside_display(Point::new(x as i32, y as i32), width, height, rotation2) {
                     break;
                 } else {
@@ -513,6 +1103,81 @@ mod tests {
         }
     }
 
+    #[test]
+    fn graphics_mirroring() {
+        // 16x2 pixels
+        let mut buffer = [0xFF; 2 * 2];
+        let mut display = VarDisplay::new(16, 2, &mut buffer);
+
+        display.set_mirroring(Mirroring::HORIZONTAL);
+        let _ = Pixel(Point::new(0, 0), Black).draw(&mut display);
+        assert_eq!(display.buffer(), &[0xFF, 0xFE, 0xFF, 0xFF]);
+
+        display.clear_buffer(Color::White);
+        display.set_mirroring(Mirroring {
+            horizontal: true,
+            vertical: true,
+        });
+        let _ = Pixel(Point::new(0, 0), Black).draw(&mut display);
+        assert_eq!(display.buffer(), &[0xFF, 0xFF, 0xFF, 0xFE]);
+
+        // the axes are the ones of the rotated display, which is 2x16 pixels now
+        display.clear_buffer(Color::White);
+        display.set_rotation(DisplayRotation::Rotate90);
+        display.set_mirroring(Mirroring::VERTICAL);
+        let _ = Pixel(Point::new(0, 0), Black).draw(&mut display);
+        assert_eq!(display.buffer(), &[0x7F, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn region_guard_clips() {
+        // 16x2 pixels, only the right half of the first row may be drawn
+        let mut buffer = [0xFF; 2 * 2];
+        let mut display = VarDisplay::new(16, 2, &mut buffer);
+        let area = Rectangle::new(Point::new(8, 0), Size::new(8, 1));
+        let mut guard = display.region_guard(area);
+        assert_eq!(guard.bounding_box(), area);
+
+        let _ = Line::new(Point::new(0, 0), Point::new(15, 0))
+            .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+            .draw(&mut guard);
+        let _ = guard.fill_solid(&Rectangle::new(Point::zero(), Size::new(16, 2)), Black);
+        assert_eq!(display.buffer(), &[0xFF, 0x00, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn orientation_native_area() {
+        // 16x8 pixels, 8x16 when rotated
+        let area = Rectangle::new(Point::new(1, 2), Size::new(3, 4));
+        let rotated = Orientation::new(DisplayRotation::Rotate90);
+        assert_eq!(
+            rotated.native_area(area, 16, 8),
+            Some(Rectangle::new(Point::new(10, 1), Size::new(4, 3)))
+        );
+        assert_eq!(
+            rotated.partial_window(area, 16, 8),
+            Some(Rectangle::new(Point::new(8, 1), Size::new(8, 3)))
+        );
+
+        let mirrored = Orientation {
+            rotation: DisplayRotation::Rotate0,
+            mirroring: Mirroring::HORIZONTAL,
+        };
+        assert_eq!(
+            mirrored.native_area(area, 16, 8),
+            Some(Rectangle::new(Point::new(12, 2), Size::new(3, 4)))
+        );
+
+        let outside = Rectangle::new(Point::new(8, 0), Size::new(4, 4));
+        assert_eq!(rotated.native_area(outside, 16, 8), None);
+
+        let mut buffer = [0xFF; 16 / 8 * 8];
+        let mut display = VarDisplay::new(16, 8, &mut buffer);
+        display.set_orientation(rotated);
+        assert_eq!(display.orientation(), rotated);
+        assert_eq!(display.rotation(), DisplayRotation::Rotate90);
+    }
+
     #[test]
     fn graphics_rotation_90() {
         use crate::epd2in9::DEFAULT_BACKGROUND_COLOR;
@@ -539,4 +1204,94 @@ mod tests {
             assert_eq!(byte, DEFAULT_BACKGROUND_COLOR.get_byte_value());
         }
     }
+
+    #[test]
+    fn partial_window_aligned_and_clamped() {
+        let window = partial_window(Rectangle::new(Point::new(3, 2), Size::new(10, 4)), 32, 16);
+        assert_eq!(
+            window,
+            Some(Rectangle::new(Point::new(0, 2), Size::new(16, 4)))
+        );
+
+        // clamped to the display, the last byte of a row counts completely
+        let window = partial_window(
+            Rectangle::new(Point::new(-4, 10), Size::new(40, 10)),
+            30,
+            16,
+        );
+        assert_eq!(
+            window,
+            Some(Rectangle::new(Point::new(0, 10), Size::new(32, 6)))
+        );
+
+        let window = partial_window(Rectangle::new(Point::new(40, 0), Size::new(8, 8)), 32, 16);
+        assert_eq!(window, None);
+    }
+
+    #[test]
+    fn copy_window_rows() {
+        // 16x3 pixels
+        let frame = [0x00, 0x01, 0x10, 0x11, 0x20, 0x21];
+        let mut out = [0u8; 2];
+        copy_window(
+            &frame,
+            16,
+            Rectangle::new(Point::new(8, 1), Size::new(8, 2)),
+            &mut out,
+        );
+        assert_eq!(out, [0x11, 0x21]);
+    }
+
+    #[test]
+    fn fallible_constructors() {
+        use super::{BufferTooSmall, VarOctDisplay};
+
+        let mut buffer = [0xFF; 3];
+        assert_eq!(
+            VarDisplay::try_new(16, 2, &mut buffer).err(),
+            Some(BufferTooSmall { needed: 4, len: 3 })
+        );
+        let display = VarDisplay::try_new(12, 1, &mut buffer).unwrap();
+        assert_eq!(display.buffer().len(), 2);
+        assert!(VarOctDisplay::try_new(2, 3, &mut buffer).is_ok());
+
+        // pixels outside of a short buffer are skipped
+        let mut display = VarDisplay::new(16, 2, &mut buffer);
+        Pixel(Point::new(15, 1), Black).draw(&mut display).unwrap();
+        assert_eq!(buffer, [0xFF; 3]);
+    }
+
+    #[test]
+    fn padded_lines() {
+        use super::{OctDisplay, VarOctDisplay};
+
+        // the 122 pixels of the 2.13" displays, the last 6 bits of every line are unused
+        let mut buffer = [0xFF; 16 * 2];
+        let mut display = VarDisplay::new(122, 2, &mut buffer);
+        assert_eq!(display.bytes_per_line(), 16);
+        Pixel(Point::new(121, 1), Black).draw(&mut display).unwrap();
+        assert_eq!(buffer[31], 0xBF);
+
+        let mut buffer = [0u8; 2 * 2];
+        let display = VarOctDisplay::new(3, 2, &mut buffer);
+        assert_eq!(display.bytes_per_line(), 2);
+        assert_eq!(display.buffer().len(), 4);
+    }
+
+    #[test]
+    fn var_oct_display() {
+        use super::{OctColor, OctDisplay, VarOctDisplay};
+
+        // 4x2 pixels with two pixels per byte, the spare byte isn't used
+        let mut buffer = [0u8; 5];
+        let mut display = VarOctDisplay::new(4, 2, &mut buffer);
+        display.clear_buffer(OctColor::White);
+        Pixel(Point::new(1, 0), OctColor::Red)
+            .draw(&mut display)
+            .unwrap();
+        Pixel(Point::new(2, 1), OctColor::Blue)
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(display.buffer(), [0x14, 0x11, 0x11, 0x31]);
+    }
 }
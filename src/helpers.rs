@@ -0,0 +1,251 @@
+//! Common sequences of display operations
+//!
+//! [`WaveshareDisplayExt`] is implemented for every [`WaveshareDisplay`] and combines its
+//! operations into the flows most applications need, like showing a frame and putting the
+//! display to sleep right after. The trait is part of the [prelude](crate::prelude).
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!# use epd_waveshare::{color::*, epd4in2::*, helpers::{CenteredError, TestPattern}, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!#
+//!let mut epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// check the wiring and the panel
+//!let mut buffer = [0u8; WIDTH as usize / 8 * HEIGHT as usize];
+//!epd.show_test_pattern(&mut spi, TestPattern::Checkerboard, &mut buffer, &mut delay)?;
+//!
+//!// a logo of 64x64 pixels in the middle of a white display
+//!# let logo = [0u8; 64 / 8 * 64];
+//!epd.set_background_color(Color::White);
+//!match epd.display_centered(&mut spi, &logo, 64, 64, &mut delay) {
+//!    Err(CenteredError::Interface(error)) => return Err(error),
+//!    // e.g. show the logo as part of a full frame instead
+//!    Err(CenteredError::NoPartialRefresh) | Err(CenteredError::TooLarge) => {}
+//!    Ok(()) => {}
+//!}
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use crate::buffer_len;
+use crate::traits::{DisplayError, WaveshareDisplay};
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+/// Patterns to check a display with [`WaveshareDisplayExt::show_test_pattern`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestPattern {
+    /// Black and white squares of 8x8 pixels, black in the top left corner
+    Checkerboard,
+    /// Black and white rows of 8 pixels, black at the top
+    HorizontalStripes,
+    /// Black and white columns of 8 pixels, black on the left
+    VerticalStripes,
+    /// A black line of 1 pixel along the edges of the display
+    Border,
+}
+
+impl TestPattern {
+    /// Renders the pattern into a black/white buffer of `width` x `height` pixels
+    ///
+    /// If `buffer` is shorter than a frame buffer of this size, only the pixels it holds are
+    /// rendered.
+    pub fn render(self, buffer: &mut [u8], width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let row_len = buffer_len(width as usize, 1);
+        let rows = buffer.chunks_mut(row_len).take(height as usize);
+        for (y, row) in rows.enumerate() {
+            let y = y as u32;
+            for (column, byte) in row.iter_mut().enumerate() {
+                let black_byte = match self {
+                    TestPattern::Checkerboard => (column as u32 + y / 8) % 2 == 0,
+                    TestPattern::HorizontalStripes => (y / 8) % 2 == 0,
+                    TestPattern::VerticalStripes => column % 2 == 0,
+                    TestPattern::Border => y == 0 || y == height - 1,
+                };
+                *byte = if black_byte { 0x00 } else { 0xFF };
+            }
+            if self == TestPattern::Border {
+                row[0] &= 0x7F;
+                let last = width - 1;
+                if let Some(byte) = row.get_mut(last as usize / 8) {
+                    *byte &= !(0x80 >> (last % 8));
+                }
+            }
+        }
+    }
+}
+
+/// Error of [`WaveshareDisplayExt::display_centered`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CenteredError<E> {
+    /// Sending to the display failed
+    Interface(E),
+    /// The display can't update a window, nothing was sent, see
+    /// [`Capabilities::partial_refresh`](crate::traits::Capabilities::partial_refresh)
+    NoPartialRefresh,
+    /// The image is larger than the display, nothing was sent
+    TooLarge,
+}
+
+/// Multi-step flows for every [`WaveshareDisplay`], see the [module docs](self)
+pub trait WaveshareDisplayExt<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sets the background color, clears the frame with it and displays it
+    ///
+    /// The frame is written and refreshed once, also on the displays whose
+    /// [`clear_frame`](WaveshareDisplay::clear_frame) refreshes already, see
+    /// [`Capabilities::clear_refreshes`](crate::traits::Capabilities::clear_refreshes).
+    fn clear_and_display(
+        &mut self,
+        spi: &mut SPI,
+        color: Self::DisplayColor,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.set_background_color(color);
+        self.clear_frame(spi, delay)?;
+        if Self::CAPABILITIES.clear_refreshes {
+            return Ok(());
+        }
+        self.display_frame(spi, delay)
+    }
+
+    /// Shows an image of `width` x `height` pixels in the middle of a cleared frame
+    ///
+    /// The frame is cleared with the background color. The left edge of the image is moved
+    /// to the left to a multiple of 8, so the image doesn't have to be shifted. Only the
+    /// displays with a [partial refresh](crate::traits::Capabilities::partial_refresh) can
+    /// place the image on their own, the others fail without sending anything. The displays
+    /// whose [`clear_frame`](WaveshareDisplay::clear_frame) refreshes show the cleared frame
+    /// first.
+    #[allow(clippy::type_complexity)]
+    fn display_centered(
+        &mut self,
+        spi: &mut SPI,
+        image: &[u8],
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), CenteredError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        if !Self::CAPABILITIES.partial_refresh {
+            return Err(CenteredError::NoPartialRefresh);
+        }
+        if width > self.width() || height > self.height() {
+            return Err(CenteredError::TooLarge);
+        }
+        let x = (self.width() - width) / 2 / 8 * 8;
+        let y = (self.height() - height) / 2;
+        self.clear_frame(spi, delay)
+            .map_err(CenteredError::Interface)?;
+        self.update_partial_frame(spi, image, x, y, width, height)
+            .map_err(CenteredError::Interface)?;
+        self.display_frame(spi, delay)
+            .map_err(CenteredError::Interface)
+    }
+
+    /// Renders a [`TestPattern`] into `buffer` and displays it
+    ///
+    /// `buffer` holds a black/white frame, the 7-color displays expect another layout.
+    fn show_test_pattern(
+        &mut self,
+        spi: &mut SPI,
+        pattern: TestPattern,
+        buffer: &mut [u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        pattern.render(buffer, self.width(), self.height());
+        self.update_and_display_frame(spi, buffer, delay)
+    }
+
+    /// Transmits and displays a frame and puts the display to sleep once it's done
+    ///
+    /// The typical update of a battery powered device which wakes up for a new frame.
+    fn update_wait_sleep(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_and_display_frame(spi, buffer, delay)?;
+        self.sleep(spi, delay)
+    }
+}
+
+impl<T, SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplayExt<SPI, CS, BUSY, DC, RST, DELAY> for T
+where
+    T: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns() {
+        // 16x16 pixels, two bytes per row
+        let mut buffer = [0u8; 16 / 8 * 16];
+
+        TestPattern::Checkerboard.render(&mut buffer, 16, 16);
+        assert_eq!(buffer[..2], [0x00, 0xFF]);
+        assert_eq!(buffer[16..18], [0xFF, 0x00]);
+
+        TestPattern::HorizontalStripes.render(&mut buffer, 16, 16);
+        assert_eq!(buffer[14..18], [0x00, 0x00, 0xFF, 0xFF]);
+
+        TestPattern::VerticalStripes.render(&mut buffer, 16, 16);
+        assert!(buffer.chunks(2).all(|row| row == [0x00, 0xFF]));
+
+        TestPattern::Border.render(&mut buffer, 16, 16);
+        assert_eq!(buffer[..2], [0x00, 0x00]);
+        assert_eq!(buffer[2..4], [0x7F, 0xFE]);
+        assert_eq!(buffer[30..], [0x00, 0x00]);
+    }
+
+    #[test]
+    fn border_of_unaligned_width() {
+        // 12x3 pixels, the last 4 bits of every row are padding
+        let mut buffer = [0u8; 2 * 3];
+        TestPattern::Border.render(&mut buffer, 12, 3);
+        assert_eq!(buffer[2..4], [0x7F, 0xEF]);
+    }
+
+    #[test]
+    fn patterns_of_empty_displays() {
+        let mut buffer = [0xAAu8; 2];
+        TestPattern::Border.render(&mut buffer, 0, 16);
+        TestPattern::Border.render(&mut buffer, 16, 0);
+        assert_eq!(buffer, [0xAA; 2]);
+    }
+}
@@ -0,0 +1,133 @@
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use embedded_hal::{
+    blocking::{delay::*, i2c::Write},
+    digital::v2::*,
+};
+
+use crate::traits::{Error, PinError};
+
+/// The [Error] of the I2C displays, which have no chip select and no DC pin
+pub type I2cError<I2C, BUSY, RST> = Error<
+    <I2C as Write>::Error,
+    PinError<Infallible, <BUSY as InputPin>::Error, Infallible, <RST as OutputPin>::Error>,
+>;
+
+/// The connection interface of the Waveshare EPDs which are driven via I2C
+///
+/// Unlike the SPI displays these controllers have no DC pin, commands and data are told apart
+/// by the address they are written to.
+pub(crate) struct I2cInterface<I2C, BUSY, RST, DELAY> {
+    /// I2C
+    _i2c: PhantomData<I2C>,
+    /// DELAY
+    _delay: PhantomData<DELAY>,
+    /// Busy pin, whether it is low or high while busy depends on the device
+    busy: BUSY,
+    /// Pin for Resetting
+    rst: RST,
+    /// Address the commands are written to
+    command_address: u8,
+    /// Address the data is written to
+    data_address: u8,
+}
+
+impl<I2C, BUSY, RST, DELAY> I2cInterface<I2C, BUSY, RST, DELAY>
+where
+    I2C: Write,
+    BUSY: InputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Creates a new `I2cInterface` writing to `command_address` and `data_address`
+    pub(crate) fn new(busy: BUSY, rst: RST, command_address: u8, data_address: u8) -> Self {
+        I2cInterface {
+            _i2c: PhantomData,
+            _delay: PhantomData,
+            busy,
+            rst,
+            command_address,
+            data_address,
+        }
+    }
+
+    /// Basic function for sending a single command byte
+    pub(crate) fn cmd(
+        &mut self,
+        i2c: &mut I2C,
+        command: u8,
+    ) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        i2c.write(self.command_address, &[command])
+            .map_err(Error::Spi)
+    }
+
+    /// Basic function for sending an array of u8-values of data over i2c
+    pub(crate) fn data(
+        &mut self,
+        i2c: &mut I2C,
+        data: &[u8],
+    ) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        i2c.write(self.data_address, data).map_err(Error::Spi)
+    }
+
+    /// Sends the commands one after the other, each in its own write
+    pub(crate) fn cmds(
+        &mut self,
+        i2c: &mut I2C,
+        commands: &[u8],
+    ) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        for &command in commands {
+            self.cmd(i2c, command)?;
+        }
+        Ok(())
+    }
+
+    /// Waits until the device isn't busy anymore, see [is_busy()](I2cInterface::is_busy())
+    pub(crate) fn wait_until_idle(
+        &mut self,
+        is_busy_low: bool,
+    ) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        while self.read_busy(is_busy_low)? {}
+        Ok(())
+    }
+
+    /// Checks if the device is still busy, a failed read counts as idle
+    pub(crate) fn is_busy(&self, is_busy_low: bool) -> bool {
+        self.read_busy(is_busy_low).unwrap_or(false)
+    }
+
+    /// Checks like [is_busy()](I2cInterface::is_busy()), but returns a failed read
+    fn read_busy(&self, is_busy_low: bool) -> Result<bool, I2cError<I2C, BUSY, RST>> {
+        if is_busy_low {
+            self.busy.is_low()
+        } else {
+            self.busy.is_high()
+        }
+        .map_err(|e| Error::Pin(PinError::Busy(e)))
+    }
+
+    /// Resets the device, keeping the reset pin low for `duration` ms
+    pub(crate) fn reset(
+        &mut self,
+        delay: &mut DELAY,
+        duration: u8,
+    ) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        self.set_rst(true)?;
+        delay.delay_ms(200);
+
+        self.set_rst(false)?;
+        delay.delay_ms(duration);
+        self.set_rst(true)?;
+        delay.delay_ms(200);
+        Ok(())
+    }
+
+    fn set_rst(&mut self, high: bool) -> Result<(), I2cError<I2C, BUSY, RST>> {
+        if high {
+            self.rst.set_high()
+        } else {
+            self.rst.set_low()
+        }
+        .map_err(|e| Error::Pin(PinError::Rst(e)))
+    }
+}
@@ -1,7 +1,31 @@
-use crate::traits::Command;
+#[cfg(feature = "graphics")]
+use crate::graphics::{DisplayRotation, Orientation};
+use crate::planes::split_planes;
+use crate::settings::InitStep;
+use crate::time::{Clock, Stopwatch};
+#[cfg(feature = "graphics")]
+use crate::traits::OrientationSetting;
+use crate::traits::{
+    AbortableClear, BatteryCheck, BounceBuffering, BusyState, BusyTimeoutSetting, BusyTimeouts,
+    ChangedPlanes, Command, DisplayError, DriverSnapshot, DriverState, Error, ErrorKind,
+    FrameTransform, InterruptedUpload, LifecycleEvent, LifecycleHooks, Link, PinError, RefreshLut,
+    RefreshState, Snapshot, Telemetry, TimeoutClass, UploadProgress, VoltageGuard,
+    WaveshareDisplay, DEFAULT_ULTRAFAST_LIMIT,
+};
+use crate::{buffer_len, FRAME_CHUNK_LEN};
+use core::cell::Cell;
 use core::marker::PhantomData;
+
+/// How long the busy pin is given to respond to a ping, in ms, see [`DisplayInterface::ping`]
+const PING_MS: u8 = 10;
+/// How long the busy pin is given to report a refresh, in ms, see
+/// [`DisplayInterface::wait_for_refresh_start`]
+const REFRESH_START_MS: u8 = 10;
 use embedded_hal::{
-    blocking::{delay::*, spi::Write},
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
     digital::v2::*,
 };
 
@@ -20,6 +44,289 @@ pub(crate) struct DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY> {
     dc: DC,
     /// Pin for Resetting
     rst: RST,
+    /// Settings of the application and what is reported back to it
+    state: InterfaceState,
+    /// Why the display is busy, i.e. the last started long running operation
+    busy_reason: BusyState,
+    /// Whether the controller was only powered off and kept its configuration, see [FastWake](crate::traits::FastWake)
+    retained: bool,
+    /// Whether the display was reset since the driver was created
+    initialized: bool,
+    /// Number of writes to the display, wraps around
+    writes: u32,
+    /// Planes sent last by [PlaneTracking](crate::traits::PlaneTracking)
+    sent_planes: Option<SentPlanes>,
+    /// Temperature of an external sensor, see [TemperatureSetting](crate::traits::TemperatureSetting)
+    temperature: Option<i8>,
+}
+
+/// The part of a [DisplayInterface] which doesn't depend on the bus and the pins
+///
+/// The hooks and settings of the application and the state reported back to it. The traits
+/// which only deal with these are implemented once for all drivers, see [InterfaceAccess].
+pub(crate) struct InterfaceState {
+    /// Hooks which are called while frame data is sent
+    frame_hooks: FrameHooks,
+    /// Clock in milliseconds for the busy timeouts, they are disabled without it
+    busy_clock: Option<Clock>,
+    /// Timeouts of the busy waits
+    busy_timeouts: BusyTimeouts,
+    /// Sleep between the polls of the waits with a delay, counted against the timeouts without a clock
+    busy_tick: Option<u8>,
+    /// Class of the last busy wait which timed out
+    timed_out: Option<TimeoutClass>,
+    /// Battery check before full refreshes
+    battery_check: Option<BatteryCheck>,
+    /// Called on lifecycle events
+    lifecycle_hook: Option<fn(LifecycleEvent)>,
+    /// Orientation of the areas of partial updates
+    #[cfg(feature = "graphics")]
+    orientation: Orientation,
+    /// Counters, see [Telemetry](crate::traits::Telemetry)
+    counters: DriverState,
+    /// Ultrafast refreshes since the last full one, see [UltrafastRefresh](crate::traits::UltrafastRefresh)
+    ghosting: u16,
+    /// Ultrafast refreshes allowed before a full one
+    ultrafast_limit: u16,
+    /// Kind of the last error, a cell so that the failed reads of [DisplayInterface::busy_state] are kept too
+    last_error: Cell<Option<ErrorKind>>,
+    /// Measures the running refresh with the busy clock
+    refresh_started: Option<Stopwatch>,
+    /// Whether a clear was aborted
+    aborted: bool,
+    /// Whether the last clear was aborted, the drivers send nothing more after it
+    clear_aborted: bool,
+}
+
+impl InterfaceState {
+    fn new() -> Self {
+        InterfaceState {
+            frame_hooks: FrameHooks::default(),
+            busy_clock: None,
+            busy_timeouts: BusyTimeouts::default(),
+            busy_tick: None,
+            timed_out: None,
+            battery_check: None,
+            lifecycle_hook: None,
+            #[cfg(feature = "graphics")]
+            orientation: Orientation::new(DisplayRotation::Rotate0),
+            counters: DriverState::default(),
+            ghosting: 0,
+            ultrafast_limit: DEFAULT_ULTRAFAST_LIMIT,
+            last_error: Cell::new(None),
+            refresh_started: None,
+            aborted: false,
+            clear_aborted: false,
+        }
+    }
+
+    /// Returns the snapshot of a driver with its background color and selected refresh LUT
+    pub(crate) fn snapshot<C>(
+        &self,
+        background_color: C,
+        refresh: RefreshLut,
+    ) -> DriverSnapshot<C> {
+        DriverSnapshot {
+            background_color,
+            refresh,
+            ghosting: self.ghosting,
+            #[cfg(feature = "graphics")]
+            orientation: self.orientation,
+        }
+    }
+
+    /// Restores the part of a snapshot which is kept here, the driver restores the rest
+    pub(crate) fn restore<C>(&mut self, snapshot: &DriverSnapshot<C>) {
+        self.ghosting = snapshot.ghosting;
+        #[cfg(feature = "graphics")]
+        {
+            self.orientation = snapshot.orientation;
+        }
+    }
+
+    /// Returns the state of the driver with its selected refresh LUT
+    pub(crate) fn driver_state(&self, refresh: RefreshLut) -> DriverState {
+        DriverState {
+            refresh,
+            full_refresh_due: self.ghosting >= self.ultrafast_limit,
+            last_error: self.last_error.get(),
+            ..self.counters
+        }
+    }
+
+    /// Updates the [DriverState] and calls the lifecycle hook, if any is set
+    fn emit(&mut self, event: LifecycleEvent) {
+        match event {
+            LifecycleEvent::AfterReset => self.counters.asleep = false,
+            LifecycleEvent::Sleep => self.counters.asleep = true,
+            LifecycleEvent::RefreshStart => {
+                self.counters.refreshes = self.counters.refreshes.wrapping_add(1);
+                self.refresh_started = Some(Stopwatch::start(self.busy_clock));
+            }
+            LifecycleEvent::RefreshEnd => {
+                if let Some(elapsed) = self.refresh_started.take().and_then(|s| s.elapsed()) {
+                    self.counters.last_refresh_ms = Some(elapsed);
+                }
+            }
+            LifecycleEvent::BeforeReset | LifecycleEvent::PowerOn => {}
+        }
+        if let Some(hook) = self.lifecycle_hook {
+            hook(event);
+        }
+    }
+
+    /// Keeps `kind` as the last error
+    fn set_error(&self, kind: ErrorKind) {
+        self.last_error.set(Some(kind));
+    }
+}
+
+/// Drivers which talk to the display through a [DisplayInterface]
+///
+/// Gives access to its [InterfaceState], the traits which only deal with that, like
+/// [FrameTransform] or [BusyTimeoutSetting], are implemented for all of these drivers below.
+pub(crate) trait InterfaceAccess {
+    /// The state of the interface
+    fn interface_state(&self) -> &InterfaceState;
+
+    /// The state of the interface, to change its settings
+    fn interface_state_mut(&mut self) -> &mut InterfaceState;
+}
+
+impl<T: InterfaceAccess> FrameTransform for T {
+    fn set_frame_transform(&mut self, transform: Option<fn(&mut [u8])>) {
+        self.interface_state_mut().frame_hooks.transform = transform;
+    }
+}
+
+impl<T: InterfaceAccess> UploadProgress for T {
+    fn set_upload_progress(&mut self, progress: Option<fn(usize, usize)>) {
+        self.interface_state_mut().frame_hooks.progress = progress;
+    }
+}
+
+impl<T: InterfaceAccess> BounceBuffering for T {
+    fn set_bounce_buffering(&mut self, enabled: bool) {
+        self.interface_state_mut().frame_hooks.bounce = enabled;
+    }
+}
+
+impl<T: InterfaceAccess> AbortableClear for T {
+    fn set_clear_abort(&mut self, abort: Option<fn() -> bool>) {
+        self.interface_state_mut().frame_hooks.abort = abort;
+    }
+
+    fn take_aborted(&mut self) -> bool {
+        core::mem::replace(&mut self.interface_state_mut().aborted, false)
+    }
+}
+
+impl<T: InterfaceAccess> BusyTimeoutSetting for T {
+    fn set_busy_timeouts(&mut self, clock: Option<fn() -> u32>, timeouts: BusyTimeouts) {
+        let state = self.interface_state_mut();
+        state.busy_clock = clock;
+        state.busy_tick = None;
+        state.busy_timeouts = timeouts;
+    }
+
+    fn set_busy_tick_timeouts(&mut self, tick: u8, timeouts: BusyTimeouts) {
+        let state = self.interface_state_mut();
+        state.busy_clock = None;
+        state.busy_tick = Some(tick);
+        state.busy_timeouts = timeouts;
+    }
+
+    fn take_timeout(&mut self) -> Option<TimeoutClass> {
+        self.interface_state_mut().timed_out.take()
+    }
+}
+
+impl<T: InterfaceAccess> LifecycleHooks for T {
+    fn set_lifecycle_hook(&mut self, hook: Option<fn(LifecycleEvent)>) {
+        self.interface_state_mut().lifecycle_hook = hook;
+    }
+}
+
+impl<T: InterfaceAccess + Snapshot> Telemetry for T {
+    fn driver_state(&self) -> DriverState {
+        self.interface_state().driver_state(self.snapshot().refresh)
+    }
+}
+
+impl<T, SPI, CS, BUSY, DC, RST, DELAY> VoltageGuard<SPI, CS, BUSY, DC, RST, DELAY> for T
+where
+    T: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY> + Telemetry + InterfaceAccess,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_battery_check(&mut self, check: Option<BatteryCheck>) {
+        self.interface_state_mut().battery_check = check;
+    }
+
+    fn battery_check(&self) -> Option<BatteryCheck> {
+        self.interface_state().battery_check
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl<T, SPI, CS, BUSY, DC, RST, DELAY> OrientationSetting<SPI, CS, BUSY, DC, RST, DELAY> for T
+where
+    T: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY> + InterfaceAccess,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_orientation(&mut self, orientation: Orientation) {
+        self.interface_state_mut().orientation = orientation;
+    }
+
+    fn orientation(&self) -> Orientation {
+        self.interface_state().orientation
+    }
+}
+
+/// Fingerprints of the planes sent by [PlaneTracking](crate::traits::PlaneTracking)
+#[derive(Clone, Copy)]
+struct SentPlanes {
+    fingerprints: [u32; 2],
+    /// Number of writes right after the planes were sent and displayed
+    writes: u32,
+}
+
+/// Optional user hooks for the transmission of frame data
+#[derive(Clone, Copy, Default)]
+pub(crate) struct FrameHooks {
+    /// Applied to every chunk of frame data before it is sent
+    pub(crate) transform: Option<fn(&mut [u8])>,
+    /// Called with the bytes sent so far and the total after every chunk
+    pub(crate) progress: Option<fn(usize, usize)>,
+    /// Polled before every chunk of a clear, stops the clear when it returns `true`
+    pub(crate) abort: Option<fn() -> bool>,
+    /// Copies the frame data into an aligned buffer on the stack before it is sent
+    pub(crate) bounce: bool,
+}
+
+/// Buffer for one chunk of frame data, aligned for DMA transfers
+#[repr(align(4))]
+struct ChunkBuffer([u8; FRAME_CHUNK_LEN]);
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// Returns the settings and the reported state, see [InterfaceAccess]
+    pub(crate) fn state(&self) -> &InterfaceState {
+        &self.state
+    }
+
+    /// Returns the settings and the reported state to change them
+    pub(crate) fn state_mut(&mut self) -> &mut InterfaceState {
+        &mut self.state
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>
@@ -33,21 +340,154 @@ where
 {
     pub fn new(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
         DisplayInterface {
-            _spi: PhantomData::default(),
-            _delay: PhantomData::default(),
+            _spi: PhantomData,
+            _delay: PhantomData,
             cs,
             busy,
             dc,
             rst,
+            state: InterfaceState::new(),
+            busy_reason: BusyState::PoweringUp,
+            retained: false,
+            initialized: false,
+            writes: 0,
+            sent_planes: None,
+            temperature: None,
+        }
+    }
+
+    /// Marks the display idle once the busy pin released it, whatever it was busy with
+    ///
+    /// Sends [LifecycleEvent::RefreshEnd] if it was a refresh.
+    fn end_busy(&mut self) {
+        if core::mem::replace(&mut self.busy_reason, BusyState::Idle) == BusyState::Refreshing {
+            self.emit(LifecycleEvent::RefreshEnd);
+        }
+    }
+
+    /// Sets what the display is busy with until it's idle again, see [BusyState]
+    ///
+    /// Sends the [LifecycleEvent] of the started operation.
+    pub(crate) fn set_busy_reason(&mut self, reason: BusyState) {
+        self.busy_reason = reason;
+        match reason {
+            BusyState::Idle => {}
+            BusyState::PoweringUp => self.emit(LifecycleEvent::PowerOn),
+            BusyState::Refreshing => self.emit(LifecycleEvent::RefreshStart),
+        }
+    }
+
+    /// Updates the [DriverState] and calls the lifecycle hook, if any is set
+    pub(crate) fn emit(&mut self, event: LifecycleEvent) {
+        self.state.emit(event);
+    }
+
+    /// Whether the display was reset and initialized, see [LazyInit](crate::traits::LazyInit)
+    pub(crate) fn initialized(&self) -> bool {
+        self.initialized
+    }
+
+    /// Marks whether the controller still has its configuration while the panel is powered off
+    pub(crate) fn set_retained(&mut self, retained: bool) {
+        self.retained = retained;
+    }
+
+    /// Whether [FastWake::wake_up_fast](crate::traits::FastWake::wake_up_fast) can skip the init sequence
+    pub(crate) fn retained(&self) -> bool {
+        self.retained
+    }
+
+    /// Returns which planes differ from the ones sent last by [PlaneTracking](crate::traits::PlaneTracking)
+    ///
+    /// Both planes changed if anything else was written to the display since.
+    pub(crate) fn changed_planes(&self, black: &[u8], chromatic: &[u8]) -> ChangedPlanes {
+        match self.sent_planes {
+            Some(sent) if sent.writes == self.writes => ChangedPlanes {
+                achromatic: sent.fingerprints[0] != fingerprint(black),
+                chromatic: sent.fingerprints[1] != fingerprint(chromatic),
+            },
+            _ => ChangedPlanes {
+                achromatic: true,
+                chromatic: true,
+            },
+        }
+    }
+
+    /// Remembers the planes which are on the display now
+    pub(crate) fn planes_sent(&mut self, black: &[u8], chromatic: &[u8]) {
+        self.sent_planes = Some(SentPlanes {
+            fingerprints: [fingerprint(black), fingerprint(chromatic)],
+            writes: self.writes,
+        });
+    }
+
+    /// Forgets the planes which were sent, e.g. when the memory of the controller was lost
+    pub(crate) fn forget_planes(&mut self) {
+        self.sent_planes = None;
+    }
+
+    /// Sets the number of ultrafast refreshes before a full one
+    pub(crate) fn set_ultrafast_limit(&mut self, limit: u16) {
+        self.state.ultrafast_limit = limit;
+    }
+
+    /// Returns the number of ultrafast refreshes before a full one
+    pub(crate) fn ultrafast_limit(&self) -> u16 {
+        self.state.ultrafast_limit
+    }
+
+    /// Sets the temperature of an external sensor in °C, `None` for the internal sensor
+    pub(crate) fn set_temperature(&mut self, celsius: Option<i8>) {
+        self.temperature = celsius;
+    }
+
+    /// Returns the temperature of the external sensor, `None` while the internal one is used
+    pub(crate) fn temperature(&self) -> Option<i8> {
+        self.temperature
+    }
+
+    /// Returns the number of ultrafast refreshes since the last full one
+    pub(crate) fn ghosting(&self) -> u16 {
+        self.state.ghosting
+    }
+
+    /// Counts a refresh with the LUT `refresh` and returns the LUT it has to be done with
+    ///
+    /// This is [`RefreshLut::Full`] for an ultrafast refresh once the limit is reached.
+    pub(crate) fn count_refresh(&mut self, refresh: RefreshLut) -> RefreshLut {
+        let state = &mut self.state;
+        match refresh {
+            RefreshLut::Ultrafast if state.ghosting >= state.ultrafast_limit => {
+                state.ghosting = 0;
+                RefreshLut::Full
+            }
+            RefreshLut::Ultrafast => {
+                state.ghosting += 1;
+                RefreshLut::Ultrafast
+            }
+            RefreshLut::Full => {
+                state.ghosting = 0;
+                RefreshLut::Full
+            }
+            RefreshLut::Quick => RefreshLut::Quick,
         }
     }
 
+    /// Returns the currently set frame hooks
+    pub(crate) fn frame_hooks(&self) -> FrameHooks {
+        self.state.frame_hooks
+    }
+
     /// Basic function for sending [Commands](Command).
     ///
     /// Enables direct interaction with the device with the help of [data()](DisplayInterface::data())
-    pub(crate) fn cmd<T: Command>(&mut self, spi: &mut SPI, command: T) -> Result<(), SPI::Error> {
+    pub(crate) fn cmd<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         // low for commands
-        let _ = self.dc.set_low();
+        self.set_dc(false)?;
 
         // Transfer the command over spi
         self.write(spi, &[command.address()])
@@ -56,9 +496,13 @@ where
     /// Basic function for sending an array of u8-values of data over spi
     ///
     /// Enables direct interaction with the device with the help of [command()](Epd4in2::command())
-    pub(crate) fn data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+    pub(crate) fn data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         // high for data
-        let _ = self.dc.set_high();
+        self.set_dc(true)?;
 
         for val in data.iter().copied() {
             // Transfer data one u8 at a time over spi
@@ -76,11 +520,133 @@ where
         spi: &mut SPI,
         command: T,
         data: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         self.cmd(spi, command)?;
         self.data(spi, data)
     }
 
+    /// Sends the steps of an init sequence, see [InitSequence](crate::traits::InitSequence)
+    pub(crate) fn run_init_sequence(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        steps: &[InitStep],
+        is_busy_low: bool,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.run_init_sequence_with(spi, delay, steps, |interface, _, delay| {
+            interface.wait_until_idle_ticked(is_busy_low, delay)
+        })
+    }
+
+    /// Sends the steps of an init sequence like [run_init_sequence()](DisplayInterface::run_init_sequence()),
+    /// but waits with `wait`, e.g. for the controllers which only update the busy pin when
+    /// their status is read
+    pub(crate) fn run_init_sequence_with(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        steps: &[InitStep],
+        mut wait: impl FnMut(
+            &mut Self,
+            &mut SPI,
+            &mut DELAY,
+        ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        for step in steps {
+            self.cmd(spi, step.command)?;
+            if !step.data.is_empty() {
+                self.data(spi, step.data)?;
+            }
+            if step.delay_ms > 0 {
+                delay.delay_ms(step.delay_ms);
+            }
+            if step.wait_until_idle {
+                wait(self, spi, delay)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends frame data, passing it through the [frame hooks](FrameHooks) if any are set
+    ///
+    /// Unlike [data()](DisplayInterface::data()) this must only be used for the content of frame
+    /// buffers and never for command parameters.
+    pub(crate) fn frame_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let hooks = self.state.frame_hooks;
+        for_each_frame_chunk(hooks, data, |chunk| self.data(spi, chunk))
+    }
+
+    /// Sends frame data from `offset` on in chunks of [FRAME_CHUNK_LEN] bytes
+    ///
+    /// If a chunk fails, the error tells at which offset the upload can be resumed,
+    /// see [ResumableUpload](crate::traits::ResumableUpload). The progress is reported
+    /// for the whole frame.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn frame_data_from(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+        offset: usize,
+    ) -> Result<(), InterruptedUpload<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        let hooks = FrameHooks {
+            progress: None,
+            ..self.state.frame_hooks
+        };
+        let progress = self.state.frame_hooks.progress;
+        let mut sent = offset.min(data.len());
+        for chunk in data[sent..].chunks(FRAME_CHUNK_LEN) {
+            for_each_frame_chunk(hooks, chunk, |chunk| self.data(spi, chunk))
+                .map_err(|error| InterruptedUpload { error, sent })?;
+            sent += chunk.len();
+            if let Some(progress) = progress {
+                progress(sent, data.len());
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a [Command](Command) followed by frame data, see [frame_data()](DisplayInterface::frame_data())
+    pub(crate) fn cmd_with_frame_data<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.cmd(spi, command)?;
+        self.frame_data(spi, data)
+    }
+
+    /// Sends a [Command](Command) followed by one plane of a 2 bit grayscale frame
+    ///
+    /// `high` selects the plane of the high bits, see [split_planes]. The frame hooks work on
+    /// the grayscale data as it was passed in, like with [frame_data()](DisplayInterface::frame_data()).
+    ///
+    /// Fails like [check_window()](DisplayInterface::check_window()) unless `gray` holds
+    /// exactly two planes of `plane_len` bytes.
+    pub(crate) fn cmd_with_gray_plane<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+        gray: &[u8],
+        plane_len: usize,
+        high: bool,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.check_window(Some(gray.len()) == plane_len.checked_mul(2))?;
+        self.cmd(spi, command)?;
+        let hooks = self.state.frame_hooks;
+        let mut planes = ([0u8; FRAME_CHUNK_LEN / 2], [0u8; FRAME_CHUNK_LEN / 2]);
+        for_each_chunk(hooks, gray, |pairs| {
+            let len = pairs.len() / 2;
+            let (high_plane, low_plane) = (&mut planes.0[..len], &mut planes.1[..len]);
+            split_planes(pairs, high_plane, low_plane);
+            self.data(spi, if high { high_plane } else { low_plane })
+        })
+    }
+
     /// Basic function for sending the same byte of data (one u8) multiple times over spi
     ///
     /// Enables direct interaction with the device with the help of [command()](ConnectionInterface::command())
@@ -89,9 +655,9 @@ where
         spi: &mut SPI,
         val: u8,
         repetitions: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
         // high for data
-        let _ = self.dc.set_high();
+        self.set_dc(true)?;
         // Transfer data (u8) over spi
         for _ in 0..repetitions {
             self.write(spi, &[val])?;
@@ -99,26 +665,72 @@ where
         Ok(())
     }
 
+    /// Sends the same byte of data `repetitions` times to clear a plane of the display memory
+    ///
+    /// Like [data_x_times()](DisplayInterface::data_x_times()), but in chunks of
+    /// [FRAME_CHUNK_LEN] bytes. The progress is reported after every chunk and the
+    /// [abort check](FrameHooks::abort) is polled before it. An aborted clear stops without
+    /// an error, the rest of the plane keeps its old content. The drivers check
+    /// [clear_aborted()](DisplayInterface::clear_aborted()) after it and return before the
+    /// next plane and the refresh.
+    pub(crate) fn clear_data(
+        &mut self,
+        spi: &mut SPI,
+        val: u8,
+        repetitions: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let hooks = self.state.frame_hooks;
+        self.state.clear_aborted = false;
+        if hooks.progress.is_none() && hooks.abort.is_none() {
+            return self.data_x_times(spi, val, repetitions);
+        }
+
+        let total = repetitions as usize;
+        let mut sent = 0;
+        while sent < total {
+            if hooks.abort.is_some_and(|abort| abort()) {
+                self.state.aborted = true;
+                self.state.clear_aborted = true;
+                return Ok(());
+            }
+            let len = (total - sent).min(FRAME_CHUNK_LEN);
+            self.data_x_times(spi, val, len as u32)?;
+            sent += len;
+            if let Some(progress) = hooks.progress {
+                progress(sent, total);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the last [clear_data()](DisplayInterface::clear_data()) was aborted
+    pub(crate) fn clear_aborted(&self) -> bool {
+        self.state.clear_aborted
+    }
+
     // spi write helper/abstraction function
-    fn write(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+    fn write(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.writes = self.writes.wrapping_add(1);
         // activate spi with cs low
-        let _ = self.cs.set_low();
+        self.set_cs(false)?;
 
         // transfer spi data
         // Be careful!! Linux has a default limit of 4096 bytes per spi transfer
         // see https://raspberrypi.stackexchange.com/questions/65595/spi-transfer-fails-with-buffer-size-greater-than-4096
-        if cfg!(target_os = "linux") {
-            for data_chunk in data.chunks(4096) {
-                spi.write(data_chunk)?;
-            }
+        let result = if cfg!(target_os = "linux") {
+            data.chunks(4096)
+                .try_for_each(|data_chunk| spi.write(data_chunk))
         } else {
-            spi.write(data)?;
-        }
+            spi.write(data)
+        };
+        self.check(result.map_err(Error::Spi))?;
 
         // deactivate spi with cs high
-        let _ = self.cs.set_high();
-
-        Ok(())
+        self.set_cs(true)
     }
 
     /// Waits until device isn't busy anymore (busy == HIGH)
@@ -134,14 +746,74 @@ where
     ///
     /// Most likely there was a mistake with the 2in9 busy connection
     /// //TODO: use the #cfg feature to make this compile the right way for the certain types
-    pub(crate) fn wait_until_idle(&mut self, is_busy_low: bool) {
-        // //tested: worked without the delay for all tested devices
-        // //self.delay_ms(1);
-        while self.is_busy(is_busy_low) {
-            // //tested: REMOVAL of DELAY: it's only waiting for the signal anyway and should continue work asap
-            // //old: shorten the time? it was 100 in the beginning
-            // //self.delay_ms(5);
+    ///
+    /// With a [busy clock](DisplayInterface::set_busy_timeouts) the wait is given up after the
+    /// timeout of the [class](TimeoutClass) of the current [busy reason](BusyState).
+    pub(crate) fn wait_until_idle(
+        &mut self,
+        is_busy_low: bool,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        // //tested: REMOVAL of DELAY: it's only waiting for the signal anyway and should continue work asap
+        // //old: shorten the time? it was 100 in the beginning
+        self.wait_until_idle_with(is_busy_low, |_| Ok(0))
+    }
+
+    /// Waits like [wait_until_idle()](DisplayInterface::wait_until_idle()), but sleeps for
+    /// `interval` ms between the polls of the busy pin
+    ///
+    /// For the refreshes of many seconds, which would otherwise poll the pin in a busy loop.
+    /// Only delays of `interval` are passed to `delay`, however long the wait takes.
+    pub(crate) fn wait_until_idle_delayed(
+        &mut self,
+        is_busy_low: bool,
+        delay: &mut DELAY,
+        interval: u8,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle_with(is_busy_low, |_| {
+            delay.delay_ms(interval);
+            Ok(u32::from(interval))
+        })
+    }
+
+    /// Waits like [wait_until_idle()](DisplayInterface::wait_until_idle()), but sleeps for the
+    /// [busy tick](DisplayInterface::set_busy_tick_timeouts) between the polls, if there is one
+    ///
+    /// So the wait also times out without a clock.
+    pub(crate) fn wait_until_idle_ticked(
+        &mut self,
+        is_busy_low: bool,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        match self.state.busy_tick {
+            Some(tick) => self.wait_until_idle_delayed(is_busy_low, delay, tick),
+            None => self.wait_until_idle(is_busy_low),
+        }
+    }
+
+    /// Polls until the display is idle, `between_polls` returns the ms it slept
+    ///
+    /// Fails with [Error::BusyTimeout] once the timeout of the current
+    /// [busy reason](BusyState) is reached.
+    pub(crate) fn wait_until_idle_with(
+        &mut self,
+        is_busy_low: bool,
+        mut between_polls: impl FnMut(&mut Self) -> Result<u32, DisplayError<SPI, CS, BUSY, DC, RST>>,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let class = TimeoutClass::from(self.busy_reason);
+        let timeout = self.state.busy_timeouts.of(class);
+        let stopwatch = Stopwatch::start(self.state.busy_clock);
+        // without a clock the slept time is all there is to measure
+        let count_sleeps = self.state.busy_clock.is_none() && self.state.busy_tick.is_some();
+        let mut slept: u32 = 0;
+        while self.read_busy(is_busy_low)? {
+            slept = slept.saturating_add(between_polls(self)?);
+            if stopwatch.expired(timeout) || (count_sleeps && slept >= timeout) {
+                self.state.timed_out = Some(class);
+                return self.check(Err(Error::BusyTimeout(class)));
+            }
         }
+        self.end_busy();
+        Ok(())
     }
 
     /// Checks if device is still busy
@@ -157,27 +829,1347 @@ where
     ///
     /// Most likely there was a mistake with the 2in9 busy connection
     /// //TODO: use the #cfg feature to make this compile the right way for the certain types
+    ///
+    /// A failed read counts as idle and is kept as the last error, see
+    /// [read_busy()](DisplayInterface::read_busy()) to get it.
     pub(crate) fn is_busy(&self, is_busy_low: bool) -> bool {
-        (is_busy_low && self.busy.is_low().unwrap_or(false))
-            || (!is_busy_low && self.busy.is_high().unwrap_or(false))
+        self.read_busy(is_busy_low).unwrap_or(false)
     }
 
-    /// Resets the device.
+    /// Reads the busy pin like [is_busy()](DisplayInterface::is_busy()), but returns a failed read
+    pub(crate) fn read_busy(
+        &self,
+        is_busy_low: bool,
+    ) -> Result<bool, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let busy = if is_busy_low {
+            self.busy.is_low()
+        } else {
+            self.busy.is_high()
+        };
+        self.check(busy.map_err(|e| Error::Pin(PinError::Busy(e))))
+    }
+
+    /// Sets the chip select pin
+    fn set_cs(&mut self, high: bool) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let set = set_level(&mut self.cs, high);
+        self.check(set.map_err(|e| Error::Pin(PinError::Cs(e))))
+    }
+
+    /// Sets the data/command pin
+    fn set_dc(&mut self, high: bool) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let set = set_level(&mut self.dc, high);
+        self.check(set.map_err(|e| Error::Pin(PinError::Dc(e))))
+    }
+
+    /// Sets the reset pin
+    fn set_rst(&mut self, high: bool) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let set = set_level(&mut self.rst, high);
+        self.check(set.map_err(|e| Error::Pin(PinError::Rst(e))))
+    }
+
+    /// Keeps the kind of a failure as the last error and passes the result on
+    pub(crate) fn check<T>(
+        &self,
+        result: Result<T, DisplayError<SPI, CS, BUSY, DC, RST>>,
+    ) -> Result<T, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if let Err(error) = &result {
+            self.state.set_error(error.kind());
+        }
+        result
+    }
+
+    /// Fails with [`Error::InvalidWindow`], kept as the last error, unless the window is `valid`
+    pub(crate) fn check_window(
+        &self,
+        valid: bool,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        match valid {
+            true => Ok(()),
+            false => self.check(Err(Error::InvalidWindow)),
+        }
+    }
+
+    /// Fails like [check_window()](DisplayInterface::check_window()) unless `buffer` holds
+    /// exactly the data of a `width` x `height` window
+    pub(crate) fn check_buffer(
+        &self,
+        buffer: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.check_window(buffer.len() == buffer_len(width as usize, height as usize))
+    }
+
+    /// Returns the last column and line of the `width` x `height` window at `x`, `y`
     ///
-    /// Often used to awake the module from deep sleep. See [Epd4in2::sleep()](Epd4in2::sleep())
+    /// Fails like [check_window()](DisplayInterface::check_window()) for an empty window or
+    /// one that ends beyond `u32::MAX`.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn window_end(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(u32, u32), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let end = |start: u32, len: u32| len.checked_sub(1).and_then(|l| start.checked_add(l));
+        match (end(x, width), end(y, height)) {
+            (Some(end_x), Some(end_y)) => Ok((end_x, end_y)),
+            _ => self.check(Err(Error::InvalidWindow)),
+        }
+    }
+
+    /// Fails like [check_window()](DisplayInterface::check_window()) unless the `width` x
+    /// `height` window at `x`, `y` isn't empty and lies on a panel of `panel` pixels
     ///
-    /// The timing of keeping the reset pin low seems to be important and different per device.
-    /// Most displays seem to require keeping it low for 10ms, but the 7in5_v2 only seems to reset
-    /// properly with 2ms
-    pub(crate) fn reset(&mut self, delay: &mut DELAY, duration: u8) {
-        let _ = self.rst.set_high();
-        delay.delay_ms(10);
+    /// A line of the window may end with the last byte of a panel line, like the windows of
+    /// [partial_window()](crate::graphics::partial_window) do.
+    pub(crate) fn check_area(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        panel: (u32, u32),
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        let fits = |start: u32, len: u32, size: u32| {
+            len > 0 && start.checked_add(len).is_some_and(|end| end <= size)
+        };
+        self.check_window(fits(x, width, panel.0.div_ceil(8) * 8) && fits(y, height, panel.1))
+    }
 
-        let _ = self.rst.set_low();
-        delay.delay_ms(duration);
-        let _ = self.rst.set_high();
-        //TODO: the upstream libraries always sleep for 200ms here
-        // 10ms works fine with just for the 7in5_v2 but this needs to be validated for other devices
-        delay.delay_ms(200);
+    /// Fails like [check_buffer()](DisplayInterface::check_buffer()) and
+    /// [check_area()](DisplayInterface::check_area()), before anything of a partial update is sent
+    pub(crate) fn check_partial(
+        &self,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        panel: (u32, u32),
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.check_buffer(buffer, width, height)?;
+        self.check_area(x, y, width, height, panel)
+    }
+
+    /// Returns the busy state from the busy pin and the last started operation
+    ///
+    /// See [is_busy()](DisplayInterface::is_busy()) for the polarity
+    pub(crate) fn busy_state(&self, is_busy_low: bool) -> BusyState {
+        if self.is_busy(is_busy_low) {
+            self.busy_reason
+        } else {
+            BusyState::Idle
+        }
+    }
+
+    /// Checks whether a refresh started without waiting is done, see
+    /// [NonBlockingRefresh](crate::traits::NonBlockingRefresh)
+    ///
+    /// Like the end of a wait, the first poll after the refresh reports the end of it.
+    pub(crate) fn poll_refresh(
+        &mut self,
+        is_busy_low: bool,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if self.read_busy(is_busy_low)? {
+            return Ok(RefreshState::Running);
+        }
+        self.end_busy();
+        Ok(RefreshState::Complete)
+    }
+
+    /// Waits until the busy pin reports the refresh activated by the last command
+    ///
+    /// The controllers raise the pin up to a few hundred µs after the activation, so a
+    /// [poll_refresh()](DisplayInterface::poll_refresh()) right after it could still read the
+    /// idle pin and report the refresh as complete before it began. A pin which doesn't follow
+    /// the refresh within [`REFRESH_START_MS`], like the one of a loose cable, is given up on.
+    pub(crate) fn wait_for_refresh_start(
+        &mut self,
+        is_busy_low: bool,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        for _ in 0..REFRESH_START_MS {
+            if self.read_busy(is_busy_low)? {
+                return Ok(());
+            }
+            delay.delay_ms(1);
+        }
+        Ok(())
+    }
+
+    /// Starts a short operation with `start` and checks that the busy pin follows it
+    ///
+    /// See [ConnectionCheck](crate::traits::ConnectionCheck). Waits for a running operation
+    /// first, a timeout of this or of the wait for the pinged operation means the pin is stuck.
+    /// The pin is polled every ms with `delay` and has to respond within [`PING_MS`].
+    pub(crate) fn ping<F>(
+        &mut self,
+        spi: &mut SPI,
+        is_busy_low: bool,
+        delay: &mut DELAY,
+        start: F,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnOnce(&mut Self, &mut SPI) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>,
+    {
+        if !self.initialized || self.state.counters.asleep {
+            return Ok(Link::Asleep);
+        }
+        // keep a timeout of the application, only the ones of the ping are reported by it
+        let pending = self.state.timed_out.take();
+        if let Some(link) = stuck(self.wait_until_idle(is_busy_low))? {
+            return Ok(link);
+        }
+
+        self.set_busy_reason(BusyState::Idle);
+        start(self, spi)?;
+        let mut responded = false;
+        for _ in 0..PING_MS {
+            if self.read_busy(is_busy_low)? {
+                responded = true;
+                break;
+            }
+            delay.delay_ms(1);
+        }
+        if let Some(link) = stuck(self.wait_until_idle(is_busy_low))? {
+            return Ok(link);
+        }
+        self.state.timed_out = pending;
+
+        Ok(if responded {
+            Link::Alive
+        } else {
+            Link::NoResponse
+        })
+    }
+
+    /// Resets the device.
+    ///
+    /// Often used to awake the module from deep sleep. See [Epd4in2::sleep()](Epd4in2::sleep())
+    ///
+    /// The timing of keeping the reset pin low seems to be important and different per device.
+    /// Most displays seem to require keeping it low for 10ms, but the 7in5_v2 only seems to reset
+    /// properly with 2ms
+    pub(crate) fn reset(
+        &mut self,
+        delay: &mut DELAY,
+        duration: u8,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.emit(LifecycleEvent::BeforeReset);
+        self.retained = false;
+        self.sent_planes = None;
+        self.set_rst(true)?;
+        delay.delay_ms(10);
+
+        self.set_rst(false)?;
+        delay.delay_ms(duration);
+        self.set_rst(true)?;
+        //TODO: the upstream libraries always sleep for 200ms here
+        // 10ms works fine with just for the 7in5_v2 but this needs to be validated for other devices
+        delay.delay_ms(200);
+        self.initialized = true;
+        self.emit(LifecycleEvent::AfterReset);
+        self.set_busy_reason(BusyState::PoweringUp);
+        Ok(())
+    }
+}
+
+/// Sets `pin` high or low
+fn set_level<P: OutputPin>(pin: &mut P, high: bool) -> Result<(), P::Error> {
+    if high {
+        pin.set_high()
+    } else {
+        pin.set_low()
+    }
+}
+
+/// Turns a timed out wait of a ping into [Link::StuckBusy]
+fn stuck<S, P>(wait: Result<(), Error<S, P>>) -> Result<Option<Link>, Error<S, P>> {
+    match wait {
+        Err(Error::BusyTimeout(_)) => Ok(Some(Link::StuckBusy)),
+        other => other.map(|()| None),
+    }
+}
+
+/// FNV-1a hash of a plane, to tell whether it changed
+fn fingerprint(plane: &[u8]) -> u32 {
+    plane.iter().fold(0x811C_9DC5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Calls `f` with `data`, or with chunks of [FRAME_CHUNK_LEN] bytes of it if any `hooks` are set
+///
+/// Each chunk is a copy of the data passed through the transform and the progress is
+/// reported after each of them. Used by drivers which need to further process the frame
+/// data before sending it, the hooks always work on the data as it was passed in by the user.
+pub(crate) fn for_each_frame_chunk<E>(
+    hooks: FrameHooks,
+    data: &[u8],
+    mut f: impl FnMut(&[u8]) -> Result<(), E>,
+) -> Result<(), E> {
+    if hooks.transform.is_none() && hooks.progress.is_none() && !hooks.bounce {
+        return f(data);
+    }
+    for_each_chunk(hooks, data, f)
+}
+
+/// Calls `f` with chunks of [FRAME_CHUNK_LEN] bytes of `data`, whether any `hooks` are set or not
+///
+/// See [for_each_frame_chunk], for the drivers which can only process a chunk at a time.
+pub(crate) fn for_each_chunk<E>(
+    hooks: FrameHooks,
+    data: &[u8],
+    mut f: impl FnMut(&[u8]) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut buffer = ChunkBuffer([0u8; FRAME_CHUNK_LEN]);
+    let mut sent = 0;
+    for part in data.chunks(FRAME_CHUNK_LEN) {
+        let chunk = &mut buffer.0[..part.len()];
+        chunk.copy_from_slice(part);
+        if let Some(transform) = hooks.transform {
+            transform(chunk);
+        }
+        f(chunk)?;
+
+        sent += chunk.len();
+        if let Some(progress) = hooks.progress {
+            progress(sent, data.len());
+        }
+    }
+    Ok(())
+}
+
+/// Fills `row` for each of the `rows` rows with `f` and passes it on to `send`
+///
+/// The row is reset to `background` before every call of `f`.
+pub(crate) fn render_rows<E>(
+    rows: u32,
+    row: &mut [u8],
+    background: u8,
+    mut f: impl FnMut(u32, &mut [u8]),
+    mut send: impl FnMut(&[u8]) -> Result<(), E>,
+) -> Result<(), E> {
+    for y in 0..rows {
+        row.iter_mut().for_each(|b| *b = background);
+        f(y, row);
+        send(row)?;
+    }
+    Ok(())
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sends a [Command] and reads its data into `buffer`
+    ///
+    /// The panels have no data output, the controllers answer on their data input. So the
+    /// reads only work if MISO is connected to DIN as well, e.g. through a resistor, and the
+    /// bus lets go of MOSI while reading (3-wire SPI).
+    pub(crate) fn read_data<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+        buffer: &mut [u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.cmd(spi, command)?;
+
+        // high for data
+        self.set_dc(true)?;
+        self.set_cs(false)?;
+        buffer.iter_mut().for_each(|byte| *byte = 0x00);
+        let result = spi.transfer(buffer).map(|_| ());
+        self.check(result.map_err(Error::Spi))?;
+        self.set_cs(true)
+    }
+}
+
+/// Bus and pins for the tests of the drivers
+#[cfg(test)]
+pub(crate) mod mock {
+    extern crate std;
+    use core::cell::Cell;
+    use embedded_hal::{
+        blocking::spi::Write,
+        digital::v2::{InputPin, OutputPin},
+    };
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    /// Pin which ignores all writes and reads low, so a busy pin which is active high is idle
+    pub(crate) struct NoPin;
+    impl OutputPin for NoPin {
+        type Error = ();
+        fn set_low(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+    impl InputPin for NoPin {
+        type Error = ();
+        fn is_high(&self) -> Result<bool, ()> {
+            Ok(false)
+        }
+        fn is_low(&self) -> Result<bool, ()> {
+            Ok(true)
+        }
+    }
+
+    /// Pin which reads high, so a busy pin which is active low is idle
+    pub(crate) struct HighPin;
+    impl InputPin for HighPin {
+        type Error = ();
+        fn is_high(&self) -> Result<bool, ()> {
+            Ok(true)
+        }
+        fn is_low(&self) -> Result<bool, ()> {
+            Ok(false)
+        }
+    }
+
+    /// Bus which records all written bytes, commands and data alike
+    #[derive(Default)]
+    pub(crate) struct RecordingSpi(pub(crate) Vec<u8>);
+    impl Write<u8> for RecordingSpi {
+        type Error = ();
+        fn write(&mut self, words: &[u8]) -> Result<(), ()> {
+            self.0.extend_from_slice(words);
+            Ok(())
+        }
+    }
+
+    impl RecordingSpi {
+        /// Whether `command` was written directly followed by `data`
+        pub(crate) fn sent(&self, command: u8, data: &[u8]) -> bool {
+            self.0
+                .iter()
+                .enumerate()
+                .filter(|&(_, &byte)| byte == command)
+                .any(|(i, _)| self.0[i + 1..].starts_with(data))
+        }
+    }
+
+    /// Bus which records the written bytes together with the level of its [DcPin]
+    #[derive(Default)]
+    pub(crate) struct CommandSpi {
+        /// The written bytes, `true` for the data bytes
+        written: Vec<(bool, u8)>,
+        dc: Rc<Cell<bool>>,
+    }
+    impl Write<u8> for CommandSpi {
+        type Error = ();
+        fn write(&mut self, words: &[u8]) -> Result<(), ()> {
+            let data = self.dc.get();
+            self.written.extend(words.iter().map(|&word| (data, word)));
+            Ok(())
+        }
+    }
+
+    impl CommandSpi {
+        /// The data/command pin to pass to the driver
+        pub(crate) fn dc(&self) -> DcPin {
+            DcPin(self.dc.clone())
+        }
+
+        /// The commands written so far, in order and without their data
+        pub(crate) fn commands(&self) -> Vec<u8> {
+            self.written
+                .iter()
+                .filter(|&&(data, _)| !data)
+                .map(|&(_, command)| command)
+                .collect()
+        }
+
+        /// Whether `command` was written with exactly `data`
+        pub(crate) fn sent(&self, command: u8, data: &[u8]) -> bool {
+            self.written.iter().enumerate().any(|(i, &byte)| {
+                let rest = &self.written[i + 1..];
+                byte == (false, command)
+                    && rest.len() >= data.len()
+                    && rest
+                        .iter()
+                        .zip(data)
+                        .all(|(&sent, &byte)| sent == (true, byte))
+                    && rest.get(data.len()).map_or(true, |&(data, _)| !data)
+            })
+        }
+
+        /// Forgets the bytes written so far
+        pub(crate) fn clear(&mut self) {
+            self.written.clear();
+        }
+    }
+
+    /// Data/command pin which tells its [CommandSpi] whether commands or data are written
+    pub(crate) struct DcPin(Rc<Cell<bool>>);
+    impl OutputPin for DcPin {
+        type Error = ();
+        fn set_low(&mut self) -> Result<(), ()> {
+            self.0.set(false);
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), ()> {
+            self.0.set(true);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::{NoPin, RecordingSpi};
+    use super::*;
+    use crate::traits::PinKind;
+
+    fn invert(chunk: &mut [u8]) {
+        for b in chunk.iter_mut() {
+            *b = !*b;
+        }
+    }
+
+    #[test]
+    fn frame_chunks_untransformed() {
+        let data = [0xAA; FRAME_CHUNK_LEN * 2 + 1];
+        let mut calls = 0;
+        for_each_frame_chunk::<()>(FrameHooks::default(), &data, |chunk| {
+            calls += 1;
+            assert_eq!(chunk, &data[..]);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn frame_chunks_transformed() {
+        let data = [0xAA; FRAME_CHUNK_LEN * 2 + 1];
+        let mut lengths = [0; 3];
+        let mut calls = 0;
+        let hooks = FrameHooks {
+            transform: Some(invert),
+            ..FrameHooks::default()
+        };
+        for_each_frame_chunk::<()>(hooks, &data, |chunk| {
+            assert!(chunk.iter().all(|&b| b == 0x55));
+            lengths[calls] = chunk.len();
+            calls += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(lengths, [FRAME_CHUNK_LEN, FRAME_CHUNK_LEN, 1]);
+        // the original data stays untouched
+        assert!(data.iter().all(|&b| b == 0xAA));
+    }
+
+    #[test]
+    fn frame_chunks_bounced() {
+        let data = [0xAA; FRAME_CHUNK_LEN + 3];
+        let hooks = FrameHooks {
+            bounce: true,
+            ..FrameHooks::default()
+        };
+        let mut sizes = [0; 2];
+        let mut calls = 0;
+        for_each_frame_chunk::<()>(hooks, &data[1..], |chunk| {
+            // copies at an aligned address, even if the data isn't aligned
+            assert_eq!(chunk.as_ptr() as usize % 4, 0);
+            assert_ne!(chunk.as_ptr(), data[1..].as_ptr());
+            assert!(chunk.iter().all(|&b| b == 0xAA));
+            sizes[calls] = chunk.len();
+            calls += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(sizes, [FRAME_CHUNK_LEN, 2]);
+    }
+
+    #[test]
+    fn frame_chunks_progress() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static SENT: AtomicUsize = AtomicUsize::new(0);
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        fn progress(sent: usize, total: usize) {
+            assert_eq!(total, FRAME_CHUNK_LEN * 2 + 1);
+            SENT.store(sent, Ordering::Relaxed);
+            CALLS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let data = [0xAA; FRAME_CHUNK_LEN * 2 + 1];
+        let hooks = FrameHooks {
+            progress: Some(progress),
+            ..FrameHooks::default()
+        };
+        for_each_frame_chunk::<()>(hooks, &data, |chunk| {
+            // without a transform the data stays as it is
+            assert!(chunk.iter().all(|&b| b == 0xAA));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(CALLS.load(Ordering::Relaxed), 3);
+        assert_eq!(SENT.load(Ordering::Relaxed), data.len());
+    }
+
+    #[test]
+    fn rows_rendered_in_order() {
+        let mut row = [0u8; 2];
+        let mut sent = [[0u8; 2]; 3];
+        let mut count = 0;
+        render_rows::<()>(
+            3,
+            &mut row,
+            0xFF,
+            |y, row| row[1] = y as u8,
+            |row| {
+                sent[count].copy_from_slice(row);
+                count += 1;
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(sent, [[0xFF, 0], [0xFF, 1], [0xFF, 2]]);
+    }
+
+    #[test]
+    fn busy_state_from_pin_and_reason() {
+        use embedded_hal_mock::{delay, pin, spi};
+
+        let expectations = [
+            pin::Transaction::get(pin::State::High),
+            pin::Transaction::get(pin::State::Low),
+            pin::Transaction::get(pin::State::Low),
+        ];
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(
+                pin::Mock::new(&[]),
+                pin::Mock::new(&expectations),
+                pin::Mock::new(&[]),
+                pin::Mock::new(&[]),
+            );
+
+        assert_eq!(interface.busy_state(true), BusyState::Idle);
+        assert_eq!(interface.busy_state(true), BusyState::PoweringUp);
+        interface.set_busy_reason(BusyState::Refreshing);
+        assert_eq!(interface.busy_state(true), BusyState::Refreshing);
+
+        interface.busy.done();
+    }
+
+    #[test]
+    fn poll_refresh_ends_the_refresh_once() {
+        use embedded_hal_mock::{delay, pin, spi};
+
+        let expectations = [
+            pin::Transaction::get(pin::State::Low),
+            pin::Transaction::get(pin::State::High),
+        ];
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(
+                pin::Mock::new(&[]),
+                pin::Mock::new(&expectations),
+                pin::Mock::new(&[]),
+                pin::Mock::new(&[]),
+            );
+
+        interface.set_busy_reason(BusyState::Refreshing);
+        assert_eq!(interface.poll_refresh(true), Ok(RefreshState::Running));
+        assert_eq!(interface.poll_refresh(true), Ok(RefreshState::Complete));
+        assert_eq!(interface.busy_reason, BusyState::Idle);
+
+        interface.busy.done();
+    }
+
+    #[test]
+    fn waits_end_every_busy_reason() {
+        use embedded_hal_mock::{delay, pin, spi};
+
+        let expectations = [
+            pin::Transaction::get(pin::State::High),
+            pin::Transaction::get(pin::State::Low),
+        ];
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(
+                pin::Mock::new(&[]),
+                pin::Mock::new(&expectations),
+                pin::Mock::new(&[]),
+                pin::Mock::new(&[]),
+            );
+
+        // as after a reset
+        interface.set_busy_reason(BusyState::PoweringUp);
+        interface.wait_until_idle(true).unwrap();
+        assert_eq!(interface.busy_reason, BusyState::Idle);
+        assert_eq!(interface.busy_state(true), BusyState::Idle);
+
+        interface.busy.done();
+    }
+
+    #[test]
+    fn refresh_start_waits_for_the_busy_pin() {
+        use embedded_hal_mock::{delay, pin, spi};
+
+        // still idle for two polls after the activation, then busy until the end of the refresh
+        let expectations = [
+            pin::Transaction::get(pin::State::High),
+            pin::Transaction::get(pin::State::High),
+            pin::Transaction::get(pin::State::Low),
+            pin::Transaction::get(pin::State::Low),
+            pin::Transaction::get(pin::State::High),
+        ];
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(
+                pin::Mock::new(&[]),
+                pin::Mock::new(&expectations),
+                pin::Mock::new(&[]),
+                pin::Mock::new(&[]),
+            );
+
+        interface.set_busy_reason(BusyState::Refreshing);
+        interface
+            .wait_for_refresh_start(true, &mut delay::MockNoop::new())
+            .unwrap();
+        assert_eq!(interface.poll_refresh(true), Ok(RefreshState::Running));
+        assert_eq!(interface.poll_refresh(true), Ok(RefreshState::Complete));
+
+        interface.busy.done();
+    }
+
+    #[test]
+    fn reset_forgets_retained_state() {
+        use embedded_hal_mock::{delay, pin, spi};
+
+        let expectations = [
+            pin::Transaction::set(pin::State::High),
+            pin::Transaction::set(pin::State::Low),
+            pin::Transaction::set(pin::State::High),
+        ];
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(
+                pin::Mock::new(&[]),
+                pin::Mock::new(&[]),
+                pin::Mock::new(&[]),
+                pin::Mock::new(&expectations),
+            );
+
+        assert!(!interface.retained());
+        interface.set_retained(true);
+        assert!(interface.retained());
+        interface.reset(&mut delay::MockNoop::new(), 10).unwrap();
+        assert!(!interface.retained());
+
+        interface.rst.done();
+    }
+
+    #[test]
+    fn ultrafast_refreshes_until_the_limit() {
+        use embedded_hal_mock::{delay, spi};
+
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, NoPin, NoPin, NoPin);
+        interface.set_ultrafast_limit(2);
+        assert_eq!(
+            interface.count_refresh(RefreshLut::Ultrafast),
+            RefreshLut::Ultrafast
+        );
+        assert_eq!(
+            interface.count_refresh(RefreshLut::Quick),
+            RefreshLut::Quick
+        );
+        assert_eq!(
+            interface.count_refresh(RefreshLut::Ultrafast),
+            RefreshLut::Ultrafast
+        );
+        assert_eq!(interface.ghosting(), 2);
+
+        // the third one in a row clears the ghosts
+        assert_eq!(
+            interface.count_refresh(RefreshLut::Ultrafast),
+            RefreshLut::Full
+        );
+        assert_eq!(interface.ghosting(), 0);
+
+        // so does a full refresh of the application
+        interface.count_refresh(RefreshLut::Ultrafast);
+        interface.count_refresh(RefreshLut::Full);
+        assert_eq!(interface.ghosting(), 0);
+    }
+
+    #[test]
+    fn changed_planes_since_last_send() {
+        use embedded_hal_mock::delay;
+
+        struct Sink;
+        impl Write<u8> for Sink {
+            type Error = ();
+            fn write(&mut self, _words: &[u8]) -> Result<(), ()> {
+                Ok(())
+            }
+        }
+
+        let mut interface: DisplayInterface<Sink, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, NoPin, NoPin, NoPin);
+        let (black, chromatic) = ([0x00; 4], [0xFF; 4]);
+        let both = ChangedPlanes {
+            achromatic: true,
+            chromatic: true,
+        };
+        assert_eq!(interface.changed_planes(&black, &chromatic), both);
+
+        interface.planes_sent(&black, &chromatic);
+        assert_eq!(
+            interface.changed_planes(&black, &chromatic),
+            ChangedPlanes {
+                achromatic: false,
+                chromatic: false,
+            }
+        );
+        assert_eq!(
+            interface.changed_planes(&[0x00, 0x00, 0x01, 0x00], &chromatic),
+            ChangedPlanes {
+                achromatic: true,
+                chromatic: false,
+            }
+        );
+
+        // any other write could have changed the memory of the controller
+        interface.write(&mut Sink, &[0x12]).unwrap();
+        assert_eq!(interface.changed_planes(&black, &chromatic), both);
+
+        interface.planes_sent(&black, &chromatic);
+        interface.forget_planes();
+        assert_eq!(interface.changed_planes(&black, &chromatic), both);
+    }
+
+    #[test]
+    fn frame_data_resumes_at_failed_chunk() {
+        use embedded_hal_mock::delay;
+
+        /// Fails the write with the index `fail_at`, counts the written bytes
+        ///
+        /// The data is written byte by byte, so this is the index of the byte.
+        struct FlakySpi {
+            writes: usize,
+            fail_at: Option<usize>,
+            written: usize,
+        }
+        impl Write<u8> for FlakySpi {
+            type Error = ();
+            fn write(&mut self, words: &[u8]) -> Result<(), ()> {
+                self.writes += 1;
+                if self.fail_at == Some(self.writes - 1) {
+                    return Err(());
+                }
+                self.written += words.len();
+                Ok(())
+            }
+        }
+
+        let data = [0xAA; FRAME_CHUNK_LEN * 3 + 1];
+        let mut interface: DisplayInterface<FlakySpi, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, NoPin, NoPin, NoPin);
+        let mut spi = FlakySpi {
+            writes: 0,
+            fail_at: Some(2 * FRAME_CHUNK_LEN + 10),
+            written: 0,
+        };
+        let interrupted = interface.frame_data_from(&mut spi, &data, 0).unwrap_err();
+        assert_eq!(interrupted.sent, 2 * FRAME_CHUNK_LEN);
+
+        spi.fail_at = None;
+        interface
+            .frame_data_from(&mut spi, &data, interrupted.sent)
+            .unwrap();
+        // the part of the failed chunk is sent again
+        assert_eq!(spi.written, data.len() + 10);
+    }
+
+    #[test]
+    fn gray_planes_split_after_the_command() {
+        use embedded_hal_mock::delay;
+
+        let command = crate::type_a::command::Command::WriteRam;
+        // black, dark gray, light gray, white and 4 times white, then a frame chunk of black
+        let mut gray = [0u8; 2 + FRAME_CHUNK_LEN];
+        gray[..2].copy_from_slice(&[0b00_01_10_11, 0xFF]);
+        let mut interface: DisplayInterface<RecordingSpi, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, NoPin, NoPin, NoPin);
+
+        let mut bus = RecordingSpi::default();
+        interface
+            .cmd_with_gray_plane(&mut bus, command, &gray, gray.len() / 2, true)
+            .unwrap();
+        assert_eq!(bus.0.len(), 1 + 1 + FRAME_CHUNK_LEN / 2);
+        assert_eq!(bus.0[..2], [0x24, 0b0011_1111]);
+        assert!(bus.0[2..].iter().all(|&byte| byte == 0x00));
+
+        // the transform works on the grayscale data
+        interface.state.frame_hooks.transform = Some(invert);
+        let mut bus = RecordingSpi::default();
+        interface
+            .cmd_with_gray_plane(&mut bus, command, &gray, gray.len() / 2, false)
+            .unwrap();
+        assert_eq!(bus.0[..2], [0x24, 0b1010_0000]);
+        assert!(bus.0[2..].iter().all(|&byte| byte == 0xFF));
+
+        // an odd byte at the end doesn't make up a pixel of either plane
+        let mut bus = RecordingSpi::default();
+        let result = interface.cmd_with_gray_plane(&mut bus, command, &gray[1..], 1, true);
+        assert!(matches!(result, Err(Error::InvalidWindow)));
+        assert!(bus.0.is_empty());
+    }
+
+    #[test]
+    fn init_sequence_waits_after_the_marked_steps() {
+        extern crate std;
+        use embedded_hal_mock::delay;
+
+        let steps = [
+            InitStep::new(0x01, &[0x07, 0x07]),
+            InitStep::new(0x04, &[]).delay(100).wait(),
+            InitStep::new(0x00, &[0x1F]),
+        ];
+        let mut interface: DisplayInterface<RecordingSpi, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, NoPin, NoPin, NoPin);
+
+        // the bytes written when each wait starts
+        let mut waits = std::vec::Vec::new();
+        let mut bus = RecordingSpi::default();
+        interface
+            .run_init_sequence_with(
+                &mut bus,
+                &mut delay::MockNoop::new(),
+                &steps,
+                |_, spi, _| {
+                    waits.push(spi.0.len());
+                    Ok(())
+                },
+            )
+            .unwrap();
+        assert_eq!(bus.0, [0x01, 0x07, 0x07, 0x04, 0x00, 0x1F]);
+        assert_eq!(waits, [4]);
+    }
+
+    #[test]
+    fn read_data_transfers_after_the_command() {
+        extern crate std;
+        use embedded_hal_mock::delay;
+
+        /// Bus which records the writes and answers every transfer with `0x19`
+        #[derive(Default)]
+        struct Bus {
+            written: std::vec::Vec<u8>,
+            transferred: usize,
+        }
+        impl Write<u8> for Bus {
+            type Error = ();
+            fn write(&mut self, words: &[u8]) -> Result<(), ()> {
+                self.written.extend_from_slice(words);
+                Ok(())
+            }
+        }
+        impl Transfer<u8> for Bus {
+            type Error = ();
+            fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], ()> {
+                self.transferred += words.len();
+                words.iter_mut().for_each(|word| *word = 0x19);
+                Ok(words)
+            }
+        }
+
+        let mut bus = Bus::default();
+        let mut interface: DisplayInterface<Bus, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, NoPin, NoPin, NoPin);
+        let mut buffer = [0xFF; 2];
+        interface
+            .read_data(
+                &mut bus,
+                crate::type_a::command::Command::TemperatureSensorRead,
+                &mut buffer,
+            )
+            .unwrap();
+        assert_eq!(bus.written, [0x1B]);
+        assert_eq!(bus.transferred, 2);
+        assert_eq!(buffer, [0x19, 0x19]);
+    }
+
+    #[test]
+    fn ping_checks_the_busy_response() {
+        use core::cell::Cell;
+        use embedded_hal_mock::delay;
+
+        struct Sink;
+        impl Write<u8> for Sink {
+            type Error = ();
+            fn write(&mut self, _words: &[u8]) -> Result<(), ()> {
+                Ok(())
+            }
+        }
+
+        /// Busy pin which is high for the reads in `busy`, counts the reads
+        struct PulsePin {
+            reads: Cell<u32>,
+            busy: core::ops::Range<u32>,
+        }
+        impl InputPin for PulsePin {
+            type Error = ();
+            fn is_high(&self) -> Result<bool, ()> {
+                let read = self.reads.get();
+                self.reads.set(read + 1);
+                Ok(self.busy.contains(&read))
+            }
+            fn is_low(&self) -> Result<bool, ()> {
+                self.is_high().map(|high| !high)
+            }
+        }
+
+        /// Delay which only counts the waited ms
+        struct Elapsed(u32);
+        impl DelayMs<u8> for Elapsed {
+            fn delay_ms(&mut self, ms: u8) {
+                self.0 += u32::from(ms);
+            }
+        }
+
+        let ping = |busy| {
+            let pin = PulsePin {
+                reads: Cell::new(0),
+                busy,
+            };
+            let mut interface: DisplayInterface<Sink, _, _, _, _, Elapsed> =
+                DisplayInterface::new(NoPin, pin, NoPin, NoPin);
+            interface.initialized = true;
+            let mut elapsed = Elapsed(0);
+            let link = interface
+                .ping(&mut Sink, false, &mut elapsed, |interface, spi| {
+                    interface.write(spi, &[0x40])
+                })
+                .unwrap();
+            (link, elapsed.0)
+        };
+
+        // idle at the first read, busy for two reads after the command
+        assert_eq!(ping(1..3), (Link::Alive, 0));
+        // a slow answer is waited for, however fast the pin is read
+        assert_eq!(ping(4..6), (Link::Alive, 3));
+        // the pin never changes, e.g. with a loose cable
+        assert_eq!(ping(0..0), (Link::NoResponse, u32::from(PING_MS)));
+
+        // a sleeping display doesn't answer, so it isn't pinged
+        let mut interface: DisplayInterface<Sink, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, NoPin, NoPin, NoPin);
+        assert_eq!(
+            interface.ping(&mut Sink, false, &mut delay::MockNoop::new(), |_, _| Ok(())),
+            Ok(Link::Asleep)
+        );
+    }
+
+    #[test]
+    fn busy_wait_times_out_per_class() {
+        use crate::time::sim::sim_clock;
+        use embedded_hal_mock::{delay, pin, spi};
+
+        sim_clock!(TIME, clock, 10);
+
+        let busy = || pin::Transaction::get(pin::State::High);
+        let expectations = [busy(), busy(), busy(), busy()];
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(
+                pin::Mock::new(&[]),
+                pin::Mock::new(&expectations),
+                pin::Mock::new(&[]),
+                pin::Mock::new(&[]),
+            );
+        let timeouts = BusyTimeouts {
+            short: 10,
+            medium: 30,
+            refresh: 1000,
+        };
+        interface.state.busy_clock = Some(clock);
+        interface.state.busy_timeouts = timeouts;
+
+        // powering up after the creation, gives up after 3 busy polls
+        assert_eq!(
+            interface.wait_until_idle(false),
+            Err(Error::BusyTimeout(TimeoutClass::Medium))
+        );
+        assert_eq!(interface.state.timed_out.take(), Some(TimeoutClass::Medium));
+        assert_eq!(interface.state.timed_out.take(), None);
+
+        interface.set_busy_reason(BusyState::Idle);
+        assert_eq!(
+            interface.wait_until_idle(false),
+            Err(Error::BusyTimeout(TimeoutClass::Short))
+        );
+        assert_eq!(interface.state.timed_out.take(), Some(TimeoutClass::Short));
+
+        interface.busy.done();
+    }
+
+    #[test]
+    fn delayed_busy_wait_sleeps_between_polls() {
+        extern crate std;
+        use embedded_hal_mock::{pin, spi};
+
+        /// Sums up the delays and remembers the longest one
+        #[derive(Default)]
+        struct Delays {
+            total: u32,
+            longest: u8,
+        }
+        impl DelayMs<u8> for Delays {
+            fn delay_ms(&mut self, ms: u8) {
+                self.total += u32::from(ms);
+                self.longest = self.longest.max(ms);
+            }
+        }
+
+        let busy = |state| pin::Transaction::get(state);
+        let mut expectations = std::vec![busy(pin::State::High); 1500];
+        expectations.push(busy(pin::State::Low));
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, Delays> =
+            DisplayInterface::new(NoPin, pin::Mock::new(&expectations), NoPin, NoPin);
+
+        // a wait of 30 seconds only ever delays for the interval
+        let mut delay = Delays::default();
+        interface
+            .wait_until_idle_delayed(false, &mut delay, 20)
+            .unwrap();
+        assert_eq!((delay.total, delay.longest), (30_000, 20));
+        interface.busy.done();
+    }
+
+    #[test]
+    fn busy_wait_times_out_in_ticks_without_a_clock() {
+        use embedded_hal_mock::{delay, pin, spi};
+
+        let busy = || pin::Transaction::get(pin::State::High);
+        let expectations = [busy(), busy(), busy()];
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, pin::Mock::new(&expectations), NoPin, NoPin);
+        let timeouts = BusyTimeouts {
+            short: 10,
+            medium: 30,
+            refresh: 1000,
+        };
+        interface.state.busy_tick = Some(10);
+        interface.state.busy_timeouts = timeouts;
+        let mut delay = delay::MockNoop::new();
+
+        // a stuck busy line is given up after 3 ticks
+        assert_eq!(
+            interface.wait_until_idle_ticked(false, &mut delay),
+            Err(Error::BusyTimeout(TimeoutClass::Medium))
+        );
+        assert_eq!(interface.state.timed_out.take(), Some(TimeoutClass::Medium));
+
+        // the waits without a delay can't measure the ticks
+        interface.set_busy_reason(BusyState::Idle);
+        interface.state.busy_tick = None;
+        let expectations = [pin::Transaction::get(pin::State::Low)];
+        interface.busy.done();
+        interface.busy = pin::Mock::new(&expectations);
+        interface.wait_until_idle_ticked(false, &mut delay).unwrap();
+        assert_eq!(interface.state.timed_out.take(), None);
+        interface.busy.done();
+    }
+
+    #[test]
+    fn lifecycle_events() {
+        use core::sync::atomic::{AtomicU8, Ordering};
+        use embedded_hal_mock::{delay, pin, spi};
+
+        static EVENTS: [AtomicU8; 5] = [
+            AtomicU8::new(0),
+            AtomicU8::new(0),
+            AtomicU8::new(0),
+            AtomicU8::new(0),
+            AtomicU8::new(0),
+        ];
+        static COUNT: AtomicU8 = AtomicU8::new(0);
+        fn hook(event: LifecycleEvent) {
+            let index = COUNT.fetch_add(1, Ordering::Relaxed) as usize;
+            EVENTS[index].store(event as u8 + 1, Ordering::Relaxed);
+        }
+
+        let rst = [
+            pin::Transaction::set(pin::State::High),
+            pin::Transaction::set(pin::State::Low),
+            pin::Transaction::set(pin::State::High),
+        ];
+        let busy = [pin::Transaction::get(pin::State::Low)];
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(
+                pin::Mock::new(&[]),
+                pin::Mock::new(&busy),
+                pin::Mock::new(&[]),
+                pin::Mock::new(&rst),
+            );
+        interface.state.lifecycle_hook = Some(hook);
+
+        assert!(!interface.initialized());
+        interface.reset(&mut delay::MockNoop::new(), 10).unwrap();
+        assert!(interface.initialized());
+        interface.set_busy_reason(BusyState::Refreshing);
+        interface.wait_until_idle(false).unwrap();
+
+        let expected = [
+            LifecycleEvent::BeforeReset,
+            LifecycleEvent::AfterReset,
+            LifecycleEvent::PowerOn,
+            LifecycleEvent::RefreshStart,
+            LifecycleEvent::RefreshEnd,
+        ];
+        for (event, expected) in EVENTS.iter().zip(expected) {
+            assert_eq!(event.load(Ordering::Relaxed), expected as u8 + 1);
+        }
+        assert_eq!(COUNT.load(Ordering::Relaxed), 5);
+        assert_eq!(interface.busy_reason, BusyState::Idle);
+
+        interface.busy.done();
+        interface.rst.done();
+    }
+
+    #[test]
+    fn driver_state_tracks_refreshes_and_errors() {
+        use crate::time::sim::sim_clock;
+        use embedded_hal_mock::{delay, pin, spi};
+
+        sim_clock!(TIME, clock, 100);
+
+        let busy = [
+            pin::Transaction::get(pin::State::Low),
+            pin::Transaction::get(pin::State::High),
+        ];
+        let mut interface: DisplayInterface<spi::Mock, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(
+                pin::Mock::new(&[]),
+                pin::Mock::new(&busy),
+                pin::Mock::new(&[]),
+                pin::Mock::new(&[]),
+            );
+        interface.state.busy_clock = Some(clock);
+
+        interface.set_busy_reason(BusyState::Refreshing);
+        interface.wait_until_idle(false).unwrap();
+        interface.emit(LifecycleEvent::Sleep);
+        let state = interface.state.driver_state(RefreshLut::Quick);
+        assert_eq!(state.refresh, RefreshLut::Quick);
+        assert_eq!(state.refreshes, 1);
+        assert_eq!(state.last_refresh_ms, Some(200));
+        assert!(state.asleep);
+        assert_eq!(state.last_error, None);
+
+        interface.state.busy_timeouts.short = 100;
+        assert_eq!(
+            interface.wait_until_idle(false),
+            Err(Error::BusyTimeout(TimeoutClass::Short))
+        );
+        assert_eq!(
+            interface.state.driver_state(RefreshLut::Full).last_error,
+            Some(ErrorKind::Timeout(TimeoutClass::Short))
+        );
+
+        interface.busy.done();
+    }
+
+    #[test]
+    fn pin_failures_are_kept_as_last_error() {
+        use embedded_hal_mock::delay;
+
+        /// A pin whose every operation fails
+        struct BrokenPin;
+        impl OutputPin for BrokenPin {
+            type Error = ();
+            fn set_low(&mut self) -> Result<(), ()> {
+                Err(())
+            }
+            fn set_high(&mut self) -> Result<(), ()> {
+                Err(())
+            }
+        }
+        impl InputPin for BrokenPin {
+            type Error = ();
+            fn is_high(&self) -> Result<bool, ()> {
+                Err(())
+            }
+            fn is_low(&self) -> Result<bool, ()> {
+                Err(())
+            }
+        }
+
+        struct Sink;
+        impl Write<u8> for Sink {
+            type Error = ();
+            fn write(&mut self, _words: &[u8]) -> Result<(), ()> {
+                Ok(())
+            }
+        }
+
+        let mut interface: DisplayInterface<Sink, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, NoPin, BrokenPin, NoPin);
+        // the command is not sent, the pin failure is returned and kept
+        assert_eq!(
+            interface.cmd(&mut Sink, 0x12u8),
+            Err(Error::Pin(PinError::Dc(())))
+        );
+        assert_eq!(
+            interface.state.driver_state(RefreshLut::Full).last_error,
+            Some(ErrorKind::Pin(PinKind::Dc))
+        );
+
+        let interface: DisplayInterface<Sink, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, BrokenPin, NoPin, NoPin);
+        assert!(!interface.is_busy(true));
+        assert_eq!(
+            interface.state.driver_state(RefreshLut::Full).last_error,
+            Some(ErrorKind::Pin(PinKind::Busy))
+        );
+    }
+
+    #[test]
+    fn clear_data_aborts_between_chunks() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        use embedded_hal_mock::delay;
+
+        static CHECKS: AtomicUsize = AtomicUsize::new(0);
+        fn abort() -> bool {
+            CHECKS.fetch_add(1, Ordering::Relaxed) == 2
+        }
+
+        struct CountingSpi(usize);
+        impl Write<u8> for CountingSpi {
+            type Error = ();
+            fn write(&mut self, words: &[u8]) -> Result<(), ()> {
+                self.0 += words.len();
+                Ok(())
+            }
+        }
+
+        let mut interface: DisplayInterface<CountingSpi, _, _, _, _, delay::MockNoop> =
+            DisplayInterface::new(NoPin, NoPin, NoPin, NoPin);
+        let mut spi = CountingSpi(0);
+        interface.state.frame_hooks.abort = Some(abort);
+        interface
+            .clear_data(&mut spi, 0xFF, 4 * FRAME_CHUNK_LEN as u32)
+            .unwrap();
+        assert_eq!(spi.0, 2 * FRAME_CHUNK_LEN);
+        assert!(interface.state.aborted);
+        assert!(interface.clear_aborted());
+
+        // the next clear starts over, the abort is still kept for take_aborted
+        interface.state.frame_hooks.abort = None;
+        interface.clear_data(&mut spi, 0xFF, 10).unwrap();
+        assert_eq!(spi.0, 2 * FRAME_CHUNK_LEN + 10);
+        assert!(!interface.clear_aborted());
+        assert!(interface.state.aborted);
     }
 }
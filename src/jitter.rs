@@ -0,0 +1,169 @@
+//! Burn-in prevention for static content
+//!
+//! Showing the same layout for months can leave permanent ghost images on e-paper panels.
+//! [`Jitter`] moves the whole frame by one pixel in a different direction every few refreshes,
+//! so the edges of the content don't stay at the same position forever. The frame buffer of
+//! the application stays unchanged, [`Jitter::apply`] writes a shifted copy of it which is sent
+//! to the display instead.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::{color::Color, jitter::Jitter};
+//!
+//! // 16x16 pixels
+//! let frame = [0xFF; 16 / 8 * 16];
+//! let mut shifted = [0u8; 16 / 8 * 16];
+//!
+//! // move to the next position after every 10th refresh
+//! let mut jitter = Jitter::new(10);
+//! for _ in 0..100 {
+//!     jitter.apply(&frame, &mut shifted, 16, Color::White);
+//!     // send `shifted` to the display and refresh it
+//!     jitter.next_refresh();
+//! }
+//!```
+
+use crate::color::Color;
+
+/// The frame offsets in pixels which are cycled through
+const OFFSETS: [(i32, i32); 5] = [(0, 0), (1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// Moves the frame by one pixel on a schedule, see the [module docs](self)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jitter {
+    interval: u32,
+    refreshes: u32,
+    position: usize,
+}
+
+impl Jitter {
+    /// Creates a jitter which moves to the next offset after every `interval` refreshes
+    ///
+    /// An interval of `0` disables the jitter.
+    pub const fn new(interval: u32) -> Self {
+        Jitter {
+            interval,
+            refreshes: 0,
+            position: 0,
+        }
+    }
+
+    /// The current offset of the frame in pixels, as `(x, y)`
+    pub fn offset(&self) -> (i32, i32) {
+        OFFSETS[self.position]
+    }
+
+    /// Counts a refresh and moves to the next offset when the interval is over
+    ///
+    /// Returns `true` if the offset changed, then the shifted frame needs to be sent again.
+    pub fn next_refresh(&mut self) -> bool {
+        if self.interval == 0 {
+            return false;
+        }
+        self.refreshes += 1;
+        if self.refreshes < self.interval {
+            return false;
+        }
+        self.refreshes = 0;
+        self.position = (self.position + 1) % OFFSETS.len();
+        true
+    }
+
+    /// Copies the black/white buffer `src` to `dst`, moved by the current [offset](Self::offset)
+    ///
+    /// Pixels which are moved in at the edges get the `background` color.
+    /// Both buffers need to have the same length and a width of `width` pixels, the rows of
+    /// `dst` beyond the full rows of `src` get the `background` color as well.
+    pub fn apply(&self, src: &[u8], dst: &mut [u8], width: u32, background: Color) {
+        let row_len = (width as usize).div_ceil(8).max(1);
+        let (dx, dy) = self.offset();
+
+        for (y, dst_row) in dst.chunks_mut(row_len).enumerate() {
+            let src_y = y as i64 - i64::from(dy);
+            let src_row = match src_y {
+                0.. => src.chunks_exact(row_len).nth(src_y as usize),
+                _ => None,
+            };
+            let Some(src_row) = src_row else {
+                dst_row.fill(background.get_byte_value());
+                continue;
+            };
+            for x in 0..width as i32 {
+                let src_x = x - dx;
+                let white = if src_x < 0 || src_x >= width as i32 {
+                    background == Color::White
+                } else {
+                    src_row[src_x as usize / 8] & (0x80 >> (src_x % 8)) != 0
+                };
+                let mask = 0x80 >> (x % 8);
+                let Some(byte) = dst_row.get_mut(x as usize / 8) else {
+                    break;
+                };
+                if white {
+                    *byte |= mask;
+                } else {
+                    *byte &= !mask;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule() {
+        let mut jitter = Jitter::new(2);
+        assert_eq!(jitter.offset(), (0, 0));
+        assert!(!jitter.next_refresh());
+        assert!(jitter.next_refresh());
+        assert_eq!(jitter.offset(), (1, 0));
+
+        for _ in 0..2 * (OFFSETS.len() - 1) {
+            jitter.next_refresh();
+        }
+        assert_eq!(jitter.offset(), (0, 0));
+
+        let mut disabled = Jitter::new(0);
+        assert!(!disabled.next_refresh());
+        assert_eq!(disabled.offset(), (0, 0));
+    }
+
+    #[test]
+    fn shift_right_and_down() {
+        // 8x2: first row black on the left half, second row white
+        let src = [0x0F, 0xFF];
+        let mut dst = [0u8; 2];
+
+        let mut jitter = Jitter::new(1);
+        jitter.apply(&src, &mut dst, 8, Color::White);
+        assert_eq!(dst, src);
+
+        jitter.next_refresh();
+        jitter.apply(&src, &mut dst, 8, Color::White);
+        assert_eq!(dst, [0b1000_0111, 0xFF]);
+
+        jitter.next_refresh();
+        jitter.apply(&src, &mut dst, 8, Color::Black);
+        assert_eq!(dst, [0x00, 0x0F]);
+    }
+
+    #[test]
+    fn shift_left_and_up() {
+        let src = [0x0F, 0x00];
+        let mut dst = [0u8; 2];
+        let mut jitter = Jitter::new(1);
+        for _ in 0..3 {
+            jitter.next_refresh();
+        }
+        jitter.apply(&src, &mut dst, 8, Color::White);
+        assert_eq!(dst, [0b0001_1111, 0b0000_0001]);
+
+        jitter.next_refresh();
+        jitter.apply(&src, &mut dst, 8, Color::White);
+        assert_eq!(dst, [0x00, 0xFF]);
+    }
+}
@@ -12,7 +12,7 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
 //!use embedded_graphics::{
 //!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 //!};
@@ -54,6 +54,17 @@
 //! - Buffersize: Wherever a buffer is used it always needs to be of the size: `width / 8 * length`,
 //!   where width and length being either the full e-ink size or the partial update window size
 //!
+//! ### Panics
+//!
+//! The drivers and the display buffers don't panic at runtime: pixels outside of a buffer are
+//! not drawn, and buffers or windows which don't fit the display make the drivers return
+//! [`Error::InvalidWindow`](crate::prelude::Error::InvalidWindow) before anything of the update
+//! is sent. The crate denies `panic!`, `unwrap` and `expect` outside of the tests. The
+//! remaining panics are documented in the `# Panics` sections of the functions, like the buffer
+//! checks of some constructors, which have `try_new` alternatives returning a
+//! [`BufferTooSmall`](crate::graphics::BufferTooSmall) error instead. Operations a display
+//! doesn't support, like partial updates of some controllers, send nothing and return `Ok`.
+//!
 //! ### SPI
 //!
 //! MISO is not connected/available. SPI_MODE_0 is used (CPHL = 0, CPOL = 0) with 8 bits per word, MSB first.
@@ -62,47 +73,176 @@
 //!
 #![no_std]
 #![deny(missing_docs)]
+#![cfg_attr(
+    not(test),
+    deny(clippy::panic, clippy::unwrap_used, clippy::expect_used)
+)]
+
+#[cfg(feature = "std")]
+extern crate std;
 
 #[cfg(feature = "graphics")]
 pub mod graphics;
 
+#[cfg(feature = "graphics")]
+pub mod diffusion;
+
+#[cfg(feature = "text")]
+pub mod text;
+
+#[cfg(feature = "text")]
+pub mod ticker;
+
+#[cfg(feature = "qr")]
+pub mod qr;
+
 mod traits;
 
+pub mod animation;
+
+#[cfg(feature = "std")]
+pub mod assets;
+
+#[cfg(feature = "asynch")]
+pub mod asynch;
+
 pub mod color;
 
+#[cfg(feature = "graphics")]
+pub mod composite;
+
+#[cfg(feature = "std")]
+pub mod debug;
+
+pub mod energy;
+
+pub mod generic;
+
+pub mod helpers;
+
+pub mod jitter;
+
+pub mod lut;
+
+pub mod planes;
+
+#[cfg(all(feature = "std", feature = "graphics"))]
+pub mod preview;
+
+pub mod settings;
+
+#[cfg(feature = "critical-section")]
+pub mod shared;
+
+pub mod splash;
+
+#[cfg(all(feature = "std", feature = "text"))]
+pub mod sprite;
+
+pub mod stats;
+
+#[cfg(feature = "std")]
+pub mod testing;
+
+pub mod tiles;
+
+pub(crate) mod time;
+
+pub mod transport;
+
+#[cfg(feature = "waveform-file")]
+pub mod waveform;
+
 /// Interface for the physical connection between display and the controlling device
 mod interface;
 
+/// Interface for the displays which are connected via I2C
+mod i2c_interface;
+
+pub mod epd10in2;
+pub mod epd12in48;
+pub mod epd13in3k;
+pub mod epd1in02;
 pub mod epd1in54;
+pub mod epd1in54_v2;
 pub mod epd1in54b;
+pub mod epd1in54b_v2;
 pub mod epd1in54c;
+pub mod epd1in64g;
+pub mod epd1in9_segment;
 pub mod epd2in13_v2;
+pub mod epd2in13_v4;
+pub mod epd2in13b_v4;
 pub mod epd2in13bc;
+pub mod epd2in13d;
+pub mod epd2in15g;
+pub mod epd2in36g;
+pub mod epd2in66;
+pub mod epd2in66b;
 pub mod epd2in7;
+pub mod epd2in7_v2;
 pub mod epd2in7b;
+pub mod epd2in7b_v2;
 pub mod epd2in9;
 pub mod epd2in9_v2;
 pub mod epd2in9bc;
+pub mod epd2in9bc_v3;
+pub mod epd2in9d;
+pub mod epd3in0g;
+pub mod epd3in52;
+pub mod epd3in7;
+pub mod epd4in01f;
 pub mod epd4in2;
+pub mod epd4in26;
+pub mod epd4in2_v2;
+pub mod epd4in37g;
 pub mod epd5in65f;
+pub mod epd5in79;
+pub mod epd5in83_v2;
 pub mod epd5in83b_v2;
+pub mod epd7in3e;
+pub mod epd7in3f;
+pub mod epd7in3g;
 pub mod epd7in5;
 pub mod epd7in5_hd;
 pub mod epd7in5_v2;
 
+pub(crate) mod controllers;
 pub(crate) mod type_a;
+pub(crate) mod type_d;
+pub(crate) mod type_g;
 
 /// Includes everything important besides the chosen Display
 pub mod prelude {
-    pub use crate::color::{Color, OctColor, TriColor};
+    pub use crate::color::{Color, HexColor, OctColor, QuadColor, TriColor};
     pub use crate::traits::{
-        QuickRefresh, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+        AbortableClear, BatteryCheck, BounceBuffering, BusyState, BusyTimeoutSetting, BusyTimeouts,
+        Capabilities, ChangedPlanes, ColorSpec, ConnectionCheck, DisplayError, DriverSnapshot,
+        DriverState, Error, ErrorKind, FastWake, FrameRateSetting, FrameTransform,
+        GrayscaleDisplay, InitSequence, InterruptedUpload, LazyInit, LifecycleEvent,
+        LifecycleHooks, Link, NonBlockingRefresh, PinError, PinKind, Plane, PlaneTracking,
+        QuickRefresh, RawPlaneError, RawPlaneUpload, RefreshError, RefreshLut, RefreshState,
+        ResumableUpload, RowRendering, Snapshot, TconSetting, Telemetry, TemperatureReading,
+        TemperatureSetting, TimeoutClass, UltrafastRefresh, UploadError, UploadProgress,
+        VcomAndDataIntervalSetting, VcomMeasurement, VcomSetting, VoltageGuard, WaveshareDisplay,
+        WaveshareThreeColorDisplay, DEFAULT_ULTRAFAST_LIMIT,
     };
 
+    pub use crate::helpers::{CenteredError, WaveshareDisplayExt};
+    pub use crate::i2c_interface::I2cError;
     pub use crate::SPI_MODE;
 
+    #[cfg(feature = "asynch")]
+    pub use crate::asynch::AsyncDisplayError;
+
+    #[cfg(feature = "graphics")]
+    pub use crate::graphics::{
+        Display, DisplayRotation, HexDisplay, Mirroring, OctDisplay, Orientation, QuadDisplay,
+        TriDisplay,
+    };
+
     #[cfg(feature = "graphics")]
-    pub use crate::graphics::{Display, DisplayRotation, OctDisplay, TriDisplay};
+    pub use crate::traits::OrientationSetting;
 }
 
 /// Computes the needed buffer length. Takes care of rounding up in case width
@@ -115,7 +255,17 @@ pub mod prelude {
 /// \[XXXXX210\]\[76543210\]...\[76543210\] | height
 /// \[XXXXX210\]\[76543210\]...\[76543210\] v
 pub const fn buffer_len(width: usize, height: usize) -> usize {
-    (width + 7) / 8 * height
+    bytes_per_line(width) * height
+}
+
+/// Computes the length of one line of a black/white buffer in bytes
+///
+/// Every line starts with a new byte, so the lines of a display whose width isn't divisible by 8
+/// are padded with unused bits, e.g. the 122 pixels of the 2.13" displays take 16 bytes. Frames
+/// produced outside of this crate have to use the same stride, see
+/// [`WaveshareDisplay::bytes_per_line`](crate::traits::WaveshareDisplay::bytes_per_line).
+pub const fn bytes_per_line(width: usize) -> usize {
+    width.div_ceil(8)
 }
 
 use embedded_hal::spi::{Mode, Phase, Polarity};
@@ -126,3 +276,7 @@ pub const SPI_MODE: Mode = Mode {
     phase: Phase::CaptureOnFirstTransition,
     polarity: Polarity::IdleLow,
 };
+
+/// Size of the chunks frame data is passed to a [frame transform](traits::FrameTransform) in
+/// and in which the [upload progress](traits::UploadProgress) is reported
+pub const FRAME_CHUNK_LEN: usize = 64;
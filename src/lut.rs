@@ -0,0 +1,389 @@
+//! Typed builders for the waveform tables (LUTs) of the controllers
+//!
+//! A LUT tells the controller which voltage to drive in which phase of a refresh and for how
+//! many frames. Instead of typing the bytes of a table, the builders set the voltages, the
+//! frame counts and the repeat counts of the groups and compile down to the same byte arrays
+//! the drivers keep as constants. All methods are `const`, so the tables can be built at
+//! compile time, e.g. for the data of an [`InitStep`](crate::settings::InitStep).
+//!
+//! - [`SsdLut`] is the table of 153 bytes of the SSD1680 family (`0x32`), optionally followed
+//!   by the [voltages](SsdVoltages) in the layout of the Waveshare drivers.
+//! - [`UcLut`] is one of the tables of 42 bytes of the UC8151 family (`0x20` to `0x24`).
+//!
+//! Every group has the four phases A to D, which run one after the other for their number of
+//! frames. Groups without frames are skipped. Entries out of range are ignored.
+//!
+//!```rust
+//! use epd_waveshare::lut::{Phase, UcLut, UcVoltage};
+//!
+//! // white to white: up for 8 frames, twice, then down and up for 40 frames each
+//! const LUT_WW: [u8; 42] = UcLut::new()
+//!     .voltage(0, Phase::A, UcVoltage::Vdh)
+//!     .frames(0, Phase::A, 8)
+//!     .repeat(0, 2)
+//!     .voltage(1, Phase::A, UcVoltage::Vdl)
+//!     .frames(1, Phase::A, 40)
+//!     .voltage(1, Phase::B, UcVoltage::Vdh)
+//!     .frames(1, Phase::B, 40)
+//!     .repeat(1, 1)
+//!     .build();
+//!
+//! assert_eq!(LUT_WW[..12], [0x40, 0x08, 0, 0, 0, 0x02, 0x90, 0x28, 0x28, 0, 0, 0x01]);
+//!```
+
+/// One of the four phases of a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// The first phase
+    A,
+    /// The second phase
+    B,
+    /// The third phase
+    C,
+    /// The fourth phase
+    D,
+}
+
+impl Phase {
+    const fn index(self) -> usize {
+        match self {
+            Phase::A => 0,
+            Phase::B => 1,
+            Phase::C => 2,
+            Phase::D => 3,
+        }
+    }
+
+    /// Shift of the two bits of the phase in a byte of voltages, phase A is in the top bits
+    const fn shift(self) -> u8 {
+        6 - 2 * self.index() as u8
+    }
+}
+
+/// Source voltage of a phase of the SSD1680 family
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SsdVoltage {
+    /// VSS, no voltage
+    Vss = 0b00,
+    /// VSH1, the positive voltage which drives the pixels to white
+    Vsh1 = 0b01,
+    /// VSL, the negative voltage which drives the pixels to black
+    Vsl = 0b10,
+    /// VSH2, the second positive voltage, used for the red pixels of the tri-color panels
+    Vsh2 = 0b11,
+}
+
+/// The driving voltages which follow the waveform in the LUTs of 159 bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SsdVoltages {
+    /// The end option (`0x3F`), what the sources and gates do after the refresh
+    pub end_option: u8,
+    /// The gate voltage (`0x03`)
+    pub gate: u8,
+    /// The source voltages VSH1, VSH2 and VSL (`0x04`)
+    pub source: [u8; 3],
+    /// The VCOM voltage (`0x2C`)
+    pub vcom: u8,
+}
+
+/// Builder for the LUT of 153 bytes of the SSD1680 family
+///
+/// The table has 12 groups, each drives the five transitions with their own voltages, but
+/// with the same frame counts. The transitions are the LUTs 0 to 4, for the black/white
+/// panels LUT 0 is black to black, 1 white to black, 2 black to white and 3 white to white.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SsdLut {
+    bytes: [u8; SsdLut::LEN],
+}
+
+impl SsdLut {
+    /// Number of transitions
+    pub const TRANSITIONS: usize = 5;
+    /// Number of groups
+    pub const GROUPS: usize = 12;
+    /// Length of the table
+    pub const LEN: usize = 153;
+
+    /// Offset of the frame counts and repeats of the groups, after the voltages
+    const TIMING: usize = SsdLut::TRANSITIONS * SsdLut::GROUPS;
+    /// Bytes of the timing of a group: frames of A and B, repeat of A and B, frames of C and
+    /// D, repeat of C and D and the repeat of the group
+    const GROUP_LEN: usize = 7;
+    /// Offset of the frame rates, after the timing
+    const FRAME_RATE: usize = SsdLut::TIMING + SsdLut::GROUPS * SsdLut::GROUP_LEN;
+
+    /// An empty table, which drives nothing
+    pub const fn new() -> Self {
+        SsdLut {
+            bytes: [0; SsdLut::LEN],
+        }
+    }
+
+    /// Takes an existing table, e.g. one of a vendor, to change single entries of it
+    pub const fn from_bytes(bytes: [u8; SsdLut::LEN]) -> Self {
+        SsdLut { bytes }
+    }
+
+    /// Drives `voltage` for the `transition` in the `phase` of the `group`
+    pub const fn voltage(
+        mut self,
+        transition: usize,
+        group: usize,
+        phase: Phase,
+        voltage: SsdVoltage,
+    ) -> Self {
+        if transition < SsdLut::TRANSITIONS && group < SsdLut::GROUPS {
+            let index = transition * SsdLut::GROUPS + group;
+            let shift = phase.shift();
+            self.bytes[index] = (self.bytes[index] & !(0b11 << shift)) | (voltage as u8) << shift;
+        }
+        self
+    }
+
+    /// Sets the number of frames of the `phase` of the `group`
+    pub const fn frames(mut self, group: usize, phase: Phase, frames: u8) -> Self {
+        if group < SsdLut::GROUPS {
+            let offset = match phase {
+                Phase::A => 0,
+                Phase::B => 1,
+                Phase::C => 3,
+                Phase::D => 4,
+            };
+            self.bytes[SsdLut::TIMING + group * SsdLut::GROUP_LEN + offset] = frames;
+        }
+        self
+    }
+
+    /// Repeats the phases A and B of the `group` `count` more times before C and D
+    pub const fn repeat_ab(mut self, group: usize, count: u8) -> Self {
+        if group < SsdLut::GROUPS {
+            self.bytes[SsdLut::TIMING + group * SsdLut::GROUP_LEN + 2] = count;
+        }
+        self
+    }
+
+    /// Repeats the phases C and D of the `group` `count` more times
+    pub const fn repeat_cd(mut self, group: usize, count: u8) -> Self {
+        if group < SsdLut::GROUPS {
+            self.bytes[SsdLut::TIMING + group * SsdLut::GROUP_LEN + 5] = count;
+        }
+        self
+    }
+
+    /// Repeats the whole `group` `count` more times
+    pub const fn repeat(mut self, group: usize, count: u8) -> Self {
+        if group < SsdLut::GROUPS {
+            self.bytes[SsdLut::TIMING + group * SsdLut::GROUP_LEN + 6] = count;
+        }
+        self
+    }
+
+    /// Sets the frame rate of the `group`, `0..=15`, the even groups are in the upper nibbles
+    pub const fn frame_rate(mut self, group: usize, rate: u8) -> Self {
+        if group < SsdLut::GROUPS {
+            let index = SsdLut::FRAME_RATE + group / 2;
+            let shift = if group & 1 == 0 { 4 } else { 0 };
+            self.bytes[index] = (self.bytes[index] & !(0x0F << shift)) | (rate & 0x0F) << shift;
+        }
+        self
+    }
+
+    /// Sets the same frame rate for all groups, see [`frame_rate`](SsdLut::frame_rate)
+    pub const fn frame_rates(mut self, rate: u8) -> Self {
+        let mut group = 0;
+        while group < SsdLut::GROUPS {
+            self = self.frame_rate(group, rate);
+            group += 1;
+        }
+        self
+    }
+
+    /// The table for the LUT register
+    pub const fn build(self) -> [u8; SsdLut::LEN] {
+        self.bytes
+    }
+
+    /// The table followed by the `voltages`, for the drivers which load them with the LUT
+    pub const fn build_with_voltages(self, voltages: SsdVoltages) -> [u8; SsdLut::LEN + 6] {
+        let mut bytes = [0; SsdLut::LEN + 6];
+        let mut index = 0;
+        while index < SsdLut::LEN {
+            bytes[index] = self.bytes[index];
+            index += 1;
+        }
+        bytes[SsdLut::LEN] = voltages.end_option;
+        bytes[SsdLut::LEN + 1] = voltages.gate;
+        bytes[SsdLut::LEN + 2] = voltages.source[0];
+        bytes[SsdLut::LEN + 3] = voltages.source[1];
+        bytes[SsdLut::LEN + 4] = voltages.source[2];
+        bytes[SsdLut::LEN + 5] = voltages.vcom;
+        bytes
+    }
+}
+
+impl Default for SsdLut {
+    fn default() -> Self {
+        SsdLut::new()
+    }
+}
+
+/// Voltage of a phase of the UC8151 family
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UcVoltage {
+    /// GND, or VCOM_DC in the VCOM LUT
+    Gnd = 0b00,
+    /// VDH, or VDH + VCOM_DC in the VCOM LUT
+    Vdh = 0b01,
+    /// VDL, or VDL + VCOM_DC in the VCOM LUT
+    Vdl = 0b10,
+    /// VDHR for the red pixels, floating in the VCOM LUT
+    Vdhr = 0b11,
+}
+
+/// Builder for one LUT of 42 bytes of the UC8151 family
+///
+/// The controller has a LUT for VCOM and one for each transition, white to white, black to
+/// white, white to black and black to black. Each of them has 7 groups, with their own
+/// voltages and frame counts in each LUT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UcLut {
+    bytes: [u8; UcLut::LEN],
+}
+
+impl UcLut {
+    /// Number of groups
+    pub const GROUPS: usize = 7;
+    /// Length of a table
+    pub const LEN: usize = UcLut::GROUPS * UcLut::GROUP_LEN;
+    /// Length of the VCOM table, with the two bytes after the groups
+    pub const VCOM_LEN: usize = UcLut::LEN + 2;
+
+    /// Bytes of a group: the voltages, the frames of A to D and the repeat count
+    const GROUP_LEN: usize = 6;
+
+    /// An empty table, which drives nothing
+    pub const fn new() -> Self {
+        UcLut {
+            bytes: [0; UcLut::LEN],
+        }
+    }
+
+    /// Takes an existing table, e.g. one of a vendor, to change single entries of it
+    pub const fn from_bytes(bytes: [u8; UcLut::LEN]) -> Self {
+        UcLut { bytes }
+    }
+
+    /// Drives `voltage` in the `phase` of the `group`
+    pub const fn voltage(mut self, group: usize, phase: Phase, voltage: UcVoltage) -> Self {
+        if group < UcLut::GROUPS {
+            let index = group * UcLut::GROUP_LEN;
+            let shift = phase.shift();
+            self.bytes[index] = (self.bytes[index] & !(0b11 << shift)) | (voltage as u8) << shift;
+        }
+        self
+    }
+
+    /// Sets the number of frames of the `phase` of the `group`
+    pub const fn frames(mut self, group: usize, phase: Phase, frames: u8) -> Self {
+        if group < UcLut::GROUPS {
+            self.bytes[group * UcLut::GROUP_LEN + 1 + phase.index()] = frames;
+        }
+        self
+    }
+
+    /// Runs the `group` `count` times
+    pub const fn repeat(mut self, group: usize, count: u8) -> Self {
+        if group < UcLut::GROUPS {
+            self.bytes[group * UcLut::GROUP_LEN + 5] = count;
+        }
+        self
+    }
+
+    /// The table for one of the transition LUTs
+    pub const fn build(self) -> [u8; UcLut::LEN] {
+        self.bytes
+    }
+
+    /// The table for the VCOM LUT, which is two bytes longer
+    pub const fn build_vcom(self) -> [u8; UcLut::VCOM_LEN] {
+        let mut bytes = [0; UcLut::VCOM_LEN];
+        let mut index = 0;
+        while index < UcLut::LEN {
+            bytes[index] = self.bytes[index];
+            index += 1;
+        }
+        bytes
+    }
+}
+
+impl Default for UcLut {
+    fn default() -> Self {
+        UcLut::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ssd_lut_matches_the_partial_lut_of_the_2in9_v2() {
+        #[rustfmt::skip]
+        let expected: [u8; 153] = [
+            0x0, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x80, 0x80, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x40, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0A, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x22, 0x22, 0x22, 0x22, 0x22,
+            0x22, 0x0, 0x0, 0x0,
+        ];
+        let lut = SsdLut::new()
+            .voltage(0, 1, Phase::A, SsdVoltage::Vsh1)
+            .voltage(1, 0, Phase::A, SsdVoltage::Vsl)
+            .voltage(1, 1, Phase::A, SsdVoltage::Vsl)
+            .voltage(2, 0, Phase::A, SsdVoltage::Vsh1)
+            .voltage(2, 1, Phase::A, SsdVoltage::Vsh1)
+            .voltage(3, 1, Phase::A, SsdVoltage::Vsl)
+            .frames(0, Phase::A, 0x0A)
+            .repeat(0, 2)
+            .frames(1, Phase::A, 1)
+            .frames(2, Phase::A, 1)
+            .frame_rates(2)
+            // out of range
+            .voltage(5, 0, Phase::A, SsdVoltage::Vsh2)
+            .frames(12, Phase::A, 1);
+        assert_eq!(lut.build(), expected);
+
+        let voltages = SsdVoltages {
+            end_option: 0x22,
+            gate: 0x17,
+            source: [0x41, 0xB0, 0x32],
+            vcom: 0x36,
+        };
+        assert_eq!(
+            lut.build_with_voltages(voltages)[153..],
+            [0x22, 0x17, 0x41, 0xB0, 0x32, 0x36]
+        );
+    }
+
+    #[test]
+    fn uc_lut_matches_the_vcom_lut_of_the_flexible_panels() {
+        let lut = UcLut::new()
+            .frames(0, Phase::A, 8)
+            .repeat(0, 2)
+            .voltage(1, Phase::A, UcVoltage::Vdh)
+            .voltage(1, Phase::B, UcVoltage::Vdl)
+            .frames(1, Phase::A, 0x28)
+            .frames(1, Phase::B, 0x28)
+            .repeat(1, 1)
+            .frames(2, Phase::A, 0x14)
+            .repeat(2, 1)
+            .frames(3, Phase::A, 0x12)
+            .frames(3, Phase::B, 0x12)
+            .repeat(3, 1);
+        assert_eq!(lut.build_vcom(), crate::type_d::constants::LUT_VCOM_DC);
+    }
+}
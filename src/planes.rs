@@ -0,0 +1,125 @@
+//! Conversion between 2 bit grayscale buffers and the two 1 bit planes the displays expect
+//!
+//! Grayscale modes send each pixel as two bits, with the high bits of all pixels in one
+//! plane (usually via `DataStartTransmission1`) and the low bits in the other one
+//! (usually via `DataStartTransmission2`).
+//!
+//! The grayscale buffers hold four pixels per byte, the first pixel in the highest two bits.
+//! `0` is black and `3` is white. The planes hold eight pixels per byte like the normal
+//! black/white buffers, so a grayscale buffer is always twice as long as each plane.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::planes::{merge_planes, split_planes};
+//!
+//! // black, dark gray, light gray, white, repeated
+//! let gray = [0b00_01_10_11, 0b00_01_10_11];
+//! let mut high = [0u8; 1];
+//! let mut low = [0u8; 1];
+//! split_planes(&gray, &mut high, &mut low);
+//! assert_eq!(high, [0b0011_0011]);
+//! assert_eq!(low, [0b0101_0101]);
+//!
+//! let mut merged = [0u8; 2];
+//! merge_planes(&high, &low, &mut merged);
+//! assert_eq!(merged, gray);
+//!```
+
+/// Splits a 2 bit grayscale buffer into the plane of the high bits and the plane of the low bits
+///
+/// # Panics
+///
+/// If `gray` isn't exactly twice as long as each of the planes.
+pub fn split_planes(gray: &[u8], high: &mut [u8], low: &mut [u8]) {
+    assert_eq!(gray.len(), high.len() * 2);
+    assert_eq!(gray.len(), low.len() * 2);
+
+    for ((pair, high), low) in gray.chunks(2).zip(high.iter_mut()).zip(low.iter_mut()) {
+        let pixels = u16::from_be_bytes([pair[0], pair[1]]);
+        *high = 0;
+        *low = 0;
+        for i in 0..8 {
+            let pixel = (pixels >> (14 - 2 * i)) & 0b11;
+            *high |= ((pixel >> 1) as u8) << (7 - i);
+            *low |= ((pixel & 1) as u8) << (7 - i);
+        }
+    }
+}
+
+/// Merges the plane of the high bits and the plane of the low bits into a 2 bit grayscale buffer
+///
+/// The reverse of [`split_planes`].
+///
+/// # Panics
+///
+/// If `gray` isn't exactly twice as long as each of the planes.
+pub fn merge_planes(high: &[u8], low: &[u8], gray: &mut [u8]) {
+    assert_eq!(gray.len(), high.len() * 2);
+    assert_eq!(gray.len(), low.len() * 2);
+
+    for ((pair, &high), &low) in gray.chunks_mut(2).zip(high.iter()).zip(low.iter()) {
+        let mut pixels = 0u16;
+        for i in 0..8 {
+            let pixel = u16::from((high >> (7 - i)) & 1) << 1 | u16::from((low >> (7 - i)) & 1);
+            pixels |= pixel << (14 - 2 * i);
+        }
+        pair.copy_from_slice(&pixels.to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_extremes() {
+        let mut high = [0xAA; 2];
+        let mut low = [0xAA; 2];
+
+        split_planes(&[0x00; 4], &mut high, &mut low);
+        assert_eq!(high, [0x00; 2]);
+        assert_eq!(low, [0x00; 2]);
+
+        split_planes(&[0xFF; 4], &mut high, &mut low);
+        assert_eq!(high, [0xFF; 2]);
+        assert_eq!(low, [0xFF; 2]);
+    }
+
+    #[test]
+    fn split_pixel_order() {
+        let mut high = [0u8];
+        let mut low = [0u8];
+
+        // only the first pixel is light gray (0b10)
+        split_planes(&[0b10_00_00_00, 0x00], &mut high, &mut low);
+        assert_eq!(high, [0b1000_0000]);
+        assert_eq!(low, [0x00]);
+
+        // only the last pixel is dark gray (0b01)
+        split_planes(&[0x00, 0b00_00_00_01], &mut high, &mut low);
+        assert_eq!(high, [0x00]);
+        assert_eq!(low, [0b0000_0001]);
+    }
+
+    #[test]
+    fn merge_reverses_split() {
+        let mut gray = [0u8; 64];
+        for (i, byte) in gray.iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_mul(37) ^ 0x5A;
+        }
+        let mut high = [0u8; 32];
+        let mut low = [0u8; 32];
+        split_planes(&gray, &mut high, &mut low);
+
+        let mut merged = [0u8; 64];
+        merge_planes(&high, &low, &mut merged);
+        assert_eq!(merged, gray);
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_wrong_length() {
+        split_planes(&[0u8; 3], &mut [0u8; 1], &mut [0u8; 1]);
+    }
+}
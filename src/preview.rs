@@ -0,0 +1,202 @@
+//! Rendering of display buffers to RGB pixels which need the standard library
+//!
+//! The functions turn the buffers of black/white, tri-color and 7-color displays into one
+//! [`Rgb888`] pixel per display pixel, row after row from the top. The colors are the nominal
+//! colors of the inks instead of pure RGB values, so a preview on the desktop or the image of a
+//! snapshot test looks close to the real panel.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::{
+//!     color::TriColor,
+//!     epd2in13bc::{Display2in13bc, HEIGHT, WIDTH},
+//!     graphics::{DisplayColorRendering, TriDisplay},
+//!     preview::{render_tri, Palette},
+//! };
+//!
+//! let mut display = Display2in13bc::default();
+//! display.clear_buffer(TriColor::Chromatic);
+//!
+//! let pixels = render_tri(
+//!     display.buffer(),
+//!     WIDTH,
+//!     HEIGHT,
+//!     DisplayColorRendering::Positive,
+//!     &Palette::RED,
+//! );
+//! assert_eq!(pixels.len(), (WIDTH * HEIGHT) as usize);
+//! assert_eq!(pixels[0], Palette::RED.chromatic);
+//!```
+
+use crate::buffer_len;
+use crate::color::OctColor;
+use crate::graphics::DisplayColorRendering;
+use embedded_graphics_core::pixelcolor::Rgb888;
+use std::vec::Vec;
+
+/// Nominal colors of the inks of a black/white or tri-color panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    /// Color of the white pixels, i.e. of the paper
+    pub white: Rgb888,
+    /// Color of the black ink
+    pub black: Rgb888,
+    /// Color of the chromatic ink, unused for black/white panels
+    pub chromatic: Rgb888,
+}
+
+impl Palette {
+    /// Black/white panels
+    pub const BLACK_WHITE: Palette = Palette {
+        white: PAPER,
+        black: INK,
+        chromatic: INK,
+    };
+    /// Tri-color panels with red ink
+    pub const RED: Palette = Palette {
+        white: PAPER,
+        black: INK,
+        chromatic: Rgb888::new(0xB4, 0x1E, 0x1E),
+    };
+    /// Tri-color panels with yellow ink
+    pub const YELLOW: Palette = Palette {
+        white: PAPER,
+        black: INK,
+        chromatic: Rgb888::new(0xE6, 0xC3, 0x1E),
+    };
+}
+
+const PAPER: Rgb888 = Rgb888::new(0xE8, 0xE6, 0xDC);
+const INK: Rgb888 = Rgb888::new(0x1E, 0x1E, 0x22);
+
+/// Nominal color of an ink of the 7-color panels
+pub fn oct_ink(color: OctColor) -> Rgb888 {
+    match color {
+        OctColor::Black => INK,
+        OctColor::White => PAPER,
+        OctColor::Green => Rgb888::new(0x3A, 0x6B, 0x3A),
+        OctColor::Blue => Rgb888::new(0x3A, 0x4A, 0x8C),
+        OctColor::Red => Rgb888::new(0xB4, 0x32, 0x2A),
+        OctColor::Yellow => Rgb888::new(0xE6, 0xC8, 0x3C),
+        OctColor::Orange => Rgb888::new(0xD2, 0x78, 0x32),
+        OctColor::HiZ => Rgb888::new(0xB4, 0xB4, 0xAA),
+    }
+}
+
+/// Renders a black/white buffer of `width` x `height` pixels
+///
+/// Missing bytes are rendered as white.
+pub fn render_bw(buffer: &[u8], width: u32, height: u32, palette: &Palette) -> Vec<Rgb888> {
+    pixels(width, height, |x, y| {
+        if bit(buffer, 0, width, x, y).unwrap_or(true) {
+            palette.white
+        } else {
+            palette.black
+        }
+    })
+}
+
+/// Renders the buffer of a tri-color display of `width` x `height` pixels
+///
+/// The buffer holds the black/white plane followed by the chromatic plane, like the one of a
+/// [`TriDisplay`](crate::graphics::TriDisplay). `rendering` is the one the display uses for the
+/// chromatic plane. Missing bytes are rendered as white.
+pub fn render_tri(
+    buffer: &[u8],
+    width: u32,
+    height: u32,
+    rendering: DisplayColorRendering,
+    palette: &Palette,
+) -> Vec<Rgb888> {
+    let offset = buffer_len(width as usize, height as usize);
+    pixels(width, height, |x, y| {
+        let chromatic = match rendering {
+            DisplayColorRendering::Positive => bit(buffer, offset, width, x, y) == Some(false),
+            DisplayColorRendering::Negative => bit(buffer, offset, width, x, y) == Some(true),
+        };
+        if chromatic {
+            palette.chromatic
+        } else if bit(buffer, 0, width, x, y).unwrap_or(true) {
+            palette.white
+        } else {
+            palette.black
+        }
+    })
+}
+
+/// Renders the buffer of a 7-color display of `width` x `height` pixels
+///
+/// Missing bytes and invalid nibbles are rendered as white.
+pub fn render_oct(buffer: &[u8], width: u32, height: u32) -> Vec<Rgb888> {
+    let row_len = width.div_ceil(2) as usize;
+    pixels(width, height, |x, y| {
+        let byte = buffer
+            .get(y as usize * row_len + x as usize / 2)
+            .copied()
+            .unwrap_or(0x11);
+        let nibble = if x % 2 == 0 { byte >> 4 } else { byte };
+        oct_ink(OctColor::from_nibble(nibble).unwrap_or(OctColor::White))
+    })
+}
+
+/// Collects the colors of all pixels, row after row
+fn pixels(width: u32, height: u32, color: impl Fn(u32, u32) -> Rgb888) -> Vec<Rgb888> {
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height {
+        for x in 0..width {
+            pixels.push(color(x, y));
+        }
+    }
+    pixels
+}
+
+/// Whether the pixel is set in the plane at `offset`, `None` if the byte is missing
+fn bit(buffer: &[u8], offset: usize, width: u32, x: u32, y: u32) -> Option<bool> {
+    let index = offset + y as usize * buffer_len(width as usize, 1) + x as usize / 8;
+    buffer.get(index).map(|byte| byte & (0x80 >> (x % 8)) != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bw_and_tri() {
+        // 8x1 pixels, black on the left half
+        let bw = [0x0F];
+        let pixels = render_bw(&bw, 8, 1, &Palette::BLACK_WHITE);
+        assert_eq!(pixels[0], INK);
+        assert_eq!(pixels[7], PAPER);
+
+        // the first pixel is chromatic in both renderings
+        let positive = render_tri(
+            &[0x0F, 0x7F],
+            8,
+            1,
+            DisplayColorRendering::Positive,
+            &Palette::RED,
+        );
+        let negative = render_tri(
+            &[0x0F, 0x80],
+            8,
+            1,
+            DisplayColorRendering::Negative,
+            &Palette::RED,
+        );
+        assert_eq!(positive, negative);
+        assert_eq!(positive[0], Palette::RED.chromatic);
+        assert_eq!(positive[1], INK);
+        assert_eq!(positive[7], PAPER);
+    }
+
+    #[test]
+    fn oct() {
+        let buffer = [OctColor::colors_byte(OctColor::Red, OctColor::Blue)];
+        let pixels = render_oct(&buffer, 2, 2);
+        assert_eq!(pixels[0], oct_ink(OctColor::Red));
+        assert_eq!(pixels[1], oct_ink(OctColor::Blue));
+        // the second row is missing
+        assert_eq!(pixels[2], PAPER);
+    }
+}
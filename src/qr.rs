@@ -0,0 +1,169 @@
+//! QR code rendering for EPDs
+//!
+//! Encodes a string as QR code and draws it as large as possible and centered
+//! into a display buffer, including the quiet zone around it.
+//!
+//! No heap is needed, the code is encoded in buffers on the stack. Their size
+//! limits the payload to QR codes up to version [`MAX_QR_VERSION`], which still
+//! holds over 200 bytes and is plenty for the usual pairing urls or wifi credentials.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::{epd1in54::Display1in54, qr::draw_qr_code};
+//!
+//! let mut display = Display1in54::default();
+//! draw_qr_code(&mut display, "https://github.com/caemor/epd-waveshare").unwrap();
+//!```
+
+use embedded_graphics_core::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+use qrcodegen_no_heap::{QrCode, QrCodeEcc, Version};
+
+/// Highest QR code version which can be rendered
+pub const MAX_QR_VERSION: u8 = 10;
+
+const QR_BUFFER_LEN: usize = Version::new(MAX_QR_VERSION).buffer_len();
+
+/// Width of the light border around the code in modules, as required by the standard
+const QUIET_ZONE: u32 = 4;
+
+/// Errors while rendering a QR code
+#[derive(Debug, PartialEq, Eq)]
+pub enum QrError<E> {
+    /// The text doesn't fit into a QR code up to version [`MAX_QR_VERSION`]
+    DataTooLong,
+    /// The area is too small to draw every module with at least one pixel
+    AreaTooSmall,
+    /// Drawing into the display failed
+    Draw(E),
+}
+
+/// Draws `text` as QR code centered into the whole display
+///
+/// See [`draw_qr_code_in`] for details.
+pub fn draw_qr_code<D>(display: &mut D, text: &str) -> Result<(), QrError<D::Error>>
+where
+    D: DrawTarget,
+    D::Color: From<BinaryColor>,
+{
+    let area = display.bounding_box();
+    draw_qr_code_in(display, text, area)
+}
+
+/// Draws `text` as QR code centered into `area`
+///
+/// Every module is scaled up to the largest whole number of pixels that still fits into the area.
+/// Dark modules are drawn in black, light modules and the quiet zone in white.
+pub fn draw_qr_code_in<D>(
+    display: &mut D,
+    text: &str,
+    area: Rectangle,
+) -> Result<(), QrError<D::Error>>
+where
+    D: DrawTarget,
+    D::Color: From<BinaryColor>,
+{
+    let mut temp_buffer = [0u8; QR_BUFFER_LEN];
+    let mut out_buffer = [0u8; QR_BUFFER_LEN];
+    let qr = QrCode::encode_text(
+        text,
+        &mut temp_buffer,
+        &mut out_buffer,
+        QrCodeEcc::Medium,
+        Version::MIN,
+        Version::new(MAX_QR_VERSION),
+        None,
+        true,
+    )
+    .map_err(|_| QrError::DataTooLong)?;
+
+    let modules = qr.size() as u32;
+    let scale = area.size.width.min(area.size.height) / (modules + 2 * QUIET_ZONE);
+    if scale == 0 {
+        return Err(QrError::AreaTooSmall);
+    }
+
+    // center the code including its quiet zone
+    let code_size = (modules + 2 * QUIET_ZONE) * scale;
+    let origin = area.top_left
+        + Point::new(
+            ((area.size.width - code_size) / 2) as i32,
+            ((area.size.height - code_size) / 2) as i32,
+        );
+
+    display
+        .fill_solid(
+            &Rectangle::new(origin, Size::new(code_size, code_size)),
+            BinaryColor::Off.into(),
+        )
+        .map_err(QrError::Draw)?;
+
+    let origin = origin + Point::new((QUIET_ZONE * scale) as i32, (QUIET_ZONE * scale) as i32);
+    for y in 0..qr.size() {
+        for x in 0..qr.size() {
+            if qr.get_module(x, y) {
+                let module = Rectangle::new(
+                    origin + Point::new(x * scale as i32, y * scale as i32),
+                    Size::new(scale, scale),
+                );
+                display
+                    .fill_solid(&module, BinaryColor::On.into())
+                    .map_err(QrError::Draw)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::graphics::{Display, VarDisplay};
+
+    fn is_black(display: &VarDisplay, x: u32, y: u32) -> bool {
+        let byte = display.buffer()[(y * 64 / 8 + x / 8) as usize];
+        byte & (0x80 >> (x % 8)) == 0
+    }
+
+    #[test]
+    fn qr_centered_and_scaled() {
+        let mut buffer = [Color::Black.get_byte_value(); 64 / 8 * 64];
+        let mut display = VarDisplay::new(64, 64, &mut buffer);
+
+        draw_qr_code(&mut display, "epd").unwrap();
+
+        // Version 1: 21 modules + 2 * 4 quiet zone = 29 modules, scaled by 2 to 58 pixels,
+        // which leaves a 3 pixel margin on each side
+        assert!(is_black(&display, 2, 2));
+        for p in 3..11 {
+            assert!(!is_black(&display, p, p));
+        }
+        // top left corner of the top left finder pattern
+        assert!(is_black(&display, 11, 11));
+        assert!(is_black(&display, 12, 12));
+        // top right corner of the top right finder pattern
+        assert!(is_black(&display, 52, 11));
+        assert!(!is_black(&display, 53, 11));
+    }
+
+    #[test]
+    fn qr_area_too_small() {
+        let mut buffer = [Color::White.get_byte_value(); 16 / 8 * 16];
+        let mut display = VarDisplay::new(16, 16, &mut buffer);
+
+        assert_eq!(
+            draw_qr_code(&mut display, "epd"),
+            Err(QrError::AreaTooSmall)
+        );
+    }
+
+    #[test]
+    fn qr_data_too_long() {
+        let mut buffer = [Color::White.get_byte_value(); 64 / 8 * 64];
+        let mut display = VarDisplay::new(64, 64, &mut buffer);
+
+        let text = core::str::from_utf8(&[b'x'; 1000]).unwrap();
+        assert_eq!(draw_qr_code(&mut display, text), Err(QrError::DataTooLong));
+    }
+}
@@ -0,0 +1,297 @@
+//! Typed values for configuration registers shared by many of the display controllers
+
+/// Value of the VCOM and data interval setting register (`0x50`)
+///
+/// It selects what is driven on the border, the polarity of the frame data and the
+/// time between the data and the VCOM signal. Some panel batches need a different
+/// interval or border setting than the defaults of the drivers to avoid artifacts.
+///
+/// Most controllers use a single byte for this setting (see [`to_byte`](Self::to_byte)),
+/// the newer ones of the 7.5" and 5.83" V2 displays use two bytes (see [`to_bytes`](Self::to_bytes)).
+/// Fields which don't exist in the layout of a controller are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VcomAndDataInterval {
+    /// Border output selection (`VBD`/`BDV`), `0..=3`
+    ///
+    /// Which value selects which border color depends on the controller and [`data_polarity`](Self::data_polarity).
+    pub border: u8,
+    /// Keeps the border output in high impedance (`BDZ`), only available with two bytes
+    pub border_hiz: bool,
+    /// Copies the new data to the old data when no refresh happens (`N2OCP`), only available with two bytes
+    pub copy_new_to_old: bool,
+    /// Data polarity (`DDX`), `0..=3`
+    pub data_polarity: u8,
+    /// VCOM and data interval (`CDI`), `0..=15`
+    ///
+    /// The interval is `17 - interval` hsync periods, so `7` results in the usual 10 hsyncs.
+    pub interval: u8,
+}
+
+impl VcomAndDataInterval {
+    /// Parses the single byte layout: `VBD[1:0] DDX[1:0] CDI[3:0]`
+    pub const fn from_byte(value: u8) -> Self {
+        VcomAndDataInterval {
+            border: value >> 6,
+            border_hiz: false,
+            copy_new_to_old: false,
+            data_polarity: (value >> 4) & 0b11,
+            interval: value & 0x0f,
+        }
+    }
+
+    /// Encodes the single byte layout: `VBD[1:0] DDX[1:0] CDI[3:0]`
+    pub const fn to_byte(self) -> u8 {
+        (self.border & 0b11) << 6 | (self.data_polarity & 0b11) << 4 | (self.interval & 0x0f)
+    }
+
+    /// Parses the two byte layout: `BDZ - BDV[1:0] N2OCP - DDX[1:0]` and `- - - - CDI[3:0]`
+    pub const fn from_bytes(value: [u8; 2]) -> Self {
+        VcomAndDataInterval {
+            border: (value[0] >> 4) & 0b11,
+            border_hiz: value[0] & 0x80 != 0,
+            copy_new_to_old: value[0] & 0x08 != 0,
+            data_polarity: value[0] & 0b11,
+            interval: value[1] & 0x0f,
+        }
+    }
+
+    /// Encodes the two byte layout: `BDZ - BDV[1:0] N2OCP - DDX[1:0]` and `- - - - CDI[3:0]`
+    pub const fn to_bytes(self) -> [u8; 2] {
+        [
+            (self.border_hiz as u8) << 7
+                | (self.border & 0b11) << 4
+                | (self.copy_new_to_old as u8) << 3
+                | (self.data_polarity & 0b11),
+            self.interval & 0x0f,
+        ]
+    }
+}
+
+/// Value of the TCON setting register (`0x60`): the gate/source non-overlap periods
+///
+/// The non-overlap periods separate the switching of the gate and the source outputs.
+/// The defaults work for most panels, but at temperature extremes longer periods can
+/// remove faint vertical streaks.
+///
+/// Each period is `4 * (value + 1)` clock cycles, e.g. the default of `2` results in 12 cycles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TconTiming {
+    /// Source to gate non-overlap period (`S2G`), `0..=15`
+    pub source_to_gate: u8,
+    /// Gate to source non-overlap period (`G2S`), `0..=15`
+    pub gate_to_source: u8,
+}
+
+impl TconTiming {
+    /// The power-on default of the controllers: 12 cycles for both periods
+    pub const DEFAULT: TconTiming = TconTiming::from_byte(0x22);
+
+    /// Parses the register layout: `S2G[3:0] G2S[3:0]`
+    pub const fn from_byte(value: u8) -> Self {
+        TconTiming {
+            source_to_gate: value >> 4,
+            gate_to_source: value & 0x0f,
+        }
+    }
+
+    /// Encodes the register layout: `S2G[3:0] G2S[3:0]`
+    pub const fn to_byte(self) -> u8 {
+        (self.source_to_gate & 0x0f) << 4 | (self.gate_to_source & 0x0f)
+    }
+
+    /// Source to gate non-overlap period in clock cycles
+    pub const fn source_to_gate_cycles(self) -> u32 {
+        4 * ((self.source_to_gate & 0x0f) as u32 + 1)
+    }
+
+    /// Gate to source non-overlap period in clock cycles
+    pub const fn gate_to_source_cycles(self) -> u32 {
+        4 * ((self.gate_to_source & 0x0f) as u32 + 1)
+    }
+}
+
+impl Default for TconTiming {
+    fn default() -> Self {
+        TconTiming::DEFAULT
+    }
+}
+
+/// Frame rate selected by the PLL control register (`0x30`)
+///
+/// Lower frame rates reduce the power needed for a refresh, higher ones can reduce
+/// ghosting on some panels but make the refresh take longer with the same LUTs.
+/// The power-on default of the controllers is [`Hz50`](Self::Hz50).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameRate {
+    /// 50 Hz
+    Hz50,
+    /// 100 Hz
+    Hz100,
+    /// 150 Hz
+    Hz150,
+    /// 171 Hz
+    Hz171,
+    /// 200 Hz
+    Hz200,
+}
+
+impl FrameRate {
+    /// Parses the register layout: `M[2:0] N[2:0]`
+    ///
+    /// Returns `None` for combinations which aren't one of the frame rates above.
+    pub const fn from_byte(value: u8) -> Option<Self> {
+        match value {
+            0x3C => Some(FrameRate::Hz50),
+            0x3A => Some(FrameRate::Hz100),
+            0x29 => Some(FrameRate::Hz150),
+            0x31 => Some(FrameRate::Hz171),
+            0x39 => Some(FrameRate::Hz200),
+            _ => None,
+        }
+    }
+
+    /// Encodes the register layout: `M[2:0] N[2:0]`
+    pub const fn to_byte(self) -> u8 {
+        match self {
+            FrameRate::Hz50 => 0x3C,
+            FrameRate::Hz100 => 0x3A,
+            FrameRate::Hz150 => 0x29,
+            FrameRate::Hz171 => 0x31,
+            FrameRate::Hz200 => 0x39,
+        }
+    }
+}
+
+/// One entry of an init sequence: a command with its data, optionally followed by a delay
+/// and by waiting until the display isn't busy any more
+///
+/// The drivers which support [`InitSequence`](crate::traits::InitSequence) keep their init
+/// sequence as a table of these steps in an `INIT_SEQUENCE` constant. To change single entries
+/// for a problem panel, copy the table into a `static` and replace them:
+///
+///```rust
+/// use epd_waveshare::{epd4in2, settings::InitStep};
+///
+/// static INIT: [InitStep; epd4in2::INIT_SEQUENCE.len()] = {
+///     let mut steps = epd4in2::INIT_SEQUENCE;
+///     // power setting with a lower VDH/VDL
+///     steps[0] = InitStep::new(0x01, &[0x03, 0x00, 0x26, 0x26, 0xff]);
+///     steps
+/// };
+/// // then `epd.set_init_sequence(&INIT)` before the next init or wake up
+///```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitStep {
+    /// Address of the command
+    pub command: u8,
+    /// Data which is sent after the command, can be empty
+    pub data: &'static [u8],
+    /// Delay after the data in milliseconds
+    pub delay_ms: u8,
+    /// Waits until the display isn't busy any more after the delay
+    pub wait_until_idle: bool,
+}
+
+impl InitStep {
+    /// A command with its data, without delay or waiting
+    pub const fn new(command: u8, data: &'static [u8]) -> Self {
+        InitStep {
+            command,
+            data,
+            delay_ms: 0,
+            wait_until_idle: false,
+        }
+    }
+
+    /// Sets the delay after the data
+    pub const fn delay(mut self, delay_ms: u8) -> Self {
+        self.delay_ms = delay_ms;
+        self
+    }
+
+    /// Waits until the display isn't busy any more after the step
+    pub const fn wait(mut self) -> Self {
+        self.wait_until_idle = true;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tcon_timing() {
+        let timing = TconTiming::default();
+        assert_eq!(timing.to_byte(), 0x22);
+        assert_eq!(timing.source_to_gate_cycles(), 12);
+        assert_eq!(timing.gate_to_source_cycles(), 12);
+
+        let timing = TconTiming {
+            source_to_gate: 0x0f,
+            gate_to_source: 0x01,
+        };
+        assert_eq!(timing.to_byte(), 0xf1);
+        assert_eq!(timing.source_to_gate_cycles(), 64);
+        assert_eq!(timing.gate_to_source_cycles(), 8);
+        assert_eq!(TconTiming::from_byte(0xf1), timing);
+    }
+
+    #[test]
+    fn frame_rate() {
+        for rate in [
+            FrameRate::Hz50,
+            FrameRate::Hz100,
+            FrameRate::Hz150,
+            FrameRate::Hz171,
+            FrameRate::Hz200,
+        ] {
+            assert_eq!(FrameRate::from_byte(rate.to_byte()), Some(rate));
+        }
+        assert_eq!(FrameRate::from_byte(0x3A), Some(FrameRate::Hz100));
+        assert_eq!(FrameRate::from_byte(0x00), None);
+    }
+
+    #[test]
+    fn vcom_and_data_interval_byte() {
+        let setting = VcomAndDataInterval::from_byte(0x97);
+        assert_eq!(setting.border, 0b10);
+        assert_eq!(setting.data_polarity, 0b01);
+        assert_eq!(setting.interval, 0x07);
+        assert_eq!(setting.to_byte(), 0x97);
+
+        for value in 0..=u8::MAX {
+            assert_eq!(VcomAndDataInterval::from_byte(value).to_byte(), value);
+        }
+    }
+
+    #[test]
+    fn vcom_and_data_interval_bytes() {
+        let setting = VcomAndDataInterval::from_bytes([0x11, 0x07]);
+        assert_eq!(setting.border, 0b01);
+        assert!(!setting.border_hiz);
+        assert!(!setting.copy_new_to_old);
+        assert_eq!(setting.data_polarity, 0b01);
+        assert_eq!(setting.interval, 0x07);
+        assert_eq!(setting.to_bytes(), [0x11, 0x07]);
+
+        let setting = VcomAndDataInterval {
+            border_hiz: true,
+            copy_new_to_old: true,
+            ..setting
+        };
+        assert_eq!(setting.to_bytes(), [0x99, 0x07]);
+    }
+
+    #[test]
+    fn init_step_builder() {
+        let step = InitStep::new(0x04, &[]).delay(5).wait();
+        assert_eq!(step.command, 0x04);
+        assert!(step.data.is_empty());
+        assert_eq!(step.delay_ms, 5);
+        assert!(step.wait_until_idle);
+
+        let step = InitStep::new(0x00, &[0x3F]);
+        assert_eq!((step.delay_ms, step.wait_until_idle), (0, false));
+    }
+}
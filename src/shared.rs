@@ -0,0 +1,175 @@
+//! Sharing a driver between the main loop and interrupt handlers
+//!
+//! [`SharedEpd`] can be put into a `static`. The driver is only accessed inside a critical
+//! section, so e.g. a button interrupt can request an update or even draw directly while
+//! the main loop owns the driver otherwise, without any `static mut`.
+//!
+//! The critical section itself comes from the application through [`CriticalSection`], so it
+//! works with the [`critical-section`](https://docs.rs/critical-section) crate as well as with
+//! `cortex_m::interrupt::free` or the locks of an RTOS.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::shared::{CriticalSection, SharedEpd};
+//!
+//! struct Cs;
+//!
+//! // Safety: only correct on a single core with interrupts disabled in `with`
+//! unsafe impl CriticalSection for Cs {
+//!     fn with<R>(f: impl FnOnce() -> R) -> R {
+//!         // e.g. `critical_section::with(|_| f())`
+//!         f()
+//!     }
+//! }
+//!
+//! # struct Epd;
+//! static EPD: SharedEpd<Epd, Cs> = SharedEpd::new();
+//!
+//! // main: move the driver in after creating it
+//! EPD.init(Epd);
+//!
+//! // interrupt handler
+//! EPD.request_update();
+//!
+//! // main loop
+//! if EPD.take_update_request() {
+//!     EPD.lock(|epd| {
+//!         // update_frame, display_frame, ...
+//!     });
+//! }
+//!```
+
+use core::cell::{Cell, UnsafeCell};
+use core::marker::PhantomData;
+
+/// Runs code in a critical section, e.g. with disabled interrupts
+///
+/// # Safety
+///
+/// No two calls of `with` may run at the same time, e.g. on another core or in an interrupt
+/// handler, otherwise [`SharedEpd`] hands out the driver twice.
+pub unsafe trait CriticalSection {
+    /// Runs `f` in a critical section
+    fn with<R>(f: impl FnOnce() -> R) -> R;
+}
+
+/// A driver which can be shared between the main loop and interrupt handlers, see the [module docs](self)
+pub struct SharedEpd<EPD, CS> {
+    epd: UnsafeCell<Option<EPD>>,
+    /// Whether `epd` is borrowed by a [`lock`](SharedEpd::lock) call, so nested calls fail
+    locked: Cell<bool>,
+    update_requested: Cell<bool>,
+    _cs: PhantomData<CS>,
+}
+
+// The cells are only accessed inside of critical sections
+unsafe impl<EPD: Send, CS> Sync for SharedEpd<EPD, CS> {}
+
+impl<EPD, CS: CriticalSection> SharedEpd<EPD, CS> {
+    /// Creates an empty wrapper, which can be used as `static`
+    pub const fn new() -> Self {
+        SharedEpd {
+            epd: UnsafeCell::new(None),
+            locked: Cell::new(false),
+            update_requested: Cell::new(false),
+            _cs: PhantomData,
+        }
+    }
+
+    /// Moves the driver in, replacing and returning a previous one
+    ///
+    /// Returns `Err(epd)` if the driver is used by a [`lock`](SharedEpd::lock) call right now.
+    pub fn init(&self, epd: EPD) -> Result<Option<EPD>, EPD> {
+        CS::with(|| {
+            if self.locked.get() {
+                return Err(epd);
+            }
+            // Safety: in a critical section and not borrowed by `lock`
+            Ok(unsafe { &mut *self.epd.get() }.replace(epd))
+        })
+    }
+
+    /// Takes the driver out again
+    pub fn take(&self) -> Option<EPD> {
+        CS::with(|| {
+            if self.locked.get() {
+                return None;
+            }
+            // Safety: in a critical section and not borrowed by `lock`
+            unsafe { &mut *self.epd.get() }.take()
+        })
+    }
+
+    /// Runs `f` with the driver in a critical section
+    ///
+    /// Returns `None` without calling `f` if there's no driver yet or it's already used
+    /// by an outer `lock` call.
+    ///
+    /// Interrupts are blocked while `f` runs, so long running operations like waiting for
+    /// a refresh should rather be done by the main loop after a
+    /// [`request_update`](SharedEpd::request_update).
+    pub fn lock<R>(&self, f: impl FnOnce(&mut EPD) -> R) -> Option<R> {
+        CS::with(|| {
+            if self.locked.replace(true) {
+                return None;
+            }
+            // Safety: in a critical section and `locked` prevents a second borrow
+            let result = unsafe { &mut *self.epd.get() }.as_mut().map(f);
+            self.locked.set(false);
+            result
+        })
+    }
+
+    /// Requests an update from the owner of the driver, e.g. from an interrupt handler
+    pub fn request_update(&self) {
+        CS::with(|| self.update_requested.set(true));
+    }
+
+    /// Returns whether an update was requested and clears the request
+    pub fn take_update_request(&self) -> bool {
+        CS::with(|| self.update_requested.replace(false))
+    }
+}
+
+impl<EPD, CS: CriticalSection> Default for SharedEpd<EPD, CS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoCs;
+
+    // Safety: the test runs on a single thread
+    unsafe impl CriticalSection for NoCs {
+        fn with<R>(f: impl FnOnce() -> R) -> R {
+            f()
+        }
+    }
+
+    #[test]
+    fn lock_and_requests() {
+        let shared: SharedEpd<u32, NoCs> = SharedEpd::new();
+        assert_eq!(shared.lock(|epd| *epd), None);
+
+        assert_eq!(shared.init(1), Ok(None));
+        assert_eq!(shared.lock(|epd| *epd += 1), Some(()));
+
+        // nested locks and replacing the locked driver fail
+        shared.lock(|_| {
+            assert_eq!(shared.lock(|epd| *epd), None);
+            assert_eq!(shared.init(5), Err(5));
+        });
+
+        assert!(!shared.take_update_request());
+        shared.request_update();
+        assert!(shared.take_update_request());
+        assert!(!shared.take_update_request());
+
+        assert_eq!(shared.take(), Some(2));
+    }
+}
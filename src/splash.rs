@@ -0,0 +1,130 @@
+//! Boot/splash images shown straight from storage
+//!
+//! [`display_splash`] streams a raw frame from any [`ImageSource`] to the display and
+//! refreshes it in one call. Only a single row is buffered, so it can be used right after
+//! boot before the main application allocates its frame buffer, e.g. for instant-on branding.
+//!
+//! The image has to be stored in the native buffer layout of the display, the same as a
+//! buffer passed to [`WaveshareDisplay::update_frame`], row after row from the top.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!# use epd_waveshare::{epd2in9::*, prelude::*, splash::display_splash};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!#
+//!let mut epd = Epd2in9::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// e.g. `include_bytes!("logo.bin")` or memory-mapped flash
+//!static LOGO: [u8; WIDTH as usize / 8 * HEIGHT as usize] = [0xFF; WIDTH as usize / 8 * HEIGHT as usize];
+//!
+//!display_splash(&mut epd, &mut spi, &mut delay, &mut &LOGO[..]).ok();
+//!# Ok(())
+//!# }
+//!```
+
+use crate::traits::{DisplayError, RowRendering, WaveshareDisplay};
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+/// Raw storage an image can be read from, like external flash or an in-memory slice
+pub trait ImageSource {
+    /// Error while reading
+    type Error;
+
+    /// Fills `buf` with the bytes starting at `offset`
+    fn read(&mut self, offset: usize, buf: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+/// The image in the slice is shorter than the frame of the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageTooShort;
+
+impl ImageSource for &[u8] {
+    type Error = ImageTooShort;
+
+    fn read(&mut self, offset: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let data = self.get(offset..offset + buf.len()).ok_or(ImageTooShort)?;
+        buf.copy_from_slice(data);
+        Ok(())
+    }
+}
+
+/// Errors while showing a splash image
+#[derive(Debug, PartialEq, Eq)]
+pub enum SplashError<SpiError, ReadError> {
+    /// Sending to the display failed
+    Spi(SpiError),
+    /// Reading the image failed, the display wasn't refreshed
+    Read(ReadError),
+}
+
+/// Streams a full frame from `source` to the display and refreshes it
+///
+/// If reading fails the remaining rows are sent in the background color and the display
+/// isn't refreshed, so the previous content stays visible.
+#[allow(clippy::type_complexity)]
+pub fn display_splash<EPD, SPI, CS, BUSY, DC, RST, DELAY, S>(
+    epd: &mut EPD,
+    spi: &mut SPI,
+    delay: &mut DELAY,
+    source: &mut S,
+) -> Result<(), SplashError<DisplayError<SPI, CS, BUSY, DC, RST>, S::Error>>
+where
+    EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+        + RowRendering<SPI, CS, BUSY, DC, RST, DELAY>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    S: ImageSource,
+{
+    let mut read_error = None;
+    epd.update_frame_with(spi, delay, |y, row| {
+        if read_error.is_none() {
+            if let Err(e) = source.read(y as usize * row.len(), row) {
+                read_error = Some(e);
+            }
+        }
+    })
+    .map_err(SplashError::Spi)?;
+
+    if let Some(e) = read_error {
+        return Err(SplashError::Read(e));
+    }
+    epd.display_frame(spi, delay).map_err(SplashError::Spi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_from_slice() {
+        let data = [0u8, 1, 2, 3, 4, 5];
+        let mut source = &data[..];
+        let mut buf = [0u8; 2];
+
+        source.read(2, &mut buf).unwrap();
+        assert_eq!(buf, [2, 3]);
+
+        source.read(4, &mut buf).unwrap();
+        assert_eq!(buf, [4, 5]);
+
+        assert_eq!(source.read(5, &mut buf), Err(ImageTooShort));
+    }
+}
@@ -0,0 +1,134 @@
+//! Pre-rendering text into 1 bit sprites on the host
+//!
+//! Fonts for complex scripts or a lot of glyphs don't fit on small targets. Instead, the
+//! strings can be rendered ahead of time on the host, e.g. in a build script, with any
+//! embedded-graphics text renderer. The [`Sprite`]s are packed like the frame buffers of the
+//! black/white displays, so they can be sent with
+//! [`update_partial_frame`](crate::prelude::WaveshareDisplay::update_partial_frame) as they are.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::{sprite::Sprite, text::default_text_style};
+//!
+//! let sprite = Sprite::render("Hello", default_text_style());
+//! assert_eq!((sprite.width, sprite.height), (32, 10));
+//!
+//! // e.g. written to a file in `OUT_DIR` by a build script and included by the firmware
+//! let source = sprite.to_rust("GREETING");
+//! assert!(source.starts_with("pub const GREETING_WIDTH: u32 = 32;"));
+//!```
+
+use crate::color::Color;
+use crate::graphics::VarDisplay;
+use crate::tiles::Tile;
+use embedded_graphics::{
+    prelude::*,
+    text::{renderer::TextRenderer, Baseline, Text},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use std::fmt::Write;
+use std::string::String;
+use std::vec;
+use std::vec::Vec;
+
+/// A black/white bitmap with rows padded to whole bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sprite {
+    /// Width in pixels, always a multiple of 8
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Pixels, one bit each with set bits for white, the first pixel in the highest bit
+    pub data: Vec<u8>,
+}
+
+impl Sprite {
+    /// Renders `text` with `style` into the smallest sprite which holds it
+    ///
+    /// The width is rounded up to whole bytes, the padding is white.
+    pub fn render<S>(text: &str, style: S) -> Sprite
+    where
+        S: TextRenderer<Color = BinaryColor>,
+    {
+        let bounds = style
+            .measure_string(text, Point::zero(), Baseline::Top)
+            .bounding_box;
+        let width = bounds.size.width.div_ceil(8) * 8;
+        let height = bounds.size.height;
+
+        let mut data = vec![Color::White.get_byte_value(); (width / 8 * height) as usize];
+        if width > 0 && height > 0 {
+            let mut display = VarDisplay::new(width, height, &mut data);
+            let _ = Text::with_baseline(text, -bounds.top_left, style, Baseline::Top)
+                .draw(&mut display);
+        }
+        Sprite {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// The sprite as [`Tile`], e.g. to send it to the display on the host
+    pub fn tile(&self) -> Tile<'_> {
+        Tile::new(self.width, self.height, &self.data)
+    }
+
+    /// Rust source with the size and data of the sprite as constants
+    ///
+    /// `name` is the prefix of the constants `<name>_WIDTH`, `<name>_HEIGHT` and `<name>`.
+    pub fn to_rust(&self, name: &str) -> String {
+        let mut source = String::new();
+        let _ = writeln!(source, "pub const {}_WIDTH: u32 = {};", name, self.width);
+        let _ = writeln!(source, "pub const {}_HEIGHT: u32 = {};", name, self.height);
+        let _ = writeln!(source, "pub const {}: [u8; {}] = [", name, self.data.len());
+        for row in self.data.chunks((self.width / 8).max(1) as usize) {
+            source.push_str("   ");
+            for byte in row {
+                let _ = write!(source, " 0x{:02X},", byte);
+            }
+            source.push('\n');
+        }
+        source.push_str("];\n");
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::default_text_style;
+
+    #[test]
+    fn render_text() {
+        let sprite = Sprite::render("Hi!", default_text_style());
+        // 3 characters of 6 pixels, rounded up to 24
+        assert_eq!((sprite.width, sprite.height), (24, 10));
+        assert_eq!(sprite.data.len(), 3 * 10);
+        assert!(sprite.data.iter().any(|&byte| byte != 0xFF));
+        // the padding stays white
+        assert!(sprite.data.chunks(3).all(|row| row[2] & 0x3F == 0x3F));
+
+        let empty = Sprite::render("", default_text_style());
+        assert!(empty.data.is_empty());
+    }
+
+    #[test]
+    fn rust_source() {
+        let sprite = Sprite {
+            width: 8,
+            height: 2,
+            data: vec![0xFF, 0x0F],
+        };
+        assert_eq!(
+            sprite.to_rust("ICON"),
+            "pub const ICON_WIDTH: u32 = 8;\n\
+             pub const ICON_HEIGHT: u32 = 2;\n\
+             pub const ICON: [u8; 2] = [\n    \
+             0xFF,\n    \
+             0x0F,\n\
+             ];\n"
+        );
+    }
+}
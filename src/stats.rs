@@ -0,0 +1,229 @@
+//! Statistics about the content of frame buffers
+//!
+//! Counts the pixels of each color in a buffer and the pixels which changed
+//! compared to a previous frame. This can be used to decide if a quick or partial
+//! refresh is good enough or if it's time for a full refresh to clean up ghosting.
+//!
+//! All functions work on the raw buffers as they are sent to the displays, so they can
+//! be used with and without the `graphics` feature. Buffers with a width which isn't
+//! a multiple of 8 are padded at the end of each row, these padding bits are ignored.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::stats::{changed_pixels, PixelCounts};
+//!
+//! let old = [0xFF; 4];
+//! let new = [0xFF, 0x0F, 0xFF, 0xFF];
+//!
+//! let counts = PixelCounts::from_bw(&new, 16);
+//! assert_eq!(counts.black, 4);
+//! assert_eq!(counts.white, 28);
+//!
+//! assert_eq!(changed_pixels(&old, &new, 16), 4);
+//!```
+
+use crate::color::OctColor;
+#[cfg(feature = "graphics")]
+use crate::graphics::DisplayColorRendering;
+
+/// Number of pixels per color of a frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PixelCounts {
+    /// Black pixels
+    pub black: u32,
+    /// White pixels
+    pub white: u32,
+    /// Pixels of any other color (red/yellow on tri-color displays, all colors besides
+    /// black and white on 7-color displays)
+    pub chromatic: u32,
+}
+
+impl PixelCounts {
+    /// Counts the pixels of a black/white buffer
+    ///
+    /// A set bit is a white pixel, a cleared bit is a black one.
+    pub fn from_bw(buffer: &[u8], width: u32) -> Self {
+        let mut counts = PixelCounts::default();
+        for_each_row(buffer, width, |byte, mask| {
+            let white = (byte & mask).count_ones();
+            counts.white += white;
+            counts.black += mask.count_ones() - white;
+        });
+        counts
+    }
+
+    /// Counts the pixels of a tri-color display from its black/white and its chromatic plane
+    ///
+    /// Chromatic pixels take precedence over the black/white plane, like on the displays.
+    /// `rendering` tells how the chromatic plane marks a colored pixel.
+    #[cfg(feature = "graphics")]
+    pub fn from_tri(
+        bw: &[u8],
+        chromatic: &[u8],
+        width: u32,
+        rendering: DisplayColorRendering,
+    ) -> Self {
+        let mut counts = PixelCounts::default();
+        let row_len = row_len(width);
+        for (bw_row, chromatic_row) in bw.chunks(row_len).zip(chromatic.chunks(row_len)) {
+            for (i, (&bw_byte, &chromatic_byte)) in bw_row.iter().zip(chromatic_row).enumerate() {
+                let mask = pixel_mask(i, width);
+                let colored = match rendering {
+                    DisplayColorRendering::Positive => !chromatic_byte,
+                    DisplayColorRendering::Negative => chromatic_byte,
+                } & mask;
+                let white = bw_byte & mask & !colored;
+                counts.chromatic += colored.count_ones();
+                counts.white += white.count_ones();
+                counts.black += mask.count_ones() - colored.count_ones() - white.count_ones();
+            }
+        }
+        counts
+    }
+
+    /// Counts the pixels of a 7-color buffer with two pixels per byte
+    ///
+    /// Invalid nibbles and [`OctColor::HiZ`] are counted as chromatic.
+    pub fn from_oct(buffer: &[u8]) -> Self {
+        let mut counts = PixelCounts::default();
+        for &byte in buffer {
+            for nibble in [byte >> 4, byte & 0x0f] {
+                match OctColor::from_nibble(nibble) {
+                    Ok(OctColor::Black) => counts.black += 1,
+                    Ok(OctColor::White) => counts.white += 1,
+                    _ => counts.chromatic += 1,
+                }
+            }
+        }
+        counts
+    }
+
+    /// Total number of counted pixels
+    pub fn total(&self) -> u32 {
+        self.black + self.white + self.chromatic
+    }
+}
+
+/// Counts the pixels which differ between two 1 bit per pixel buffers
+///
+/// Works for black/white buffers as well as for single planes of tri-color buffers.
+/// Only the common length of both buffers is compared.
+pub fn changed_pixels(old: &[u8], new: &[u8], width: u32) -> u32 {
+    let row_len = row_len(width);
+    old.chunks(row_len)
+        .zip(new.chunks(row_len))
+        .map(|(old_row, new_row)| {
+            old_row
+                .iter()
+                .zip(new_row)
+                .enumerate()
+                .map(|(i, (old, new))| ((old ^ new) & pixel_mask(i, width)).count_ones())
+                .sum::<u32>()
+        })
+        .sum()
+}
+
+/// Counts the pixels which differ between two 7-color buffers with two pixels per byte
+pub fn changed_oct_pixels(old: &[u8], new: &[u8]) -> u32 {
+    old.iter()
+        .zip(new)
+        .map(|(old, new)| {
+            let diff = old ^ new;
+            u32::from(diff & 0xf0 != 0) + u32::from(diff & 0x0f != 0)
+        })
+        .sum()
+}
+
+/// Bytes per row, including the padding
+fn row_len(width: u32) -> usize {
+    width.div_ceil(8).max(1) as usize
+}
+
+/// Mask of the bits in the `i`th byte of a row which are actual pixels
+fn pixel_mask(i: usize, width: u32) -> u8 {
+    let remaining = width.saturating_sub(i as u32 * 8);
+    if remaining >= 8 {
+        0xff
+    } else {
+        !(0xffu8 >> remaining)
+    }
+}
+
+fn for_each_row(buffer: &[u8], width: u32, mut f: impl FnMut(u8, u8)) {
+    for row in buffer.chunks(row_len(width)) {
+        for (i, &byte) in row.iter().enumerate() {
+            f(byte, pixel_mask(i, width));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bw_counts() {
+        let counts = PixelCounts::from_bw(&[0xFF, 0x00, 0xF0], 8);
+        assert_eq!(counts.white, 12);
+        assert_eq!(counts.black, 12);
+        assert_eq!(counts.chromatic, 0);
+        assert_eq!(counts.total(), 24);
+    }
+
+    #[test]
+    fn bw_counts_ignore_padding() {
+        // 10 pixels wide: 2 bytes per row with 6 padding bits each
+        let counts = PixelCounts::from_bw(&[0xFF, 0xFF, 0x00, 0x00], 10);
+        assert_eq!(counts.white, 10);
+        assert_eq!(counts.black, 10);
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn tri_counts() {
+        // positive: cleared chromatic bit is a chromatic pixel
+        let counts =
+            PixelCounts::from_tri(&[0xF0], &[0b1100_1111], 8, DisplayColorRendering::Positive);
+        assert_eq!(counts.chromatic, 2);
+        assert_eq!(counts.white, 2);
+        assert_eq!(counts.black, 4);
+
+        // negative: set chromatic bit is a chromatic pixel
+        let counts =
+            PixelCounts::from_tri(&[0xF0], &[0b0011_0000], 8, DisplayColorRendering::Negative);
+        assert_eq!(counts.chromatic, 2);
+        assert_eq!(counts.white, 2);
+        assert_eq!(counts.black, 4);
+    }
+
+    #[test]
+    fn oct_counts() {
+        let buffer = [
+            OctColor::colors_byte(OctColor::Black, OctColor::White),
+            OctColor::colors_byte(OctColor::Red, OctColor::White),
+        ];
+        let counts = PixelCounts::from_oct(&buffer);
+        assert_eq!(counts.black, 1);
+        assert_eq!(counts.white, 2);
+        assert_eq!(counts.chromatic, 1);
+    }
+
+    #[test]
+    fn changed() {
+        assert_eq!(changed_pixels(&[0xFF, 0xFF], &[0xFF, 0xFF], 16), 0);
+        assert_eq!(changed_pixels(&[0xFF, 0xFF], &[0x7F, 0xFE], 16), 2);
+        // padding bits don't count as changed pixels
+        assert_eq!(changed_pixels(&[0xFF, 0xFF], &[0x7F, 0x00], 10), 3);
+    }
+
+    #[test]
+    fn changed_oct() {
+        let old = [OctColor::colors_byte(OctColor::Black, OctColor::White); 2];
+        let new = [
+            OctColor::colors_byte(OctColor::Black, OctColor::Red),
+            OctColor::colors_byte(OctColor::Green, OctColor::Blue),
+        ];
+        assert_eq!(changed_oct_pixels(&old, &new), 3);
+    }
+}
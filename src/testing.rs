@@ -0,0 +1,382 @@
+//! Pixel exact comparisons of display buffers for tests, which need the standard library
+//!
+//! [`diff_buffers`] compares two black/white buffers (or single planes of tri-color buffers)
+//! pixel by pixel and returns a [`BufferDiff`] with the number of differing pixels, the first
+//! one and the window around all of them. [`assert_buffers_eq`] and [`assert_matches_pbm`] panic
+//! with this report, so a regression in e.g. rotation, blitting or dithering code shows where
+//! it went wrong instead of two long byte arrays.
+//!
+//! The expected frames can be kept as [PBM](https://netpbm.sourceforge.net/doc/pbm.html) images
+//! next to the tests, both the plain (`P1`) and the raw (`P4`) format are supported.
+//! [`Pbm::from_buffer`] and [`Pbm::to_bytes`] create them from a buffer which is known to be
+//! right. Save the image of a failing comparison with
+//! [`save_diff_image`](crate::debug::save_diff_image) to see the whole difference.
+//!
+//! # Example
+//!
+//!```rust
+//! use epd_waveshare::testing::assert_matches_pbm;
+//!
+//! // 8x2 pixels, the left half of the second row is black
+//! let buffer = [0xFF, 0x0F];
+//! let expected = b"P1\n8 2\n0 0 0 0 0 0 0 0\n1 1 1 1 0 0 0 0\n";
+//! assert_matches_pbm(&buffer, expected);
+//!```
+
+use crate::buffer_len;
+use crate::debug::Window;
+use core::fmt;
+use std::format;
+use std::vec;
+use std::vec::Vec;
+
+/// Result of the comparison of two buffers, see [`diff_buffers`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferDiff {
+    /// Width of the compared frames in pixels
+    pub width: u32,
+    /// Height of the compared frames in pixels
+    pub height: u32,
+    /// Number of pixels which differ
+    pub mismatches: u32,
+    /// Coordinates of the first differing pixel, row after row from the top
+    pub first: Option<(u32, u32)>,
+    /// Smallest window which holds all differing pixels
+    pub bounds: Option<Window>,
+    /// Lengths of the actual and the expected buffer
+    pub lengths: (usize, usize),
+}
+
+impl BufferDiff {
+    /// Whether the buffers are the same, including their lengths
+    pub fn is_equal(&self) -> bool {
+        self.mismatches == 0 && self.lengths.0 == self.lengths.1
+    }
+}
+
+impl fmt::Display for BufferDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.lengths.0 != self.lengths.1 {
+            write!(
+                f,
+                "buffer lengths differ, {} bytes instead of {}; ",
+                self.lengths.0, self.lengths.1
+            )?;
+        }
+        write!(
+            f,
+            "{} of {} pixels differ",
+            self.mismatches,
+            self.width * self.height
+        )?;
+        if let Some((x, y)) = self.first {
+            write!(f, ", the first at ({}, {})", x, y)?;
+        }
+        if let Some(bounds) = self.bounds {
+            write!(
+                f,
+                ", all within {}x{} at ({}, {})",
+                bounds.width, bounds.height, bounds.x, bounds.y
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the pixels of two buffers of `width` x `height` pixels
+///
+/// Missing bytes count as white, the padding bits at the end of the rows are not compared.
+pub fn diff_buffers(actual: &[u8], expected: &[u8], width: u32, height: u32) -> BufferDiff {
+    let mut diff = BufferDiff {
+        width,
+        height,
+        mismatches: 0,
+        first: None,
+        bounds: None,
+        lengths: (actual.len(), expected.len()),
+    };
+    let (mut min, mut max) = ((u32::MAX, u32::MAX), (0, 0));
+    for y in 0..height {
+        for x in 0..width {
+            if is_white(actual, width, x, y) == is_white(expected, width, x, y) {
+                continue;
+            }
+            diff.mismatches += 1;
+            diff.first.get_or_insert((x, y));
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+    }
+    if diff.mismatches > 0 {
+        diff.bounds = Some(Window {
+            x: min.0,
+            y: min.1,
+            width: max.0 - min.0 + 1,
+            height: max.1 - min.1 + 1,
+        });
+    }
+    diff
+}
+
+/// Panics with a [`BufferDiff`] report if the buffers differ
+#[track_caller]
+pub fn assert_buffers_eq(actual: &[u8], expected: &[u8], width: u32, height: u32) {
+    let diff = diff_buffers(actual, expected, width, height);
+    assert!(diff.is_equal(), "buffers differ: {}", diff);
+}
+
+/// Panics with a [`BufferDiff`] report if the buffer differs from the PBM image `expected`
+///
+/// The size of the frame is the one of the image.
+///
+/// # Panics
+///
+/// Also if `expected` is no valid PBM image.
+#[track_caller]
+#[allow(clippy::panic)]
+pub fn assert_matches_pbm(actual: &[u8], expected: &[u8]) {
+    let expected = match Pbm::parse(expected) {
+        Ok(pbm) => pbm,
+        Err(e) => panic!("invalid expected image: {}", e),
+    };
+    let diff = diff_buffers(actual, &expected.buffer, expected.width, expected.height);
+    assert!(diff.is_equal(), "buffer differs from the image: {}", diff);
+}
+
+/// Errors when parsing a PBM image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PbmError {
+    /// The image doesn't start with `P1` or `P4`
+    UnknownFormat,
+    /// The width or the height is missing or invalid
+    InvalidHeader,
+    /// There are less pixels than the header says
+    Truncated,
+}
+
+impl fmt::Display for PbmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PbmError::UnknownFormat => write!(f, "not a P1 or P4 PBM image"),
+            PbmError::InvalidHeader => write!(f, "invalid PBM header"),
+            PbmError::Truncated => write!(f, "PBM image is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for PbmError {}
+
+/// A black/white image, with the pixels in the layout of the display buffers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pbm {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Rows padded to whole bytes, set bits are white
+    pub buffer: Vec<u8>,
+}
+
+impl Pbm {
+    /// Parses a plain (`P1`) or raw (`P4`) PBM image
+    ///
+    /// PBM images use set bits for black, they are turned into the white bits of the buffers.
+    pub fn parse(image: &[u8]) -> Result<Pbm, PbmError> {
+        let mut pos = 0;
+        let raw = match next_token(image, &mut pos) {
+            Some(b"P1") => false,
+            Some(b"P4") => true,
+            _ => return Err(PbmError::UnknownFormat),
+        };
+        let width = parse_number(next_token(image, &mut pos))?;
+        let height = parse_number(next_token(image, &mut pos))?;
+        let len = buffer_len(width as usize, height as usize);
+
+        let buffer = if raw {
+            // a single whitespace character separates the header from the pixels
+            let data = image.get(pos + 1..).unwrap_or(&[]);
+            if data.len() < len {
+                return Err(PbmError::Truncated);
+            }
+            data[..len].iter().map(|byte| !byte).collect()
+        } else {
+            let row_len = buffer_len(width as usize, 1);
+            let mut buffer = vec![0xFF; len];
+            for index in 0..(width * height) as usize {
+                let black = plain_pixel(image, &mut pos).ok_or(PbmError::Truncated)?;
+                let (x, y) = (index % width as usize, index / width as usize);
+                if black {
+                    buffer[y * row_len + x / 8] &= !(0x80 >> (x % 8));
+                }
+            }
+            buffer
+        };
+        Ok(Pbm {
+            width,
+            height,
+            buffer,
+        })
+    }
+
+    /// Copies a buffer of `width` x `height` pixels, missing bytes are white
+    pub fn from_buffer(buffer: &[u8], width: u32, height: u32) -> Pbm {
+        let mut copy = vec![0xFF; buffer_len(width as usize, height as usize)];
+        let len = copy.len().min(buffer.len());
+        copy[..len].copy_from_slice(&buffer[..len]);
+        Pbm {
+            width,
+            height,
+            buffer: copy,
+        }
+    }
+
+    /// Returns the image in the raw (`P4`) format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = format!("P4\n{} {}\n", self.width, self.height).into_bytes();
+        bytes.extend(self.buffer.iter().map(|byte| !byte));
+        bytes
+    }
+}
+
+/// Whether the pixel is set in a buffer with padded rows, missing bytes count as white
+fn is_white(buffer: &[u8], width: u32, x: u32, y: u32) -> bool {
+    let index = y as usize * buffer_len(width as usize, 1) + x as usize / 8;
+    buffer
+        .get(index)
+        .map_or(true, |byte| byte & (0x80 >> (x % 8)) != 0)
+}
+
+/// Returns the next whitespace separated token of the header and skips comments
+fn next_token<'a>(image: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    loop {
+        match image.get(*pos)? {
+            b'#' => {
+                while image.get(*pos).is_some_and(|&byte| byte != b'\n') {
+                    *pos += 1;
+                }
+            }
+            byte if byte.is_ascii_whitespace() => *pos += 1,
+            _ => break,
+        }
+    }
+    let start = *pos;
+    while image
+        .get(*pos)
+        .is_some_and(|byte| !byte.is_ascii_whitespace() && *byte != b'#')
+    {
+        *pos += 1;
+    }
+    Some(&image[start..*pos])
+}
+
+fn parse_number(token: Option<&[u8]>) -> Result<u32, PbmError> {
+    token
+        .and_then(|token| core::str::from_utf8(token).ok())
+        .and_then(|token| token.parse().ok())
+        .filter(|&number| number > 0)
+        .ok_or(PbmError::InvalidHeader)
+}
+
+/// Reads the next pixel of a plain image, the `0`s and `1`s don't have to be separated
+fn plain_pixel(image: &[u8], pos: &mut usize) -> Option<bool> {
+    loop {
+        match image.get(*pos)? {
+            b'#' => {
+                while image.get(*pos).is_some_and(|&byte| byte != b'\n') {
+                    *pos += 1;
+                }
+            }
+            b'0' => {
+                *pos += 1;
+                return Some(false);
+            }
+            b'1' => {
+                *pos += 1;
+                return Some(true);
+            }
+            _ => *pos += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn diff_report() {
+        // 10x2 pixels, two bytes per row
+        let expected = [0xFF, 0xC0, 0xFF, 0xC0];
+        let mut actual = expected;
+        actual[0] = 0x7F;
+        actual[3] = 0x80;
+
+        let diff = diff_buffers(&actual, &expected, 10, 2);
+        assert_eq!(diff.mismatches, 2);
+        assert_eq!(diff.first, Some((0, 0)));
+        assert_eq!(
+            diff.bounds,
+            Some(Window {
+                x: 0,
+                y: 0,
+                width: 10,
+                height: 2
+            })
+        );
+        assert_eq!(
+            diff.to_string(),
+            "2 of 20 pixels differ, the first at (0, 0), all within 10x2 at (0, 0)"
+        );
+
+        // the padding bits are not compared
+        actual = expected;
+        actual[1] = 0xFF;
+        assert!(diff_buffers(&actual, &expected, 10, 2).is_equal());
+        assert!(!diff_buffers(&actual[..3], &expected, 10, 2).is_equal());
+    }
+
+    #[test]
+    fn pbm_formats() {
+        let plain = Pbm::parse(b"P1\n# comment\n3 2\n1 0 0\n001\n").unwrap();
+        assert_eq!((plain.width, plain.height), (3, 2));
+        assert_eq!(plain.buffer, [0x7F, 0xDF]);
+
+        let raw = Pbm::parse(&plain.to_bytes()).unwrap();
+        assert_eq!(raw, plain);
+        assert_eq!(Pbm::from_buffer(&[0x7F, 0xDF], 3, 2), plain);
+
+        assert_eq!(Pbm::parse(b"P2\n3 2\n"), Err(PbmError::UnknownFormat));
+        assert_eq!(Pbm::parse(b"P1\n3\n"), Err(PbmError::InvalidHeader));
+        assert_eq!(Pbm::parse(b"P1\n3 2\n1 0 0\n"), Err(PbmError::Truncated));
+    }
+
+    #[test]
+    #[should_panic(expected = "1 of 16 pixels differ, the first at (7, 1)")]
+    fn assert_reports_mismatch() {
+        assert_matches_pbm(&[0xFF, 0xFF], b"P1\n8 2\n00000000\n00000001\n");
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn rotated_drawing() {
+        use crate::color::Black;
+        use crate::graphics::{Display, DisplayRotation, VarDisplay};
+        use embedded_graphics_core::prelude::*;
+
+        let mut buffer = [0xFF; 4];
+        let mut display = VarDisplay::new(8, 4, &mut buffer);
+        display.set_rotation(DisplayRotation::Rotate90);
+        Pixel(Point::new(0, 0), Black).draw(&mut display).unwrap();
+        Pixel(Point::new(3, 2), Black).draw(&mut display).unwrap();
+
+        assert_matches_pbm(
+            display.buffer(),
+            b"P1\n8 4\n\
+              00000001\n\
+              00000000\n\
+              00000000\n\
+              00000100\n",
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Text helpers for EPDs
+//!
+//! A thin convenience layer over the text support of [`embedded-graphics`] for the
+//! black/white displays of this crate. It comes with a small built-in bitmap font
+//! and defaults that look right on e-paper: black text on a white background,
+//! positioned by its top left corner and without any anti-aliasing.
+//!
+//! [`embedded-graphics`]: https://docs.rs/embedded-graphics/
+//!
+//! # Example
+//!
+//!```rust
+//! use embedded_graphics::prelude::*;
+//! use epd_waveshare::{epd2in9::Display2in9, text::draw_text};
+//!
+//! let mut display = Display2in9::default();
+//!
+//! // Uses the built-in font and black-on-white
+//! let next = draw_text(&mut display, "Hello Rust!", Point::new(5, 5), None).unwrap();
+//!
+//! // `next` is the position right after the drawn text
+//! let _ = draw_text(&mut display, " and ePaper", next, None);
+//!```
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    prelude::*,
+    text::{Baseline, Text},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+/// The built-in font which is used if no other style is given
+pub const DEFAULT_FONT: &MonoFont<'static> = &FONT_6X10;
+
+/// Returns the default text style: [`DEFAULT_FONT`] in black on a white background
+///
+/// The background is always drawn, so text can be redrawn in place without
+/// clearing the area first.
+pub fn default_text_style() -> MonoTextStyle<'static, BinaryColor> {
+    MonoTextStyleBuilder::new()
+        .font(DEFAULT_FONT)
+        .text_color(BinaryColor::On)
+        .background_color(BinaryColor::Off)
+        .build()
+}
+
+/// Draws `text` with its top left corner at `position`
+///
+/// If `style` is `None` the [default style](default_text_style) is used.
+///
+/// Returns the position directly after the last drawn character, so several
+/// calls can be chained to continue a line.
+pub fn draw_text<'a, D>(
+    display: &mut D,
+    text: &'a str,
+    position: Point,
+    style: Option<MonoTextStyle<'a, BinaryColor>>,
+) -> Result<Point, D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let style = match style {
+        Some(style) => style,
+        None => default_text_style(),
+    };
+    Text::with_baseline(text, position, style, Baseline::Top).draw(display)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::graphics::{Display, VarDisplay};
+
+    #[test]
+    fn draw_default_text() {
+        let mut buffer = [Color::White.get_byte_value(); 32 / 8 * 16];
+        let mut display = VarDisplay::new(32, 16, &mut buffer);
+
+        let next = draw_text(&mut display, "Hi", Point::new(0, 0), None).unwrap();
+        assert_eq!(next, Point::new(2 * 6, 0));
+
+        // some pixels must have been drawn in black
+        assert!(display
+            .buffer()
+            .iter()
+            .any(|&byte| byte != Color::White.get_byte_value()));
+    }
+
+    #[test]
+    fn draw_empty_text() {
+        let mut buffer = [Color::White.get_byte_value(); 32 / 8 * 16];
+        let mut display = VarDisplay::new(32, 16, &mut buffer);
+
+        let next = draw_text(&mut display, "", Point::new(3, 4), None).unwrap();
+        assert_eq!(next, Point::new(3, 4));
+        assert!(display
+            .buffer()
+            .iter()
+            .all(|&byte| byte == Color::White.get_byte_value()));
+    }
+}
@@ -0,0 +1,224 @@
+//! Scrolling text (marquee) in a region of the display
+//!
+//! A [`Ticker`] scrolls a line of text from right to left through a region of the display.
+//! Every [`tick`](Ticker::tick) moves the text by a few pixels, uploads only the region with
+//! [`WaveshareDisplay::update_partial_frame`] and refreshes it with the quick LUT. Optionally
+//! a full refresh is done every few ticks to clean up the ghosting of the quick refreshes.
+//!
+//! The region is given in the native orientation of the display, its `x` position and width
+//! should be multiples of 8.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!# use epd_waveshare::{epd2in9::*, prelude::*, ticker::Ticker};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!#
+//!let mut epd = Epd2in9::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// a region of 128x16 pixels at the top of the display
+//!let mut buffer = [0u8; 128 / 8 * 16];
+//!let mut ticker = Ticker::new("Breaking news: e-paper scrolls!", 0, 0, 128, 16, &mut buffer);
+//!ticker.set_full_refresh_interval(Some(20));
+//!
+//!loop {
+//!    ticker.tick(&mut epd, &mut spi, &mut delay)?;
+//!    // wait between the ticks
+//!}
+//!# }
+//!```
+
+use crate::buffer_len;
+use crate::color::Color;
+use crate::graphics::{Display, VarDisplay};
+use crate::text::default_text_style;
+use crate::traits::{DisplayError, RefreshLut, WaveshareDisplay};
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+/// Scrolls a line of text through a region of the display, see the [module docs](self)
+pub struct Ticker<'a> {
+    text: &'a str,
+    style: MonoTextStyle<'a, BinaryColor>,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    buffer: &'a mut [u8],
+    /// Pixels the text moved since it entered the region
+    offset: u32,
+    step: u32,
+    full_refresh_interval: Option<u32>,
+    quick_refreshes: u32,
+    lut: Option<RefreshLut>,
+}
+
+impl<'a> Ticker<'a> {
+    /// Creates a ticker for the region at `x`/`y` with the size `width` x `height`
+    ///
+    /// The text is drawn with the [default style](default_text_style) and moves
+    /// 8 pixels per tick. `buffer` holds the region between the ticks.
+    ///
+    /// # Panics
+    ///
+    /// If `buffer` is shorter than a frame buffer of the region.
+    pub fn new(
+        text: &'a str,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        buffer: &'a mut [u8],
+    ) -> Self {
+        let len = buffer_len(width as usize, height as usize);
+        assert!(buffer.len() >= len);
+        Ticker {
+            text,
+            style: default_text_style(),
+            x,
+            y,
+            width,
+            height,
+            buffer: &mut buffer[..len],
+            offset: 0,
+            step: 8,
+            full_refresh_interval: None,
+            quick_refreshes: 0,
+            lut: None,
+        }
+    }
+
+    /// Replaces the text, it starts again at the right side of the region
+    pub fn set_text(&mut self, text: &'a str) {
+        self.text = text;
+        self.offset = 0;
+    }
+
+    /// Sets the style of the text
+    ///
+    /// The style should have a background color, otherwise the text is drawn on white.
+    pub fn set_style(&mut self, style: MonoTextStyle<'a, BinaryColor>) {
+        self.style = style;
+    }
+
+    /// Sets how many pixels the text moves per tick
+    pub fn set_step(&mut self, step: u32) {
+        self.step = step;
+    }
+
+    /// Does a full refresh after every `interval` quick refreshes, `None` disables it
+    pub fn set_full_refresh_interval(&mut self, interval: Option<u32>) {
+        self.full_refresh_interval = interval;
+    }
+
+    /// The region as it was sent with the last tick
+    pub fn buffer(&self) -> &[u8] {
+        self.buffer
+    }
+
+    /// Moves the text one step, then uploads and refreshes the region
+    ///
+    /// When the text has left the region completely it starts again at the right side.
+    pub fn tick<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+    {
+        self.offset += self.step;
+        if self.offset > self.width + self.text_width() {
+            self.offset = self.step;
+        }
+        self.render();
+
+        let lut = match self.full_refresh_interval {
+            Some(interval) if self.quick_refreshes >= interval => RefreshLut::Full,
+            _ => RefreshLut::Quick,
+        };
+        if self.lut != Some(lut) {
+            epd.set_lut(spi, Some(lut))?;
+            self.lut = Some(lut);
+        }
+        match lut {
+            RefreshLut::Full => self.quick_refreshes = 0,
+            RefreshLut::Quick | RefreshLut::Ultrafast => self.quick_refreshes += 1,
+        }
+
+        epd.update_partial_frame(spi, self.buffer, self.x, self.y, self.width, self.height)?;
+        epd.display_frame(spi, delay)
+    }
+
+    /// Draws the text at the current offset into the buffer
+    fn render(&mut self) {
+        let mut display = VarDisplay::new(self.width, self.height, self.buffer);
+        display.clear_buffer(Color::White);
+        let position = Point::new(self.width as i32 - self.offset as i32, 0);
+        let _ =
+            Text::with_baseline(self.text, position, self.style, Baseline::Top).draw(&mut display);
+    }
+
+    /// Width of the whole text in pixels
+    fn text_width(&self) -> u32 {
+        let font = self.style.font;
+        self.text.chars().count() as u32 * (font.character_size.width + font.character_spacing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_enters_from_the_right() {
+        let mut buffer = [0u8; 16 / 8 * 10];
+        let mut ticker = Ticker::new("I", 0, 0, 16, 10, &mut buffer);
+
+        // the text starts right behind the region
+        ticker.render();
+        assert!(ticker
+            .buffer()
+            .iter()
+            .all(|&byte| byte == Color::White.get_byte_value()));
+
+        // after one step the first character is visible in the right half
+        ticker.offset = 8;
+        ticker.render();
+        let rows = ticker.buffer().chunks(2);
+        assert!(rows.clone().all(|row| row[0] == 0xFF));
+        assert!(rows.clone().any(|row| row[1] != 0xFF));
+    }
+
+    #[test]
+    fn text_width() {
+        let mut buffer = [0u8; 16 / 8 * 10];
+        let ticker = Ticker::new("Hi!", 0, 0, 16, 10, &mut buffer);
+        assert_eq!(ticker.text_width(), 3 * 6);
+    }
+}
@@ -0,0 +1,160 @@
+//! Composing a frame from pre-packed tiles without a frame buffer
+//!
+//! UIs which are made of fixed assets, like weather icons and digits, don't need a frame
+//! buffer on the host. Each [`Tile`] is written straight into the RAM of the controller with
+//! [`WaveshareDisplay::update_partial_frame`], one partial window after the other, and the
+//! display is refreshed once at the end.
+//!
+//! The tiles are packed like the frame buffers of the black/white displays, e.g. by
+//! [`Sprite`](crate::sprite::Sprite) on the host. Their position and width need to be
+//! multiples of 8 in the native orientation of the display.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+//!# use epd_waveshare::{epd2in9::*, prelude::*, tiles::{place_row, place_tile, Tile}};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!# static SUN: [u8; 4 * 32] = [0; 4 * 32];
+//!# static DIGITS: [[u8; 16]; 10] = [[0; 16]; 10];
+//!const ICON: Tile = Tile::new(32, 32, &SUN);
+//!let digits: [Tile; 10] = core::array::from_fn(|i| Tile::new(8, 16, &DIGITS[i]));
+//!
+//!let mut epd = Epd2in9::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!epd.clear_frame(&mut spi, &mut delay)?;
+//!place_tile(&mut epd, &mut spi, &ICON, 0, 0)?;
+//!// "21"
+//!place_row(&mut epd, &mut spi, &digits, [2, 1], 40, 8, 0)?;
+//!epd.display_frame(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use crate::traits::{DisplayError, Error, WaveshareDisplay};
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+/// A pre-packed black/white bitmap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile<'a> {
+    width: u32,
+    height: u32,
+    data: &'a [u8],
+}
+
+impl<'a> Tile<'a> {
+    /// Creates a tile of `width` x `height` pixels
+    ///
+    /// # Panics
+    ///
+    /// If `width` isn't a multiple of 8 or `data` doesn't have the length of a buffer of this size.
+    pub const fn new(width: u32, height: u32, data: &'a [u8]) -> Self {
+        assert!(width % 8 == 0);
+        assert!(data.len() == (width / 8 * height) as usize);
+        Tile {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Width in pixels
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in pixels
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The packed pixels
+    pub const fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// Writes `tile` into the RAM of the display at `x`/`y`
+pub fn place_tile<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+    epd: &mut EPD,
+    spi: &mut SPI,
+    tile: &Tile<'_>,
+    x: u32,
+    y: u32,
+) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    epd.update_partial_frame(spi, tile.data, x, y, tile.width, tile.height)
+}
+
+/// Writes the tiles with the indices `indices` of `tiles` next to each other, starting at `x`/`y`
+///
+/// `spacing` is the gap between two tiles in pixels and needs to be a multiple of 8 as well.
+/// Returns the `x` position after the last tile. An index out of the bounds of `tiles` stops
+/// the row with [`Error::InvalidWindow`], after the tiles before it were written.
+pub fn place_row<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+    epd: &mut EPD,
+    spi: &mut SPI,
+    tiles: &[Tile<'_>],
+    indices: impl IntoIterator<Item = usize>,
+    x: u32,
+    y: u32,
+    spacing: u32,
+) -> Result<u32, DisplayError<SPI, CS, BUSY, DC, RST>>
+where
+    EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    let mut x = x;
+    for (i, index) in indices.into_iter().enumerate() {
+        if i > 0 {
+            x += spacing;
+        }
+        let tile = tiles.get(index).ok_or(Error::InvalidWindow)?;
+        place_tile(epd, spi, tile, x, y)?;
+        x += tile.width;
+    }
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_size() {
+        static DATA: [u8; 2 * 3] = [0; 6];
+        let tile = Tile::new(16, 3, &DATA);
+        assert_eq!((tile.width(), tile.height()), (16, 3));
+        assert_eq!(tile.data().len(), 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn tile_wrong_length() {
+        let _ = Tile::new(16, 3, &[0; 5]);
+    }
+}
@@ -0,0 +1,125 @@
+//! Time keeping of the drivers
+//!
+//! The drivers don't have a clock of their own, the application can pass one in with
+//! [`BusyTimeoutSetting::set_busy_timeouts`](crate::traits::BusyTimeoutSetting::set_busy_timeouts).
+//! All time measurements go through a [`Stopwatch`], so the tests can replace the clock and the
+//! delay with a [simulated time](sim) and check the timing dependent logic deterministically.
+
+/// Returns milliseconds, e.g. since the start of the MCU, may wrap around
+pub(crate) type Clock = fn() -> u32;
+
+/// Measures the time since it was started, if there is a clock
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stopwatch {
+    clock: Option<Clock>,
+    start: u32,
+}
+
+impl Stopwatch {
+    /// Starts measuring with `clock`, without a clock nothing is measured
+    pub(crate) fn start(clock: Option<Clock>) -> Self {
+        Stopwatch {
+            clock,
+            start: clock.map_or(0, |clock| clock()),
+        }
+    }
+
+    /// Milliseconds since the start, `None` without a clock
+    pub(crate) fn elapsed(&self) -> Option<u32> {
+        self.clock.map(|clock| clock().wrapping_sub(self.start))
+    }
+
+    /// Whether at least `timeout` milliseconds passed since the start, never without a clock
+    pub(crate) fn expired(&self, timeout: u32) -> bool {
+        self.elapsed().is_some_and(|elapsed| elapsed >= timeout)
+    }
+}
+
+/// Simulated time for the tests
+///
+/// A [`SimTime`](sim::SimTime) is advanced by the delays of its [`SimDelay`](sim::SimDelay)
+/// and by a fixed step on every read of the clock, so busy loops without delays make progress
+/// as well. The clock has to be a plain function, [`sim_clock!`] creates one with its own time
+/// for every test, so tests running in parallel don't influence each other.
+#[cfg(test)]
+pub(crate) mod sim {
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use embedded_hal::blocking::delay::DelayMs;
+
+    /// The simulated milliseconds
+    pub(crate) struct SimTime {
+        now: AtomicU32,
+        step: u32,
+    }
+
+    impl SimTime {
+        /// Starts at `0` and advances by `step` on every read
+        pub(crate) const fn new(step: u32) -> Self {
+            SimTime {
+                now: AtomicU32::new(0),
+                step,
+            }
+        }
+
+        /// Reads the time and advances it by the step
+        pub(crate) fn tick(&self) -> u32 {
+            self.now.fetch_add(self.step, Ordering::Relaxed)
+        }
+
+        /// Reads the time without advancing it
+        pub(crate) fn now(&self) -> u32 {
+            self.now.load(Ordering::Relaxed)
+        }
+
+        /// A delay which advances this time
+        pub(crate) fn delay(&'static self) -> SimDelay {
+            SimDelay { time: self }
+        }
+    }
+
+    /// Delay which only advances a [`SimTime`]
+    pub(crate) struct SimDelay {
+        time: &'static SimTime,
+    }
+
+    impl DelayMs<u8> for SimDelay {
+        fn delay_ms(&mut self, ms: u8) {
+            self.time.now.fetch_add(u32::from(ms), Ordering::Relaxed);
+        }
+    }
+
+    /// Defines a [`SimTime`] `$time` with the step `$step` and the clock function `$clock` for it
+    macro_rules! sim_clock {
+        ($time:ident, $clock:ident, $step:expr) => {
+            static $time: $crate::time::sim::SimTime = $crate::time::sim::SimTime::new($step);
+            fn $clock() -> u32 {
+                $time.tick()
+            }
+        };
+    }
+    pub(crate) use sim_clock;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sim::sim_clock;
+    use super::*;
+    use embedded_hal::blocking::delay::DelayMs;
+
+    #[test]
+    fn stopwatch_with_simulated_time() {
+        sim_clock!(TIME, clock, 1);
+
+        let stopwatch = Stopwatch::start(Some(clock));
+        TIME.delay().delay_ms(100);
+        // the read of the elapsed time advances by one step as well
+        assert_eq!(stopwatch.elapsed(), Some(101));
+        assert!(stopwatch.expired(102));
+        assert!(!stopwatch.expired(200));
+        assert_eq!(TIME.now(), 104);
+
+        let without_clock = Stopwatch::start(None);
+        assert_eq!(without_clock.elapsed(), None);
+        assert!(!without_clock.expired(0));
+    }
+}
@@ -1,6 +1,16 @@
+use crate::color::Color;
+use crate::energy::RefreshEnergy;
+#[cfg(feature = "graphics")]
+use crate::graphics::{partial_window, Orientation};
+use crate::settings::{FrameRate, InitStep, TconTiming, VcomAndDataInterval};
 use core::marker::Sized;
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::primitives::Rectangle;
 use embedded_hal::{
-    blocking::{delay::*, spi::Write},
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
     digital::v2::*,
 };
 
@@ -10,22 +20,62 @@ pub(crate) trait Command {
     fn address(self) -> u8;
 }
 
+/// Raw addresses, e.g. of the [`InitStep`]s of an init sequence
+impl Command for u8 {
+    fn address(self) -> u8 {
+        self
+    }
+}
+
 /// Seperates the different LUT for the Display Refresh process
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Default)]
 pub enum RefreshLut {
     /// The "normal" full Lookuptable for the Refresh-Sequence
+    #[default]
     Full,
     /// The quick LUT where not the full refresh sequence is followed.
     /// This might lead to some
     Quick,
+    /// An A2-style LUT which only drives the pixels between black and white, without any flashing
+    ///
+    /// Much faster than [`Quick`](Self::Quick) but leaves ghosts behind, which add up with every
+    /// refresh, see [`UltrafastRefresh`]. Only available on the displays with
+    /// [`Capabilities::ultrafast`], the others use [`Quick`](Self::Quick) instead.
+    Ultrafast,
 }
 
-impl Default for RefreshLut {
-    fn default() -> Self {
-        RefreshLut::Full
+impl RefreshLut {
+    /// The LUT of a display without an ultrafast waveform, which uses the quick one instead
+    pub(crate) fn without_ultrafast(self) -> Self {
+        match self {
+            RefreshLut::Ultrafast => RefreshLut::Quick,
+            refresh => refresh,
+        }
     }
 }
 
+/// Features of a display, so generic code can adapt to it without knowing the exact model
+///
+/// Available as [`WaveshareDisplay::CAPABILITIES`] for every driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// [`WaveshareDisplay::update_partial_frame`] is supported
+    pub partial_refresh: bool,
+    /// Number of gray levels including black and white, `2` for pure black/white displays
+    pub grayscale_levels: u8,
+    /// Number of colors besides black and white
+    pub chromatic_colors: u8,
+    /// Data can be read back from the controller
+    pub readback: bool,
+    /// A faster refresh than the full one is available, e.g. with [`RefreshLut::Quick`] or [`QuickRefresh`]
+    pub fast_mode: bool,
+    /// [`RefreshLut::Ultrafast`] has a waveform of its own, see [`UltrafastRefresh`]
+    pub ultrafast: bool,
+    /// [`WaveshareDisplay::clear_frame`] refreshes the display too, like the `Clear` of the
+    /// Waveshare drivers
+    pub clear_refreshes: bool,
+}
+
 pub(crate) trait InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -45,10 +95,49 @@ where
     /// This function calls [reset](WaveshareDisplay::reset),
     /// so you don't need to call reset your self when trying to wake your device up
     /// after setting it to sleep.
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 }
 
 /// Functions to interact with three color panels
+///
+/// Implemented by every b/c panel in the same way, so applications can be generic over them:
+/// the methods wait until the display is idle and write whole planes, but don't refresh the
+/// display, that's left to [`display_frame`](WaveshareDisplay::display_frame). A plane stays
+/// in the display until it's written again, so after the first frame only the plane which
+/// changed needs to be sent.
+///
+/// ```rust, no_run
+/// use embedded_hal::{
+///     blocking::{delay::DelayMs, spi::Write},
+///     digital::v2::{InputPin, OutputPin},
+/// };
+/// use epd_waveshare::prelude::*;
+///
+/// /// Shows the planes on any tri-color display
+/// fn show<E, SPI, CS, BUSY, DC, RST, DELAY>(
+///     epd: &mut E,
+///     spi: &mut SPI,
+///     delay: &mut DELAY,
+///     black: &[u8],
+///     chromatic: &[u8],
+/// ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+/// where
+///     E: WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+///     SPI: Write<u8>,
+///     CS: OutputPin,
+///     BUSY: InputPin,
+///     DC: OutputPin,
+///     RST: OutputPin,
+///     DELAY: DelayMs<u8>,
+/// {
+///     epd.update_color_frame(spi, black, chromatic)?;
+///     epd.display_frame(spi, delay)
+/// }
+/// ```
 pub trait WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>:
     WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
 where
@@ -67,19 +156,187 @@ where
         spi: &mut SPI,
         black: &[u8],
         chromatic: &[u8],
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Update only the black/white data of the display.
     ///
-    /// This must be finished by calling `update_chromatic_frame`.
-    fn update_achromatic_frame(&mut self, spi: &mut SPI, black: &[u8]) -> Result<(), SPI::Error>;
+    /// The chromatic plane keeps what was written before.
+    fn update_achromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Update only the chromatic data of the display.
     ///
-    /// This should be preceded by a call to `update_achromatic_frame`.
+    /// The black/white plane keeps what was written before.
     /// This data takes precedence over the black/white data.
-    fn update_chromatic_frame(&mut self, spi: &mut SPI, chromatic: &[u8])
-        -> Result<(), SPI::Error>;
+    fn update_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        chromatic: &[u8],
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Sets the background of both planes, which is used by `clear_frame`
+    ///
+    /// `set_background_color` only changes the black/white plane.
+    fn set_background(&mut self, background: ColorSpec);
+
+    /// Get the current background of both planes
+    fn background(&self) -> ColorSpec;
+}
+
+/// Showing 4 gray levels on the panels whose controllers support them
+///
+/// The drivers start in black and white. In the gray mode the frames have 2 bits per pixel, in
+/// the format of the `Gray2` color of embedded-graphics, e.g. of a display buffer of the driver
+/// in its gray mode, and every refresh is a full one with the gray waveform. The
+/// waveform is loaded when entering the mode, from the LUTs of the driver or the OTP of the
+/// controller. The image on the display stays when switching the mode.
+pub trait GrayscaleDisplay<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Switches to frames with 4 gray levels and loads the gray waveform
+    fn enter_gray_mode(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Switches back to black and white frames and their waveform
+    fn leave_gray_mode(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Whether the frames have 4 gray levels
+    fn is_gray_mode(&self) -> bool;
+
+    /// Writes a frame with 2 bits per pixel, entering the gray mode first if necessary
+    fn update_gray_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if !self.is_gray_mode() {
+            self.enter_gray_mode(spi, delay)?;
+        }
+        self.update_frame(spi, buffer, delay)
+    }
+
+    /// Writes a frame with 2 bits per pixel and refreshes the display with it
+    fn update_and_display_gray_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        self.update_gray_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+}
+
+/// Planes which differed from the ones on the display, see [`PlaneTracking`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangedPlanes {
+    /// The black/white plane was sent
+    pub achromatic: bool,
+    /// The chromatic plane was sent
+    pub chromatic: bool,
+}
+
+/// Sending only the changed planes of a tri-color display
+///
+/// Most updates of a tri-color display only change one of its planes, e.g. the black text of
+/// a label with a red logo, but [`WaveshareThreeColorDisplay::update_color_frame`] sends both
+/// planes every time. The driver keeps a fingerprint of the planes it sent last, so
+/// [`update_changed_planes_and_display`](PlaneTracking::update_changed_planes_and_display)
+/// sends only the planes which differ and then refreshes the display as usual, which halves
+/// the transfer time when only one plane changed.
+///
+/// The fingerprints are forgotten with every other write to the display and with every reset,
+/// e.g. after [`WaveshareDisplay::sleep`] or [`WaveshareDisplay::update_frame`], so both planes
+/// are sent again whenever the memory of the controller could be different. Call
+/// [`forget_planes`](PlaneTracking::forget_planes) if the memory was lost otherwise, e.g. by
+/// a power cycle of the display.
+pub trait PlaneTracking<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sends the planes which changed since the last call and displays the frame
+    ///
+    /// Returns which planes were sent.
+    fn update_changed_planes_and_display(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+        chromatic: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<ChangedPlanes, DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Forgets the planes which were sent, the next update sends both planes
+    fn forget_planes(&mut self);
+}
+
+/// Background of the two planes of a tri-color display
+///
+/// See [`WaveshareThreeColorDisplay::set_background`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorSpec {
+    /// Background of the black/white plane
+    pub achromatic: Color,
+    /// Whether the chromatic plane is filled with the chromatic color (red or yellow)
+    ///
+    /// The chromatic plane takes precedence over the black/white plane.
+    pub chromatic: bool,
+}
+
+impl ColorSpec {
+    /// Background with the given black/white color and an empty chromatic plane
+    pub const fn achromatic(color: Color) -> Self {
+        ColorSpec {
+            achromatic: color,
+            chromatic: false,
+        }
+    }
+
+    /// Background filled with the chromatic color
+    pub const fn chromatic() -> Self {
+        ColorSpec {
+            achromatic: Color::White,
+            chromatic: true,
+        }
+    }
+
+    /// Byte value of the chromatic plane for drivers which show the chromatic color for cleared bits
+    pub(crate) fn chromatic_byte(&self) -> u8 {
+        if self.chromatic {
+            0x00
+        } else {
+            0xFF
+        }
+    }
+}
+
+impl Default for ColorSpec {
+    fn default() -> Self {
+        ColorSpec::achromatic(Color::White)
+    }
 }
 
 /// All the functions to interact with the EPDs
@@ -90,7 +347,7 @@ where
 ///
 ///```rust, no_run
 ///# use embedded_hal_mock::*;
-///# fn main() -> Result<(), MockError> {
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
 ///use embedded_graphics::{
 ///    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 ///};
@@ -137,6 +394,13 @@ where
 {
     /// The Color Type used by the Display
     type DisplayColor;
+
+    /// The features supported by the Display
+    const CAPABILITIES: Capabilities;
+
+    /// Rough estimate of the energy of the refreshes, see [energy](crate::energy)
+    const ENERGY: RefreshEnergy;
+
     /// Creates a new driver from a SPI peripheral, CS Pin, Busy InputPin, DC
     ///
     /// This already initialises the device.
@@ -147,19 +411,27 @@ where
         dc: DC,
         rst: RST,
         delay: &mut DELAY,
-    ) -> Result<Self, SPI::Error>
+    ) -> Result<Self, DisplayError<SPI, CS, BUSY, DC, RST>>
     where
         Self: Sized;
 
     /// Let the device enter deep-sleep mode to save power.
     ///
     /// The deep sleep mode returns to standby with a hardware reset.
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Wakes the device up from sleep
     ///
     /// Also reintialises the device if necessary.
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Sets the backgroundcolor for various commands like [clear_frame](WaveshareDisplay::clear_frame)
     fn set_background_color(&mut self, color: Self::DisplayColor);
@@ -173,13 +445,22 @@ where
     /// Get the height of the display
     fn height(&self) -> u32;
 
+    /// Length of one line of a frame in bytes, including the padding at the end of the line
+    ///
+    /// A frame for [`update_frame`](Self::update_frame) is [`height`](Self::height) lines of this
+    /// length. The black/white and tri-color displays pad every line to a full byte, see
+    /// [`bytes_per_line`](crate::bytes_per_line).
+    fn bytes_per_line(&self) -> u32 {
+        crate::bytes_per_line(self.width() as usize) as u32
+    }
+
     /// Transmit a full frame to the SRAM of the EPD
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Transmits partial data to the SRAM of the EPD
     ///
@@ -194,12 +475,77 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Transmits a full frame like [`update_frame`](Self::update_frame), unless the display is busy
+    ///
+    /// Writing to the memory of a controller which is still refreshing corrupts the image.
+    /// Instead of waiting until the display is idle this returns [`UploadError::Busy`] right away
+    /// without sending anything, so the application can retry later.
+    #[allow(clippy::type_complexity)]
+    fn try_update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), UploadError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        UploadError::check_idle(self.busy_state())?;
+        self.update_frame(spi, buffer, delay)
+            .map_err(UploadError::Interface)
+    }
+
+    /// Transmits partial data like [`update_partial_frame`](Self::update_partial_frame), unless the display is busy
+    ///
+    /// See [`try_update_frame`](Self::try_update_frame).
+    #[allow(clippy::type_complexity)]
+    fn try_update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), UploadError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        UploadError::check_idle(self.busy_state())?;
+        self.update_partial_frame(spi, buffer, x, y, width, height)
+            .map_err(UploadError::Interface)
+    }
+
+    /// Transmits the data of a rectangular area of the display, like [`update_partial_frame`](Self::update_partial_frame)
+    ///
+    /// The area is clamped to the display and aligned to whole bytes with
+    /// [`partial_window`](crate::graphics::partial_window), `buffer` has to hold the data of
+    /// this aligned window, e.g. copied from a full buffer with [`copy_window`](crate::graphics::copy_window).
+    /// Nothing is sent if the area is completely outside of the display.
+    #[cfg(feature = "graphics")]
+    fn update_partial_area(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        area: Rectangle,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        match partial_window(area, self.width(), self.height()) {
+            Some(window) => self.update_partial_frame(
+                spi,
+                buffer,
+                window.top_left.x as u32,
+                window.top_left.y as u32,
+                window.size.width,
+                window.size.height,
+            ),
+            None => Ok(()),
+        }
+    }
 
     /// Displays the frame data from SRAM
     ///
     /// This function waits until the device isn`t busy anymore
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Provide a combined update&display and save some time (skipping a busy check in between)
     fn update_and_display_frame(
@@ -207,12 +553,18 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Clears the frame buffer on the EPD with the declared background color
     ///
     /// The background color can be changed with [`WaveshareDisplay::set_background_color`]
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    ///
+    /// Some drivers show the cleared frame right away, see [`Capabilities::clear_refreshes`].
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Trait for using various Waveforms from different LUTs
     /// E.g. for partial refreshes
@@ -226,14 +578,417 @@ where
         &mut self,
         spi: &mut SPI,
         refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Returns what the display is busy with, see [`BusyState`]
+    ///
+    /// The busy pin is read with the right polarity for the display.
+    fn busy_state(&self) -> BusyState;
 
     /// Checks if the display is busy transmitting data
     ///
     /// This is normally handled by the more complicated commands themselves,
     /// but in the case you send data and commands directly you might need to check
     /// if the device is still busy
-    fn is_busy(&self) -> bool;
+    fn is_busy(&self) -> bool {
+        self.busy_state() != BusyState::Idle
+    }
+}
+
+/// Errors of [`WaveshareDisplay::try_update_frame`] and [`WaveshareDisplay::try_update_partial_frame`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadError<E> {
+    /// Sending to the display failed
+    Interface(E),
+    /// The display was still busy, nothing was sent
+    Busy(BusyState),
+}
+
+impl<E> UploadError<E> {
+    /// Fails with [`UploadError::Busy`] unless the display is idle
+    pub(crate) fn check_idle(state: BusyState) -> Result<(), Self> {
+        match state {
+            BusyState::Idle => Ok(()),
+            state => Err(UploadError::Busy(state)),
+        }
+    }
+}
+
+/// What a display is busy with
+///
+/// None of the displays can report this themselves, so the reason is the long running
+/// operation the driver started last, while the busy pin tells whether it's still running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusyState {
+    /// Ready for new commands
+    Idle,
+    /// Powering up after a reset, e.g. in [`WaveshareDisplay::wake_up`]
+    PoweringUp,
+    /// Refreshing the display
+    Refreshing,
+}
+
+/// Progress of a refresh started with [`NonBlockingRefresh::start_display_frame`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshState {
+    /// The display is still refreshing
+    Running,
+    /// The refresh is done, the display takes new commands
+    Complete,
+}
+
+/// Transitions in the lifecycle of a display, see [`LifecycleHooks`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    /// Right before the reset pin is pulsed
+    BeforeReset,
+    /// Right after the reset, before the init sequence is sent
+    AfterReset,
+    /// The panel is powered up, after a reset or with [`FastWake::wake_up_fast`]
+    ///
+    /// The booster runs until the display isn't busy any more.
+    PowerOn,
+    /// A refresh was started
+    RefreshStart,
+    /// The display isn't busy any more after a refresh
+    ///
+    /// This is sent by the next wait for the display, which is right after the refresh for most
+    /// drivers and at the start of the next operation for the others.
+    RefreshEnd,
+    /// The display is put to sleep
+    Sleep,
+}
+
+/// Callback for the lifecycle events of a driver
+///
+/// Useful e.g. to switch external loads, to pause the radio while the noisy booster is
+/// running or to measure how long the refreshes take.
+pub trait LifecycleHooks {
+    /// Sets the callback for the [`LifecycleEvent`]s, `None` removes it
+    fn set_lifecycle_hook(&mut self, hook: Option<fn(LifecycleEvent)>);
+}
+
+/// Errors of the drivers
+///
+/// Carries the error of the HAL for the bus or for the pin which failed. The drivers of the
+/// SPI displays return a [`DisplayError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<SpiError, PinError> {
+    /// Writing to or reading from the bus failed
+    Spi(SpiError),
+    /// Setting or reading a pin failed
+    Pin(PinError),
+    /// The busy pin stayed busy until the timeout of a wait, see [`BusyTimeoutSetting`]
+    BusyTimeout(TimeoutClass),
+    /// The window of a partial update isn't on the display or doesn't match its buffer, or a
+    /// buffer doesn't have the length of a frame, nothing of the update was sent
+    InvalidWindow,
+}
+
+/// A pin of the connection to the display which failed, with the error of its HAL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinError<CsError, BusyError, DcError, RstError> {
+    /// Chip select
+    Cs(CsError),
+    /// Busy input
+    Busy(BusyError),
+    /// Data/command select
+    Dc(DcError),
+    /// Reset
+    Rst(RstError),
+}
+
+/// The [`Error`] of a driver on the bus `SPI` with the pins `CS`, `BUSY`, `DC` and `RST`
+pub type DisplayError<SPI, CS, BUSY, DC, RST> = Error<
+    <SPI as Write<u8>>::Error,
+    PinError<
+        <CS as OutputPin>::Error,
+        <BUSY as InputPin>::Error,
+        <DC as OutputPin>::Error,
+        <RST as OutputPin>::Error,
+    >,
+>;
+
+impl<CsError, BusyError, DcError, RstError> PinError<CsError, BusyError, DcError, RstError> {
+    /// Which pin failed
+    pub fn pin(&self) -> PinKind {
+        match self {
+            PinError::Cs(_) => PinKind::Cs,
+            PinError::Busy(_) => PinKind::Busy,
+            PinError::Dc(_) => PinKind::Dc,
+            PinError::Rst(_) => PinKind::Rst,
+        }
+    }
+}
+
+impl<SpiError, CsError, BusyError, DcError, RstError>
+    Error<SpiError, PinError<CsError, BusyError, DcError, RstError>>
+{
+    /// The kind of the error, as kept in [`DriverState::last_error`]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Spi(_) => ErrorKind::Spi,
+            Error::Pin(pin) => ErrorKind::Pin(pin.pin()),
+            Error::BusyTimeout(class) => ErrorKind::Timeout(*class),
+            Error::InvalidWindow => ErrorKind::InvalidWindow,
+        }
+    }
+}
+
+/// Kind of the last error of a driver, see [`DriverState`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Writing to the SPI bus failed
+    Spi,
+    /// A busy wait timed out, see [`BusyTimeoutSetting`]
+    Timeout(TimeoutClass),
+    /// Setting or reading a pin failed, see [`PinError`]
+    ///
+    /// [`WaveshareDisplay::busy_state`] can't return the error, a failed read of the busy pin
+    /// counts as idle there and is only kept here.
+    Pin(PinKind),
+    /// An update was given an invalid window or buffer, see [`Error::InvalidWindow`]
+    InvalidWindow,
+}
+
+/// A pin of the connection to the display, see [`ErrorKind::Pin`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinKind {
+    /// Chip select
+    Cs,
+    /// Busy input
+    Busy,
+    /// Data/command select
+    Dc,
+    /// Reset
+    Rst,
+}
+
+/// Health of a driver, e.g. to be included in the heartbeat messages of a device
+///
+/// The durations are only measured with a clock set by [`BusyTimeoutSetting::set_busy_timeouts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DriverState {
+    /// Selected refresh LUT
+    pub refresh: RefreshLut,
+    /// Whether enough ultrafast refreshes were done that the next one is a full refresh, see
+    /// [`UltrafastRefresh`]
+    pub full_refresh_due: bool,
+    /// Number of refreshes since the driver was created
+    pub refreshes: u32,
+    /// Duration of the last finished refresh in milliseconds
+    pub last_refresh_ms: Option<u32>,
+    /// Whether the display was put to sleep and not woken up since
+    pub asleep: bool,
+    /// Kind of the last error, if any
+    pub last_error: Option<ErrorKind>,
+}
+
+impl DriverState {
+    /// Whether the next refresh is a full one, with the selected LUT or forced by
+    /// [`UltrafastRefresh`]
+    pub fn next_refresh_is_full(&self) -> bool {
+        match self.refresh {
+            RefreshLut::Full => true,
+            RefreshLut::Ultrafast => self.full_refresh_due,
+            RefreshLut::Quick => false,
+        }
+    }
+}
+
+/// Reports the [`DriverState`] without keeping track of it in the application
+pub trait Telemetry {
+    /// Returns the current state of the driver
+    fn driver_state(&self) -> DriverState;
+}
+
+/// Battery check of a [`VoltageGuard`]
+#[derive(Debug, Clone, Copy)]
+pub struct BatteryCheck {
+    /// Measures the battery voltage in millivolts
+    pub read_millivolts: fn() -> u16,
+    /// Lowest voltage at which a full refresh is started
+    pub min_millivolts: u16,
+}
+
+/// Errors of [`VoltageGuard::try_display_frame`] and [`VoltageGuard::try_update_and_display_frame`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshError<E> {
+    /// Sending to the display failed
+    Interface(E),
+    /// The battery voltage was below the threshold, the refresh wasn't started
+    LowVoltage {
+        /// Measured battery voltage
+        millivolts: u16,
+    },
+}
+
+impl<E> RefreshError<E> {
+    /// Fails with [`RefreshError::LowVoltage`] if a full refresh would start below the threshold
+    pub(crate) fn check_voltage(check: Option<BatteryCheck>, full: bool) -> Result<(), Self> {
+        match check {
+            Some(check) if full => {
+                let millivolts = (check.read_millivolts)();
+                if millivolts < check.min_millivolts {
+                    return Err(RefreshError::LowVoltage { millivolts });
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Refusing full refreshes while the battery is low
+///
+/// A full refresh drives the panel for seconds with the highest currents. If the supply browns
+/// out during that time, the refresh stops halfway and some panels keep marks of the old image
+/// for good. With a [`BatteryCheck`], [`try_display_frame`](VoltageGuard::try_display_frame)
+/// measures the voltage first and returns [`RefreshError::LowVoltage`] without starting the
+/// refresh if it is too low. Quick refreshes draw much less current and are always started,
+/// an ultrafast refresh which [`UltrafastRefresh`] turns into a full one is checked, see
+/// [`DriverState::next_refresh_is_full`].
+///
+/// Example:
+///```rust, no_run
+///# use embedded_hal_mock::*;
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+///# use epd_waveshare::{epd4in2::*, prelude::*};
+///#
+///# let expectations = [];
+///# let mut spi = spi::Mock::new(&expectations);
+///# let expectations = [];
+///# let cs_pin = pin::Mock::new(&expectations);
+///# let busy_in = pin::Mock::new(&expectations);
+///# let dc = pin::Mock::new(&expectations);
+///# let rst = pin::Mock::new(&expectations);
+///# let mut delay = delay::MockNoop::new();
+///# let frame = [0xFF; WIDTH as usize / 8 * HEIGHT as usize];
+///fn battery_millivolts() -> u16 {
+///    // e.g. read the ADC
+///#   3300
+///}
+///
+///let mut epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+///epd.set_battery_check(Some(BatteryCheck {
+///    read_millivolts: battery_millivolts,
+///    min_millivolts: 3000,
+///}));
+///match epd.try_update_and_display_frame(&mut spi, &frame, &mut delay) {
+///    Err(RefreshError::LowVoltage { .. }) => {
+///        // keep the old image and retry after charging
+///    }
+///    Err(RefreshError::Interface(error)) => return Err(error),
+///    Ok(()) => {}
+///}
+///# Ok(())
+///# }
+///```
+pub trait VoltageGuard<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY> + Telemetry
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sets the battery check, `None` removes it
+    fn set_battery_check(&mut self, check: Option<BatteryCheck>);
+
+    /// Returns the battery check, if any
+    fn battery_check(&self) -> Option<BatteryCheck>;
+
+    /// Displays the frame like [`display_frame`](WaveshareDisplay::display_frame), unless the battery is low
+    #[allow(clippy::type_complexity)]
+    fn try_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), RefreshError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RefreshError::check_voltage(
+            self.battery_check(),
+            self.driver_state().next_refresh_is_full(),
+        )?;
+        self.display_frame(spi, delay)
+            .map_err(RefreshError::Interface)
+    }
+
+    /// Transmits and displays a frame like [`update_and_display_frame`](WaveshareDisplay::update_and_display_frame), unless the battery is low
+    ///
+    /// The voltage is checked before anything is sent, so the display memory keeps the old
+    /// frame as well.
+    #[allow(clippy::type_complexity)]
+    fn try_update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RefreshError<DisplayError<SPI, CS, BUSY, DC, RST>>> {
+        RefreshError::check_voltage(
+            self.battery_check(),
+            self.driver_state().next_refresh_is_full(),
+        )?;
+        self.update_and_display_frame(spi, buffer, delay)
+            .map_err(RefreshError::Interface)
+    }
+}
+
+/// Classes of busy waits with very different worst case durations, see [`BusyTimeouts`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutClass {
+    /// Waits after commands which are processed quickly, e.g. a software reset
+    Short,
+    /// Waits for the power up of the panel
+    Medium,
+    /// Waits for a refresh
+    Refresh,
+}
+
+impl From<BusyState> for TimeoutClass {
+    fn from(state: BusyState) -> Self {
+        match state {
+            BusyState::Idle => TimeoutClass::Short,
+            BusyState::PoweringUp => TimeoutClass::Medium,
+            BusyState::Refreshing => TimeoutClass::Refresh,
+        }
+    }
+}
+
+/// Maximum durations of the busy waits per [`TimeoutClass`] in milliseconds
+///
+/// The defaults leave a lot of room for slow panels, e.g. the 7 color display needs
+/// about 30 seconds for a refresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusyTimeouts {
+    /// Timeout of [`TimeoutClass::Short`]
+    pub short: u32,
+    /// Timeout of [`TimeoutClass::Medium`]
+    pub medium: u32,
+    /// Timeout of [`TimeoutClass::Refresh`]
+    pub refresh: u32,
+}
+
+impl BusyTimeouts {
+    /// The timeout of a class
+    pub const fn of(&self, class: TimeoutClass) -> u32 {
+        match class {
+            TimeoutClass::Short => self.short,
+            TimeoutClass::Medium => self.medium,
+            TimeoutClass::Refresh => self.refresh,
+        }
+    }
+}
+
+impl Default for BusyTimeouts {
+    fn default() -> Self {
+        BusyTimeouts {
+            short: 200,
+            medium: 2_000,
+            refresh: 60_000,
+        }
+    }
 }
 
 /// Allows quick refresh support for displays that support it; lets you send both
@@ -243,11 +998,17 @@ where
 /// buffer data marked as old, and new. This is used to determine which pixels need to change,
 /// and how they will change. This isn't required when using full refreshes.
 ///
+/// The drivers of the controllers which compare an old and a new frame implement the trait and
+/// select the quick waveform for the new frames themselves, unless
+/// [`set_quick_lut`](QuickRefresh::set_quick_lut) selects another one. Drivers with a fast waveform which
+/// doesn't use an old frame, e.g. [`epd2in9`](crate::epd2in9), select it with
+/// [`WaveshareDisplay::set_lut`] and [`RefreshLut::Quick`] instead.
+///
 /// (todo: Example ommitted due to CI failures.)
 /// Example:
 ///```rust, no_run
 ///# use embedded_hal_mock::*;
-///# fn main() -> Result<(), MockError> {
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
 ///# use embedded_graphics::{
 ///#   pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 ///# };
@@ -296,7 +1057,7 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Updates the new frame.
     fn update_new_frame(
@@ -304,10 +1065,14 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Displays the new frame
-    fn display_new_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Updates and displays the new frame.
     fn update_and_display_new_frame(
@@ -315,7 +1080,7 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Updates the old frame for a portion of the display.
     fn update_partial_old_frame(
@@ -326,7 +1091,7 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Updates the new frame for a portion of the display.
     fn update_partial_new_frame(
@@ -337,7 +1102,7 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
 
     /// Clears the partial frame buffer on the EPD with the declared background color
     /// The background color can be changed with [`WaveshareDisplay::set_background_color`]
@@ -348,5 +1113,964 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Selects the waveform of [`display_new_frame`](QuickRefresh::display_new_frame)
+    ///
+    /// [`RefreshLut::Quick`] is the default and only drives the pixels which differ from the old
+    /// frame. [`RefreshLut::Full`] drives all pixels like a full refresh, which clears the
+    /// ghosting of the quick refreshes, and still works with the old and the new frame.
+    /// [`RefreshLut::Ultrafast`] falls back to the quick waveform on the displays without it.
+    fn set_quick_lut(
+        &mut self,
+        spi: &mut SPI,
+        refresh: RefreshLut,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Number of [`RefreshLut::Ultrafast`] refreshes before a full refresh, unless set otherwise
+pub const DEFAULT_ULTRAFAST_LIMIT: u16 = 10;
+
+/// Tracks the ghosting of [`RefreshLut::Ultrafast`] and forces full refreshes to clear it
+///
+/// The ultrafast waveform takes about 300 ms, which is enough for interactive prototypes, but it
+/// only drives the pixels between black and white. Every refresh leaves a faint ghost of the
+/// previous content and the ghosts add up. The driver counts the ultrafast refreshes since the
+/// last full refresh as the [`ghosting`](Self::ghosting), and once it reaches the
+/// [limit](Self::set_ultrafast_limit) the next ultrafast refresh is done as a full refresh with
+/// the full LUT. The LUT stays at ultrafast for the following refreshes.
+///
+/// So an ultrafast refresh may take as long as a full one now and then, plan the UI for that.
+/// Full refreshes requested by the application reset the ghosting as well.
+///
+/// Example:
+///
+///```rust, no_run
+///# use embedded_hal_mock::*;
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+///# use epd_waveshare::{epd1in54::*, prelude::*};
+///#
+///# let expectations = [];
+///# let mut spi = spi::Mock::new(&expectations);
+///# let expectations = [];
+///# let cs_pin = pin::Mock::new(&expectations);
+///# let busy_in = pin::Mock::new(&expectations);
+///# let dc = pin::Mock::new(&expectations);
+///# let rst = pin::Mock::new(&expectations);
+///# let mut delay = delay::MockNoop::new();
+///# let frame = [0xFF; WIDTH as usize / 8 * HEIGHT as usize];
+///let mut epd = Epd1in54::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+///epd.set_lut(&mut spi, Some(RefreshLut::Ultrafast))?;
+///// a full refresh after every 20 ultrafast ones
+///epd.set_ultrafast_limit(20);
+///
+///epd.update_and_display_frame(&mut spi, &frame, &mut delay)?;
+///assert_eq!(epd.ghosting(), 1);
+///# Ok(())
+///# }
+///```
+pub trait UltrafastRefresh {
+    /// Sets after how many ultrafast refreshes the next one is a full refresh
+    ///
+    /// [`DEFAULT_ULTRAFAST_LIMIT`] by default, `0` turns every ultrafast refresh into a full one.
+    fn set_ultrafast_limit(&mut self, limit: u16);
+
+    /// Returns the limit of ultrafast refreshes in a row
+    fn ultrafast_limit(&self) -> u16;
+
+    /// Returns the number of ultrafast refreshes since the last full refresh
+    fn ghosting(&self) -> u16;
+}
+
+/// Transforms frame data on its way to the display
+///
+/// The transform is applied to all frame buffers passed to the update functions, e.g. to decrypt
+/// or unscramble frames from untrusted storage on the fly. The data is copied into a small
+/// internal buffer and passed to the transform in chunks of at most [FRAME_CHUNK_LEN](crate::FRAME_CHUNK_LEN)
+/// bytes in the order they are sent, so no second full-frame buffer is needed and the
+/// buffers passed in stay untouched.
+///
+/// Command parameters and the constant data of clear functions aren't transformed.
+///
+/// Example:
+///```rust, no_run
+///# use embedded_hal_mock::*;
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+///# use epd_waveshare::{epd4in2::*, prelude::*};
+///#
+///# let expectations = [];
+///# let mut spi = spi::Mock::new(&expectations);
+///# let expectations = [];
+///# let cs_pin = pin::Mock::new(&expectations);
+///# let busy_in = pin::Mock::new(&expectations);
+///# let dc = pin::Mock::new(&expectations);
+///# let rst = pin::Mock::new(&expectations);
+///# let mut delay = delay::MockNoop::new();
+///#
+///# let mut epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+///fn unscramble(chunk: &mut [u8]) {
+///    for byte in chunk.iter_mut() {
+///        *byte ^= 0x5A;
+///    }
+///}
+///
+///epd.set_frame_transform(Some(unscramble));
+///# let scrambled_frame = [0x5A; WIDTH as usize / 8 * HEIGHT as usize];
+///epd.update_frame(&mut spi, &scrambled_frame, &mut delay)?;
+///# Ok(())
+///# }
+///```
+pub trait FrameTransform {
+    /// Sets the transform for all following frame uploads, `None` disables it
+    fn set_frame_transform(&mut self, transform: Option<fn(&mut [u8])>);
+}
+
+/// Orientation of the application coordinates for partial updates
+///
+/// The frame memory of the displays is always in their native orientation. When the
+/// [`Display`](crate::graphics::Display) is rotated or mirrored, the areas which the application
+/// redraws have to be mapped the same way before they are sent. Setting the same [`Orientation`]
+/// on the display buffer and the driver keeps both in sync, e.g. when an accelerometer reports
+/// that the device was turned.
+///
+/// Example:
+///```rust, no_run
+///# use embedded_hal_mock::*;
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+///# use embedded_graphics::{prelude::*, primitives::Rectangle};
+///# use epd_waveshare::{epd2in9::*, graphics::{copy_window, Display, DisplayRotation, Orientation}, prelude::*};
+///#
+///# let expectations = [];
+///# let mut spi = spi::Mock::new(&expectations);
+///# let expectations = [];
+///# let cs_pin = pin::Mock::new(&expectations);
+///# let busy_in = pin::Mock::new(&expectations);
+///# let dc = pin::Mock::new(&expectations);
+///# let rst = pin::Mock::new(&expectations);
+///# let mut delay = delay::MockNoop::new();
+///#
+///# let mut epd = Epd2in9::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+///let mut display = Display2in9::default();
+///
+///// the device was turned to landscape
+///let orientation = Orientation::new(DisplayRotation::Rotate90);
+///display.set_orientation(orientation);
+///epd.set_orientation(orientation);
+///
+///// redraw an area in landscape coordinates and send it
+///let area = Rectangle::new(Point::new(10, 10), Size::new(100, 20));
+///if let Some(window) = epd.oriented_window(area) {
+///    let mut partial = [0u8; 128 / 8 * 296];
+///    copy_window(display.buffer(), WIDTH, window, &mut partial);
+///    epd.update_oriented_area(&mut spi, &partial, area)?;
+///}
+///# Ok(())
+///# }
+///```
+#[cfg(feature = "graphics")]
+pub trait OrientationSetting<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sets the orientation of the coordinates passed to [`update_oriented_area`](Self::update_oriented_area)
+    fn set_orientation(&mut self, orientation: Orientation);
+
+    /// Returns the orientation set with [`set_orientation`](Self::set_orientation)
+    fn orientation(&self) -> Orientation;
+
+    /// The byte aligned native window which [`update_oriented_area`](Self::update_oriented_area) sends for `area`
+    ///
+    /// `None` if nothing of `area` is on the display.
+    fn oriented_window(&self, area: Rectangle) -> Option<Rectangle> {
+        self.orientation()
+            .partial_window(area, self.width(), self.height())
+    }
+
+    /// Transmits the data of an area in the coordinates of the application
+    ///
+    /// Like [`update_partial_area`](WaveshareDisplay::update_partial_area), but `area` is first
+    /// mapped with the orientation. `buffer` has to hold the data of the
+    /// [`oriented_window`](Self::oriented_window), e.g. copied from the display buffer with
+    /// [`copy_window`](crate::graphics::copy_window). Nothing is sent if the area is completely
+    /// outside of the display.
+    fn update_oriented_area(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        area: Rectangle,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        match self
+            .orientation()
+            .native_area(area, self.width(), self.height())
+        {
+            Some(native) => self.update_partial_area(spi, buffer, native),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Renders frames row by row with a callback instead of from a full frame buffer
+///
+/// Useful for procedurally generated content like plots or gradients on devices
+/// which can't hold a whole frame in memory. Only a single row is buffered.
+///
+/// Example:
+///```rust, no_run
+///# use embedded_hal_mock::*;
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+///# use epd_waveshare::{epd4in2::*, prelude::*};
+///#
+///# let expectations = [];
+///# let mut spi = spi::Mock::new(&expectations);
+///# let expectations = [];
+///# let cs_pin = pin::Mock::new(&expectations);
+///# let busy_in = pin::Mock::new(&expectations);
+///# let dc = pin::Mock::new(&expectations);
+///# let rst = pin::Mock::new(&expectations);
+///# let mut delay = delay::MockNoop::new();
+///#
+///# let mut epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+///// vertical stripes which get wider towards the bottom
+///epd.update_frame_with(&mut spi, &mut delay, |y, row| {
+///    for (i, byte) in row.iter_mut().enumerate() {
+///        if i as u32 % (y / 30 + 2) == 0 {
+///            *byte = Color::Black.get_byte_value();
+///        }
+///    }
+///})?;
+///epd.display_frame(&mut spi, &mut delay)?;
+///# Ok(())
+///# }
+///```
+pub trait RowRendering<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Transmits a full frame, calling `f` with the index and the buffer of each row to fill it
+    ///
+    /// The row buffer has the layout of one row of a frame buffer for this display and
+    /// is filled with the background color before each call. Rows are requested from
+    /// top to bottom, displays which need to receive the frame twice call `f` again for every row.
+    ///
+    /// Like [`WaveshareDisplay::update_frame`] this doesn't refresh the display.
+    fn update_frame_with<F>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        f: F,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut(u32, &mut [u8]);
+}
+
+/// Allows tuning the [VCOM and data interval setting](VcomAndDataInterval) of displays which support it
+///
+/// The setting is kept and also used whenever the display is initialised again,
+/// e.g. by [`WaveshareDisplay::wake_up`].
+pub trait VcomAndDataIntervalSetting<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sets and directly sends the VCOM and data interval setting
+    fn set_vcom_and_data_interval(
+        &mut self,
+        spi: &mut SPI,
+        setting: VcomAndDataInterval,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Returns the current VCOM and data interval setting
+    fn vcom_and_data_interval(&self) -> VcomAndDataInterval;
+}
+
+/// Reports the progress while frame data is uploaded to the display
+///
+/// Uploading a frame to the big displays can take a noticeable time, the callback allows
+/// e.g. animating a LED or showing a progress indicator meanwhile. It is called with the
+/// bytes sent so far and the total length after every [FRAME_CHUNK_LEN](crate::FRAME_CHUNK_LEN)
+/// bytes of each buffer passed to an update function. Displays with several planes report
+/// each plane on its own, and [`RowRendering::update_frame_with`] reports every row.
+/// [`WaveshareDisplay::clear_frame`] reports the planes it clears in the same way.
+///
+/// Example:
+///```rust, no_run
+///# use embedded_hal_mock::*;
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+///# use epd_waveshare::{epd7in5_v2::*, prelude::*};
+///#
+///# let expectations = [];
+///# let mut spi = spi::Mock::new(&expectations);
+///# let expectations = [];
+///# let cs_pin = pin::Mock::new(&expectations);
+///# let busy_in = pin::Mock::new(&expectations);
+///# let dc = pin::Mock::new(&expectations);
+///# let rst = pin::Mock::new(&expectations);
+///# let mut delay = delay::MockNoop::new();
+///#
+///# let mut epd = Epd7in5::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+///fn progress(sent: usize, total: usize) {
+///    let _percent = sent * 100 / total;
+///    // update the progress indicator here
+///}
+///
+///epd.set_upload_progress(Some(progress));
+///# let frame = [0xFF; WIDTH as usize / 8 * HEIGHT as usize];
+///epd.update_frame(&mut spi, &frame, &mut delay)?;
+///# Ok(())
+///# }
+///```
+pub trait UploadProgress {
+    /// Sets the progress callback for all following frame uploads, `None` disables it
+    fn set_upload_progress(&mut self, progress: Option<fn(usize, usize)>);
+}
+
+/// Sending frame buffers which the SPI peripheral can't read directly
+///
+/// Large frame buffers, like the ones of the 7-color displays, often don't fit into the
+/// internal RAM and are placed into external memory, e.g. the PSRAM of an ESP32-S3 with
+/// [`VarOctDisplay`](crate::graphics::VarOctDisplay). The SPI DMA of many MCUs can't read from
+/// there or needs aligned buffers. With bounce buffering the drivers copy the frame data in
+/// chunks of [FRAME_CHUNK_LEN](crate::FRAME_CHUNK_LEN) bytes into a 4 byte aligned buffer on
+/// the stack, which is in internal RAM, and send the chunks from there.
+///
+/// Clearing the display doesn't read a frame buffer and is sent as before.
+pub trait BounceBuffering {
+    /// Enables or disables the bounce buffer for all following frame uploads
+    fn set_bounce_buffering(&mut self, enabled: bool);
+}
+
+/// Aborting [`WaveshareDisplay::clear_frame`] while it's sending
+///
+/// Clearing a big display sends a lot of data. The clear is sent in chunks of
+/// [FRAME_CHUNK_LEN](crate::FRAME_CHUNK_LEN) bytes, which are also reported to the
+/// [upload progress](UploadProgress). The abort check is called before every chunk and stops
+/// the clear when it returns `true`, e.g. because a button was pressed and something else needs to
+/// be shown. The clear then returns without an error and the rest of the display memory keeps
+/// its old content.
+pub trait AbortableClear {
+    /// Sets the abort check for all following clears, `None` disables it
+    fn set_clear_abort(&mut self, abort: Option<fn() -> bool>);
+
+    /// Returns whether a clear was aborted since the last call and resets it
+    fn take_aborted(&mut self) -> bool;
+}
+
+/// Refreshing with the temperature of an external sensor instead of the internal one
+///
+/// The controllers pick the waveform of a refresh by the temperature, which they measure with
+/// their internal sensor by default. In a cold enclosure or next to a warm MCU that sensor can
+/// be far off the temperature of the panel, and the wrong waveform leaves ghosting behind.
+/// A temperature set here is written to the controller before every refresh which would
+/// otherwise measure it, until [`use_internal_sensor`](TemperatureSetting::use_internal_sensor)
+/// is called. Refreshes with waveforms from registers don't depend on the temperature.
+pub trait TemperatureSetting {
+    /// Uses `celsius` instead of the internal sensor for the following refreshes
+    fn set_temperature(&mut self, celsius: i8);
+
+    /// Uses the internal sensor again for the following refreshes
+    fn use_internal_sensor(&mut self);
+
+    /// Returns the temperature set last, `None` while the internal sensor is used
+    fn temperature(&self) -> Option<i8>;
+}
+
+/// Reading the temperature of the internal sensor of the controller
+///
+/// Logging the temperature of the panel helps to plan the full refreshes, as the ghosting
+/// grows faster in the cold. [`read_temperature`](TemperatureReading::read_temperature)
+/// starts a measurement, which doesn't change the image or the settings, and reads the result
+/// back. The panels have no data output, so MISO has to be connected to DIN as well and the
+/// bus has to release MOSI while reading (3-wire SPI), otherwise the value is garbage.
+///
+/// The display has to be awake.
+pub trait TemperatureReading<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Measures the temperature and returns it in °C
+    fn read_temperature(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<i8, DisplayError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Allows tuning the [gate/source non-overlap timing](TconTiming) of displays which support it
+///
+/// The timing is kept and also used whenever the display is initialised again,
+/// e.g. by [`WaveshareDisplay::wake_up`].
+pub trait TconSetting<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sets and directly sends the TCON setting
+    fn set_tcon_timing(
+        &mut self,
+        spi: &mut SPI,
+        timing: TconTiming,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Returns the current TCON setting
+    fn tcon_timing(&self) -> TconTiming;
+}
+
+/// Setting the VCOM voltage of displays which support it
+///
+/// The best VCOM differs from panel to panel, the vendor measures it for every one and prints
+/// it on the flex cable, e.g. `-1.15V`. A wrong VCOM leaves a gray tint or lets the image fade.
+/// The voltage is kept and also used whenever the display is initialised again, e.g. by
+/// [`WaveshareDisplay::wake_up`]. It's given in mV, rounded to the steps of the controller
+/// and clamped to its range.
+pub trait VcomSetting<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sets and directly sends the VCOM voltage in mV
+    fn set_vcom(
+        &mut self,
+        spi: &mut SPI,
+        millivolts: i16,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Returns the current VCOM voltage in mV
+    fn vcom(&self) -> i16;
+}
+
+/// Measuring the VCOM of a panel with the kick-back procedure of the controller
+///
+/// For panels without the value on the flex cable: the controller switches the gates for a
+/// few seconds and measures the voltage which the panel kicks back onto VCOM. The result can
+/// be applied with [`VcomSetting::set_vcom`], e.g. once and then stored with the settings of
+/// the device. Like [`TemperatureReading`] this needs a bus which can read back over DIN.
+pub trait VcomMeasurement<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Measures VCOM and returns it in mV, without changing the VCOM setting
+    fn measure_vcom(&mut self, spi: &mut SPI) -> Result<i16, DisplayError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Allows selecting the [frame rate](FrameRate) of displays with a programmable PLL
+///
+/// The frame rate is kept and also used whenever the display is initialised again,
+/// e.g. by [`WaveshareDisplay::wake_up`].
+pub trait FrameRateSetting<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sets and directly sends the frame rate
+    fn set_frame_rate(
+        &mut self,
+        spi: &mut SPI,
+        rate: FrameRate,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Returns the current frame rate
+    fn frame_rate(&self) -> FrameRate;
+}
+
+/// Minimal software state of a driver, see [`Snapshot`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriverSnapshot<COLOR> {
+    /// Background color used by [`WaveshareDisplay::clear_frame`]
+    pub background_color: COLOR,
+    /// Selected refresh LUT, always [`RefreshLut::Full`] for displays without a quick refresh
+    pub refresh: RefreshLut,
+    /// Ultrafast refreshes since the last full one, see [`UltrafastRefresh::ghosting`]
+    pub ghosting: u16,
+    /// Orientation of the areas of partial updates, see [`OrientationSetting`]
+    #[cfg(feature = "graphics")]
+    pub orientation: Orientation,
+}
+
+/// Creating a driver without powering on the display
+///
+/// [`WaveshareDisplay::new`] always resets and initializes the display, which is wasted energy
+/// on devices which often wake up without anything new to show. A driver created with
+/// [`new_uninitialized`](LazyInit::new_uninitialized) doesn't talk to the display at all.
+/// [`WaveshareDisplay::update_frame`], [`WaveshareDisplay::update_and_display_frame`] and
+/// [`WaveshareDisplay::clear_frame`] initialize it on their first call.
+/// Call [`ensure_initialized`](LazyInit::ensure_initialized) before any other operation, e.g.
+/// before partial updates, which don't get a delay to initialize the display with.
+///
+/// Example:
+///```rust, no_run
+///# use embedded_hal_mock::*;
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+///# use epd_waveshare::{epd4in2::*, prelude::*};
+///#
+///# let expectations = [];
+///# let mut spi = spi::Mock::new(&expectations);
+///# let expectations = [];
+///# let cs_pin = pin::Mock::new(&expectations);
+///# let busy_in = pin::Mock::new(&expectations);
+///# let dc = pin::Mock::new(&expectations);
+///# let rst = pin::Mock::new(&expectations);
+///# let mut delay = delay::MockNoop::new();
+///#
+///# let something_changed = true;
+///let mut epd = Epd4in2::new_uninitialized(cs_pin, busy_in, dc, rst);
+///if something_changed {
+///    // initializes the display first
+///#   let frame = [0xFF; WIDTH as usize / 8 * HEIGHT as usize];
+///    epd.update_and_display_frame(&mut spi, &frame, &mut delay)?;
+///    epd.sleep(&mut spi, &mut delay)?;
+///}
+///# Ok(())
+///# }
+///```
+pub trait LazyInit<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Creates the driver without sending anything to the display
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self
+    where
+        Self: Sized;
+
+    /// Whether the display was initialized since the driver was created
+    fn is_initialized(&self) -> bool;
+
+    /// Initializes the display, unless it already is
+    fn ensure_initialized(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>> {
+        if !self.is_initialized() {
+            self.wake_up(spi, delay)?;
+        }
+        Ok(())
+    }
+}
+
+/// Waking up without sending the whole init sequence again
+///
+/// [`WaveshareDisplay::sleep`] puts the controller into deep sleep, which loses all settings, so
+/// [`WaveshareDisplay::wake_up`] resets the display and sends the power settings and LUTs again.
+/// [`FastWake::power_off`] only switches off the power of the panel. The controller keeps its
+/// configuration and frame memory, so [`FastWake::wake_up_fast`] just powers the panel on again
+/// and waits until it's ready, which takes tens instead of hundreds of milliseconds.
+///
+/// The controller draws more current while powered off than in deep sleep, so this is meant
+/// for short pauses between updates.
+pub trait FastWake<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Switches off the power of the panel, but keeps the controller out of deep sleep
+    fn power_off(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Powers the panel on again after [`power_off`](FastWake::power_off)
+    ///
+    /// Does a full [`wake_up`](WaveshareDisplay::wake_up) if the controller didn't keep its
+    /// configuration, e.g. because the display was put to [`sleep`](WaveshareDisplay::sleep).
+    fn wake_up_fast(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Timeouts for the busy waits of a driver
+///
+/// Without a clock the drivers wait as long as the display is busy. With a clock, which
+/// returns milliseconds, e.g. since the start of the MCU, every wait is given up after the
+/// timeout of its [`TimeoutClass`]. The class follows from what the display is
+/// [busy with](BusyState). The method which waited then returns [`Error::BusyTimeout`], so a
+/// display which hangs doesn't block the application forever.
+///
+/// Without a clock, the timeouts can be measured in ticks of the `DELAY` of the driver with
+/// [`set_busy_tick_timeouts`](BusyTimeoutSetting::set_busy_tick_timeouts), e.g. so that a
+/// disconnected busy line doesn't hang the firmware. The class of the wait which timed out is
+/// also returned by [`take_timeout`](BusyTimeoutSetting::take_timeout) and kept as the
+/// [last error](DriverState::last_error) of the [`Telemetry`].
+pub trait BusyTimeoutSetting {
+    /// Sets the clock and the timeouts, `None` disables the timeouts again
+    fn set_busy_timeouts(&mut self, clock: Option<fn() -> u32>, timeouts: BusyTimeouts);
+
+    /// Measures the timeouts with the delay instead of a clock
+    ///
+    /// The waits of `init` and [`display_frame`](WaveshareDisplay::display_frame) sleep for
+    /// `tick` ms between the polls of the busy pin and are given up once the slept time reaches
+    /// the timeout. The time of the polls themselves isn't counted, so the waits take a bit
+    /// longer than the timeouts. The waits of the methods without a delay, like
+    /// [`update_partial_frame`](WaveshareDisplay::update_partial_frame), can't time out
+    /// without a clock.
+    /// [`set_busy_timeouts`](BusyTimeoutSetting::set_busy_timeouts) replaces the ticks again.
+    fn set_busy_tick_timeouts(&mut self, tick: u8, timeouts: BusyTimeouts);
+
+    /// Returns the class of the last wait which timed out, if any, and clears it
+    fn take_timeout(&mut self) -> Option<TimeoutClass>;
+}
+
+/// Refreshing without blocking until the display is done
+///
+/// [`WaveshareDisplay::display_frame`] waits for the busy pin for the whole refresh, which
+/// takes seconds on most panels. Superloops and RTIC tasks can start the refresh with
+/// [`start_display_frame`](NonBlockingRefresh::start_display_frame) instead, serve other
+/// work and call [`poll_display_complete`](NonBlockingRefresh::poll_display_complete),
+/// e.g. from a timer or the interrupt of the busy pin, until it reports
+/// [`RefreshState::Complete`]. Nothing else may be sent to the display meanwhile.
+pub trait NonBlockingRefresh<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Starts the refresh with the frame written before and returns once it runs
+    ///
+    /// An operation which is still running, like the previous refresh, is waited for first.
+    /// After the activation `delay` waits the few ms until the busy pin reports the refresh,
+    /// so the first [`poll_display_complete`](NonBlockingRefresh::poll_display_complete)
+    /// doesn't mistake the idle pin from before it for the end of the refresh.
+    fn start_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DisplayError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Checks the busy pin once and tells whether the refresh is done
+    ///
+    /// The displays which power the panel off after every refresh send the power off with `spi`
+    /// once the refresh is done, the next operation waits for it.
+    fn poll_display_complete(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<RefreshState, DisplayError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Result of [`ConnectionCheck::ping`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Link {
+    /// The busy pin followed the pinged operation, the display is connected
+    Alive,
+    /// The busy pin didn't change, e.g. because the cable to the panel came loose
+    NoResponse,
+    /// The busy pin stayed busy until the timeout, see [`BusyTimeoutSetting`]
+    StuckBusy,
+    /// The display sleeps or wasn't initialized yet and can't answer, nothing was sent
+    Asleep,
+}
+
+/// Checking whether the display still answers
+///
+/// Most setups can't read from the display (see [`TemperatureReading`] for the wiring which
+/// does), so a frame sent to a display whose cable came loose is lost without an error and the old image stays on it. [`ping`](ConnectionCheck::ping)
+/// starts a temperature measurement of the controller, which doesn't change the image or the
+/// settings, and checks that the busy pin reports it. Long running devices can ping the
+/// display e.g. before every update and raise an alarm instead of showing a stale frame.
+///
+/// A stuck busy pin is only detected with the [busy timeouts](BusyTimeoutSetting), without
+/// a clock the ping waits for it like every other operation.
+pub trait ConnectionCheck<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Pings the display and reports how its busy pin answered
+    ///
+    /// `delay` paces the polls of the busy pin, so the answer is awaited for the same time on
+    /// fast and slow MCUs.
+    fn ping(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Link, DisplayError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Error of a frame upload which failed partway, see [`ResumableUpload`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterruptedUpload<E> {
+    /// Error of the failed chunk
+    pub error: E,
+    /// Bytes of the frame which were sent completely, the upload can be resumed from there
+    pub sent: usize,
+}
+
+/// Resuming frame uploads which failed partway
+///
+/// The frame is sent in chunks of [`FRAME_CHUNK_LEN`](crate::FRAME_CHUNK_LEN) bytes. When
+/// a chunk fails, e.g. because of noise on a long cable, the upload can be resumed from the
+/// failed chunk instead of sending the whole frame again. The RAM address counter of the
+/// controller is moved to the position of the chunk first.
+///
+///```rust, no_run
+///# use embedded_hal_mock::*;
+///# fn main() -> Result<(), epd_waveshare::prelude::DisplayError<spi::Mock, pin::Mock, pin::Mock, pin::Mock, pin::Mock>> {
+///# use epd_waveshare::{epd2in9::*, prelude::*};
+///#
+///# let expectations = [];
+///# let mut spi = spi::Mock::new(&expectations);
+///# let expectations = [];
+///# let cs_pin = pin::Mock::new(&expectations);
+///# let busy_in = pin::Mock::new(&expectations);
+///# let dc = pin::Mock::new(&expectations);
+///# let rst = pin::Mock::new(&expectations);
+///# let mut delay = delay::MockNoop::new();
+///# let mut epd = Epd2in9::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+///# let frame = [0xFF; WIDTH as usize / 8 * HEIGHT as usize];
+///let mut offset = 0;
+///for _ in 0..3 {
+///    match epd.update_frame_from(&mut spi, &frame, offset, &mut delay) {
+///        Ok(()) => break,
+///        Err(interrupted) => offset = interrupted.sent,
+///    }
+///}
+///# Ok(())
+///# }
+///```
+pub trait ResumableUpload<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Transmits the frame like [`update_frame`](WaveshareDisplay::update_frame), but only
+    /// from byte `offset` of `buffer` on
+    ///
+    /// An `offset` of `0` sends the whole frame.
+    #[allow(clippy::type_complexity)]
+    fn update_frame_from(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        offset: usize,
+        delay: &mut DELAY,
+    ) -> Result<(), InterruptedUpload<DisplayError<SPI, CS, BUSY, DC, RST>>>;
+}
+
+/// Inspecting and replacing the init sequence of a driver
+///
+/// The sequence is a table of [`InitStep`]s which is sent after the reset when the driver is
+/// created and by [`WaveshareDisplay::wake_up`]. The default is the `INIT_SEQUENCE` constant
+/// of the driver module. Values which have their own setters, like the frame rate, the VCOM
+/// and data interval or the LUTs, are sent after the sequence.
+pub trait InitSequence {
+    /// The init sequence which is currently used
+    fn init_sequence(&self) -> &'static [InitStep];
+
+    /// Replaces the init sequence, it's used from the next init or wake up on
+    fn set_init_sequence(&mut self, sequence: &'static [InitStep]);
+}
+
+/// Captures and restores the software state of a driver across deep sleep cycles of the MCU
+///
+/// The snapshot is a small `Copy` value which can be kept in memory that survives the deep sleep,
+/// e.g. the RTC memory. After waking up, create the driver again, restore the snapshot and call
+/// [`WaveshareDisplay::wake_up`], which sends the restored LUT again.
+///
+/// The count of ultrafast refreshes is restored as well, so the full refresh which clears their
+/// ghosts isn't put off by the sleep cycles. The orientation of the driver is part of the
+/// snapshot, the one of a [`Display`](crate::graphics::Display) buffer is kept by the buffer.
+pub trait Snapshot {
+    /// Color type of the background color
+    type Color: Copy;
+
+    /// Returns the current software state
+    fn snapshot(&self) -> DriverSnapshot<Self::Color>;
+
+    /// Overwrites the software state, without sending anything to the display
+    fn restore(&mut self, snapshot: DriverSnapshot<Self::Color>);
+}
+
+/// A plane of the display memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plane {
+    /// The black/white plane, the only plane of black/white displays
+    ///
+    /// On the 7-color display this is its only plane, with 4 bits per pixel.
+    Bw,
+    /// The chromatic (red/yellow) plane of tri-color displays
+    Chromatic,
+}
+
+/// Errors while uploading a raw plane
+#[derive(Debug, PartialEq, Eq)]
+pub enum RawPlaneError<E> {
+    /// Sending to the display failed
+    Interface(E),
+    /// The display doesn't have this plane, nothing was sent
+    UnsupportedPlane(Plane),
+    /// The data doesn't have the length of a full plane, nothing was sent
+    WrongLength {
+        /// Length of a full plane in bytes
+        expected: usize,
+        /// Length of the passed data in bytes
+        actual: usize,
+    },
+}
+
+impl<E> RawPlaneError<E> {
+    /// Checks that `data` has the length of a full plane
+    pub(crate) fn check_len(data: &[u8], expected: usize) -> Result<(), Self> {
+        if data.len() == expected {
+            Ok(())
+        } else {
+            Err(RawPlaneError::WrongLength {
+                expected,
+                actual: data.len(),
+            })
+        }
+    }
+}
+
+/// Uploads pre-packed frame data straight to a plane of the display memory
+///
+/// Meant for data which is prepared outside of this crate, e.g. the output of a dithering pipeline
+/// for camera images or frames generated on a host, and works without the `graphics` feature.
+/// The data has to be in the same layout as the buffers of the display: row after row from the top,
+/// 8 pixels per byte for the black/white and chromatic planes, 2 pixels per byte for the 7-color display.
+/// Every row is [`WaveshareDisplay::bytes_per_line`] long, the unused bits at the end of a row
+/// of a width which isn't divisible by 8 are ignored.
+///
+/// Like [`WaveshareDisplay::update_frame`] this doesn't refresh the display.
+pub trait RawPlaneUpload<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Checks the length of `data` and uploads it to `plane`
+    ///
+    /// On tri-color displays the chromatic plane should be written after the black/white one,
+    /// like with [`WaveshareThreeColorDisplay::update_chromatic_frame`].
+    #[allow(clippy::type_complexity)]
+    fn write_raw_plane(
+        &mut self,
+        spi: &mut SPI,
+        plane: Plane,
+        data: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), RawPlaneError<DisplayError<SPI, CS, BUSY, DC, RST>>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_plane_len() {
+        assert_eq!(RawPlaneError::<()>::check_len(&[0; 4], 4), Ok(()));
+        assert_eq!(
+            RawPlaneError::<()>::check_len(&[0; 3], 4),
+            Err(RawPlaneError::WrongLength {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn upload_only_when_idle() {
+        assert_eq!(UploadError::<()>::check_idle(BusyState::Idle), Ok(()));
+        assert_eq!(
+            UploadError::<()>::check_idle(BusyState::Refreshing),
+            Err(UploadError::Busy(BusyState::Refreshing))
+        );
+    }
+
+    #[test]
+    fn refresh_only_above_threshold() {
+        let check = BatteryCheck {
+            read_millivolts: || 2900,
+            min_millivolts: 3000,
+        };
+        assert_eq!(
+            RefreshError::<()>::check_voltage(Some(check), true),
+            Err(RefreshError::LowVoltage { millivolts: 2900 })
+        );
+        assert_eq!(
+            RefreshError::<()>::check_voltage(Some(check), false),
+            Ok(())
+        );
+        assert_eq!(RefreshError::<()>::check_voltage(None, true), Ok(()));
+
+        let charged = BatteryCheck {
+            min_millivolts: 2900,
+            ..check
+        };
+        assert_eq!(
+            RefreshError::<()>::check_voltage(Some(charged), true),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn color_spec_chromatic_byte() {
+        assert_eq!(ColorSpec::default().chromatic_byte(), 0xFF);
+        assert_eq!(ColorSpec::achromatic(Color::Black).chromatic_byte(), 0xFF);
+        assert_eq!(ColorSpec::chromatic().chromatic_byte(), 0x00);
+    }
 }
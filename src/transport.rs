@@ -0,0 +1,262 @@
+//! Alternative transports between the MCU and the display
+//!
+//! All drivers talk to the display through an SPI bus and the CS, BUSY, DC and RST pins.
+//! Setups which don't have these directly, like display heads behind an I2C-to-SPI bridge
+//! (e.g. the SC18IS602B) or pins behind a shift register, only need to implement the
+//! few operations of [`EpdTransport`]. [`SharedTransport::split`] then turns the transport
+//! into the bus and pins every driver expects, without any changes to the drivers.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# use core::convert::Infallible;
+//!# use epd_waveshare::prelude::{Error, PinError};
+//!# fn main() -> Result<(), Error<Infallible, PinError<Infallible, Infallible, Infallible, Infallible>>> {
+//! use epd_waveshare::{epd4in2::Epd4in2, prelude::*, transport::{EpdTransport, SharedTransport}};
+//!
+//! /// e.g. an I2C-to-SPI bridge which also drives the control lines
+//! struct Bridge;
+//!
+//! impl EpdTransport for Bridge {
+//!     type Error = core::convert::Infallible;
+//!
+//!     fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+//!         // write `bytes` with DC low
+//!         Ok(())
+//!     }
+//!     fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+//!         // write `bytes` with DC high
+//!         Ok(())
+//!     }
+//!     fn busy_is_high(&mut self) -> Result<bool, Self::Error> {
+//!         Ok(false)
+//!     }
+//!     fn set_reset(&mut self, high: bool) -> Result<(), Self::Error> {
+//!         Ok(())
+//!     }
+//! }
+//!
+//!# let mut delay = delay::MockNoop::new();
+//! let transport = SharedTransport::new(Bridge);
+//! let (mut spi, cs, busy, dc, rst) = transport.split();
+//! let mut epd = Epd4in2::new(&mut spi, cs, busy, dc, rst, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use core::cell::{Cell, RefCell};
+use embedded_hal::{
+    blocking::spi::Write,
+    digital::v2::{InputPin, OutputPin},
+};
+
+/// The operations the drivers need from the connection to the display
+///
+/// Chip select is left to the transport, it can select the display for every call.
+pub trait EpdTransport {
+    /// Error of the transport
+    type Error;
+
+    /// Sends command bytes, i.e. with the DC line low
+    fn command(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Sends data bytes, i.e. with the DC line high
+    fn data(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Returns whether the BUSY line is high
+    ///
+    /// The drivers know the polarity of their display, so this is the raw level of the line.
+    fn busy_is_high(&mut self) -> Result<bool, Self::Error>;
+
+    /// Sets the RST line
+    fn set_reset(&mut self, high: bool) -> Result<(), Self::Error>;
+}
+
+/// Shares an [`EpdTransport`] between the bus and the pins passed to a driver
+pub struct SharedTransport<T> {
+    transport: RefCell<T>,
+    /// Level of the emulated DC line, high for data
+    data_mode: Cell<bool>,
+}
+
+impl<T: EpdTransport> SharedTransport<T> {
+    /// Wraps a transport
+    pub fn new(transport: T) -> Self {
+        SharedTransport {
+            transport: RefCell::new(transport),
+            data_mode: Cell::new(false),
+        }
+    }
+
+    /// Returns the bus and the CS, BUSY, DC and RST pins in the order the drivers take them
+    #[allow(clippy::type_complexity)]
+    pub fn split(
+        &self,
+    ) -> (
+        TransportBus<'_, T>,
+        TransportPin<'_, T>,
+        TransportBusy<'_, T>,
+        TransportPin<'_, T>,
+        TransportPin<'_, T>,
+    ) {
+        (
+            TransportBus { shared: self },
+            TransportPin {
+                shared: self,
+                line: Line::Cs,
+            },
+            TransportBusy { shared: self },
+            TransportPin {
+                shared: self,
+                line: Line::Dc,
+            },
+            TransportPin {
+                shared: self,
+                line: Line::Rst,
+            },
+        )
+    }
+
+    /// Returns the wrapped transport
+    pub fn into_inner(self) -> T {
+        self.transport.into_inner()
+    }
+}
+
+/// The SPI bus of a [`SharedTransport`]
+///
+/// Writes are passed on as command or data depending on the DC pin.
+pub struct TransportBus<'a, T> {
+    shared: &'a SharedTransport<T>,
+}
+
+impl<'a, T: EpdTransport> Write<u8> for TransportBus<'a, T> {
+    type Error = T::Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        let mut transport = self.shared.transport.borrow_mut();
+        if self.shared.data_mode.get() {
+            transport.data(words)
+        } else {
+            transport.command(words)
+        }
+    }
+}
+
+/// Which of the output lines a [`TransportPin`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line {
+    Cs,
+    Dc,
+    Rst,
+}
+
+/// One of the CS, DC and RST pins of a [`SharedTransport`]
+///
+/// CS is handled by the transport itself, so setting it does nothing.
+pub struct TransportPin<'a, T> {
+    shared: &'a SharedTransport<T>,
+    line: Line,
+}
+
+impl<'a, T: EpdTransport> TransportPin<'a, T> {
+    fn set(&mut self, high: bool) -> Result<(), T::Error> {
+        match self.line {
+            Line::Cs => Ok(()),
+            Line::Dc => {
+                self.shared.data_mode.set(high);
+                Ok(())
+            }
+            Line::Rst => self.shared.transport.borrow_mut().set_reset(high),
+        }
+    }
+}
+
+impl<'a, T: EpdTransport> OutputPin for TransportPin<'a, T> {
+    type Error = T::Error;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(false)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(true)
+    }
+}
+
+/// The BUSY pin of a [`SharedTransport`]
+pub struct TransportBusy<'a, T> {
+    shared: &'a SharedTransport<T>,
+}
+
+impl<'a, T: EpdTransport> InputPin for TransportBusy<'a, T> {
+    type Error = T::Error;
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        self.shared.transport.borrow_mut().busy_is_high()
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Remembers the last command and data byte and the RST line
+    #[derive(Default)]
+    struct Recorder {
+        command: Option<u8>,
+        data: Option<u8>,
+        reset: bool,
+    }
+
+    impl EpdTransport for Recorder {
+        type Error = ();
+
+        fn command(&mut self, bytes: &[u8]) -> Result<(), ()> {
+            self.command = bytes.last().copied();
+            Ok(())
+        }
+
+        fn data(&mut self, bytes: &[u8]) -> Result<(), ()> {
+            self.data = bytes.last().copied();
+            Ok(())
+        }
+
+        fn busy_is_high(&mut self) -> Result<bool, ()> {
+            Ok(self.reset)
+        }
+
+        fn set_reset(&mut self, high: bool) -> Result<(), ()> {
+            self.reset = high;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn dc_selects_command_or_data() {
+        let transport = SharedTransport::new(Recorder::default());
+        {
+            let (mut spi, mut cs, busy, mut dc, mut rst) = transport.split();
+            cs.set_low().unwrap();
+
+            dc.set_low().unwrap();
+            spi.write(&[0x12]).unwrap();
+            dc.set_high().unwrap();
+            spi.write(&[0x34, 0x56]).unwrap();
+
+            assert!(!busy.is_high().unwrap());
+            rst.set_high().unwrap();
+            assert!(busy.is_high().unwrap());
+            assert!(!busy.is_low().unwrap());
+        }
+        let recorder = transport.into_inner();
+        assert_eq!(recorder.command, Some(0x12));
+        assert_eq!(recorder.data, Some(0x56));
+        assert!(recorder.reset);
+    }
+}
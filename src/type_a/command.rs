@@ -17,6 +17,10 @@ pub(crate) enum Command {
     ///     0.. B[2:0]
     ///     Default: Set A[8:0] = 0x127 and B[2:0] = 0x0
     DriverOutputControl = 0x01,
+    /// Gate driving voltage VGH, SSD1680 family only
+    GateVoltage = 0x03,
+    /// Source driving voltages VSH1, VSH2 and VSL, SSD1680 family only
+    SourceVoltage = 0x04,
     /// Booster Soft start control
     ///     3 Databytes:
     ///     1.. A[6:0]
@@ -39,8 +43,13 @@ pub(crate) enum Command {
 
     SwReset = 0x12,
 
+    TemperatureSensorSelection = 0x18,
+
     TemperatureSensorControl = 0x1A,
 
+    /// Reads the temperature register, two bytes like [`TemperatureSensorControl`](Command::TemperatureSensorControl)
+    TemperatureSensorRead = 0x1B,
+
     MasterActivation = 0x20,
 
     DisplayUpdateControl1 = 0x21,
@@ -63,6 +72,9 @@ pub(crate) enum Command {
 
     BorderWaveformControl = 0x3C,
 
+    /// Option of the end of a LUT and the gate level during it, SSD1680 family only
+    EndOption = 0x3F,
+
     SetRamXAddressStartEndPosition = 0x44,
 
     SetRamYAddressStartEndPosition = 0x45,
@@ -23,3 +23,13 @@ pub(crate) const LUT_FULL_UPDATE: [u8; 30] =[
     0x50, 0xAA, 0x55, 0xAA, 0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
 ];
+
+#[rustfmt::skip]
+// The first two phases of the partial waveform, A2-style: only the changed pixels are driven
+// to black or white, for ultrafast refreshes
+pub(crate) const LUT_ULTRAFAST_UPDATE: [u8; 30] =[
+    0x18, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x0F, 0x01, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+];
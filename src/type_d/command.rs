@@ -0,0 +1,72 @@
+//! SPI Commands for the Waveshare flexible 2.13" (D) and 2.9" (D) E-Ink Displays
+
+use crate::traits;
+
+/// Commands of the UC8151 controller of the flexible (D) displays
+///
+/// Should rarely (never?) be needed directly.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Resolution, LUT selection, scan directions and the booster switch
+    PanelSetting = 0x00,
+    /// Internal or external power and the driving voltages
+    PowerSetting = 0x01,
+    /// Switches off the charge pump and the drivers, registers are kept
+    PowerOff = 0x02,
+    /// Switches on the charge pump and the drivers, the busy pin is low until they are ready
+    PowerOn = 0x04,
+    /// Soft start of the boosters
+    BoosterSoftStart = 0x06,
+    /// Deep sleep after 0xA5, only a reset wakes the display up again
+    DeepSleep = 0x07,
+    /// The old frame, which the LUTs compare the new one with
+    DataStartTransmission1 = 0x10,
+    /// Refreshes the display, the busy pin is low until the refresh is done
+    DisplayRefresh = 0x12,
+    /// The new frame
+    DataStartTransmission2 = 0x13,
+    /// LUT of VCOM
+    LutForVcom = 0x20,
+    /// LUT of the pixels which stay white
+    LutWhiteToWhite = 0x21,
+    /// LUT of the pixels which change from black to white
+    LutBlackToWhite = 0x22,
+    /// LUT of the pixels which change from white to black
+    LutWhiteToBlack = 0x23,
+    /// LUT of the pixels which stay black
+    LutBlackToBlack = 0x24,
+    /// Frame rate
+    PllControl = 0x30,
+    /// Border level, data polarity and the interval between VCOM and the data
+    VcomAndDataIntervalSetting = 0x50,
+    /// Non-overlap periods of the gates and the sources
+    TconSetting = 0x60,
+    /// Number of sources and gates
+    ResolutionSetting = 0x61,
+    /// Status of the controller, the busy pin is updated with it
+    GetStatus = 0x71,
+    /// Measures VCOM with the kick-back of the gates
+    AutoMeasurementVcom = 0x80,
+    /// Reads the result of [`AutoMeasurementVcom`](Command::AutoMeasurementVcom)
+    ReadVcomValue = 0x81,
+    /// VCOM DC voltage
+    VcmDcSetting = 0x82,
+    /// Window of the partial mode
+    PartialWindow = 0x90,
+    /// Enters the partial mode, only the window is written and refreshed
+    PartialIn = 0x91,
+    /// Leaves the partial mode
+    PartialOut = 0x92,
+    /// Cascade setting, bit 1 selects the LUTs by the temperature of [`ForceTemperature`](Command::ForceTemperature)
+    CascadeSetting = 0xE0,
+    /// Temperature in °C which the LUTs are selected by instead of the measured one
+    ForceTemperature = 0xE5,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
@@ -0,0 +1,79 @@
+//! SPI Commands for the Waveshare "G" 4-color E-Ink Displays
+
+use crate::traits;
+
+/// Commands of the 1.64", 2.15", 2.36", 3.0", 4.37" and 7.3" (G) displays
+///
+/// Should rarely (never?) be needed directly. Most of the registers are only described by the
+/// values of the Waveshare C drivers, and not every panel uses every one of them.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Resolution, scan directions and the booster switch
+    PanelSetting = 0x00,
+    /// Internal or external power and the driving voltages
+    PowerSetting = 0x01,
+    /// Switches off the charge pump and the drivers, the busy pin is low until they are off
+    PowerOff = 0x02,
+    /// Power off sequence
+    PowerOffSequenceSetting = 0x03,
+    /// Switches on the charge pump and the drivers, the busy pin is low until they are ready
+    PowerOn = 0x04,
+    /// Soft start of booster 1
+    BoosterSoftStart1 = 0x05,
+    /// Soft start of booster 2
+    BoosterSoftStart2 = 0x06,
+    /// Deep sleep after 0xA5, only a reset wakes the display up again
+    DeepSleep = 0x07,
+    /// Soft start of booster 3
+    BoosterSoftStart3 = 0x08,
+    /// The frame, four pixels per byte
+    DataStartTransmission = 0x10,
+    /// Refreshes the display, the busy pin is low until the refresh is done
+    DisplayRefresh = 0x12,
+    /// Internal power control
+    InternalPowerControl = 0x13,
+    /// Frame rate
+    PllControl = 0x30,
+    /// Selects the internal temperature sensor
+    TemperatureSensorEnable = 0x41,
+    /// Not described, sent with 0x78 by the Waveshare driver of the 2.15" display
+    VendorSetting4D = 0x4D,
+    /// Border color and the interval between VCOM and the data
+    VcomAndDataIntervalSetting = 0x50,
+    /// Non-overlap period of the gate and the source
+    TconSetting = 0x60,
+    /// Number of sources and gates
+    TconResolution = 0x61,
+    /// Source voltages of the four colors
+    SourceVoltage = 0x62,
+    /// First gate and source of the frame
+    GateSourceStart = 0x65,
+    /// Unlocks the command set of the small panels with a magic sequence
+    Unlock = 0x66,
+    /// Not described, sent by the Waveshare drivers of the 1.64" and the 7.3" display
+    VendorSetting = 0x84,
+    /// Unlocks the command set of the 7.3" display with a magic sequence
+    UnlockExtended = 0xAA,
+    /// Not described, sent with 0x00 by the Waveshare drivers
+    VendorSettingB0 = 0xB0,
+    /// Not described, sent with 0xD0 by the Waveshare drivers
+    VendorSettingB4 = 0xB4,
+    /// Not described, sent with 0x03 by the Waveshare drivers
+    VendorSettingB5 = 0xB5,
+    /// Clock cascading
+    CascadeSetting = 0xE0,
+    /// Power saving of the source and the gate
+    PowerSaving = 0xE3,
+    /// Not described, sent with 0x1C by the Waveshare drivers
+    VendorSettingE7 = 0xE7,
+    /// Not described, sent with 0x01 by the Waveshare drivers
+    VendorSettingE9 = 0xE9,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
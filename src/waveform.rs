@@ -0,0 +1,181 @@
+//! Parser for the LUT files of the panel vendors
+//!
+//! Panel vendors hand out the waveforms tuned for a batch or a temperature range as binary
+//! files, usually dumps of the LUT registers. [`parse`] tells the layouts apart by their
+//! length and converts them into the builders of the [`lut`](crate::lut) module, whose tables
+//! can be changed further and sent like the constants of the drivers.
+//!
+//! | Length | Content |
+//! |--------|---------|
+//! | 153    | SSD1680 LUT ([`Waveform::Ssd`]) |
+//! | 159    | SSD1680 LUT with the end option, gate, source and VCOM voltages |
+//! | 210    | UC8151 LUTs VCOM, WW, BW, WB and BB of 42 bytes each ([`Waveform::Uc`]) |
+//! | 212    | UC8151 LUTs with the VCOM LUT of 44 bytes |
+//!
+//! E Ink `.wbf` files are recognized, but rejected with [`WaveformError::Wbf`]: they hold the
+//! grayscale waveforms for the timing controller of an EPDC, which don't map onto the LUTs of
+//! these controllers.
+//!
+//! The parser doesn't need the standard library, so it works at runtime on the target, e.g.
+//! with a file from an SD card, as well as in a build script which writes the tables into the
+//! firmware.
+//!
+//!```rust
+//! use epd_waveshare::waveform::{parse, Waveform};
+//!
+//! let file = [0u8; 159];
+//! match parse(&file) {
+//!     Ok(Waveform::Ssd { lut, voltages }) => {
+//!         assert_eq!(lut.build().len(), 153);
+//!         assert!(voltages.is_some());
+//!     }
+//!     _ => unreachable!(),
+//! }
+//!```
+
+use crate::lut::{SsdLut, SsdVoltages, UcLut};
+
+/// Length of the header of an E Ink `.wbf` file
+const WBF_HEADER_LEN: usize = 48;
+
+/// A waveform read from a vendor file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    /// The LUT of the SSD1680 family, with the voltages if the file has them
+    Ssd {
+        /// The waveform
+        lut: SsdLut,
+        /// The driving voltages after the waveform
+        voltages: Option<SsdVoltages>,
+    },
+    /// The LUTs of the UC8151 family
+    Uc(UcWaveform),
+}
+
+/// The five LUTs of the UC8151 family
+///
+/// The two bytes after the groups of a VCOM LUT of 44 bytes are dropped, they are unused
+/// and [`build_vcom`](UcLut::build_vcom) sends them as zeros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UcWaveform {
+    /// LUT of VCOM (`0x20`)
+    pub vcom: UcLut,
+    /// LUT of white to white (`0x21`)
+    pub ww: UcLut,
+    /// LUT of black to white (`0x22`)
+    pub bw: UcLut,
+    /// LUT of white to black (`0x23`)
+    pub wb: UcLut,
+    /// LUT of black to black (`0x24`)
+    pub bb: UcLut,
+}
+
+/// Error of [`parse`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaveformError {
+    /// The file has none of the known lengths
+    UnknownLength(usize),
+    /// The file is an E Ink `.wbf` file, whose waveforms can't be converted
+    Wbf,
+}
+
+/// Reads the waveform of a vendor file, see the [module](self) for the supported layouts
+pub fn parse(bytes: &[u8]) -> Result<Waveform, WaveformError> {
+    if is_wbf(bytes) {
+        return Err(WaveformError::Wbf);
+    }
+
+    match bytes.len() {
+        SSD_LEN | SSD_WITH_VOLTAGES_LEN => {
+            let (waveform, voltages) = bytes.split_at(SsdLut::LEN);
+            let voltages = match *voltages {
+                [end_option, gate, vsh1, vsh2, vsl, vcom] => Some(SsdVoltages {
+                    end_option,
+                    gate,
+                    source: [vsh1, vsh2, vsl],
+                    vcom,
+                }),
+                _ => None,
+            };
+            Ok(Waveform::Ssd {
+                lut: SsdLut::from_bytes(copy_table(waveform)),
+                voltages,
+            })
+        }
+        UC_LEN | UC_WITH_LONG_VCOM_LEN => {
+            let (vcom, luts) = bytes.split_at(bytes.len() - 4 * UcLut::LEN);
+            let lut = |index: usize| UcLut::from_bytes(copy_table(&luts[index * UcLut::LEN..]));
+            Ok(Waveform::Uc(UcWaveform {
+                vcom: UcLut::from_bytes(copy_table(vcom)),
+                ww: lut(0),
+                bw: lut(1),
+                wb: lut(2),
+                bb: lut(3),
+            }))
+        }
+        len => Err(WaveformError::UnknownLength(len)),
+    }
+}
+
+const SSD_LEN: usize = SsdLut::LEN;
+const SSD_WITH_VOLTAGES_LEN: usize = SsdLut::LEN + 6;
+const UC_LEN: usize = 5 * UcLut::LEN;
+const UC_WITH_LONG_VCOM_LEN: usize = UcLut::VCOM_LEN + 4 * UcLut::LEN;
+
+/// The `.wbf` files start with a checksum and their own length as little endian `u32`
+fn is_wbf(bytes: &[u8]) -> bool {
+    match bytes.get(4..8) {
+        Some(&[a, b, c, d]) => {
+            bytes.len() >= WBF_HEADER_LEN
+                && u32::from_le_bytes([a, b, c, d]) as usize == bytes.len()
+        }
+        _ => false,
+    }
+}
+
+/// Copies the first `N` bytes, the missing ones are zeros
+fn copy_table<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut table = [0; N];
+    for (entry, byte) in table.iter_mut().zip(bytes) {
+        *entry = *byte;
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_d::constants::{LUT_BB, LUT_BW, LUT_VCOM_DC, LUT_WB, LUT_WW};
+
+    #[test]
+    fn uc_file_to_luts() {
+        let mut file = [0; UC_WITH_LONG_VCOM_LEN];
+        let tables: [&[u8]; 5] = [&LUT_VCOM_DC, &LUT_WW, &LUT_BW, &LUT_WB, &LUT_BB];
+        let mut offset = 0;
+        for table in tables.iter() {
+            file[offset..offset + table.len()].copy_from_slice(table);
+            offset += table.len();
+        }
+
+        match parse(&file) {
+            Ok(Waveform::Uc(waveform)) => {
+                assert_eq!(waveform.vcom.build_vcom(), LUT_VCOM_DC);
+                assert_eq!(waveform.ww.build(), LUT_WW);
+                assert_eq!(waveform.bw.build(), LUT_BW);
+                assert_eq!(waveform.wb.build(), LUT_WB);
+                assert_eq!(waveform.bb.build(), LUT_BB);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_and_wbf_files() {
+        assert_eq!(parse(&[0; 100]), Err(WaveformError::UnknownLength(100)));
+
+        // a .wbf header with the length of the file, which happens to be a known length
+        let mut file = [0; SSD_LEN];
+        file[4] = SSD_LEN as u8;
+        assert_eq!(parse(&file), Err(WaveformError::Wbf));
+    }
+}